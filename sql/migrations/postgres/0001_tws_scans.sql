@@ -0,0 +1,54 @@
+-- Migration bookkeeping, read by `scanner db status`.
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version integer PRIMARY KEY,
+    name text NOT NULL,
+    applied_at timestamptz NOT NULL DEFAULT now()
+);
+
+ALTER TABLE schema_migrations ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS "Allow read for anon" ON schema_migrations;
+CREATE POLICY "Allow read for anon" ON schema_migrations
+    FOR SELECT
+    TO anon
+    USING (true);
+
+-- Per-symbol scan summary rows plus the enrichment cache.
+CREATE TABLE IF NOT EXISTS tws_scans (
+    id bigint GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    symbol text UNIQUE NOT NULL,
+    first_seen timestamptz NOT NULL,
+    last_seen timestamptz NOT NULL,
+    scanners text NOT NULL,              -- comma-separated list
+    hit_count integer DEFAULT 1
+);
+
+-- Columns added after the table was first created by hand
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS last_price float8;
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS change_pct float8;
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS rvol float8;
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS float_shares float8;
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS catalyst text;               -- news headline
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS catalyst_time bigint;        -- unix seconds
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS name text;
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS sector text;
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS industry text;
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS short_pct float8;
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS avg_volume bigint;
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS avg_volume_10d bigint;
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS news_headlines text;         -- JSON array
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS enriched_at timestamptz;
+
+CREATE INDEX IF NOT EXISTS tws_scans_first_seen ON tws_scans (first_seen);
+
+ALTER TABLE tws_scans ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS "Allow all for anon" ON tws_scans;
+CREATE POLICY "Allow all for anon" ON tws_scans
+    FOR ALL
+    TO anon
+    USING (true)
+    WITH CHECK (true);
+
+INSERT INTO schema_migrations (version, name) VALUES (1, 'tws_scans')
+    ON CONFLICT (version) DO NOTHING;
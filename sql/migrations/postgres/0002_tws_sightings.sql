@@ -0,0 +1,26 @@
+-- Append-only sightings: one row per (symbol, scanner) hit per poll cycle.
+CREATE TABLE IF NOT EXISTS tws_sightings (
+    id bigint GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    symbol text NOT NULL,
+    seen_at timestamptz NOT NULL,
+    scanner text NOT NULL,
+    rank integer NOT NULL,
+    price float8,
+    change_pct float8,
+    volume bigint,                       -- IB round lots (x100 for shares)
+    rvol float8
+);
+
+CREATE INDEX IF NOT EXISTS tws_sightings_symbol_seen_at ON tws_sightings (symbol, seen_at);
+
+ALTER TABLE tws_sightings ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS "Allow all for anon" ON tws_sightings;
+CREATE POLICY "Allow all for anon" ON tws_sightings
+    FOR ALL
+    TO anon
+    USING (true)
+    WITH CHECK (true);
+
+INSERT INTO schema_migrations (version, name) VALUES (2, 'tws_sightings')
+    ON CONFLICT (version) DO NOTHING;
@@ -0,0 +1,17 @@
+-- Day-scoped scan history: one tws_scans row per (symbol, trading_date),
+-- where trading_date is the US/Eastern session date.
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS trading_date date;
+
+UPDATE tws_scans
+SET trading_date = (first_seen AT TIME ZONE 'America/New_York')::date
+WHERE trading_date IS NULL;
+
+ALTER TABLE tws_scans ALTER COLUMN trading_date SET NOT NULL;
+ALTER TABLE tws_scans DROP CONSTRAINT IF EXISTS tws_scans_symbol_key;
+ALTER TABLE tws_scans DROP CONSTRAINT IF EXISTS tws_scans_symbol_trading_date_key;
+ALTER TABLE tws_scans ADD CONSTRAINT tws_scans_symbol_trading_date_key UNIQUE (symbol, trading_date);
+
+CREATE INDEX IF NOT EXISTS tws_scans_trading_date ON tws_scans (trading_date);
+
+INSERT INTO schema_migrations (version, name) VALUES (3, 'trading_date')
+    ON CONFLICT (version) DO NOTHING;
@@ -0,0 +1,13 @@
+-- Sighting volumes are stored in shares; rows written before this
+-- migration held IB round lots. The guard keeps a re-run from scaling twice.
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM schema_migrations WHERE version = 4) THEN
+        UPDATE tws_sightings SET volume = volume * 100 WHERE volume IS NOT NULL;
+    END IF;
+END $$;
+
+COMMENT ON COLUMN tws_sightings.volume IS 'Shares';
+
+INSERT INTO schema_migrations (version, name) VALUES (4, 'volume_shares')
+    ON CONFLICT (version) DO NOTHING;
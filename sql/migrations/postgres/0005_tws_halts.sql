@@ -0,0 +1,23 @@
+-- Append-only halt log: one row per halt and one per resume.
+CREATE TABLE IF NOT EXISTS tws_halts (
+    id bigint GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    symbol text NOT NULL,
+    at timestamptz NOT NULL,
+    state text NOT NULL,                 -- halted, volatility or trading (resume)
+    halt_price float8,
+    duration_secs bigint                 -- resumes only
+);
+
+CREATE INDEX IF NOT EXISTS tws_halts_symbol_at ON tws_halts (symbol, at);
+
+ALTER TABLE tws_halts ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS "Allow all for anon" ON tws_halts;
+CREATE POLICY "Allow all for anon" ON tws_halts
+    FOR ALL
+    TO anon
+    USING (true)
+    WITH CHECK (true);
+
+INSERT INTO schema_migrations (version, name) VALUES (5, 'tws_halts')
+    ON CONFLICT (version) DO NOTHING;
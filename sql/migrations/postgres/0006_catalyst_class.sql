@@ -0,0 +1,7 @@
+-- Catalyst category and sentiment, stored with the catalyst headline so
+-- restored rows keep them (filings, which classify by form, aren't stored).
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS catalyst_kind text;        -- fda, earnings, merger, offering, ...
+ALTER TABLE tws_scans ADD COLUMN IF NOT EXISTS catalyst_sentiment text;   -- bullish, bearish or neutral
+
+INSERT INTO schema_migrations (version, name) VALUES (6, 'catalyst_class')
+    ON CONFLICT (version) DO NOTHING;
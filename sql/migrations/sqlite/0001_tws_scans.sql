@@ -0,0 +1,24 @@
+-- Per-symbol scan summary rows plus the enrichment cache.
+CREATE TABLE IF NOT EXISTS tws_scans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    symbol TEXT UNIQUE NOT NULL,
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL,
+    scanners TEXT NOT NULL,
+    hit_count INTEGER DEFAULT 1,
+    last_price REAL,
+    change_pct REAL,
+    rvol REAL,
+    float_shares REAL,
+    catalyst TEXT,
+    catalyst_time INTEGER,
+    name TEXT,
+    sector TEXT,
+    industry TEXT,
+    short_pct REAL,
+    avg_volume INTEGER,
+    avg_volume_10d INTEGER,
+    news_headlines TEXT,
+    enriched_at TEXT
+);
+CREATE INDEX IF NOT EXISTS tws_scans_first_seen ON tws_scans (first_seen);
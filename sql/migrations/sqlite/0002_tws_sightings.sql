@@ -0,0 +1,13 @@
+-- Append-only sightings: one row per (symbol, scanner) hit per poll cycle.
+CREATE TABLE IF NOT EXISTS tws_sightings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    symbol TEXT NOT NULL,
+    seen_at TEXT NOT NULL,
+    scanner TEXT NOT NULL,
+    rank INTEGER NOT NULL,
+    price REAL,
+    change_pct REAL,
+    volume INTEGER,
+    rvol REAL
+);
+CREATE INDEX IF NOT EXISTS tws_sightings_symbol_seen_at ON tws_sightings (symbol, seen_at);
@@ -0,0 +1,47 @@
+-- Day-scoped scan history: one tws_scans row per (symbol, trading_date).
+-- SQLite cannot change a UNIQUE constraint in place, so rebuild the table.
+-- eastern_date() is registered by the store when the connection is opened.
+ALTER TABLE tws_scans RENAME TO tws_scans_old;
+DROP INDEX IF EXISTS tws_scans_first_seen;
+
+CREATE TABLE tws_scans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    symbol TEXT NOT NULL,
+    trading_date TEXT NOT NULL,
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL,
+    scanners TEXT NOT NULL,
+    hit_count INTEGER DEFAULT 1,
+    last_price REAL,
+    change_pct REAL,
+    rvol REAL,
+    float_shares REAL,
+    catalyst TEXT,
+    catalyst_time INTEGER,
+    name TEXT,
+    sector TEXT,
+    industry TEXT,
+    short_pct REAL,
+    avg_volume INTEGER,
+    avg_volume_10d INTEGER,
+    news_headlines TEXT,
+    enriched_at TEXT,
+    UNIQUE (symbol, trading_date)
+);
+
+INSERT INTO tws_scans (
+    id, symbol, trading_date, first_seen, last_seen, scanners, hit_count,
+    last_price, change_pct, rvol, float_shares, catalyst, catalyst_time, name,
+    sector, industry, short_pct, avg_volume, avg_volume_10d, news_headlines,
+    enriched_at
+)
+SELECT
+    id, symbol, eastern_date(first_seen), first_seen, last_seen, scanners, hit_count,
+    last_price, change_pct, rvol, float_shares, catalyst, catalyst_time, name,
+    sector, industry, short_pct, avg_volume, avg_volume_10d, news_headlines,
+    enriched_at
+FROM tws_scans_old;
+
+DROP TABLE tws_scans_old;
+CREATE INDEX tws_scans_first_seen ON tws_scans (first_seen);
+CREATE INDEX tws_scans_trading_date ON tws_scans (trading_date);
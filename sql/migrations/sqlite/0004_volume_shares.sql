@@ -0,0 +1,3 @@
+-- Sighting volumes are stored in shares; rows written before this
+-- migration held IB round lots.
+UPDATE tws_sightings SET volume = volume * 100 WHERE volume IS NOT NULL;
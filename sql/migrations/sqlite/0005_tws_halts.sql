@@ -0,0 +1,10 @@
+-- Append-only halt log: one row per halt and one per resume.
+CREATE TABLE IF NOT EXISTS tws_halts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    symbol TEXT NOT NULL,
+    at TEXT NOT NULL,
+    state TEXT NOT NULL,
+    halt_price REAL,
+    duration_secs INTEGER
+);
+CREATE INDEX IF NOT EXISTS tws_halts_symbol_at ON tws_halts (symbol, at);
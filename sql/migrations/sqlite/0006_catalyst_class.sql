@@ -0,0 +1,4 @@
+-- Catalyst category and sentiment, stored with the catalyst headline so
+-- restored rows keep them (filings, which classify by form, aren't stored).
+ALTER TABLE tws_scans ADD COLUMN catalyst_kind TEXT;
+ALTER TABLE tws_scans ADD COLUMN catalyst_sentiment TEXT;
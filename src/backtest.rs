@@ -0,0 +1,415 @@
+//! Backtests of momentum criteria over recorded scanner history.
+//!
+//! Each symbol's first sighting on a trading date is an entry at the
+//! sighted price. The entry is evaluated against a criteria profile with
+//! what was known at that moment, and the day's bars give its forward
+//! returns. Outcomes are summarized per pillar configuration (which
+//! pillars failed), so thresholds can be tuned against evidence.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::criteria::{momentum_score, MomentumCriteria, MomentumInputs, PillarReport};
+use crate::history::HistoryStore;
+use crate::market_data::{Bar, MarketDataSource};
+use crate::models::{Sighting, TwsScan};
+use crate::session;
+
+/// Forward-return horizons, in minutes after entry.
+pub const HORIZONS: [i64; 4] = [5, 15, 30, 60];
+
+/// Column labels for `HORIZONS` followed by end of day.
+pub const HORIZON_LABELS: [&str; 5] = ["5m", "15m", "30m", "60m", "EOD"];
+
+/// A symbol's first sighting on a trading date.
+#[derive(Debug, Clone, Serialize)]
+pub struct Entry {
+    pub symbol: String,
+    pub date: NaiveDate,
+    pub at: DateTime<Utc>,
+    pub price: f64,
+    pub pillars: PillarReport,
+    pub score: f64,
+}
+
+/// What happened after an entry. Returns are in percent.
+#[derive(Debug, Clone, Serialize)]
+pub struct Outcome {
+    #[serde(flatten)]
+    pub entry: Entry,
+    /// Return at each of `HORIZONS`; None when the bars end first.
+    pub returns: Vec<Option<f64>>,
+    /// Return at the 16:00 ET close, or at the last bar for after-hours entries.
+    pub eod: Option<f64>,
+    /// Maximum adverse excursion: the worst low before the close (<= 0).
+    pub mae: Option<f64>,
+}
+
+impl Outcome {
+    /// Returns at every horizon, end of day last.
+    pub fn all_returns(&self) -> impl Iterator<Item = Option<f64>> + '_ {
+        self.returns.iter().copied().chain(std::iter::once(self.eod))
+    }
+}
+
+/// The entry for one `tws_scans` row, from its sightings.
+///
+/// Enrichment comes from the row (the latest values), except that a
+/// catalyst published after the entry does not count. Rows without
+/// sightings or without a sighted price have no entry.
+pub fn entry(
+    scan: &TwsScan,
+    sightings: &[Sighting],
+    criteria: &MomentumCriteria,
+    scanner_total: usize,
+) -> Option<Entry> {
+    let date = scan.trading_date?;
+    let first = sightings.first()?;
+    let at = DateTime::parse_from_rfc3339(&first.seen_at).ok()?.with_timezone(&Utc);
+    // Every scanner that reported the symbol in that first poll cycle
+    let cycle: Vec<&Sighting> = sightings.iter().filter(|s| s.seen_at == first.seen_at).collect();
+    let price = cycle.iter().find_map(|s| s.price)?;
+
+    let catalyst_known = scan.catalyst.is_some() && scan.catalyst_time.is_none_or(|t| t <= at.timestamp());
+    let has_catalyst = crate::criteria::catalyst_state(
+        catalyst_known,
+        scan.catalyst_sentiment,
+        scan.catalyst.is_some() || scan.enriched_at.is_some(),
+    );
+    let inputs = MomentumInputs {
+        price: Some(price),
+        change_pct: cycle.iter().find_map(|s| s.change_pct),
+        rvol: cycle.iter().find_map(|s| s.rvol),
+        float_shares: scan.float_shares,
+        short_pct: scan.short_pct,
+        has_catalyst,
+        catalyst_time: scan.catalyst_time.filter(|_| catalyst_known),
+        scanner_hits: cycle.len() as u32,
+        scanner_total: scanner_total as u32,
+        // Sightings do not record the quote, so quote pillars are unknown
+        ..Default::default()
+    };
+
+    Some(Entry {
+        symbol: scan.symbol.clone(),
+        date,
+        at,
+        price,
+        pillars: criteria.evaluate(&inputs),
+        score: momentum_score(&inputs, criteria, at.timestamp()),
+    })
+}
+
+/// Forward returns and adverse excursion of `entry` over the day's `bars`
+/// (oldest first). Prices at a horizon are the close of the last bar
+/// completed by then.
+pub fn outcome(entry: Entry, bars: &[Bar]) -> Outcome {
+    let bar_len = bars
+        .windows(2)
+        .map(|w| w[1].time - w[0].time)
+        .filter(|d| *d > Duration::zero())
+        .min()
+        .unwrap_or(Duration::minutes(5));
+    let data_end = bars.last().map(|b| b.time + bar_len);
+    // Bars completed after the entry
+    let after: Vec<&Bar> = bars.iter().filter(|b| b.time + bar_len > entry.at).collect();
+
+    let price_at = |t: DateTime<Utc>| -> Option<f64> {
+        if data_end.is_none_or(|end| t > end) {
+            return None;
+        }
+        after.iter().rev().find(|b| b.time + bar_len <= t).map(|b| b.close)
+    };
+    let entry_price = entry.price;
+    let pct = |p: f64| (p / entry_price - 1.0) * 100.0;
+
+    let returns = HORIZONS
+        .iter()
+        .map(|m| price_at(entry.at + Duration::minutes(*m)).map(pct))
+        .collect();
+
+    let close = session::eastern_time(entry.date, 16, 0);
+    let (eod_price, until) = if entry.at < close {
+        (price_at(close), close)
+    } else {
+        (after.last().map(|b| b.close), data_end.unwrap_or(close))
+    };
+    let mae = after
+        .iter()
+        .filter(|b| b.time < until)
+        .map(|b| b.low)
+        .min_by(f64::total_cmp)
+        .map(|low| pct(low).min(0.0));
+
+    let eod = eod_price.map(pct);
+    Outcome { entry, returns, eod, mae }
+}
+
+/// Aggregate outcomes for one pillar configuration.
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    /// "all entries", "all pass", or the failed pillars ("failed: rvol,float").
+    pub config: String,
+    pub entries: usize,
+    /// Percent of entries closing above the entry price.
+    pub hit_rate: Option<f64>,
+    /// Mean return per `HORIZON_LABELS` column.
+    pub avg: Vec<Option<f64>>,
+    /// Median return per `HORIZON_LABELS` column.
+    pub median: Vec<Option<f64>>,
+    pub avg_mae: Option<f64>,
+    pub worst_mae: Option<f64>,
+}
+
+impl Summary {
+    fn of(config: String, outcomes: &[&Outcome]) -> Self {
+        let columns = HORIZON_LABELS.len();
+        let column = |i: usize| -> Vec<f64> {
+            outcomes.iter().filter_map(|o| o.all_returns().nth(i).flatten()).collect()
+        };
+        let eods = column(columns - 1);
+        let maes: Vec<f64> = outcomes.iter().filter_map(|o| o.mae).collect();
+        Self {
+            config,
+            entries: outcomes.len(),
+            hit_rate: (!eods.is_empty())
+                .then(|| eods.iter().filter(|r| **r > 0.0).count() as f64 * 100.0 / eods.len() as f64),
+            avg: (0..columns).map(|i| mean(&column(i))).collect(),
+            median: (0..columns).map(|i| median(column(i))).collect(),
+            avg_mae: mean(&maes),
+            worst_mae: maes.iter().copied().min_by(f64::total_cmp),
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+/// Pillar configuration of an entry.
+fn config_key(report: &PillarReport) -> String {
+    if report.passes_all() {
+        "all pass".to_string()
+    } else {
+        let failed: Vec<&str> = report.failed.iter().map(|p| p.label()).collect();
+        format!("failed: {}", failed.join(","))
+    }
+}
+
+/// One row for all entries, then one per pillar configuration: "all pass"
+/// first, the rest by entry count.
+pub fn summarize(outcomes: &[Outcome]) -> Vec<Summary> {
+    let mut groups: HashMap<String, Vec<&Outcome>> = HashMap::new();
+    for o in outcomes {
+        groups.entry(config_key(&o.entry.pillars)).or_default().push(o);
+    }
+    let mut groups: Vec<(String, Vec<&Outcome>)> = groups.into_iter().collect();
+    groups.sort_by(|a, b| {
+        (b.0 == "all pass")
+            .cmp(&(a.0 == "all pass"))
+            .then(b.1.len().cmp(&a.1.len()))
+            .then(a.0.cmp(&b.0))
+    });
+
+    let all: Vec<&Outcome> = outcomes.iter().collect();
+    std::iter::once(Summary::of("all entries".to_string(), &all))
+        .chain(groups.into_iter().map(|(config, group)| Summary::of(config, &group)))
+        .collect()
+}
+
+/// Outcomes of a backtest run.
+#[derive(Debug, Default, Serialize)]
+pub struct Backtest {
+    pub outcomes: Vec<Outcome>,
+    /// Rows without sightings, entries below `min_score`, and entries
+    /// whose bars could not be fetched.
+    pub skipped: usize,
+}
+
+/// Backtest `criteria` over the sightings recorded for trading dates
+/// `from..=to`, fetching each entry's bars from `source`.
+pub async fn run(
+    store: &dyn HistoryStore,
+    source: &dyn MarketDataSource,
+    from: NaiveDate,
+    to: NaiveDate,
+    criteria: &MomentumCriteria,
+    scanner_total: usize,
+    min_score: Option<f64>,
+) -> Result<Backtest> {
+    let scans = store.get_scans_between(from, to).await?;
+    info!(rows = scans.len(), %from, %to, "backtest started");
+
+    let mut backtest = Backtest::default();
+    for scan in &scans {
+        let Some(date) = scan.trading_date else {
+            backtest.skipped += 1;
+            continue;
+        };
+        let sightings = store.get_trajectory(&scan.symbol, date).await?;
+        let Some(entry) = entry(scan, &sightings, criteria, scanner_total)
+            .filter(|e| min_score.is_none_or(|min| e.score >= min))
+        else {
+            backtest.skipped += 1;
+            continue;
+        };
+        match source.intraday_bars(&scan.symbol, date).await {
+            Ok(bars) if !bars.is_empty() => backtest.outcomes.push(outcome(entry, &bars)),
+            Ok(_) => backtest.skipped += 1,
+            Err(e) => {
+                warn!(symbol = %scan.symbol, %date, "backtest bars failed: {e}");
+                backtest.skipped += 1;
+            }
+        }
+    }
+    Ok(backtest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::criteria::Pillar;
+    use crate::units::Shares;
+    use chrono::TimeZone;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 7, 15).unwrap()
+    }
+
+    /// 5-minute bars from 09:30 ET; bar i closes at `closes[i]`.
+    fn bars(closes: &[f64]) -> Vec<Bar> {
+        let open = session::eastern_time(date(), 9, 30);
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| Bar {
+                time: open + Duration::minutes(5 * i as i64),
+                open: c,
+                high: c,
+                low: c - 0.1,
+                close: c,
+                volume: 1000.0,
+            })
+            .collect()
+    }
+
+    fn sighting(seen_at: &str, scanner: &str, price: f64) -> Sighting {
+        Sighting {
+            symbol: "ABCD".to_string(),
+            seen_at: seen_at.to_string(),
+            scanner: scanner.to_string(),
+            rank: 1,
+            price: Some(price),
+            change_pct: Some(25.0),
+            volume: Some(Shares(500_000)),
+            rvol: Some(8.0),
+        }
+    }
+
+    fn scan() -> TwsScan {
+        TwsScan {
+            symbol: "ABCD".to_string(),
+            trading_date: Some(date()),
+            float_shares: Some(4e6),
+            enriched_at: Some("2024-07-15T14:00:00+00:00".to_string()),
+            catalyst: Some("ABCD wins contract".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_entry_uses_first_cycle() {
+        // 09:30 ET = 13:30 UTC
+        let sightings = vec![
+            sighting("2024-07-15T13:30:00+00:00", "TOP_PERC_GAIN", 2.0),
+            sighting("2024-07-15T13:30:00+00:00", "HOT_BY_VOLUME", 2.0),
+            sighting("2024-07-15T13:45:00+00:00", "TOP_PERC_GAIN", 2.5),
+        ];
+        let criteria = MomentumCriteria::default();
+        let e = entry(&scan(), &sightings, &criteria, 8).unwrap();
+        assert_eq!(e.price, 2.0);
+        assert_eq!(e.at, Utc.with_ymd_and_hms(2024, 7, 15, 13, 30, 0).unwrap());
+        assert!(e.pillars.passes_all());
+
+        // A catalyst published after the entry was not known yet
+        let late = TwsScan {
+            catalyst_time: Some(e.at.timestamp() + 600),
+            ..scan()
+        };
+        let e = entry(&late, &sightings, &criteria, 8).unwrap();
+        assert_eq!(e.pillars.failed, vec![Pillar::Catalyst]);
+
+        assert!(entry(&scan(), &[], &criteria, 8).is_none());
+    }
+
+    #[test]
+    fn test_outcome_returns_and_mae() {
+        let e = Entry {
+            symbol: "ABCD".to_string(),
+            date: date(),
+            at: session::eastern_time(date(), 9, 30),
+            price: 2.0,
+            pillars: PillarReport::default(),
+            score: 0.0,
+        };
+        // Twelve 5-minute bars: 09:30-10:30 ET
+        let closes = [2.1, 2.2, 1.9, 2.4, 2.4, 2.5, 2.6, 2.6, 2.6, 2.6, 2.6, 3.0];
+        let o = outcome(e, &bars(&closes));
+        let round = |r: Option<f64>| r.map(|r| (r * 10.0).round() / 10.0);
+        let returns: Vec<Option<f64>> = o.returns.iter().map(|r| round(*r)).collect();
+        assert_eq!(returns, vec![Some(5.0), Some(-5.0), Some(25.0), Some(50.0)]);
+        // Bars end at 10:30, long before the close
+        assert_eq!(o.eod, None);
+        assert_eq!(round(o.mae), Some(-10.0));
+    }
+
+    #[test]
+    fn test_summarize_groups_by_config() {
+        let outcome_with = |failed: Vec<Pillar>, eod: f64| Outcome {
+            entry: Entry {
+                symbol: "ABCD".to_string(),
+                date: date(),
+                at: session::eastern_time(date(), 9, 30),
+                price: 2.0,
+                pillars: PillarReport { passed: vec![], failed },
+                score: 0.0,
+            },
+            returns: vec![None; HORIZONS.len()],
+            eod: Some(eod),
+            mae: Some(-eod.abs()),
+        };
+        let outcomes = vec![
+            outcome_with(vec![Pillar::RVol], -4.0),
+            outcome_with(vec![], 10.0),
+            outcome_with(vec![Pillar::RVol], 2.0),
+            outcome_with(vec![], 20.0),
+            outcome_with(vec![Pillar::RVol], 6.0),
+        ];
+        let summary = summarize(&outcomes);
+        let configs: Vec<&str> = summary.iter().map(|s| s.config.as_str()).collect();
+        assert_eq!(configs, vec!["all entries", "all pass", "failed: rvol"]);
+        assert_eq!(summary[0].entries, 5);
+        assert_eq!(summary[1].avg[4], Some(15.0));
+        assert_eq!(summary[2].median[4], Some(2.0));
+        assert_eq!(summary[2].hit_rate.map(f64::round), Some(67.0));
+        assert_eq!(summary[2].worst_mae, Some(-6.0));
+        assert_eq!(summary[1].avg[0], None);
+    }
+}
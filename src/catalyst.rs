@@ -1,51 +1,381 @@
-/// Keywords that indicate a news catalyst for momentum stocks.
-const CATALYST_KEYWORDS: &[&str] = &[
-    "fda",
-    "approval",
-    "drug",
-    "trial",
-    "earnings",
-    "revenue",
-    "beat",
-    "miss",
-    "contract",
-    "deal",
-    "acquisition",
-    "merger",
-    "offering",
-    "patent",
-    "partnership",
-    "upgrade",
-    "price target",
-    "dividend",
-    "buyback",
-    "split",
-    "ceo",
-    "appointed",
-    "resign",
+//! Catalyst classification: which headline or filing explains a move, what
+//! kind of news it is and whether it reads bullish or bearish.
+//!
+//! Headlines are matched against per-category phrase rules on word
+//! boundaries, so "deal" does not match "dealer"; each rule has phrases that
+//! veto it and phrases that set the sentiment. Filings are classified by
+//! form and 8-K item instead.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Filing, NewsHeadline};
+
+/// What a catalyst is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalystKind {
+    /// FDA decisions and clinical trial results.
+    Fda,
+    Earnings,
+    /// Mergers, acquisitions and buyouts.
+    Merger,
+    /// Offerings and other dilution, including reverse splits.
+    Offering,
+    /// Contracts, orders, partnerships and licensing.
+    Contract,
+    /// Analyst rating and price target changes.
+    Analyst,
+    /// Executive appointments and departures.
+    Management,
+    /// Patents, dividends, buybacks, splits, listing notices and
+    /// uncategorized filings.
+    Other,
+}
+
+impl CatalystKind {
+    const ALL: [Self; 8] = [
+        Self::Fda,
+        Self::Earnings,
+        Self::Merger,
+        Self::Offering,
+        Self::Contract,
+        Self::Analyst,
+        Self::Management,
+        Self::Other,
+    ];
+
+    /// Stored name, as serialized.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Fda => "fda",
+            Self::Earnings => "earnings",
+            Self::Merger => "merger",
+            Self::Offering => "offering",
+            Self::Contract => "contract",
+            Self::Analyst => "analyst",
+            Self::Management => "management",
+            Self::Other => "other",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.as_str() == s)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Fda => "FDA/clinical",
+            Self::Earnings => "earnings",
+            Self::Merger => "M&A",
+            Self::Offering => "offering/dilution",
+            Self::Contract => "contract",
+            Self::Analyst => "analyst",
+            Self::Management => "management",
+            Self::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sentiment {
+    Bullish,
+    Bearish,
+    Neutral,
+}
+
+impl Sentiment {
+    /// Stored name, as serialized; also the display label.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Bullish => "bullish",
+            Self::Bearish => "bearish",
+            Self::Neutral => "neutral",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        [Self::Bullish, Self::Bearish, Self::Neutral]
+            .into_iter()
+            .find(|x| x.as_str() == s)
+    }
+
+    pub fn label(self) -> &'static str {
+        self.as_str()
+    }
+}
+
+/// A classified catalyst headline or filing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Catalyst {
+    pub kind: CatalystKind,
+    pub sentiment: Sentiment,
+    /// 0-1: how many of the category's phrases matched, plus a little for
+    /// an explicit sentiment phrase. Filings score by form and item.
+    pub confidence: f64,
+    pub headline: String,
+    /// Publish (or filing) time, Unix epoch.
+    pub published: Option<i64>,
+}
+
+/// Phrase rules for one category. Phrases are lowercase words separated by
+/// single spaces and match whole words; a word ending in `*` matches any
+/// word with that stem.
+struct Rule {
+    kind: CatalystKind,
+    patterns: &'static [&'static str],
+    /// Phrases that veto the category, e.g. "board approves" for FDA.
+    exclude: &'static [&'static str],
+    /// Sentiment when no bullish or bearish phrase is present.
+    sentiment: Sentiment,
+    bullish: &'static [&'static str],
+    /// Checked before `bullish`: "did not meet" beats "met".
+    bearish: &'static [&'static str],
+}
+
+/// Categories in priority order; the rule matching the most phrases wins
+/// and ties go to the earlier rule, so an offering to fund a trial is an
+/// offering.
+const RULES: &[Rule] = &[
+    Rule {
+        kind: CatalystKind::Offering,
+        patterns: &[
+            "offering", "registered direct", "private placement", "at the market", "shelf",
+            "dilut*", "warrants", "reverse split", "reverse stock split",
+        ],
+        exclude: &["product offering", "service offering"],
+        sentiment: Sentiment::Bearish,
+        bullish: &["withdraw*", "terminat*", "cancel*"],
+        bearish: &[],
+    },
+    Rule {
+        kind: CatalystKind::Fda,
+        patterns: &[
+            "fda", "approv*", "clinical", "trial", "trials", "phase 1", "phase 2", "phase 3",
+            "phase i", "phase ii", "phase iii", "pdufa", "topline", "drug", "nda", "bla",
+            "breakthrough therapy", "fast track", "orphan drug", "510 k",
+        ],
+        exclude: &["board approv*", "shareholder approv*", "stockholder approv*"],
+        sentiment: Sentiment::Neutral,
+        bullish: &[
+            "approv*", "clearance", "cleared", "positive", "met", "meets", "granted",
+            "breakthrough therapy", "fast track", "orphan drug", "successful*",
+        ],
+        bearish: &[
+            "complete response", "crl", "clinical hold", "fail*", "not meet", "reject*",
+            "discontinu*", "halt*", "negative", "delay*",
+        ],
+    },
+    Rule {
+        kind: CatalystKind::Contract,
+        patterns: &[
+            "contract*", "award*", "purchase order", "partner*", "collaborat*", "license*",
+            "licensing", "supply agreement", "distribution agreement", "supply deal",
+            "distribution deal", "licensing deal",
+        ],
+        exclude: &["contract manufacturer", "contract manufacturing", "contract research"],
+        sentiment: Sentiment::Bullish,
+        bullish: &[],
+        bearish: &["terminat*", "cancel*", "lose*", "loses", "lost"],
+    },
+    Rule {
+        kind: CatalystKind::Merger,
+        patterns: &[
+            "merge*", "acquisition*", "acquir*", "buyout", "takeover", "deal", "tender offer",
+            "take private", "go private",
+        ],
+        exclude: &[],
+        sentiment: Sentiment::Bullish,
+        bullish: &[],
+        bearish: &["terminat*", "called off", "withdraw*", "collapse*", "block*", "reject*"],
+    },
+    Rule {
+        kind: CatalystKind::Earnings,
+        patterns: &[
+            "earnings", "revenue*", "eps", "quarter", "quarterly", "results", "guidance",
+            "beat", "beats", "miss", "misses", "missed", "net income",
+        ],
+        exclude: &[],
+        sentiment: Sentiment::Neutral,
+        bullish: &["beat", "beats", "tops", "exceed*", "record", "raise*", "above", "surge*", "growth"],
+        bearish: &["miss", "misses", "missed", "lower*", "cut*", "below", "decline*", "warn*", "wider loss"],
+    },
+    Rule {
+        kind: CatalystKind::Analyst,
+        patterns: &[
+            "upgrade*", "downgrade*", "price target", "initiat* coverage", "outperform",
+            "underperform", "overweight", "underweight", "analyst*", "buy rating", "sell rating",
+        ],
+        exclude: &[],
+        sentiment: Sentiment::Neutral,
+        bullish: &["upgrade*", "outperform", "overweight", "buy rating", "raise*", "boost*"],
+        bearish: &["downgrade*", "underperform", "underweight", "sell rating", "lower*", "cut*"],
+    },
+    Rule {
+        kind: CatalystKind::Management,
+        patterns: &[
+            "ceo", "cfo", "coo", "chief executive", "chief financial", "chairman", "appoint*",
+            "resign*", "steps down", "step down", "departure",
+        ],
+        exclude: &[],
+        sentiment: Sentiment::Neutral,
+        bullish: &[],
+        bearish: &["resign*", "steps down", "step down", "departure", "terminat*", "fired", "ousted"],
+    },
+    Rule {
+        kind: CatalystKind::Other,
+        patterns: &["patent*", "dividend*", "buyback*", "repurchase*", "split", "delist*"],
+        exclude: &["reverse split", "reverse stock split"],
+        sentiment: Sentiment::Bullish,
+        bullish: &[],
+        bearish: &["delist*", "suspend*", "cut*", "eliminat*"],
+    },
+];
+
+/// Form type prefixes of registrations and prospectuses, i.e. offerings.
+const OFFERING_FORMS: &[&str] = &["S-1", "S-3", "F-1", "F-3", "424B"];
+
+/// Current-report forms; material only with a material item.
+const CURRENT_REPORT_FORMS: &[&str] = &["8-K", "6-K"];
+
+/// 8-K items that report a material event, with what they say: agreements,
+/// bankruptcy, acquisitions, results, delisting, unregistered sales,
+/// control and officer changes, and the press-release items 7.01 and 8.01.
+const MATERIAL_ITEMS: &[(&str, CatalystKind, Sentiment, f64)] = &[
+    ("1.01", CatalystKind::Contract, Sentiment::Neutral, 0.6),
+    ("1.02", CatalystKind::Contract, Sentiment::Bearish, 0.6),
+    ("1.03", CatalystKind::Other, Sentiment::Bearish, 0.9),
+    ("2.01", CatalystKind::Merger, Sentiment::Neutral, 0.8),
+    ("2.02", CatalystKind::Earnings, Sentiment::Neutral, 0.8),
+    ("3.01", CatalystKind::Other, Sentiment::Bearish, 0.9),
+    ("3.02", CatalystKind::Offering, Sentiment::Bearish, 0.8),
+    ("5.01", CatalystKind::Merger, Sentiment::Neutral, 0.7),
+    ("5.02", CatalystKind::Management, Sentiment::Neutral, 0.8),
+    ("7.01", CatalystKind::Other, Sentiment::Neutral, 0.5),
+    ("8.01", CatalystKind::Other, Sentiment::Neutral, 0.5),
 ];
 
-/// Classify news items and return the first headline matching a catalyst keyword,
-/// along with its publish timestamp (Unix epoch).
+/// Lowercase words of `text`, split on anything not alphanumeric.
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Whether `phrase` occurs in `words` as consecutive whole words.
+fn has_phrase(words: &[String], phrase: &str) -> bool {
+    let parts: Vec<&str> = phrase.split(' ').collect();
+    words.windows(parts.len()).any(|window| {
+        window.iter().zip(&parts).all(|(word, part)| match part.strip_suffix('*') {
+            Some(stem) => word.starts_with(stem),
+            None => word == part,
+        })
+    })
+}
+
+impl Catalyst {
+    /// Classify a headline, or None if no category matches.
+    pub fn from_headline(title: &str, published: Option<i64>) -> Option<Self> {
+        let words = words(title);
+        let has_any = |phrases: &[&str]| phrases.iter().any(|p| has_phrase(&words, p));
+        let mut best: Option<(&Rule, usize)> = None;
+        for rule in RULES {
+            if has_any(rule.exclude) {
+                continue;
+            }
+            let matched = rule.patterns.iter().filter(|p| has_phrase(&words, p)).count();
+            if matched > 0 && best.is_none_or(|(_, n)| matched > n) {
+                best = Some((rule, matched));
+            }
+        }
+        let (rule, matched) = best?;
+        let explicit = if has_any(rule.bearish) {
+            Some(Sentiment::Bearish)
+        } else if has_any(rule.bullish) {
+            Some(Sentiment::Bullish)
+        } else {
+            None
+        };
+        let confidence = (0.3 + 0.2 * matched as f64).min(0.9) + if explicit.is_some() { 0.1 } else { 0.0 };
+        Some(Self {
+            kind: rule.kind,
+            sentiment: explicit.unwrap_or(rule.sentiment),
+            confidence,
+            headline: title.to_string(),
+            published,
+        })
+    }
+
+    /// Classify a filing: an offering registration or prospectus, or a
+    /// current report with a material item (the most telling one when it
+    /// has several). A 6-K has no items and always counts. None otherwise.
+    pub fn from_filing(filing: &Filing) -> Option<Self> {
+        let form = filing.form.trim_end_matches("/A");
+        let (kind, sentiment, confidence) = if OFFERING_FORMS.iter().any(|f| form.starts_with(f)) {
+            (CatalystKind::Offering, Sentiment::Bearish, 0.9)
+        } else if !CURRENT_REPORT_FORMS.contains(&form) {
+            return None;
+        } else if filing.items.is_empty() {
+            (CatalystKind::Other, Sentiment::Neutral, 0.5)
+        } else {
+            let mut best: Option<(CatalystKind, Sentiment, f64)> = None;
+            for item in &filing.items {
+                let Some(&(_, kind, sentiment, confidence)) =
+                    MATERIAL_ITEMS.iter().find(|(i, ..)| i == item)
+                else {
+                    continue;
+                };
+                if best.is_none_or(|(_, _, c)| confidence > c) {
+                    best = Some((kind, sentiment, confidence));
+                }
+            }
+            best?
+        };
+        Some(Self { kind, sentiment, confidence, headline: filing.headline(), published: filing.filed })
+    }
+}
+
+/// Classify news items and return the first headline that classifies as a
+/// catalyst.
 ///
 /// Each news item should have a "title" field and optionally "providerPublishTime".
-pub fn classify_catalyst(news: &[serde_json::Value]) -> Option<(String, Option<i64>)> {
-    for item in news {
-        let title = item
-            .get("title")
-            .and_then(|t| t.as_str())
-            .unwrap_or("");
-        let title_lower = title.to_lowercase();
-        for kw in CATALYST_KEYWORDS {
-            if title_lower.contains(kw) {
-                let publish_time = item
-                    .get("providerPublishTime")
-                    .and_then(|t| t.as_i64());
-                return Some((title.to_string(), publish_time));
-            }
+pub fn classify_catalyst(news: &[serde_json::Value]) -> Option<Catalyst> {
+    news.iter().find_map(|item| {
+        let title = item.get("title").and_then(|t| t.as_str())?;
+        let publish_time = item.get("providerPublishTime").and_then(|t| t.as_i64());
+        Catalyst::from_headline(title, publish_time)
+    })
+}
+
+/// `classify_catalyst` over parsed headlines, e.g. merged from several
+/// providers. Headlines are taken in order, so sort newest first.
+pub fn classify_headlines(headlines: &[NewsHeadline]) -> Option<Catalyst> {
+    headlines
+        .iter()
+        .find_map(|h| Catalyst::from_headline(&h.title, h.published))
+}
+
+/// Whether a filing is a catalyst in itself, see `Catalyst::from_filing`.
+pub fn is_catalyst_filing(filing: &Filing) -> bool {
+    Catalyst::from_filing(filing).is_some()
+}
+
+/// The first catalyst filing, newest first.
+pub fn classify_filings(filings: &[Filing]) -> Option<Catalyst> {
+    filings.iter().find_map(Catalyst::from_filing)
+}
+
+/// Catalyst over headlines and filings together: the more recent of the
+/// headline and filing catalysts.
+pub fn classify_news(headlines: &[NewsHeadline], filings: &[Filing]) -> Option<Catalyst> {
+    match (classify_headlines(headlines), classify_filings(filings)) {
+        (Some(headline), Some(filing)) => {
+            Some(if filing.published > headline.published { filing } else { headline })
         }
+        (headline, filing) => headline.or(filing),
     }
-    None
 }
 
 #[cfg(test)]
@@ -56,14 +386,17 @@ mod tests {
     #[test]
     fn test_classify_catalyst_fda() {
         let news = vec![json!({"title": "FDA Approves New Drug for ACME Corp"})];
-        let result = classify_catalyst(&news);
-        assert_eq!(result, Some(("FDA Approves New Drug for ACME Corp".to_string(), None)));
+        let result = classify_catalyst(&news).unwrap();
+        assert_eq!(result.headline, "FDA Approves New Drug for ACME Corp");
+        assert_eq!(result.published, None);
+        assert_eq!(result.kind, CatalystKind::Fda);
+        assert_eq!(result.sentiment, Sentiment::Bullish);
     }
 
     #[test]
     fn test_classify_catalyst_fda_with_timestamp() {
         let news = vec![json!({"title": "FDA Approves New Drug", "providerPublishTime": 1700000000})];
-        let result = classify_catalyst(&news);
+        let result = classify_catalyst(&news).map(|c| (c.headline, c.published));
         assert_eq!(result, Some(("FDA Approves New Drug".to_string(), Some(1700000000))));
     }
 
@@ -73,11 +406,10 @@ mod tests {
             json!({"title": "Stock market rises today"}),
             json!({"title": "ACME beats earnings expectations"}),
         ];
-        let result = classify_catalyst(&news);
-        assert_eq!(
-            result.map(|(t, _)| t),
-            Some("ACME beats earnings expectations".to_string())
-        );
+        let result = classify_catalyst(&news).unwrap();
+        assert_eq!(result.headline, "ACME beats earnings expectations");
+        assert_eq!(result.kind, CatalystKind::Earnings);
+        assert_eq!(result.sentiment, Sentiment::Bullish);
     }
 
     #[test]
@@ -96,8 +428,9 @@ mod tests {
     #[test]
     fn test_classify_catalyst_case_insensitive() {
         let news = vec![json!({"title": "CEO Resigns from Company"})];
-        let result = classify_catalyst(&news);
-        assert!(result.is_some());
+        let result = classify_catalyst(&news).unwrap();
+        assert_eq!(result.kind, CatalystKind::Management);
+        assert_eq!(result.sentiment, Sentiment::Bearish);
     }
 
     #[test]
@@ -107,7 +440,7 @@ mod tests {
             json!({"title": "Earnings beat expectations"}),
         ];
         let result = classify_catalyst(&news);
-        assert_eq!(result.map(|(t, _)| t), Some("FDA approval announced".to_string()));
+        assert_eq!(result.map(|c| c.headline), Some("FDA approval announced".to_string()));
     }
 
     #[test]
@@ -117,8 +450,102 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_classify_headlines() {
+        let headlines = vec![
+            NewsHeadline::new("Shares drift lower", Some(1_700_000_100)),
+            NewsHeadline::new("ACME announces merger with Bolt", Some(1_700_000_000)),
+        ];
+        assert_eq!(
+            classify_headlines(&headlines).map(|c| (c.headline, c.published, c.kind)),
+            Some(("ACME announces merger with Bolt".to_string(), Some(1_700_000_000), CatalystKind::Merger))
+        );
+        assert_eq!(classify_headlines(&headlines[..1]), None);
+    }
+
+    #[test]
+    fn test_classify_filings() {
+        let filing = |form: &str, items: &[&str], filed| Filing {
+            form: form.to_string(),
+            filed: Some(filed),
+            title: "-".to_string(),
+            items: items.iter().map(|i| i.to_string()).collect(),
+            ..Default::default()
+        };
+        assert!(is_catalyst_filing(&filing("424B5", &[], 0)));
+        assert!(is_catalyst_filing(&filing("S-1/A", &[], 0)));
+        assert!(is_catalyst_filing(&filing("8-K", &["1.01", "9.01"], 0)));
+        assert!(!is_catalyst_filing(&filing("8-K", &["9.01"], 0)));
+        assert!(!is_catalyst_filing(&filing("4", &[], 0)));
+
+        let filings = vec![filing("4", &[], 300), filing("S-3", &[], 200)];
+        let c = classify_filings(&filings).unwrap();
+        assert_eq!((c.headline.as_str(), c.published), ("S-3: -", Some(200)));
+        assert_eq!((c.kind, c.sentiment), (CatalystKind::Offering, Sentiment::Bearish));
+
+        // The most telling item: an unregistered sale over the agreement
+        let c = Catalyst::from_filing(&filing("8-K", &["1.01", "3.02", "9.01"], 0)).unwrap();
+        assert_eq!(c.kind, CatalystKind::Offering);
+
+        // The newer of the headline and filing catalysts
+        let headlines = vec![NewsHeadline::new("Acme wins contract", Some(100))];
+        assert_eq!(classify_news(&headlines, &filings).unwrap().headline, "S-3: -");
+        let headlines = vec![NewsHeadline::new("Acme wins contract", Some(400))];
+        assert_eq!(classify_news(&headlines, &filings).unwrap().headline, "Acme wins contract");
+        assert_eq!(classify_news(&[], &[]), None);
+    }
+
+    #[test]
+    fn test_categories_and_sentiment() {
+        let classify = |title: &str| {
+            Catalyst::from_headline(title, None).map(|c| (c.kind, c.sentiment))
+        };
+        use CatalystKind::*;
+        use Sentiment::*;
+        assert_eq!(classify("Acme prices $10M registered direct offering"), Some((Offering, Bearish)));
+        // Offering wins a tie with the trial it funds
+        assert_eq!(classify("Acme announces registered direct offering to fund phase 3 trial"), Some((Offering, Bearish)));
+        assert_eq!(classify("Acme withdraws proposed public offering"), Some((Offering, Bullish)));
+        assert_eq!(classify("Acme announces 1-for-20 reverse stock split"), Some((Offering, Bearish)));
+        assert_eq!(classify("Acme announces 2-for-1 stock split"), Some((Other, Bullish)));
+        assert_eq!(classify("Acme receives complete response letter from FDA"), Some((Fda, Bearish)));
+        assert_eq!(classify("Phase 2 trial did not meet primary endpoint"), Some((Fda, Bearish)));
+        assert_eq!(classify("Acme reports positive topline results from phase 2 trial"), Some((Fda, Bullish)));
+        assert_eq!(classify("Board approves $5M share buyback"), Some((Other, Bullish)));
+        assert_eq!(classify("Acme to acquire Bolt in $50M all-stock deal"), Some((Merger, Bullish)));
+        assert_eq!(classify("Acme terminates merger agreement with Bolt"), Some((Merger, Bearish)));
+        assert_eq!(classify("Acme signs exclusive supply deal with Bolt"), Some((Contract, Bullish)));
+        assert_eq!(classify("Acme awarded $12M Army contract"), Some((Contract, Bullish)));
+        assert_eq!(classify("Acme misses Q3 revenue estimates"), Some((Earnings, Bearish)));
+        assert_eq!(classify("Acme upgraded to buy; price target raised to $8"), Some((Analyst, Bullish)));
+        assert_eq!(classify("Acme downgraded to sell at Bolt Securities"), Some((Analyst, Bearish)));
+        assert_eq!(classify("Acme appoints Jane Doe as CFO"), Some((Management, Neutral)));
+
+        // Whole words and negative patterns
+        assert_eq!(classify("Acme expands dealer network"), None);
+        assert_eq!(classify("Acme to present at missile defense summit"), None);
+        assert_eq!(classify("Acme expands its product offering"), None);
+        assert_eq!(classify("Acme selects contract manufacturer"), None);
+    }
+
+    #[test]
+    fn test_confidence() {
+        let one = Catalyst::from_headline("Acme signs merger", None).unwrap();
+        let two = Catalyst::from_headline("Acme signs merger agreement in takeover", None).unwrap();
+        let explicit = Catalyst::from_headline("Acme terminates merger", None).unwrap();
+        assert!(two.confidence > one.confidence);
+        assert!(explicit.confidence > one.confidence);
+        assert!(two.confidence <= 1.0);
+    }
+
     #[test]
     fn test_all_keywords_match() {
+        // The keywords of the original flat keyword list
+        const CATALYST_KEYWORDS: &[&str] = &[
+            "fda", "approval", "drug", "trial", "earnings", "revenue", "beat", "miss", "contract",
+            "deal", "acquisition", "merger", "offering", "patent", "partnership", "upgrade",
+            "price target", "dividend", "buyback", "split", "ceo", "appointed", "resign",
+        ];
         for kw in CATALYST_KEYWORDS {
             let news = vec![json!({"title": format!("Something about {kw} happened")})];
             let result = classify_catalyst(&news);
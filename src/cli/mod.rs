@@ -1,14 +1,23 @@
-use std::sync::mpsc;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
 use std::time::Duration;
 
 use anyhow::Result;
 
-use crate::config::SupabaseConfig;
+use crate::backtest;
+use crate::config::{EnrichmentConfig, HistoryBackend, HistoryConfig, ScannerConfig};
+use crate::criteria::MomentumCriteria;
 use crate::engine::{AlertEngine, EngineEvent};
-use crate::enrichment;
-use crate::history::{self, SupabaseClient};
+use crate::enrichment::{self, ProviderChain};
+use crate::history;
+use crate::market_data::{Feed, Simulator, TwsSource};
 use crate::models::*;
+use crate::recording;
 use crate::scanner;
+use crate::scanner_set::{ScanFilters, ScannerSet};
+use crate::tws::bars::{self, BarRequest};
+use crate::tws::connection::ConnectionManager;
+use crate::tws::params::{self, ScannerParams};
 use crate::tws;
 
 /// Log a timestamped message. In text mode goes to stdout; in JSON mode goes to stderr.
@@ -22,56 +31,209 @@ fn log_alert(json: bool, msg: &str) {
     }
 }
 
-/// One-shot scan: connect to TWS, run scanner, enrich, print results.
+/// What `cmd_scan` runs and how it reports.
+#[derive(Debug)]
+pub struct ScanOptions<'a> {
+    /// Scanner code or alias ("list" prints the scanner parameters).
+    pub code: &'a str,
+    pub rows: u32,
+    pub filters: &'a ScanFilters,
+    pub criteria: &'a MomentumCriteria,
+    /// Scanners in the active scanner set, which scanner hits score against.
+    pub scanner_total: usize,
+    /// List results failing any pillar separately instead of in the table.
+    pub momentum_only: bool,
+}
+
+/// One-shot scan: connect to TWS, run scanner, enrich through the
+/// configured providers, print results. Each result is scored against the
+/// criteria. Filters beyond price/volume/market cap are first checked
+/// against the scanner parameters TWS advertises.
 pub async fn cmd_scan(
-    code: &str,
+    opts: &ScanOptions<'_>,
     host: &str,
     port: Option<u16>,
-    rows: u32,
-    min_price: f64,
-    max_price: Option<f64>,
+    enrichment: &EnrichmentConfig,
 ) -> Result<()> {
+    let ScanOptions { code, rows, filters, criteria, scanner_total, momentum_only } = *opts;
     let scanner_code = resolve_scanner(code);
     let ports: Vec<u16> = port
         .map(|p| vec![p])
         .unwrap_or_else(|| DEFAULT_PORTS.to_vec());
+    let conn = Arc::new(ConnectionManager::new(host, &ports));
 
     if code.to_lowercase() == "list" {
-        match tws::fetch_scanner_params(host, &ports, 3).await {
-            Some(xml) => tws::print_scanner_params(&xml, None),
-            None => eprintln!("Could not connect to TWS"),
+        match params::load_or_fetch(&conn, false).await {
+            Ok(cached) => tws::print_scanner_params(&cached.params, None),
+            Err(e) => eprintln!("{e}"),
         }
         return Ok(());
     }
 
+    if filters.needs_validation() {
+        match params::load_or_fetch(&conn, false).await {
+            Ok(cached) => cached.params.validate(Some(&scanner_code), filters)?,
+            Err(e) => eprintln!("{e}, filters not validated"),
+        }
+    }
+
     let (mut results, _port) =
-        tws::run_scan(&scanner_code, host, &ports, 1, rows, Some(min_price), max_price).await;
+        tws::run_scan(&conn, &scanner_code, rows, filters).await;
 
     if !results.is_empty() {
-        println!("Enriching with Yahoo Finance...");
-        enrichment::enrich_results(&mut results).await;
+        let chain = ProviderChain::from_config(enrichment, Arc::new(TwsSource::new(conn)))?;
+        println!("Enriching via {}...", chain.describe());
+        enrichment::enrich_results(&chain, &mut results).await;
     }
 
-    scanner::print_results(&results);
+    scanner::score_results(&mut results, criteria, scanner_total);
+    scanner::sort_by_score(&mut results);
+    if momentum_only {
+        scanner::print_results(&scanner::filter_momentum(&results, criteria));
+    } else {
+        scanner::print_results(&results);
+    }
+    if !results.is_empty() {
+        scanner::print_exclusions(&results, criteria);
+    }
+    Ok(())
+}
+
+/// What `scanner list` shows from the scanner parameters.
+#[derive(Debug, Default)]
+pub struct ListOptions<'a> {
+    /// Category group to expand (fuzzy match).
+    pub group: Option<&'a str>,
+    /// Search scan types by code or display name.
+    pub search: Option<&'a str>,
+    /// Show the filters a scan type supports.
+    pub filters: Option<&'a str>,
+    /// Print the whole parameters model as JSON.
+    pub json: bool,
+    /// Refetch from TWS even if the cache is fresh.
+    pub refresh: bool,
+}
+
+/// Output format of `cmd_bars`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum BarsFormat {
+    #[default]
+    Table,
+    Csv,
+    Json,
+}
+
+impl std::str::FromStr for BarsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(Self::Table),
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("expected table, csv or json, got '{s}'")),
+        }
+    }
+}
+
+/// Print historical bars, from the disk cache when fresh.
+pub async fn cmd_bars(
+    req: &BarRequest,
+    format: BarsFormat,
+    refresh: bool,
+    host: &str,
+    port: Option<u16>,
+) -> Result<()> {
+    let ports: Vec<u16> = port.into_iter().collect();
+    let conn = ConnectionManager::new(host, &ports);
+    let bars = bars::fetch_bars(&conn, req, refresh).await?;
+
+    let lines = match format {
+        BarsFormat::Table => {
+            println!(
+                "{} -- {} x {} bars over {}{}",
+                req.symbol,
+                bars.len(),
+                req.bar_size,
+                req.duration,
+                if req.rth { " (RTH)" } else { "" }
+            );
+            scanner::bar_table(&bars)
+        }
+        BarsFormat::Csv => scanner::bar_csv(&bars),
+        BarsFormat::Json => vec![serde_json::to_string_pretty(&bars)?],
+    };
+    for line in lines {
+        println!("{line}");
+    }
     Ok(())
 }
 
-/// Fetch and print scanner parameters / groups.
-pub async fn cmd_list(group: Option<&str>, host: &str, port: Option<u16>) -> Result<()> {
+/// Print scanner parameters / groups, from the disk cache when fresh.
+pub async fn cmd_list(opts: &ListOptions<'_>, host: &str, port: Option<u16>) -> Result<()> {
     let ports: Vec<u16> = port
         .map(|p| vec![p])
         .unwrap_or_else(|| DEFAULT_PORTS.to_vec());
-    match tws::fetch_scanner_params(host, &ports, 3).await {
-        Some(xml) => tws::print_scanner_params(&xml, group),
-        None => eprintln!("Could not connect to TWS"),
+    let conn = ConnectionManager::new(host, &ports);
+    let cached = params::load_or_fetch(&conn, opts.refresh).await?;
+
+    if opts.json {
+        println!("{}", serde_json::to_string_pretty(&cached)?);
+    } else if let Some(query) = opts.search {
+        print_scan_search(&cached.params, query);
+    } else if let Some(code) = opts.filters {
+        print_scan_filters(&cached.params, &resolve_scanner(code))?;
+    } else {
+        tws::print_scanner_params(&cached.params, opts.group);
+        eprintln!("(scanner parameters fetched {})", cached.fetched_at.format("%Y-%m-%d %H:%M UTC"));
     }
     Ok(())
 }
 
-/// Query and print Supabase tws_scans history.
-pub async fn cmd_history(what: Option<&str>) -> Result<()> {
-    let config = SupabaseConfig::from_env()?;
-    let db = SupabaseClient::new(config);
+fn print_scan_search(params: &ScannerParams, query: &str) {
+    let found = params.search(query);
+    if found.is_empty() {
+        println!("No scan type matching '{query}'");
+        return;
+    }
+    println!("{:<30}  {:<40}  Instruments", "Scanner Code", "Description");
+    println!("{}", "-".repeat(90));
+    for s in found {
+        println!("{:<30}  {:<40}  {}", s.code, s.display_name, s.instruments.join(","));
+    }
+}
+
+fn print_scan_filters(params: &ScannerParams, code: &str) -> Result<()> {
+    let Some(scan) = params.scan_type(code) else {
+        anyhow::bail!("unknown scan code '{code}' (try `scanner list --search`)");
+    };
+    let filters = params.filters_for_scan(code);
+    println!("{} ({}) -- {} filters", scan.code, scan.display_name, filters.len());
+    println!("{:<28}  {:<14}  {:<14}  Description", "Tag", "Type", "Category");
+    println!("{}", "-".repeat(90));
+    for f in filters {
+        for field in &f.fields {
+            println!(
+                "{:<28}  {:<14}  {:<14}  {}",
+                field.code, field.field_type, f.category, field.display_name
+            );
+            if !field.values.is_empty() {
+                let values: Vec<&str> = field.values.iter().map(|(c, _)| c.as_str()).collect();
+                println!("{:<28}  values: {}", "", values.join(", "));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Query and print tws_scans history from the configured store.
+pub async fn cmd_history(
+    what: Option<&str>,
+    symbol: Option<&str>,
+    date: Option<chrono::NaiveDate>,
+    config: &HistoryConfig,
+) -> Result<()> {
+    let db = history::open_store(config)?;
 
     match what {
         Some("clear") => {
@@ -86,40 +248,145 @@ pub async fn cmd_history(what: Option<&str>) -> Result<()> {
             let stocks = db.get_today().await?;
             history::print_history(&stocks, "Today");
         }
+        Some("trajectory") => {
+            let Some(symbol) = symbol else {
+                eprintln!("Usage: scanner history trajectory SYMBOL [--date YYYY-MM-DD]");
+                return Ok(());
+            };
+            let symbol = symbol.to_uppercase();
+            let date = date.unwrap_or_else(crate::session::today);
+            let sightings = db.get_trajectory(&symbol, date).await?;
+            history::print_trajectory(&symbol, date, &sightings);
+            let halts = db.get_halts(&symbol, date).await?;
+            history::print_halts(&halts);
+        }
         Some(n) => {
             if let Ok(limit) = n.parse::<u32>() {
                 let stocks = db.get_history(limit).await?;
                 history::print_history(&stocks, &format!("Last {limit}"));
             } else {
-                eprintln!("Usage: scanner history [today|all|clear|N]");
+                eprintln!("Usage: scanner history [today|all|clear|trajectory SYMBOL|N]");
             }
         }
     }
     Ok(())
 }
 
-/// Enrich symbols with Yahoo Finance data and print results.
-pub async fn cmd_enrich(symbols: &[String]) -> Result<()> {
+/// Show the history store's schema version, or apply pending migrations.
+pub async fn cmd_db(what: Option<&str>, print: bool, config: &HistoryConfig) -> Result<()> {
+    let latest = history::migrations::latest();
+
+    if print {
+        if what != Some("migrate") {
+            eprintln!("Usage: scanner db migrate --print");
+            return Ok(());
+        }
+        // Printing works without a reachable store: assume nothing is applied
+        let current = match history::open_store(config) {
+            Ok(db) => db.schema_version().await.unwrap_or(0),
+            Err(_) => 0,
+        };
+        for m in history::migrations::pending(current) {
+            println!("-- {}", m.file_name());
+            println!("{}", m.sql(config.backend));
+        }
+        return Ok(());
+    }
+
+    let db = history::open_store(config)?;
+    match what {
+        Some("status") | None => {
+            let current = db.schema_version().await?;
+            match config.backend {
+                HistoryBackend::Sqlite => {
+                    println!("History: sqlite ({})", config.path.display())
+                }
+                HistoryBackend::Supabase => println!("History: supabase"),
+            }
+            println!("Schema version: {current} (latest {latest})");
+            for m in history::migrations::MIGRATIONS {
+                let state = if m.version <= current { "applied" } else { "pending" };
+                println!("  {state:<8} {}", m.file_name());
+            }
+            if current < latest {
+                println!("Run `scanner db migrate` to apply pending migrations.");
+            }
+        }
+        Some("migrate") => {
+            let applied = db.migrate().await?;
+            if applied.is_empty() {
+                println!("Schema is up to date (version {latest})");
+            } else {
+                for m in history::migrations::MIGRATIONS {
+                    if applied.contains(&m.version) {
+                        println!("Applied {}", m.file_name());
+                    }
+                }
+                println!("Schema is at version {latest}");
+            }
+        }
+        Some(_) => eprintln!("Usage: scanner db [status|migrate [--print]]"),
+    }
+    Ok(())
+}
+
+/// Refuse to start the engine against an out-of-date history schema.
+/// An unreachable store is not an error here; the engine runs without history.
+pub fn check_history_schema(config: &HistoryConfig) -> Result<()> {
+    let Ok(db) = history::open_store(config) else {
+        return Ok(());
+    };
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(history::ensure_schema(db.as_ref()))
+}
+
+/// Enrich symbols through the configured providers and print results,
+/// with the source of each field. The IB provider connects to TWS on
+/// `host`/`port`.
+pub async fn cmd_enrich(
+    symbols: &[String],
+    host: &str,
+    port: Option<u16>,
+    config: &EnrichmentConfig,
+) -> Result<()> {
     if symbols.is_empty() {
         eprintln!("Usage: scanner enrich AAPL TSLA ...");
         return Ok(());
     }
 
-    let client = reqwest::Client::new();
+    let ports: Vec<u16> = port
+        .map(|p| vec![p])
+        .unwrap_or_else(|| DEFAULT_PORTS.to_vec());
+    let conn = Arc::new(ConnectionManager::new(host, &ports));
+    let chain = ProviderChain::from_config(config, Arc::new(TwsSource::new(conn)))?;
     for sym in symbols {
-        println!("Enriching {sym}...");
-        let data = enrichment::fetch_enrichment(&client, sym).await;
+        println!("Enriching {sym} via {}...", chain.describe());
+        let data = chain.fetch(sym).await;
         println!("  Name:        {}", data.name.as_deref().unwrap_or("-"));
         println!("  Sector:      {}", data.sector.as_deref().unwrap_or("-"));
         println!(
             "  Industry:    {}",
             data.industry.as_deref().unwrap_or("-")
         );
+        let source = |field| {
+            data.provenance
+                .get(field)
+                .map(|s| format!(" ({s})"))
+                .unwrap_or_default()
+        };
         println!(
-            "  Float:       {}",
+            "  Float:       {}{}",
             data.float_shares
                 .map(|f| format!("{:.1}M", f / 1e6))
-                .unwrap_or("-".into())
+                .unwrap_or("-".into()),
+            source(enrichment::Field::Float)
+        );
+        println!(
+            "  Shares Out:  {}{}",
+            data.shares_outstanding
+                .map(|f| format!("{:.1}M", f / 1e6))
+                .unwrap_or("-".into()),
+            source(enrichment::Field::SharesOutstanding)
         );
         println!(
             "  Short%:      {}",
@@ -143,6 +410,9 @@ pub async fn cmd_enrich(symbols: &[String]) -> Result<()> {
             "  Catalyst:    {}",
             data.catalyst.as_deref().unwrap_or("none")
         );
+        if let (Some(kind), Some(sentiment)) = (data.catalyst_kind, data.catalyst_sentiment) {
+            println!("               {}, {}", kind.label(), sentiment.label());
+        }
         if let Some(ct) = data.catalyst_time {
             println!("               {}", format_time_ago(ct));
         }
@@ -150,20 +420,33 @@ pub async fn cmd_enrich(symbols: &[String]) -> Result<()> {
             println!("  Headlines:");
             for h in data.news_headlines.iter().take(5) {
                 let ago = h.published.map(format_time_ago).unwrap_or_default();
+                let source = h.source.as_deref().map(|s| format!(" ({s})")).unwrap_or_default();
                 if ago.is_empty() {
-                    println!("    > {}", h.title);
+                    println!("    > {}{source}", h.title);
                 } else {
-                    println!("    > {} — \"{}\"", ago, h.title);
+                    println!("    > {} — \"{}\"{source}", ago, h.title);
                 }
             }
         }
+        if !data.filings.is_empty() {
+            println!("  Filings:");
+            for f in data.filings.iter().take(5) {
+                let ago = f.filed.map(|t| format!("{} — ", format_time_ago(t))).unwrap_or_default();
+                println!("    > {ago}{}", f.headline());
+            }
+        }
+        println!(
+            "  Sources:     {}",
+            data.provenance.summary().unwrap_or("none".into())
+        );
         println!();
     }
     Ok(())
 }
 
 /// Cross-check volume: fetch 5-min bars from IB historical data, sum volumes,
-/// and compare with the snapshot tick Volume value.
+/// and compare with the snapshot tick Volume value. Both are normalized to
+/// shares, so the ratio should be close to 1.
 pub async fn cmd_volume(symbols: &[String], host: &str, port: Option<u16>) -> Result<()> {
     if symbols.is_empty() {
         eprintln!("Usage: scanner volume LCUT AAPL ...");
@@ -174,28 +457,22 @@ pub async fn cmd_volume(symbols: &[String], host: &str, port: Option<u16>) -> Re
         .map(|p| vec![p])
         .unwrap_or_else(|| DEFAULT_PORTS.to_vec());
 
+    let conn = ConnectionManager::new(host, &ports);
+
     for sym in symbols {
         println!("=== {sym} ===");
-        match tws::fetch_volume_check(sym, host, &ports).await {
+        match tws::fetch_volume_check(sym, &conn).await {
             Ok((bar_sum, tick_vol, bars)) => {
-                println!(
-                    "  5-min bar volume sum : {:.0}  ({})",
-                    bar_sum,
-                    format_vol(bar_sum as i64)
-                );
+                println!("  5-min bar volume sum : {}  ({bar_sum})", bar_sum.get());
                 match tick_vol {
-                    Some(v) => println!(
-                        "  Tick Volume (×100)   : {}  ({})",
-                        v,
-                        format_vol(v)
-                    ),
-                    None => println!("  Tick Volume (×100)   : (none)"),
+                    Some(v) => println!("  Tick Volume          : {}  ({v})", v.get()),
+                    None => println!("  Tick Volume          : (none)"),
                 }
-                if let Some(v) = tick_vol {
-                    if bar_sum > 0.0 {
-                        let ratio = v as f64 / bar_sum;
-                        println!("  Ratio (tick / bars)  : {ratio:.4}x");
-                    }
+                if let Some(v) = tick_vol
+                    && bar_sum.get() > 0
+                {
+                    let ratio = v.as_f64() / bar_sum.as_f64();
+                    println!("  Ratio (tick / bars)  : {ratio:.4}x");
                 }
                 println!("  Bars: {}", bars.len());
                 // Show last 5 bars
@@ -204,8 +481,8 @@ pub async fn cmd_volume(symbols: &[String], host: &str, port: Option<u16>) -> Re
                 } else {
                     &bars
                 };
-                for (ts, close, vol) in show {
-                    println!("    {ts}  close={close:.2}  vol={vol:.0}");
+                for bar in show {
+                    println!("    {}  close={:.2}  vol={:.0}", bar.time, bar.close, bar.volume);
                 }
             }
             Err(e) => {
@@ -217,17 +494,6 @@ pub async fn cmd_volume(symbols: &[String], host: &str, port: Option<u16>) -> Re
     Ok(())
 }
 
-fn format_vol(v: i64) -> String {
-    let shares = v as f64 * 100.0;
-    if shares >= 1_000_000.0 {
-        format!("{:.1}M", shares / 1_000_000.0)
-    } else if shares >= 1_000.0 {
-        format!("{:.1}K", shares / 1_000.0)
-    } else {
-        format!("{:.0}", shares)
-    }
-}
-
 /// Format a Unix epoch timestamp as a relative "time ago" string.
 fn format_time_ago(epoch: i64) -> String {
     let now = chrono::Utc::now().timestamp();
@@ -250,7 +516,7 @@ fn format_time_ago(epoch: i64) -> String {
 }
 
 /// Print configuration.
-pub fn cmd_config() {
+pub fn cmd_config(scanner_config: &ScannerConfig) {
     println!("Configuration:");
     println!(
         "  SUPABASE_URL = {}",
@@ -265,79 +531,120 @@ pub fn cmd_config() {
         }
     );
     println!("  Default ports: {:?}", DEFAULT_PORTS);
+    println!("  Config file: {}", ScannerConfig::path().display());
+    match scanner_config.history.backend {
+        HistoryBackend::Sqlite => println!(
+            "  History: sqlite ({})",
+            scanner_config.history.path.display()
+        ),
+        HistoryBackend::Supabase => println!("  History: supabase"),
+    }
+    println!("  Enrichment: {}", scanner_config.enrichment.describe());
+    println!("  Criteria profiles (default: {}):", scanner_config.default_criteria);
+    for c in scanner_config.criteria.values() {
+        println!("    {:<12}  {}", c.name, c.describe());
+    }
+    println!("  Scanner sets (default: {}):", scanner_config.default_scanner_set);
+    for set in scanner_config.scanner_sets.values() {
+        println!("    {:<12}  {}", set.name, set.describe());
+    }
+}
+
+/// How `run_alert` runs a session.
+#[derive(Debug, Clone, Default)]
+pub struct AlertOptions {
+    pub host: String,
+    pub port: Option<u16>,
+    /// Print alerts as JSON lines.
+    pub json: bool,
+    pub criteria: MomentumCriteria,
+    pub scanner_set: ScannerSet,
+    pub feed: Feed,
+    /// Record every market input to this session file.
+    pub record: Option<PathBuf>,
+    /// Providers for the TWS feed's enrichment worker.
+    pub enrichment: EnrichmentConfig,
 }
 
 /// Headless alert streamer — polls TWS scanners and prints alerts to stdout.
-pub fn run_alert(host: &str, port: Option<u16>, json: bool) -> Result<()> {
+///
+/// With `Feed::Replay` nothing is polled: the recorded inputs drive the
+/// engine and the session ends when the recording does.
+pub fn run_alert(opts: AlertOptions, history_config: &HistoryConfig) -> Result<()> {
+    let AlertOptions { host, port, json, criteria, scanner_set, feed, record, enrichment } = opts;
     let rt = tokio::runtime::Runtime::new()?;
     let handle = rt.handle().clone();
 
-    // Setup Supabase
+    // Setup history store; simulated and replayed sessions keep theirs in memory
     crate::config::load_env();
-    let db = if let Ok(config) = SupabaseConfig::from_env() {
-        Some(SupabaseClient::new(config))
-    } else {
-        None
+    let store = match feed {
+        Feed::Tws => history::open_store(history_config),
+        Feed::Sim(_) | Feed::Replay { .. } => history::open_memory_store(),
+    };
+    let db = match store {
+        Ok(db) => {
+            handle.block_on(history::ensure_schema(db.as_ref()))?;
+            Some(db)
+        }
+        Err(e) => {
+            log_alert(json, &format!("History store unavailable: {e}"));
+            None
+        }
     };
 
     // Create enrich channel, then engine, then spawn worker
     let (enrich_tx, enrich_rx) = mpsc::channel();
 
     let mut settings = Settings::default();
-    settings.host = host.to_string();
+    settings.host = host;
     settings.port = port;
+    settings.criteria = criteria;
+    settings.scanner_set = scanner_set;
 
     let mut engine = AlertEngine::new(enrich_tx, settings, db);
+    log_alert(json, &format!("Market data: {}", feed.describe()));
 
-    // Spawn enrichment worker with Supabase cache support
-    let _worker = crate::engine::spawn_enrichment_worker(
-        engine.bg_tx.clone(),
-        enrich_rx,
-        handle.clone(),
-        engine.db.clone(),
-    );
-
-    // Spawn market data streaming worker
-    let (mktdata_tx, mktdata_rx) = mpsc::channel::<crate::engine::MktDataRequest>();
-    let mktdata_host = engine.settings.host.clone();
-    let mktdata_ports: Vec<u16> = engine.settings.port
-        .map(|p| vec![p])
-        .unwrap_or_else(|| DEFAULT_PORTS.to_vec());
-    let _mktdata_worker = crate::engine::spawn_market_data_worker(
-        engine.bg_tx.clone(),
-        mktdata_rx,
-        mktdata_host,
-        mktdata_ports,
-    );
-    engine.mktdata_tx = Some(mktdata_tx);
-
-    let ports_desc = engine.settings.port
-        .map(|p| format!("{p}"))
-        .unwrap_or_else(|| format!("{:?}", DEFAULT_PORTS));
-    log_alert(json, &format!("Probing TWS on ports {ports_desc}..."));
-
-    // Probe TWS port
-    engine.probe_port();
-    if let Some(p) = engine.connected_port {
-        log_alert(json, &format!("TWS connected on port {p}"));
-    } else {
-        log_alert(json, "TWS unavailable, alerts will be empty");
-    }
-
-    // Initialize from tws_scans
-    log_alert(json, "Loading today's tws_scans from Supabase...");
-    let (loaded, needs_enrich) = engine.init_from_tws_scans(&handle);
-    log_alert(json, &format!("Loaded {loaded} stocks from tws_scans, {needs_enrich} queued for enrichment"));
+    let replay = match feed {
+        Feed::Replay { recording, speed } => {
+            // Enrichment and ticks come from the recording too
+            drop(enrich_rx);
+            Some(recording::spawn_replay(recording, speed, engine.bg_tx.clone()))
+        }
+        Feed::Sim(config) => {
+            let sim = Simulator::new(config);
+            engine = engine.with_source(Arc::new(sim.clone()));
+            let _worker =
+                crate::engine::spawn_sim_enrichment_worker(engine.bg_tx.clone(), enrich_rx, sim);
+            start_live_feed(&mut engine, &handle, json)?;
+            None
+        }
+        Feed::Tws => {
+            // Spawn enrichment worker with history-store cache support
+            let providers = ProviderChain::from_config(&enrichment, Arc::clone(&engine.source))?;
+            log_alert(json, &format!("Enrichment: {}", providers.describe()));
+            let _worker = crate::engine::spawn_enrichment_worker(
+                engine.bg_tx.clone(),
+                enrich_rx,
+                handle.clone(),
+                engine.db.clone(),
+                providers,
+            );
+            start_live_feed(&mut engine, &handle, json)?;
+            None
+        }
+    };
 
-    // Subscribe existing alert rows to streaming market data
-    let existing_syms: Vec<String> = engine.alert_rows.iter().map(|r| r.symbol.clone()).collect();
-    for sym in &existing_syms {
-        engine.subscribe_market_data(sym, "USD");
+    if let Some(path) = record {
+        let header = recording::Header::new(engine.source.name(), &engine.settings.scanner_set.name);
+        engine.recorder = Some(recording::Recorder::create(&path, &header)?);
+        log_alert(json, &format!("Recording session to {}", path.display()));
     }
 
-    // Start polling
-    engine.poll_on();
-    log_alert(json, "Starting poll (8 scanners, 15s cycle). Ctrl+C to stop.");
+    log_alert(json, &format!(
+        "Criteria '{}': {}",
+        engine.settings.criteria.name,
+        engine.settings.criteria.describe()
+    ));
 
     // Setup Ctrl+C handler
     let running = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
@@ -347,6 +654,8 @@ pub fn run_alert(host: &str, port: Option<u16>, json: bool) -> Result<()> {
     let mut poll_timer = std::time::Instant::now();
 
     while running.load(std::sync::atomic::Ordering::Relaxed) {
+        // Checked before the tick so it drains the replay's last events
+        let replay_done = replay.as_ref().is_some_and(|h| h.is_finished());
         let events = engine.tick(&handle);
         for event in events {
             match event {
@@ -380,12 +689,14 @@ pub fn run_alert(host: &str, port: Option<u16>, json: bool) -> Result<()> {
                                     .map(|p| format!("{p:.2}"))
                                     .unwrap_or("-".into());
                                 println!(
-                                    "[{}] [ALERT] {:<6}  ${:>7}  {:>8}  {}/8 scanners",
+                                    "[{}] [ALERT] {:<6}  ${:>7}  {:>8}  {}/{} scanners  score {:.0}",
                                     row.alert_time,
                                     row.symbol,
                                     price,
                                     chg,
                                     row.scanner_hits,
+                                    engine.settings.scanner_set.len(),
+                                    row.score,
                                 );
                             }
                         }
@@ -411,12 +722,14 @@ pub fn run_alert(host: &str, port: Option<u16>, json: bool) -> Result<()> {
                                 .map(|p| format!("{p:.2}"))
                                 .unwrap_or("-".into());
                             println!(
-                                "[{}] [ALERT] {:<6}  ${:>7}  {:>8}  {}/8 scanners",
+                                "[{}] [ALERT] {:<6}  ${:>7}  {:>8}  {}/{} scanners  score {:.0}",
                                 row.alert_time,
                                 row.symbol,
                                 price,
                                 chg,
                                 row.scanner_hits,
+                                engine.settings.scanner_set.len(),
+                                row.score,
                             );
 
                             // Fundamentals card
@@ -452,14 +765,40 @@ pub fn run_alert(host: &str, port: Option<u16>, json: bool) -> Result<()> {
                                 "           Float: {}  |  Short: {}  |  RVol: {}",
                                 float, short, rvol
                             );
+                            let q = &row.quote;
+                            let spread = q
+                                .spread_pct
+                                .map(|p| format!("{p:.2}%"))
+                                .unwrap_or("-".into());
+                            let halted = q.halted.map_or("-", |h| h.label());
+                            println!(
+                                "           Spread: {}  |  Size: {} x {}  |  Status: {}",
+                                spread,
+                                crate::scanner::fmt_volume(q.bid_size),
+                                crate::scanner::fmt_volume(q.ask_size),
+                                halted
+                            );
+                            println!(
+                                "           Borrow: {}  |  SSR: {}",
+                                crate::scanner::fmt_borrow(&row.short),
+                                crate::scanner::fmt_ssr(row.short.ssr),
+                            );
+                            println!(
+                                "           Pillars: {}",
+                                row.pillars.summary()
+                            );
 
                             // Catalyst with time
                             if let Some(ref cat) = row.catalyst {
                                 let cat_ago = row.catalyst_time
                                     .map(|t| format!("{} — ", format_time_ago(t)))
                                     .unwrap_or_default();
+                                let tag = match (row.catalyst_kind, row.catalyst_sentiment) {
+                                    (Some(k), Some(s)) => format!(" [{}, {}]", k.label(), s.label()),
+                                    _ => String::new(),
+                                };
                                 println!(
-                                    "           Catalyst: {cat_ago}\"{cat}\""
+                                    "           Catalyst: {cat_ago}\"{cat}\"{tag}"
                                 );
                             }
 
@@ -470,18 +809,119 @@ pub fn run_alert(host: &str, port: Option<u16>, json: bool) -> Result<()> {
                                     let ago = h.published
                                         .map(|t| format!("{} — ", format_time_ago(t)))
                                         .unwrap_or_default();
+                                    let source = h.source
+                                        .as_deref()
+                                        .map(|s| format!(" ({s})"))
+                                        .unwrap_or_default();
                                     println!(
-                                        "             > {ago}\"{}\"",
+                                        "             > {ago}\"{}\"{source}",
                                         h.title
                                     );
                                 }
                             }
+
+                            // Filings
+                            if !row.filings.is_empty() {
+                                println!("           Filings:");
+                                for f in row.filings.iter().take(3) {
+                                    let ago = f.filed
+                                        .map(|t| format!("{} — ", format_time_ago(t)))
+                                        .unwrap_or_default();
+                                    println!("             > {ago}{}", f.headline());
+                                }
+                            }
                         }
                     }
                 }
                 EngineEvent::PortDiscovered { port } => {
                     log_alert(json, &format!("TWS port discovered: {port}"));
                 }
+                EngineEvent::ConnectionChanged { role, ref state } => {
+                    log_alert(json, &format!("TWS {}: {state}", role.label()));
+                }
+                EngineEvent::CriteriaMatch { ref symbol, ref criteria } => {
+                    if let Some(row) =
+                        engine.alert_rows.iter().find(|r| r.symbol == *symbol)
+                    {
+                        if json {
+                            println!(
+                                "{}",
+                                serde_json::to_string(row).unwrap_or_default()
+                            );
+                        } else {
+                            let ts = chrono::Local::now().format("%H:%M:%S");
+                            println!(
+                                "[{ts}] [MOMENTUM] {:<6}  passes all {} pillars of '{criteria}'",
+                                row.symbol,
+                                row.pillars.passed.len(),
+                            );
+                        }
+                    }
+                }
+                EngineEvent::Halted { ref symbol, state, at, price } => {
+                    if json {
+                        let event = serde_json::json!({
+                            "event": "halted",
+                            "symbol": symbol,
+                            "state": state,
+                            "at": at,
+                            "halt_price": price,
+                        });
+                        println!("{event}");
+                    } else {
+                        let ts = at.with_timezone(&chrono::Local).format("%H:%M:%S");
+                        println!(
+                            "[{ts}] [HALT] {:<6}  {} at ${}",
+                            symbol,
+                            state.label(),
+                            crate::scanner::fmt_price(price),
+                        );
+                    }
+                }
+                EngineEvent::NewsTick { ref symbol, ref headline } => {
+                    if json {
+                        let event = serde_json::json!({
+                            "event": "news",
+                            "symbol": symbol,
+                            "headline": headline,
+                        });
+                        println!("{event}");
+                    } else {
+                        let ts = chrono::Local::now().format("%H:%M:%S");
+                        println!(
+                            "[{ts}] [NEWS] {:<6}  \"{}\" ({})",
+                            symbol,
+                            headline.title,
+                            headline.source.as_deref().unwrap_or("-"),
+                        );
+                    }
+                }
+                EngineEvent::Resumed { ref symbol, at, halt_price, duration } => {
+                    if json {
+                        let event = serde_json::json!({
+                            "event": "resumed",
+                            "symbol": symbol,
+                            "at": at,
+                            "halt_price": halt_price,
+                            "duration_secs": duration.num_seconds(),
+                        });
+                        println!("{event}");
+                    } else {
+                        let ts = at.with_timezone(&chrono::Local).format("%H:%M:%S");
+                        let last = engine
+                            .alert_rows
+                            .iter()
+                            .find(|r| r.symbol == *symbol)
+                            .and_then(|r| r.last);
+                        println!(
+                            "[{ts}] [RESUME] {:<6}  after {}  halted at ${}, last ${}",
+                            symbol,
+                            crate::scanner::fmt_halt_duration(duration.num_seconds()),
+                            crate::scanner::fmt_price(halt_price),
+                            crate::scanner::fmt_price(last),
+                        );
+                    }
+                }
                 _ => {}
             }
         }
@@ -496,14 +936,175 @@ pub fn run_alert(host: &str, port: Option<u16>, json: bool) -> Result<()> {
             engine.run_poll_scanners();
         }
 
+        if replay_done {
+            log_alert(json, "Replay finished");
+            break;
+        }
         std::thread::sleep(Duration::from_millis(100));
     }
 
+    if let Some(recorder) = &engine.recorder {
+        log_alert(json, &format!(
+            "Recorded {} events to {}",
+            recorder.events(),
+            recorder.path().display()
+        ));
+    }
     let alert_count = engine.alert_rows.len();
     log_alert(json, &format!("Shutting down (seen {} stocks, {} alerts)", engine.alert_seen.len(), alert_count));
     Ok(())
 }
 
+/// What `scanner backtest` evaluates.
+#[derive(Debug)]
+pub struct BacktestOptions<'a> {
+    pub from: chrono::NaiveDate,
+    pub to: chrono::NaiveDate,
+    pub criteria: &'a MomentumCriteria,
+    /// Scanner set the sightings were polled with (hits are scored against it).
+    pub scanner_set: &'a ScannerSet,
+    /// Only count entries scoring at least this much.
+    pub min_score: Option<f64>,
+    /// Print every outcome and the summary as JSON.
+    pub json: bool,
+}
+
+/// Backtest a criteria profile over recorded sightings, with forward
+/// returns from TWS historical bars.
+pub async fn cmd_backtest(
+    opts: &BacktestOptions<'_>,
+    host: &str,
+    port: Option<u16>,
+    config: &HistoryConfig,
+) -> Result<()> {
+    let db = history::open_store(config)?;
+    let ports: Vec<u16> = port.into_iter().collect();
+    let source = TwsSource::new(Arc::new(ConnectionManager::new(host, &ports)));
+
+    if !opts.json {
+        println!(
+            "Backtest {}..{}, criteria '{}': {}",
+            opts.from,
+            opts.to,
+            opts.criteria.name,
+            opts.criteria.describe()
+        );
+    }
+    let run = backtest::run(
+        db.as_ref(),
+        &source,
+        opts.from,
+        opts.to,
+        opts.criteria,
+        opts.scanner_set.len(),
+        opts.min_score,
+    )
+    .await?;
+    let summary = backtest::summarize(&run.outcomes);
+
+    if opts.json {
+        let out = serde_json::json!({ "summary": summary, "outcomes": run.outcomes, "skipped": run.skipped });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} entries, {} skipped (no sightings, below --min-score, or no bars)",
+        run.outcomes.len(),
+        run.skipped
+    );
+    if run.outcomes.is_empty() {
+        return Ok(());
+    }
+    println!("Returns in percent, avg/median:");
+    print!("{:<28} {:>5} {:>5}", "Config", "N", "Hit%");
+    for label in backtest::HORIZON_LABELS {
+        print!(" {label:>13}");
+    }
+    println!(" {:>8} {:>8}", "MAE avg", "MAE max");
+
+    let pct = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{v:+.1}"));
+    for s in &summary {
+        print!(
+            "{:<28} {:>5} {:>5}",
+            scanner::truncate(&s.config, 28),
+            s.entries,
+            s.hit_rate.map_or("-".to_string(), |h| format!("{h:.0}%"))
+        );
+        for (avg, med) in s.avg.iter().zip(&s.median) {
+            print!(" {:>13}", format!("{}/{}", pct(*avg), pct(*med)));
+        }
+        println!(" {:>8} {:>8}", pct(s.avg_mae), pct(s.worst_mae));
+    }
+    Ok(())
+}
+
+/// Connect a live (TWS or simulated) alert session: stream market data,
+/// probe the feed, load today's sightings and start polling.
+fn start_live_feed(
+    engine: &mut AlertEngine,
+    handle: &tokio::runtime::Handle,
+    json: bool,
+) -> Result<()> {
+    let simulated = engine.source.name() == "sim";
+
+    // Spawn market data streaming worker
+    let (mktdata_tx, mktdata_rx) = mpsc::channel::<crate::engine::MktDataRequest>();
+    let _mktdata_worker = crate::engine::spawn_market_data_worker(
+        engine.bg_tx.clone(),
+        mktdata_rx,
+        engine.feed(),
+    );
+    engine.mktdata_tx = Some(mktdata_tx);
+    let _news_worker = crate::engine::spawn_news_worker(engine.bg_tx.clone(), engine.feed());
+
+    if !simulated {
+        let ports_desc = engine.settings.port
+            .map(|p| format!("{p}"))
+            .unwrap_or_else(|| format!("{:?}", DEFAULT_PORTS));
+        log_alert(json, &format!("Probing TWS on ports {ports_desc}..."));
+    }
+
+    // Probe TWS port
+    engine.probe_port();
+    if !simulated {
+        if let Some(p) = engine.connected_port {
+            log_alert(json, &format!("TWS connected on port {p}"));
+        } else {
+            log_alert(json, "TWS unavailable, alerts will be empty");
+        }
+    }
+
+    // Check the scanner set against what TWS advertises before polling it
+    if !simulated && engine.connected_port.is_some() {
+        match handle.block_on(params::load_or_fetch(&engine.tws, false)) {
+            Ok(cached) => cached.params.validate_set(&engine.settings.scanner_set)?,
+            Err(e) => log_alert(json, &format!("{e}, scanner set not validated")),
+        }
+    }
+
+    // Initialize from tws_scans
+    let backend = engine.db.as_ref().map_or("no store", |db| db.backend());
+    log_alert(json, &format!("Loading today's tws_scans from {backend}..."));
+    let (loaded, needs_enrich) = engine.init_from_tws_scans(handle);
+    log_alert(json, &format!("Loaded {loaded} stocks from tws_scans, {needs_enrich} queued for enrichment"));
+
+    // Subscribe existing alert rows to streaming market data
+    let existing_syms: Vec<String> = engine.alert_rows.iter().map(|r| r.symbol.clone()).collect();
+    for sym in &existing_syms {
+        engine.subscribe_market_data(sym, "USD");
+    }
+
+    // Start polling
+    engine.poll_on();
+    log_alert(json, &format!(
+        "Starting poll (set '{}': {}, 15s cycle). Ctrl+C to stop.",
+        engine.settings.scanner_set.name,
+        engine.settings.scanner_set.describe()
+    ));
+    Ok(())
+}
+
 /// Set an atomic flag to false on Ctrl+C.
 fn ctrlc_flag(flag: &std::sync::Arc<std::sync::atomic::AtomicBool>) {
     let f = flag.clone();
@@ -519,6 +1120,6 @@ mod tests {
     #[test]
     fn test_cmd_config_no_panic() {
         // Just ensure it doesn't panic
-        cmd_config();
+        cmd_config(&ScannerConfig::default());
     }
 }
@@ -1,4 +1,12 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
 use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::criteria::{MomentumCriteria, DEFAULT_CRITERIA};
+use crate::enrichment::Field;
+use crate::scanner_set::{ScannerSet, DEFAULT_SCANNER_SET};
 
 #[derive(Debug, Clone)]
 pub struct SupabaseConfig {
@@ -17,6 +25,309 @@ impl SupabaseConfig {
     }
 }
 
+/// Default config file, relative to the working directory.
+pub const CONFIG_FILE: &str = "scanner.toml";
+
+/// Settings loaded from `scanner.toml` (or the file named by `SCANNER_CONFIG`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScannerConfig {
+    /// Criteria profile used when none is selected on the command line.
+    #[serde(default = "default_criteria_name")]
+    pub default_criteria: String,
+    /// Momentum criteria profiles keyed by name.
+    #[serde(default)]
+    pub criteria: BTreeMap<String, MomentumCriteria>,
+    /// Scanner set polled when none is selected on the command line.
+    #[serde(default = "default_scanner_set_name")]
+    pub default_scanner_set: String,
+    /// Scanner sets keyed by name.
+    #[serde(default)]
+    pub scanner_sets: BTreeMap<String, ScannerSet>,
+    /// Sightings history storage (`[history]` table).
+    #[serde(default)]
+    pub history: HistoryConfig,
+    /// Enrichment providers (`[enrichment]` table).
+    #[serde(default)]
+    pub enrichment: EnrichmentConfig,
+}
+
+/// Which persistence backend holds the sightings history.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryBackend {
+    /// Supabase REST (needs SUPABASE_URL / SUPABASE_ANON_KEY).
+    #[default]
+    Supabase,
+    /// Embedded SQLite file, works offline.
+    Sqlite,
+}
+
+impl HistoryBackend {
+    pub fn label(self) -> &'static str {
+        match self {
+            HistoryBackend::Supabase => "supabase",
+            HistoryBackend::Sqlite => "sqlite",
+        }
+    }
+}
+
+/// Default SQLite history file.
+pub const DEFAULT_HISTORY_DB: &str = "var/history.db";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HistoryConfig {
+    #[serde(default)]
+    pub backend: HistoryBackend,
+    /// SQLite database file (ignored by the Supabase backend).
+    #[serde(default = "default_history_path")]
+    pub path: PathBuf,
+}
+
+fn default_history_path() -> PathBuf {
+    PathBuf::from(DEFAULT_HISTORY_DB)
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            backend: HistoryBackend::default(),
+            path: default_history_path(),
+        }
+    }
+}
+
+/// A source of fundamentals and news for `enrichment::ProviderChain`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    /// Yahoo Finance quoteSummary, RSS and search.
+    Yahoo,
+    /// IB fundamental ratios over TWS (shares outstanding).
+    Ib,
+    /// JSON file of symbol -> enrichment data, works offline.
+    Fixture,
+    /// SEC EDGAR filings (offerings, 8-Ks).
+    Edgar,
+}
+
+impl ProviderKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ProviderKind::Yahoo => "yahoo",
+            ProviderKind::Ib => "ib",
+            ProviderKind::Fixture => "fixture",
+            ProviderKind::Edgar => "edgar",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnrichmentConfig {
+    /// Providers queried for every symbol, in order of preference.
+    #[serde(default = "default_providers")]
+    pub providers: Vec<ProviderKind>,
+    /// Per-field provider order (`[enrichment.fields]`); fields not listed
+    /// use `providers`.
+    #[serde(default)]
+    pub fields: BTreeMap<Field, Vec<ProviderKind>>,
+    /// File read by the fixture provider.
+    pub fixture_path: Option<PathBuf>,
+    /// The EDGAR provider's endpoint and identity (`[enrichment.edgar]`).
+    #[serde(default)]
+    pub edgar: EdgarConfig,
+}
+
+/// SEC EDGAR settings. The SEC asks for a User-Agent naming the requester
+/// with a contact address, and blocks requests without one.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct EdgarConfig {
+    /// Serve `files/company_tickers.json` and `submissions/CIK*.json` from
+    /// here instead of www.sec.gov and data.sec.gov, e.g. a local fixture
+    /// server.
+    pub base_url: Option<String>,
+    /// e.g. "Acme Research ops@acme.test"; required when the provider is
+    /// enabled (see `EnrichmentConfig::validate`).
+    pub user_agent: String,
+    /// Filings older than this are ignored.
+    pub lookback_days: u32,
+}
+
+impl Default for EdgarConfig {
+    fn default() -> Self {
+        Self {
+            base_url: None,
+            user_agent: String::new(),
+            lookback_days: 30,
+        }
+    }
+}
+
+fn default_providers() -> Vec<ProviderKind> {
+    vec![ProviderKind::Yahoo, ProviderKind::Ib]
+}
+
+impl Default for EnrichmentConfig {
+    fn default() -> Self {
+        Self {
+            providers: default_providers(),
+            fields: BTreeMap::new(),
+            fixture_path: None,
+            edgar: EdgarConfig::default(),
+        }
+    }
+}
+
+impl EnrichmentConfig {
+    /// Providers and per-field orders, e.g. "yahoo, ib (float: ib > yahoo)".
+    pub fn describe(&self) -> String {
+        let names = |kinds: &[ProviderKind], sep: &str| {
+            kinds.iter().map(|k| k.label()).collect::<Vec<_>>().join(sep)
+        };
+        let mut out = if self.providers.is_empty() {
+            "none".to_string()
+        } else {
+            names(&self.providers, ", ")
+        };
+        if !self.fields.is_empty() {
+            let routes: Vec<String> = self
+                .fields
+                .iter()
+                .map(|(field, kinds)| format!("{}: {}", field.label(), names(kinds, " > ")))
+                .collect();
+            out.push_str(&format!(" ({})", routes.join(", ")));
+        }
+        out
+    }
+
+    /// Per-field orders may only name configured providers, the fixture
+    /// provider needs its file and the EDGAR provider a User-Agent with a
+    /// contact address.
+    pub fn validate(&self) -> Result<()> {
+        for (field, kinds) in &self.fields {
+            if let Some(kind) = kinds.iter().find(|k| !self.providers.contains(k)) {
+                anyhow::bail!(
+                    "enrichment field '{}' uses provider '{}', which is not in enrichment.providers",
+                    field.label(),
+                    kind.label()
+                );
+            }
+        }
+        if self.providers.contains(&ProviderKind::Fixture) && self.fixture_path.is_none() {
+            anyhow::bail!("the fixture enrichment provider needs enrichment.fixture_path");
+        }
+        if self.providers.contains(&ProviderKind::Edgar) && !self.edgar.user_agent.contains('@') {
+            anyhow::bail!(
+                "the edgar enrichment provider needs enrichment.edgar.user_agent with a contact \
+                 address, e.g. \"Acme Research ops@acme.test\"; the SEC blocks requests without one"
+            );
+        }
+        Ok(())
+    }
+}
+
+fn default_criteria_name() -> String {
+    DEFAULT_CRITERIA.to_string()
+}
+
+fn default_scanner_set_name() -> String {
+    DEFAULT_SCANNER_SET.to_string()
+}
+
+impl Default for ScannerConfig {
+    fn default() -> Self {
+        Self::from_toml("").expect("empty config is valid")
+    }
+}
+
+impl ScannerConfig {
+    /// Path of the config file: `SCANNER_CONFIG` if set, else `scanner.toml`.
+    pub fn path() -> PathBuf {
+        std::env::var("SCANNER_CONFIG")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(CONFIG_FILE))
+    }
+
+    /// Load the config file. A missing file yields the built-in defaults.
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::path())
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Self::from_toml(&text).with_context(|| format!("invalid config in {}", path.display()))
+    }
+
+    /// Parse config text. The built-in `momentum` profile and `default`
+    /// scanner set are always present unless the file overrides them.
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let mut config: ScannerConfig = toml::from_str(text)?;
+        config
+            .criteria
+            .entry(DEFAULT_CRITERIA.to_string())
+            .or_default();
+        for (name, c) in config.criteria.iter_mut() {
+            c.name = name.clone();
+        }
+        if !config.criteria.contains_key(&config.default_criteria) {
+            anyhow::bail!(
+                "default_criteria '{}' is not a defined profile",
+                config.default_criteria
+            );
+        }
+        config
+            .scanner_sets
+            .entry(DEFAULT_SCANNER_SET.to_string())
+            .or_default();
+        for (name, set) in config.scanner_sets.iter_mut() {
+            set.name = name.clone();
+            set.validate()?;
+        }
+        if !config.scanner_sets.contains_key(&config.default_scanner_set) {
+            anyhow::bail!(
+                "default_scanner_set '{}' is not a defined scanner set",
+                config.default_scanner_set
+            );
+        }
+        config.enrichment.validate()?;
+        Ok(config)
+    }
+
+    /// Look up a criteria profile by name, or the default profile if `None`.
+    pub fn criteria(&self, name: Option<&str>) -> Result<MomentumCriteria> {
+        let name = name.unwrap_or(&self.default_criteria);
+        self.criteria.get(name).cloned().with_context(|| {
+            format!(
+                "unknown criteria profile '{name}' (available: {})",
+                self.criteria_names().join(", ")
+            )
+        })
+    }
+
+    pub fn criteria_names(&self) -> Vec<&str> {
+        self.criteria.keys().map(|k| k.as_str()).collect()
+    }
+
+    /// Look up a scanner set by name, or the default set if `None`.
+    pub fn scanner_set(&self, name: Option<&str>) -> Result<ScannerSet> {
+        let name = name.unwrap_or(&self.default_scanner_set);
+        self.scanner_sets.get(name).cloned().with_context(|| {
+            format!(
+                "unknown scanner set '{name}' (available: {})",
+                self.scanner_set_names().join(", ")
+            )
+        })
+    }
+
+    pub fn scanner_set_names(&self) -> Vec<&str> {
+        self.scanner_sets.keys().map(|k| k.as_str()).collect()
+    }
+}
+
 /// Load .env file from the project root or current directory.
 pub fn load_env() {
     // Try project root first (where Cargo.toml lives), then cwd
@@ -33,6 +344,155 @@ mod tests {
         load_env();
     }
 
+    #[test]
+    fn test_scanner_config_default_has_momentum() {
+        let config = ScannerConfig::default();
+        assert_eq!(config.default_criteria, "momentum");
+        let c = config.criteria(None).unwrap();
+        assert_eq!(c.name, "momentum");
+        assert_eq!(c, MomentumCriteria::default());
+    }
+
+    #[test]
+    fn test_scanner_config_profiles() {
+        let config = ScannerConfig::from_toml(
+            r#"
+            default_criteria = "runners"
+
+            [criteria.runners]
+            min_price = 1.0
+            max_price = 5.0
+            min_change_pct = 20.0
+            max_float = 5_000_000
+            require_catalyst = false
+            pass_if_unknown = ["float"]
+            "#,
+        )
+        .unwrap();
+        let c = config.criteria(None).unwrap();
+        assert_eq!(c.name, "runners");
+        assert_eq!(c.max_price, Some(5.0));
+        assert!(c.min_rvol.is_none());
+        assert!(!c.require_catalyst);
+        assert_eq!(config.criteria_names(), vec!["momentum", "runners"]);
+    }
+
+    #[test]
+    fn test_scanner_config_unknown_profile() {
+        let config = ScannerConfig::default();
+        let err = config.criteria(Some("nope")).unwrap_err().to_string();
+        assert!(err.contains("available: momentum"));
+        assert!(ScannerConfig::from_toml("default_criteria = \"nope\"").is_err());
+    }
+
+    #[test]
+    fn test_scanner_sets() {
+        let config = ScannerConfig::from_toml(
+            r#"
+            default_scanner_set = "gappers"
+
+            [scanner_sets.gappers]
+            codes = ["HIGH_OPEN_GAP", "TOP_PERC_GAIN"]
+            rows = 25
+            max_price = 10.0
+            stock_type = "CORP"
+
+            [scanner_sets.gappers.tags]
+            floatSharesBelow = "20000000"
+
+            [scanner_sets.gappers.filters.TOP_PERC_GAIN]
+            changePercAbove = "10"
+            "#,
+        )
+        .unwrap();
+        let set = config.scanner_set(None).unwrap();
+        assert_eq!(set.name, "gappers");
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.rows, 25);
+        assert_eq!(set.scan.min_price, None);
+        assert_eq!(set.scan.location, "STK.US.MAJOR");
+        assert_eq!(set.scan.stock_type.as_deref(), Some("CORP"));
+        assert_eq!(set.filters_for("TOP_PERC_GAIN").len(), 2);
+        assert_eq!(set.filters_for("HIGH_OPEN_GAP").len(), 1);
+        assert_eq!(config.scanner_set_names(), vec!["default", "gappers"]);
+        assert_eq!(config.scanner_set(Some("default")).unwrap().len(), 8);
+
+        let err = config.scanner_set(Some("nope")).unwrap_err().to_string();
+        assert!(err.contains("available: default, gappers"));
+        assert!(ScannerConfig::from_toml("[scanner_sets.empty]
+codes = []
+").is_err());
+        assert!(ScannerConfig::from_toml("default_scanner_set = \"nope\"").is_err());
+    }
+
+    #[test]
+    fn test_history_backend_config() {
+        let config = ScannerConfig::default();
+        assert_eq!(config.history.backend, HistoryBackend::Supabase);
+
+        let config = ScannerConfig::from_toml("[history]\nbackend = \"sqlite\"\n").unwrap();
+        assert_eq!(config.history.backend, HistoryBackend::Sqlite);
+        assert_eq!(config.history.path, PathBuf::from(DEFAULT_HISTORY_DB));
+
+        assert!(ScannerConfig::from_toml("[history]\nbackend = \"mysql\"\n").is_err());
+    }
+
+    #[test]
+    fn test_enrichment_config() {
+        let config = ScannerConfig::default();
+        assert_eq!(config.enrichment.providers, vec![ProviderKind::Yahoo, ProviderKind::Ib]);
+        assert!(config.enrichment.fields.is_empty());
+
+        let config = ScannerConfig::from_toml(
+            r#"
+            [enrichment]
+            providers = ["yahoo", "fixture"]
+            fixture_path = "var/enrichment.json"
+
+            [enrichment.fields]
+            float = ["fixture", "yahoo"]
+            news = ["fixture"]
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.enrichment.fields[&Field::Float],
+            vec![ProviderKind::Fixture, ProviderKind::Yahoo]
+        );
+        assert_eq!(config.enrichment.fields[&Field::News], vec![ProviderKind::Fixture]);
+        assert_eq!(
+            config.enrichment.describe(),
+            "yahoo, fixture (float: fixture > yahoo, news: fixture)"
+        );
+
+        // Routes must name configured providers; the fixture needs a file
+        assert!(ScannerConfig::from_toml("[enrichment.fields]\nsector = [\"fixture\"]\n").is_err());
+        assert!(ScannerConfig::from_toml("[enrichment]\nproviders = [\"fixture\"]\n").is_err());
+        assert!(ScannerConfig::from_toml("[enrichment.fields]\nbeta = [\"yahoo\"]\n").is_err());
+
+        let config = ScannerConfig::from_toml(
+            r#"
+            [enrichment]
+            providers = ["yahoo", "edgar"]
+
+            [enrichment.edgar]
+            base_url = "http://127.0.0.1:8000"
+            user_agent = "Acme Research ops@acme.test"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.enrichment.providers[1], ProviderKind::Edgar);
+        assert_eq!(config.enrichment.edgar.base_url.as_deref(), Some("http://127.0.0.1:8000"));
+        assert_eq!(config.enrichment.edgar.lookback_days, 30);
+
+        // EDGAR needs a User-Agent with a contact address
+        assert!(ScannerConfig::from_toml("[enrichment]\nproviders = [\"edgar\"]\n").is_err());
+        assert!(ScannerConfig::from_toml(
+            "[enrichment]\nproviders = [\"edgar\"]\n[enrichment.edgar]\nuser_agent = \"scanner-rs/0.1\"\n"
+        )
+        .is_err());
+    }
+
     // Note: testing the missing-vars error case would require mutating global env vars
     // (unsafe in Rust 2024 due to multi-threaded test runner races). Omitted intentionally;
     // the error path is trivially correct from reading the code.
@@ -0,0 +1,731 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::catalyst::Sentiment;
+use crate::models::{AlertRow, ScanResult, ALERT_SCANNERS};
+use crate::quote::Quote;
+use crate::short::Borrow;
+use crate::units::Shares;
+
+/// A single momentum pillar that a stock can pass or fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Pillar {
+    Price,
+    Change,
+    RVol,
+    Float,
+    Short,
+    Catalyst,
+    Spread,
+    Size,
+    Halted,
+    Borrow,
+    Ssr,
+}
+
+impl Pillar {
+    pub fn label(self) -> &'static str {
+        match self {
+            Pillar::Price => "price",
+            Pillar::Change => "change",
+            Pillar::RVol => "rvol",
+            Pillar::Float => "float",
+            Pillar::Short => "short",
+            Pillar::Catalyst => "catalyst",
+            Pillar::Spread => "spread",
+            Pillar::Size => "size",
+            Pillar::Halted => "halted",
+            Pillar::Borrow => "borrow",
+            Pillar::Ssr => "ssr",
+        }
+    }
+}
+
+impl fmt::Display for Pillar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Which pillars a row passed and failed under a criteria profile.
+/// Pillars disabled in the profile appear in neither list.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PillarReport {
+    pub passed: Vec<Pillar>,
+    pub failed: Vec<Pillar>,
+}
+
+impl PillarReport {
+    /// True when no enabled pillar failed.
+    pub fn passes_all(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Short summary, e.g. "4/5 (failed: float)".
+    pub fn summary(&self) -> String {
+        let total = self.passed.len() + self.failed.len();
+        if self.failed.is_empty() {
+            format!("{}/{total}", self.passed.len())
+        } else {
+            let failed: Vec<&str> = self.failed.iter().map(|p| p.label()).collect();
+            format!("{}/{total} (failed: {})", self.passed.len(), failed.join(","))
+        }
+    }
+
+    fn record(&mut self, pillar: Pillar, outcome: Option<bool>, unknown_passes: bool) {
+        if outcome.unwrap_or(unknown_passes) {
+            self.passed.push(pillar);
+        } else {
+            self.failed.push(pillar);
+        }
+    }
+}
+
+/// The data a criteria profile looks at, extracted from a scan result or alert row.
+#[derive(Debug, Clone, Default)]
+pub struct MomentumInputs {
+    pub price: Option<f64>,
+    pub change_pct: Option<f64>,
+    pub rvol: Option<f64>,
+    pub float_shares: Option<f64>,
+    pub short_pct: Option<f64>,
+    /// None until enrichment has had a chance to look for news. A bearish
+    /// catalyst (see `catalyst_state`) is no catalyst.
+    pub has_catalyst: Option<bool>,
+    /// Publish time of the catalyst headline (Unix epoch seconds).
+    pub catalyst_time: Option<i64>,
+    pub scanner_hits: u32,
+    /// Scanners polled per cycle; `scanner_hits` is scored against this.
+    pub scanner_total: u32,
+    /// Bid/ask spread in percent of the midpoint.
+    pub spread_pct: Option<f64>,
+    /// The smaller of bid and ask size.
+    pub quote_size: Option<Shares>,
+    pub halted: Option<bool>,
+    pub borrow: Option<Borrow>,
+    /// Shares available to short.
+    pub shortable_shares: Option<Shares>,
+    /// Short-sale restriction triggered today.
+    pub ssr: Option<bool>,
+}
+
+impl From<&ScanResult> for MomentumInputs {
+    fn from(r: &ScanResult) -> Self {
+        let mut quote = Quote {
+            bid: r.bid,
+            ask: r.ask,
+            bid_size: r.bid_size,
+            ask_size: r.ask_size,
+            halted: r.halted,
+            ..Default::default()
+        };
+        quote.refresh();
+        Self {
+            price: r.last,
+            change_pct: r.change_pct,
+            rvol: r.rvol,
+            float_shares: r.float_shares,
+            short_pct: r.short_pct,
+            has_catalyst: catalyst_state(r.catalyst.is_some(), r.catalyst_sentiment, r.enriched),
+            catalyst_time: None,
+            // A one-shot scan result was seen by exactly one scanner
+            scanner_hits: 1,
+            scanner_total: ALERT_SCANNERS.len() as u32,
+            spread_pct: quote.spread_pct,
+            quote_size: quote.size(),
+            halted: quote.is_halted(),
+            // Snapshots cannot request the shortable ticks
+            borrow: None,
+            shortable_shares: None,
+            ssr: None,
+        }
+    }
+}
+
+impl From<&AlertRow> for MomentumInputs {
+    fn from(r: &AlertRow) -> Self {
+        Self {
+            price: r.last,
+            change_pct: r.change_pct,
+            rvol: r.rvol,
+            float_shares: r.float_shares,
+            short_pct: r.short_pct,
+            has_catalyst: catalyst_state(r.catalyst.is_some(), r.catalyst_sentiment, r.enriched),
+            catalyst_time: r.catalyst_time,
+            scanner_hits: r.scanner_hits,
+            scanner_total: ALERT_SCANNERS.len() as u32,
+            spread_pct: r.quote.spread_pct,
+            quote_size: r.quote.size(),
+            halted: r.quote.is_halted(),
+            borrow: r.short.borrow,
+            shortable_shares: r.short.shortable_shares,
+            ssr: r.short.ssr,
+        }
+    }
+}
+
+impl MomentumInputs {
+    /// Score scanner hits against a scanner set of `total` scanners.
+    pub fn with_scanner_total(mut self, total: usize) -> Self {
+        self.scanner_total = total as u32;
+        self
+    }
+}
+
+/// A bearish catalyst (dilution, a failed trial, a downgrade) explains a
+/// move but is no reason to be long it, so it fails the catalyst pillar and
+/// earns no catalyst score rather than passing with full credit.
+pub fn catalyst_state(has_catalyst: bool, sentiment: Option<Sentiment>, enriched: bool) -> Option<bool> {
+    if has_catalyst && sentiment != Some(Sentiment::Bearish) {
+        Some(true)
+    } else if enriched || has_catalyst {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+// Composite score weights (sum to 100).
+const W_PRICE: f64 = 10.0;
+const W_CHANGE: f64 = 20.0;
+const W_RVOL: f64 = 20.0;
+const W_FLOAT: f64 = 15.0;
+const W_SHORT: f64 = 5.0;
+const W_CATALYST: f64 = 15.0;
+const W_HITS: f64 = 15.0;
+
+// Full change and RVOL credit at these multiples of the profile's minimums
+const CHANGE_TARGET: f64 = 5.0;
+const RVOL_TARGET: f64 = 2.0;
+// Float credit runs from this fraction of the profile's float limit (full)
+// to its inverse multiple (none), on a log scale
+const FLOAT_SPAN: f64 = 10.0;
+
+/// Composite momentum score in 0..=100, higher is stronger.
+///
+/// Each pillar contributes a 0..=1 component scaled by its weight, so a
+/// stock that narrowly misses one threshold still ranks above one that
+/// misses everything. Targets follow `criteria`: full price credit inside
+/// its price band, full change and RVOL credit at a multiple of its
+/// minimums, and float credit scaled around its float limit. A threshold
+/// the profile leaves unset falls back to the classic profile's, except
+/// that an open price bound doesn't taper. `now` is Unix epoch seconds,
+/// used for catalyst freshness.
+pub fn momentum_score(m: &MomentumInputs, criteria: &MomentumCriteria, now: i64) -> f64 {
+    let positive = |t: Option<f64>| t.filter(|t| *t > 0.0);
+    // Full credit inside the band, tapering off either side
+    let price = m.price.map_or(0.0, |p| {
+        if p <= 0.0 {
+            0.0
+        } else if let Some(lo) = positive(criteria.min_price).filter(|lo| p < *lo) {
+            p / lo
+        } else if let Some(hi) = positive(criteria.max_price).filter(|hi| p > *hi) {
+            hi / p
+        } else {
+            1.0
+        }
+    });
+    // Red days score nothing
+    let min_change = positive(criteria.min_change_pct).unwrap_or(CLASSIC_MIN_CHANGE_PCT);
+    let change = m.change_pct.map_or(0.0, |c| (c / (min_change * CHANGE_TARGET)).clamp(0.0, 1.0));
+    let min_rvol = positive(criteria.min_rvol).unwrap_or(CLASSIC_MIN_RVOL);
+    let rvol = m.rvol.map_or(0.0, |r| (r / (min_rvol * RVOL_TARGET)).clamp(0.0, 1.0));
+    // Unknown is neutral
+    let max_float = positive(criteria.max_float).unwrap_or(CLASSIC_MAX_FLOAT);
+    let float = m.float_shares.map_or(0.5, |f| {
+        let full = max_float / FLOAT_SPAN;
+        if f <= full {
+            1.0
+        } else {
+            (1.0 - (f / full).log(FLOAT_SPAN) / 2.0).clamp(0.0, 1.0)
+        }
+    });
+    // Short interest of 30%, or no shares to borrow, is full credit
+    let short_interest = m.short_pct.map_or(0.0, |s| (s / 0.3).clamp(0.0, 1.0));
+    let borrow = match m.borrow {
+        Some(Borrow::Unavailable) => 1.0,
+        Some(Borrow::Hard) => 0.5,
+        _ => 0.0,
+    };
+    let short = short_interest.max(borrow);
+    // Halves every 24h; an undated catalyst gets half credit
+    let catalyst = match (m.has_catalyst, m.catalyst_time) {
+        (Some(true), Some(t)) => {
+            let age_hours = (now - t).max(0) as f64 / 3600.0;
+            0.5f64.powf(age_hours / 24.0)
+        }
+        (Some(true), None) => 0.5,
+        _ => 0.0,
+    };
+    let hits = (m.scanner_hits as f64 / m.scanner_total.max(1) as f64).min(1.0);
+
+    price * W_PRICE
+        + change * W_CHANGE
+        + rvol * W_RVOL
+        + float * W_FLOAT
+        + short * W_SHORT
+        + catalyst * W_CATALYST
+        + hits * W_HITS
+}
+
+/// A strategy profile: thresholds for each momentum pillar.
+///
+/// A pillar whose threshold is unset is not evaluated. `pass_if_unknown`
+/// lists pillars that pass when their input is missing (e.g. no float data
+/// from Yahoo); every other enabled pillar fails on missing data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MomentumCriteria {
+    /// Profile name (the table key in `scanner.toml`).
+    #[serde(skip)]
+    pub name: String,
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    /// Minimum change from prior close, in percent.
+    pub min_change_pct: Option<f64>,
+    pub min_rvol: Option<f64>,
+    /// Float must be strictly below this many shares.
+    pub max_float: Option<f64>,
+    /// Minimum short interest, in percent of float.
+    pub min_short_pct: Option<f64>,
+    #[serde(default)]
+    pub require_catalyst: bool,
+    /// Spread must be at most this, in percent of the midpoint.
+    pub max_spread_pct: Option<f64>,
+    /// Minimum size on the thinner side of the quote, in shares.
+    pub min_quote_size: Option<Shares>,
+    #[serde(default)]
+    pub exclude_halted: bool,
+    /// At most this many shares available to short; a stock that is not
+    /// shortable passes.
+    pub max_shortable_shares: Option<Shares>,
+    #[serde(default)]
+    pub exclude_ssr: bool,
+    #[serde(default)]
+    pub pass_if_unknown: Vec<Pillar>,
+}
+
+/// Name of the built-in profile.
+pub const DEFAULT_CRITERIA: &str = "momentum";
+
+// Thresholds of the classic profile
+const CLASSIC_MIN_CHANGE_PCT: f64 = 10.0;
+const CLASSIC_MIN_RVOL: f64 = 5.0;
+const CLASSIC_MAX_FLOAT: f64 = 10_000_000.0;
+
+impl Default for MomentumCriteria {
+    /// The classic 5 pillars: $1-$20, change >= 10%, RVol >= 5x,
+    /// float < 10M (unknown passes), has news catalyst.
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_CRITERIA.to_string(),
+            min_price: Some(1.0),
+            max_price: Some(20.0),
+            min_change_pct: Some(CLASSIC_MIN_CHANGE_PCT),
+            min_rvol: Some(CLASSIC_MIN_RVOL),
+            max_float: Some(CLASSIC_MAX_FLOAT),
+            min_short_pct: None,
+            require_catalyst: true,
+            max_spread_pct: None,
+            min_quote_size: None,
+            exclude_halted: false,
+            max_shortable_shares: None,
+            exclude_ssr: false,
+            pass_if_unknown: vec![Pillar::Float],
+        }
+    }
+}
+
+impl MomentumCriteria {
+    /// Evaluate every enabled pillar against the inputs.
+    pub fn evaluate(&self, m: &MomentumInputs) -> PillarReport {
+        let mut report = PillarReport::default();
+
+        if self.min_price.is_some() || self.max_price.is_some() {
+            let outcome = m.price.map(|p| {
+                self.min_price.is_none_or(|lo| p >= lo) && self.max_price.is_none_or(|hi| p <= hi)
+            });
+            report.record(Pillar::Price, outcome, self.unknown_passes(Pillar::Price));
+        }
+        if let Some(min) = self.min_change_pct {
+            let outcome = m.change_pct.map(|c| c >= min);
+            report.record(Pillar::Change, outcome, self.unknown_passes(Pillar::Change));
+        }
+        if let Some(min) = self.min_rvol {
+            let outcome = m.rvol.map(|r| r >= min);
+            report.record(Pillar::RVol, outcome, self.unknown_passes(Pillar::RVol));
+        }
+        if let Some(max) = self.max_float {
+            let outcome = m.float_shares.map(|f| f < max);
+            report.record(Pillar::Float, outcome, self.unknown_passes(Pillar::Float));
+        }
+        if let Some(min) = self.min_short_pct {
+            // short_pct is a fraction of float (0.15 = 15%)
+            let outcome = m.short_pct.map(|s| s * 100.0 >= min);
+            report.record(Pillar::Short, outcome, self.unknown_passes(Pillar::Short));
+        }
+        if self.require_catalyst {
+            report.record(
+                Pillar::Catalyst,
+                m.has_catalyst,
+                self.unknown_passes(Pillar::Catalyst),
+            );
+        }
+        if let Some(max) = self.max_spread_pct {
+            let outcome = m.spread_pct.map(|s| s <= max);
+            report.record(Pillar::Spread, outcome, self.unknown_passes(Pillar::Spread));
+        }
+        if let Some(min) = self.min_quote_size {
+            let outcome = m.quote_size.map(|s| s >= min);
+            report.record(Pillar::Size, outcome, self.unknown_passes(Pillar::Size));
+        }
+        if self.exclude_halted {
+            let outcome = m.halted.map(|h| !h);
+            report.record(Pillar::Halted, outcome, self.unknown_passes(Pillar::Halted));
+        }
+        if let Some(max) = self.max_shortable_shares {
+            let outcome = match (m.borrow, m.shortable_shares) {
+                (Some(Borrow::Unavailable), _) => Some(true),
+                (_, shares) => shares.map(|s| s <= max),
+            };
+            report.record(Pillar::Borrow, outcome, self.unknown_passes(Pillar::Borrow));
+        }
+        if self.exclude_ssr {
+            let outcome = m.ssr.map(|s| !s);
+            report.record(Pillar::Ssr, outcome, self.unknown_passes(Pillar::Ssr));
+        }
+
+        report
+    }
+
+    /// True if the inputs pass every enabled pillar.
+    pub fn passes(&self, m: &MomentumInputs) -> bool {
+        self.evaluate(m).passes_all()
+    }
+
+    fn unknown_passes(&self, pillar: Pillar) -> bool {
+        self.pass_if_unknown.contains(&pillar)
+    }
+
+    /// Human-readable list of enabled pillars.
+    pub fn describe(&self) -> String {
+        let unknown = |p: Pillar| if self.unknown_passes(p) { " (unknown ok)" } else { "" };
+        let mut parts = Vec::new();
+        match (self.min_price, self.max_price) {
+            (Some(lo), Some(hi)) => parts.push(format!("price ${lo}-${hi}{}", unknown(Pillar::Price))),
+            (Some(lo), None) => parts.push(format!("price >= ${lo}{}", unknown(Pillar::Price))),
+            (None, Some(hi)) => parts.push(format!("price <= ${hi}{}", unknown(Pillar::Price))),
+            (None, None) => {}
+        }
+        if let Some(c) = self.min_change_pct {
+            parts.push(format!("chg >= {c}%{}", unknown(Pillar::Change)));
+        }
+        if let Some(r) = self.min_rvol {
+            parts.push(format!("rvol >= {r}x{}", unknown(Pillar::RVol)));
+        }
+        if let Some(f) = self.max_float {
+            parts.push(format!("float < {:.1}M{}", f / 1e6, unknown(Pillar::Float)));
+        }
+        if let Some(s) = self.min_short_pct {
+            parts.push(format!("short >= {s}%{}", unknown(Pillar::Short)));
+        }
+        if self.require_catalyst {
+            parts.push(format!("catalyst{}", unknown(Pillar::Catalyst)));
+        }
+        if let Some(s) = self.max_spread_pct {
+            parts.push(format!("spread <= {s}%{}", unknown(Pillar::Spread)));
+        }
+        if let Some(s) = self.min_quote_size {
+            parts.push(format!("size >= {s}{}", unknown(Pillar::Size)));
+        }
+        if self.exclude_halted {
+            parts.push(format!("not halted{}", unknown(Pillar::Halted)));
+        }
+        if let Some(s) = self.max_shortable_shares {
+            parts.push(format!("borrow <= {s}{}", unknown(Pillar::Borrow)));
+        }
+        if self.exclude_ssr {
+            parts.push(format!("no SSR{}", unknown(Pillar::Ssr)));
+        }
+        if parts.is_empty() {
+            "no pillars".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(price: f64, chg: f64, rvol: f64) -> MomentumInputs {
+        MomentumInputs {
+            price: Some(price),
+            change_pct: Some(chg),
+            rvol: Some(rvol),
+            float_shares: Some(5_000_000.0),
+            short_pct: Some(0.25),
+            has_catalyst: Some(true),
+            catalyst_time: None,
+            scanner_hits: 4,
+            scanner_total: 8,
+            spread_pct: Some(0.5),
+            quote_size: Some(Shares(2_000)),
+            halted: Some(false),
+            borrow: Some(Borrow::Easy),
+            shortable_shares: Some(Shares(1_000_000)),
+            ssr: Some(false),
+        }
+    }
+
+    #[test]
+    fn test_default_is_classic_five_pillars() {
+        let c = MomentumCriteria::default();
+        let report = c.evaluate(&inputs(5.0, 15.0, 6.0));
+        assert_eq!(report.passed.len(), 5);
+        assert!(report.passes_all());
+        assert_eq!(report.summary(), "5/5");
+    }
+
+    #[test]
+    fn test_report_lists_failed_pillars() {
+        let c = MomentumCriteria::default();
+        let mut m = inputs(25.0, 15.0, 3.0);
+        m.float_shares = Some(50_000_000.0);
+        let report = c.evaluate(&m);
+        assert_eq!(report.failed, vec![Pillar::Price, Pillar::RVol, Pillar::Float]);
+        assert_eq!(report.summary(), "2/5 (failed: price,rvol,float)");
+    }
+
+    #[test]
+    fn test_disabled_pillars_not_evaluated() {
+        let c = MomentumCriteria {
+            name: "gappers".to_string(),
+            min_price: Some(20.0),
+            max_price: None,
+            min_change_pct: Some(4.0),
+            min_rvol: None,
+            max_float: None,
+            min_short_pct: None,
+            require_catalyst: false,
+            max_spread_pct: None,
+            min_quote_size: None,
+            exclude_halted: false,
+            max_shortable_shares: None,
+            exclude_ssr: false,
+            pass_if_unknown: vec![],
+        };
+        let report = c.evaluate(&inputs(150.0, 5.0, 0.5));
+        assert_eq!(report.passed, vec![Pillar::Price, Pillar::Change]);
+        assert!(report.failed.is_empty());
+    }
+
+    #[test]
+    fn test_missing_data_policy() {
+        let mut c = MomentumCriteria::default();
+        let mut m = inputs(5.0, 15.0, 6.0);
+        m.float_shares = None;
+        m.has_catalyst = None;
+        assert_eq!(c.evaluate(&m).failed, vec![Pillar::Catalyst]);
+
+        c.pass_if_unknown = vec![Pillar::Catalyst];
+        assert_eq!(c.evaluate(&m).failed, vec![Pillar::Float]);
+    }
+
+    #[test]
+    fn test_short_pillar_in_percent() {
+        let c = MomentumCriteria {
+            min_short_pct: Some(20.0),
+            ..MomentumCriteria::default()
+        };
+        let mut m = inputs(5.0, 15.0, 6.0);
+        assert!(c.passes(&m));
+        m.short_pct = Some(0.1);
+        assert_eq!(c.evaluate(&m).failed, vec![Pillar::Short]);
+    }
+
+    #[test]
+    fn test_quote_pillars() {
+        let c = MomentumCriteria {
+            max_spread_pct: Some(1.0),
+            min_quote_size: Some(Shares(1_000)),
+            exclude_halted: true,
+            ..MomentumCriteria::default()
+        };
+        let mut m = inputs(5.0, 15.0, 6.0);
+        assert!(c.passes(&m));
+        m.spread_pct = Some(1.5);
+        m.quote_size = Some(Shares(500));
+        m.halted = Some(true);
+        assert_eq!(c.evaluate(&m).failed, vec![Pillar::Spread, Pillar::Size, Pillar::Halted]);
+
+        // From an alert row's quote
+        let mut row = AlertRow {
+            quote: Quote {
+                bid: Some(2.0),
+                ask: Some(2.01),
+                bid_size: Some(Shares(300)),
+                ask_size: Some(Shares(4_000)),
+                halted: Some(crate::quote::HaltState::Volatility),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        row.quote.refresh();
+        let m = MomentumInputs::from(&row);
+        assert!(m.spread_pct.unwrap() < 1.0);
+        assert_eq!(m.quote_size, Some(Shares(300)));
+        assert_eq!(m.halted, Some(true));
+    }
+
+    #[test]
+    fn test_short_pillars() {
+        let c = MomentumCriteria {
+            max_shortable_shares: Some(Shares(50_000)),
+            exclude_ssr: true,
+            ..MomentumCriteria::default()
+        };
+        let mut m = inputs(5.0, 15.0, 6.0);
+        assert_eq!(c.evaluate(&m).failed, vec![Pillar::Borrow]);
+        m.shortable_shares = Some(Shares(20_000));
+        m.ssr = Some(true);
+        assert_eq!(c.evaluate(&m).failed, vec![Pillar::Ssr]);
+        // Nothing to borrow passes whatever the share count
+        m.borrow = Some(Borrow::Unavailable);
+        m.shortable_shares = None;
+        m.ssr = None;
+        assert_eq!(c.evaluate(&m).failed, vec![Pillar::Ssr]);
+
+        // Hard to borrow scores like high short interest
+        let now = 1_700_000_000;
+        let mut easy = inputs(5.0, 15.0, 6.0);
+        easy.short_pct = None;
+        let hard = MomentumInputs { borrow: Some(Borrow::Hard), ..easy.clone() };
+        assert!((momentum_score(&hard, &c, now) - momentum_score(&easy, &c, now) - W_SHORT / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_catalyst_state_from_scan_result() {
+        let mut r = ScanResult::default();
+        assert_eq!(MomentumInputs::from(&r).has_catalyst, None);
+        r.enriched = true;
+        assert_eq!(MomentumInputs::from(&r).has_catalyst, Some(false));
+        r.catalyst = Some("FDA approval".to_string());
+        assert_eq!(MomentumInputs::from(&r).has_catalyst, Some(true));
+
+        // A bearish catalyst is no catalyst
+        r.catalyst = Some("424B5: Prospectus".to_string());
+        r.catalyst_sentiment = Some(Sentiment::Bearish);
+        assert_eq!(MomentumInputs::from(&r).has_catalyst, Some(false));
+        r.enriched = false;
+        assert_eq!(MomentumInputs::from(&r).has_catalyst, Some(false));
+    }
+
+    #[test]
+    fn test_momentum_score_bounds() {
+        let now = 1_700_000_000;
+        let classic = MomentumCriteria::default();
+        assert!(momentum_score(&MomentumInputs::default(), &classic, now) < 10.0);
+
+        let perfect = MomentumInputs {
+            price: Some(5.0),
+            change_pct: Some(80.0),
+            rvol: Some(25.0),
+            float_shares: Some(800_000.0),
+            short_pct: Some(0.4),
+            has_catalyst: Some(true),
+            catalyst_time: Some(now),
+            scanner_hits: 3,
+            scanner_total: 3,
+            ..Default::default()
+        };
+        assert!((momentum_score(&perfect, &classic, now) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_momentum_score_ranks_near_misses() {
+        let now = 1_700_000_000;
+        let classic = MomentumCriteria::default();
+        let strong = inputs(5.0, 30.0, 8.0);
+        // Narrowly misses the rvol pillar but is otherwise strong
+        let near_miss = inputs(5.0, 30.0, 4.5);
+        let weak = inputs(45.0, 2.0, 0.8);
+        let s = momentum_score(&strong, &classic, now);
+        let n = momentum_score(&near_miss, &classic, now);
+        let w = momentum_score(&weak, &classic, now);
+        assert!(s > n && n > w, "{s} > {n} > {w}");
+    }
+
+    #[test]
+    fn test_momentum_score_catalyst_freshness() {
+        let now = 1_700_000_000;
+        let classic = MomentumCriteria::default();
+        let mut fresh = inputs(5.0, 15.0, 6.0);
+        fresh.catalyst_time = Some(now - 3600);
+        let mut stale = fresh.clone();
+        stale.catalyst_time = Some(now - 4 * 86400);
+        let mut none = fresh.clone();
+        none.has_catalyst = Some(false);
+        let (f, s, n) = (
+            momentum_score(&fresh, &classic, now),
+            momentum_score(&stale, &classic, now),
+            momentum_score(&none, &classic, now),
+        );
+        assert!(f > s && s > n, "{f} > {s} > {n}");
+    }
+
+    #[test]
+    fn test_momentum_score_follows_profile() {
+        let now = 1_700_000_000;
+        let classic = MomentumCriteria::default();
+        let sub5 = MomentumCriteria {
+            min_price: Some(1.0),
+            max_price: Some(5.0),
+            min_change_pct: Some(20.0),
+            max_float: Some(5_000_000.0),
+            ..MomentumCriteria::default()
+        };
+        let large_cap = MomentumCriteria {
+            min_price: Some(20.0),
+            max_price: None,
+            min_change_pct: Some(3.0),
+            min_rvol: Some(2.0),
+            max_float: None,
+            ..MomentumCriteria::default()
+        };
+        let cheap = inputs(3.0, 40.0, 6.0);
+        let mid = inputs(15.0, 40.0, 6.0);
+        let big = inputs(150.0, 15.0, 4.0);
+
+        // The classic profile ranks $3 and $15 alike on price
+        assert_eq!(momentum_score(&cheap, &classic, now), momentum_score(&mid, &classic, now));
+        assert!(momentum_score(&cheap, &sub5, now) > momentum_score(&mid, &sub5, now));
+        // A large-cap mover meets its own profile's targets in full
+        assert!(momentum_score(&big, &large_cap, now) > momentum_score(&cheap, &large_cap, now));
+        assert!(momentum_score(&big, &classic, now) < momentum_score(&cheap, &classic, now));
+        // Classic targets are unchanged: +50%, 10x RVOL, 1M float
+        let mut full = inputs(5.0, 50.0, 10.0);
+        full.float_shares = Some(1_000_000.0);
+        let mut over = inputs(5.0, 80.0, 20.0);
+        over.float_shares = Some(500_000.0);
+        assert_eq!(momentum_score(&full, &classic, now), momentum_score(&over, &classic, now));
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            MomentumCriteria::default().describe(),
+            "price $1-$20, chg >= 10%, rvol >= 5x, float < 10.0M (unknown ok), catalyst"
+        );
+        let c = MomentumCriteria {
+            max_spread_pct: Some(1.0),
+            min_quote_size: Some(Shares(500)),
+            exclude_halted: true,
+            pass_if_unknown: vec![Pillar::Halted],
+            ..MomentumCriteria::default()
+        };
+        assert!(c.describe().ends_with("catalyst, spread <= 1%, size >= 500, not halted (unknown ok)"));
+    }
+}
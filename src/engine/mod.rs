@@ -1,13 +1,24 @@
 use std::collections::{BinaryHeap, HashMap, HashSet};
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc};
 use std::time::{Duration, Instant};
 
 use tracing::{info, warn};
 
-use crate::enrichment::{fetch_enrichment_with_auth, fetch_yahoo_auth, EnrichmentData, YahooAuth};
-use crate::history::SupabaseClient;
+use crate::criteria::{momentum_score, MomentumCriteria, MomentumInputs};
+use crate::enrichment::{EnrichmentData, ProviderChain};
+use crate::history::{SharedStore, StockBatch};
+use crate::levels::IntradayLevels;
+use crate::market_data::{Bar, MarketDataSource, MarketTick, Simulator, TwsSource};
 use crate::models::*;
+use crate::quote::{HaltChange, HaltState};
+use crate::recording::Recorder;
+use crate::rvol::{self, VolumeProfile};
+use crate::scanner_set::{ScanFilters, ScannerSet};
+use crate::short::Borrow;
 use crate::tws;
+use crate::tws::bars::BarRequest;
+use crate::tws::connection::{Backoff, ClientRole, ConnectionManager, ConnectionState};
+use crate::units::Shares;
 
 /// Message from a background TWS operation.
 pub enum BgMessage {
@@ -23,9 +34,12 @@ pub enum BgMessage {
     PollComplete {
         symbol_data: HashMap<String, ScanResult>,
         symbol_scanners: HashMap<String, Vec<String>>,
+        scanner_ranks: tws::ScannerRanks,
         port: Option<u16>,
         scanners_run: usize,
         elapsed_secs: f64,
+        /// When the poll finished; the recorded time on replay.
+        at: chrono::DateTime<chrono::Utc>,
     },
     EnrichComplete {
         symbol: String,
@@ -36,6 +50,10 @@ pub enum BgMessage {
         symbol: String,
         update: crate::enrichment::NewsUpdate,
     },
+    /// A broad-tape headline, not yet matched to any symbol.
+    NewsTick {
+        headline: NewsHeadline,
+    },
     /// Real-time market data tick from the streaming thread.
     MarketDataTick {
         symbol: String,
@@ -43,20 +61,57 @@ pub enum BgMessage {
         close: Option<f64>,
         bid: Option<f64>,
         ask: Option<f64>,
-        volume: Option<i64>,
+        volume: Option<Shares>,
+        vwap: Option<f64>,
+        high: Option<f64>,
+        bid_size: Option<Shares>,
+        ask_size: Option<Shares>,
+        halted: Option<HaltState>,
+        borrow: Option<Borrow>,
+        shortable_shares: Option<Shares>,
+        /// When the tick arrived; the recorded time on replay.
+        at: chrono::DateTime<chrono::Utc>,
+    },
+    /// Today's intraday bars for a newly streamed symbol, to seed its levels.
+    IntradayBars {
+        symbol: String,
+        bars: Vec<Bar>,
+    },
+    /// Volume profile of a newly streamed symbol's prior sessions.
+    VolumeProfile {
+        symbol: String,
+        profile: VolumeProfile,
+    },
+    /// A persistent TWS connection changed state.
+    Connection {
+        role: ClientRole,
+        state: ConnectionState,
+    },
+    /// Historical bars for a `start_bars` request.
+    BarsComplete {
+        request: BarRequest,
+        result: Result<Vec<Bar>, String>,
+    },
+    /// Article body for a `start_article` request.
+    ArticleComplete {
+        provider: String,
+        article_id: String,
+        result: Result<String, String>,
     },
 }
 
-/// Request to enrich a symbol, ordered by scanner_hits (higher = higher priority).
-#[derive(Debug, Clone, Eq, PartialEq)]
+/// Request to enrich a symbol, ordered by momentum score (higher = higher priority).
+#[derive(Debug, Clone, PartialEq)]
 pub struct EnrichRequest {
     pub symbol: String,
-    pub scanner_hits: u32,
+    pub score: f64,
 }
 
+impl Eq for EnrichRequest {}
+
 impl Ord for EnrichRequest {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.scanner_hits.cmp(&other.scanner_hits)
+        self.score.total_cmp(&other.score)
     }
 }
 
@@ -90,9 +145,50 @@ pub enum EngineEvent {
         symbol: String,
         update: crate::enrichment::NewsUpdate,
     },
+    /// A broad-tape headline naming an alert row's symbol.
+    NewsTick {
+        symbol: String,
+        headline: NewsHeadline,
+    },
     PortDiscovered {
         port: u16,
     },
+    /// An alert row newly passes every pillar of the active criteria profile.
+    CriteriaMatch {
+        symbol: String,
+        criteria: String,
+    },
+    /// A persistent TWS connection (poll, streaming, ...) changed state.
+    ConnectionChanged {
+        role: ClientRole,
+        state: ConnectionState,
+    },
+    /// Historical bars requested with `start_bars`.
+    BarsComplete {
+        request: BarRequest,
+        result: Result<Vec<Bar>, String>,
+    },
+    /// A news article requested with `start_article`.
+    ArticleComplete {
+        provider: String,
+        article_id: String,
+        result: Result<String, String>,
+    },
+    /// An alert row was halted or paused.
+    Halted {
+        symbol: String,
+        state: HaltState,
+        at: chrono::DateTime<chrono::Utc>,
+        /// Last trade before the halt.
+        price: Option<f64>,
+    },
+    /// A halted alert row resumed trading.
+    Resumed {
+        symbol: String,
+        at: chrono::DateTime<chrono::Utc>,
+        halt_price: Option<f64>,
+        duration: chrono::Duration,
+    },
 }
 
 /// Request to the market data worker.
@@ -109,32 +205,56 @@ pub struct AlertEngine {
     pub settings: Settings,
     pub alert_rows: Vec<AlertRow>,
     pub alert_seen: HashSet<String>,
+    /// Symbols that have passed the active criteria (reported once each).
+    pub criteria_matched: HashSet<String>,
     pub streaming_set: HashSet<String>,
     pub polling: bool,
     pub connected_port: Option<u16>,
-    pub db: Option<SupabaseClient>,
+    /// Shared TWS connections; state changes arrive as `BgMessage::Connection`.
+    pub tws: Arc<ConnectionManager>,
+    /// Where scans, polls and streaming ticks come from (TWS unless simulated).
+    pub source: Arc<dyn MarketDataSource>,
+    pub db: Option<SharedStore>,
     pub bg_tx: mpsc::Sender<BgMessage>,
     pub bg_rx: mpsc::Receiver<BgMessage>,
     pub poll_busy: bool,
     pub scan_busy: bool,
     pub enrich_tx: mpsc::Sender<EnrichRequest>,
     pub mktdata_tx: Option<mpsc::Sender<MktDataRequest>>,
+    /// Session recording of every market input `tick` processes.
+    pub recorder: Option<Recorder>,
+    /// Time-of-day volume profiles for streamed symbols (see `rvol`).
+    pub volume_profiles: HashMap<String, VolumeProfile>,
+    /// Time of the latest poll or tick, the recorded time on replay. RVOL
+    /// is taken at this time rather than the wall clock's.
+    pub market_time: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl AlertEngine {
     pub fn new(
         enrich_tx: mpsc::Sender<EnrichRequest>,
         settings: Settings,
-        db: Option<SupabaseClient>,
+        db: Option<SharedStore>,
     ) -> Self {
         let (bg_tx, bg_rx) = mpsc::channel();
+        let state_tx = bg_tx.clone();
+        let ports = settings.port.map(|p| vec![p]).unwrap_or_default();
+        let tws = ConnectionManager::new(&settings.host, &ports).with_listener(Box::new(
+            move |role, state| {
+                let _ = state_tx.send(BgMessage::Connection { role, state: state.clone() });
+            },
+        ));
+        let tws = Arc::new(tws);
         Self {
             settings,
             alert_rows: Vec::new(),
             alert_seen: HashSet::new(),
+            criteria_matched: HashSet::new(),
             streaming_set: HashSet::new(),
             polling: false,
             connected_port: None,
+            source: Arc::new(TwsSource::new(Arc::clone(&tws))),
+            tws,
             db,
             bg_tx,
             bg_rx,
@@ -142,21 +262,192 @@ impl AlertEngine {
             scan_busy: false,
             enrich_tx,
             mktdata_tx: None,
+            recorder: None,
+            volume_profiles: HashMap::new(),
+            market_time: None,
         }
     }
 
-    /// Compute a priority score for a symbol based on its alert row data.
+    /// Streaming priority for a symbol: its current momentum score.
     /// Higher = more important to keep streaming.
-    fn streaming_priority(&self, symbol: &str) -> u32 {
-        if let Some(row) = self.alert_rows.iter().find(|r| r.symbol == symbol) {
-            let mut score = row.scanner_hits;
-            if row.catalyst.is_some() {
-                score += 3;
+    fn streaming_priority(&self, symbol: &str) -> f64 {
+        self.alert_rows
+            .iter()
+            .find(|r| r.symbol == symbol)
+            .map(|row| {
+                momentum_score(&self.momentum_inputs(row), &self.settings.criteria, self.score_time())
+            })
+            .unwrap_or(0.0)
+    }
+
+    /// Unix time catalyst freshness is scored at: the latest poll or tick,
+    /// so a replay scores as the recorded session did.
+    fn score_time(&self) -> i64 {
+        self.market_time.unwrap_or_else(chrono::Utc::now).timestamp()
+    }
+
+    /// Criteria inputs for an alert row, with hits scored against the active scanner set.
+    fn momentum_inputs(&self, row: &AlertRow) -> MomentumInputs {
+        MomentumInputs::from(row).with_scanner_total(self.settings.scanner_set.len())
+    }
+
+    /// Recompute the composite momentum score on every alert row.
+    fn update_scores(&mut self) {
+        let now = self.score_time();
+        let total = self.settings.scanner_set.len();
+        for row in &mut self.alert_rows {
+            row.score = momentum_score(
+                &MomentumInputs::from(&*row).with_scanner_total(total),
+                &self.settings.criteria,
+                now,
+            );
+        }
+    }
+
+    /// Append one sighting per (symbol, scanner) hit of a poll cycle that
+    /// finished `at` to the history store. Prices come from the alert row,
+    /// which streaming keeps fresher than the scanner rows.
+    fn record_sightings(
+        &self,
+        symbol_data: &HashMap<String, ScanResult>,
+        scanner_ranks: &tws::ScannerRanks,
+        at: chrono::DateTime<chrono::Utc>,
+        rt: &tokio::runtime::Handle,
+    ) {
+        let Some(ref db) = self.db else {
+            return;
+        };
+        let seen_at = at
+            .format(crate::history::SIGHTING_TS_FORMAT)
+            .to_string();
+        let sightings: Vec<Sighting> = scanner_ranks
+            .iter()
+            .map(|((symbol, scanner), &rank)| {
+                let row = self.alert_rows.iter().find(|r| &r.symbol == symbol);
+                let scan = symbol_data.get(symbol);
+                Sighting {
+                    symbol: symbol.clone(),
+                    seen_at: seen_at.clone(),
+                    scanner: scanner.clone(),
+                    rank,
+                    price: row.and_then(|r| r.last).or(scan.and_then(|r| r.last)),
+                    change_pct: row
+                        .and_then(|r| r.change_pct)
+                        .or(scan.and_then(|r| r.change_pct)),
+                    volume: row.and_then(|r| r.volume).or(scan.and_then(|r| r.volume)),
+                    rvol: row.and_then(|r| r.rvol),
+                }
+            })
+            .collect();
+        if sightings.is_empty() {
+            return;
+        }
+        let db = SharedStore::clone(db);
+        rt.spawn(async move {
+            if let Err(e) = db.record_sightings(&sightings).await {
+                warn!("History sightings write error: {e}");
+            }
+        });
+    }
+
+    /// Add a broad-tape headline to every alert row it names, newest first,
+    /// and reclassify those rows' catalysts over headlines and filings.
+    fn apply_news_tick(&mut self, headline: NewsHeadline) -> Vec<EngineEvent> {
+        let symbols: Vec<String> = tws::news::mentioned_symbols(
+            &headline.title,
+            self.alert_rows.iter().map(|r| r.symbol.as_str()),
+        )
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+        let mut events = Vec::new();
+        for symbol in symbols {
+            let Some(row) = self.alert_rows.iter_mut().find(|r| r.symbol == symbol) else {
+                continue;
+            };
+            if row.news_headlines.iter().any(|h| h.title.eq_ignore_ascii_case(&headline.title)) {
+                continue;
+            }
+            info!(symbol = %symbol, source = headline.source.as_deref(), "news: {}", headline.title);
+            merge_row_news(row, std::slice::from_ref(&headline));
+            events.push(EngineEvent::NewsTick { symbol, headline: headline.clone() });
+        }
+        events
+    }
+
+    /// Append a halt or resume to the history store and turn it into an
+    /// engine event.
+    fn report_halt(
+        &self,
+        symbol: String,
+        change: HaltChange,
+        rt: &tokio::runtime::Handle,
+    ) -> EngineEvent {
+        let (event, record) = match change {
+            HaltChange::Halted { state, at, price } => {
+                info!(symbol = %symbol, state = state.label(), "halted");
+                let record = HaltEvent {
+                    symbol: symbol.clone(),
+                    at: at.format(crate::history::SIGHTING_TS_FORMAT).to_string(),
+                    state,
+                    halt_price: price,
+                    duration_secs: None,
+                };
+                (EngineEvent::Halted { symbol, state, at, price }, record)
+            }
+            HaltChange::Resumed { at, halt_price, duration } => {
+                info!(symbol = %symbol, secs = duration.num_seconds(), "resumed");
+                let record = HaltEvent {
+                    symbol: symbol.clone(),
+                    at: at.format(crate::history::SIGHTING_TS_FORMAT).to_string(),
+                    state: HaltState::Trading,
+                    halt_price,
+                    duration_secs: Some(duration.num_seconds()),
+                };
+                (EngineEvent::Resumed { symbol, at, halt_price, duration }, record)
+            }
+        };
+        if let Some(ref db) = self.db {
+            let db = SharedStore::clone(db);
+            rt.spawn(async move {
+                if let Err(e) = db.record_halt(&record).await {
+                    warn!("History halt write error: {e}");
+                }
+            });
+        }
+        event
+    }
+
+    /// Sort alert rows by momentum score, highest first.
+    fn sort_alert_rows(&mut self) {
+        self.alert_rows.sort_by(|a, b| b.score.total_cmp(&a.score));
+    }
+
+    /// Switch the scanner set polled from the next cycle on.
+    pub fn set_scanner_set(&mut self, set: ScannerSet) {
+        self.settings.scanner_set = set;
+        self.update_scores();
+        self.sort_alert_rows();
+    }
+
+    /// Switch the active criteria profile and re-evaluate every alert row.
+    pub fn set_criteria(&mut self, criteria: MomentumCriteria) {
+        self.settings.criteria = criteria;
+        self.criteria_matched.clear();
+        self.evaluate_pillars();
+    }
+
+    /// Re-evaluate the active criteria on every alert row.
+    /// Returns symbols that pass all pillars for the first time.
+    fn evaluate_pillars(&mut self) -> Vec<String> {
+        let mut newly_matched = Vec::new();
+        for row in &mut self.alert_rows {
+            row.pillars = self.settings.criteria.evaluate(&MomentumInputs::from(&*row));
+            if row.pillars.passes_all() && self.criteria_matched.insert(row.symbol.clone()) {
+                newly_matched.push(row.symbol.clone());
             }
-            score
-        } else {
-            0
         }
+        newly_matched
     }
 
     /// Subscribe a symbol to streaming market data (if not already subscribed).
@@ -180,7 +471,7 @@ impl AlertEngine {
                 .streaming_set
                 .iter()
                 .map(|s| (s.clone(), self.streaming_priority(s)))
-                .min_by_key(|(_, p)| *p);
+                .min_by(|(_, a), (_, b)| a.total_cmp(b));
             if let Some((victim_sym, victim_priority)) = victim {
                 if new_priority <= victim_priority {
                     // New symbol isn't higher priority — skip it
@@ -208,13 +499,92 @@ impl AlertEngine {
             cancel: false,
         });
         self.streaming_set.insert(symbol.to_string());
+        self.seed_intraday(symbol);
+    }
+
+    /// Fetch bars for a symbol in a background thread: today's, to seed its
+    /// intraday levels (premarket range, open, VWAP before the first tick),
+    /// and prior sessions', for its time-of-day volume profile.
+    fn seed_intraday(&self, symbol: &str) {
+        let feed = self.feed();
+        let tx = self.bg_tx.clone();
+        let symbol = symbol.to_string();
+
+        std::thread::spawn(move || {
+            let today = crate::session::today();
+            let rt = tws::connection::runtime();
+            match rt.block_on(feed.intraday_bars(&symbol, today)) {
+                Ok(bars) => {
+                    let _ = tx.send(BgMessage::IntradayBars { symbol: symbol.clone(), bars });
+                }
+                Err(e) => warn!(symbol = %symbol, "intraday bars unavailable: {e:#}"),
+            }
+            match rt.block_on(feed.bars(&rvol::profile_request(&symbol, today))) {
+                Ok(bars) => match VolumeProfile::from_bars(&bars, today) {
+                    Some(profile) => {
+                        let _ = tx.send(BgMessage::VolumeProfile { symbol, profile });
+                    }
+                    None => warn!(symbol = %symbol, "no prior-session volume for RVOL profile"),
+                },
+                Err(e) => warn!(symbol = %symbol, "RVOL profile bars unavailable: {e:#}"),
+            }
+        });
+    }
+
+    /// Recompute each row's levels distances, spread and RVOL from its
+    /// latest quote and volume, as of `market_time`.
+    fn refresh_derived(&mut self) {
+        let now = self.market_time.unwrap_or_else(chrono::Utc::now);
+        for row in &mut self.alert_rows {
+            row.levels.refresh(row.last);
+            row.quote.refresh();
+            row.short.refresh(row.levels.prior_close, row.levels.low_of_day);
+            let rv = rvol::relative_volume(
+                row.volume,
+                row.avg_volume_10d,
+                row.avg_volume,
+                self.volume_profiles.get(&row.symbol),
+                now,
+            );
+            row.rvol_time_of_day = rv.time_of_day;
+            row.rvol_full_day = rv.full_day;
+            // Rows restored from history keep their stored RVOL until volume arrives
+            if let Some(v) = rv.value() {
+                row.rvol = Some(v);
+            }
+        }
+    }
+
+    /// Replace the market data source, e.g. with a `Simulator`.
+    pub fn with_source(mut self, source: Arc<dyn MarketDataSource>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Point the connection manager at the current host/port settings
+    /// (which `set host` / `set port` may have changed).
+    fn sync_endpoint(&self) {
+        let ports = self.settings.port.map(|p| vec![p]).unwrap_or_default();
+        self.tws.set_endpoint(&self.settings.host, &ports);
+    }
+
+    /// The shared connection manager.
+    pub fn connection(&self) -> Arc<ConnectionManager> {
+        self.sync_endpoint();
+        Arc::clone(&self.tws)
+    }
+
+    /// The market data source.
+    pub fn feed(&self) -> Arc<dyn MarketDataSource> {
+        self.sync_endpoint();
+        Arc::clone(&self.source)
     }
 
     /// Queue enrichment for a symbol if the channel is available.
-    pub fn queue_enrich(&self, symbol: &str, scanner_hits: u32) {
+    pub fn queue_enrich(&self, symbol: &str, score: f64) {
         let _ = self.enrich_tx.send(EnrichRequest {
             symbol: symbol.to_string(),
-            scanner_hits,
+            score,
         });
     }
 
@@ -231,20 +601,18 @@ impl AlertEngine {
         }
         self.scan_busy = true;
 
-        let ports: Vec<u16> = self
-            .settings
-            .port
-            .map(|p| vec![p])
-            .unwrap_or_else(|| DEFAULT_PORTS.to_vec());
-        let host = self.settings.host.clone();
+        let feed = self.feed();
         let tx = self.bg_tx.clone();
         let code = code.to_string();
+        let filters = ScanFilters {
+            min_price,
+            max_price,
+            ..ScanFilters::default()
+        };
 
         std::thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            let (results, port) = rt.block_on(
-                tws::run_scan(&code, &host, &ports, 1, rows, min_price, max_price),
-            );
+            let (results, port) =
+                tws::connection::runtime().block_on(feed.scan(&code, rows, &filters));
             let _ = tx.send(BgMessage::ScanComplete {
                 scanner_code: code,
                 results,
@@ -260,21 +628,42 @@ impl AlertEngine {
         }
         self.poll_busy = true;
 
-        let ports: Vec<u16> = self
-            .settings
-            .port
-            .map(|p| vec![p])
-            .unwrap_or_else(|| DEFAULT_PORTS.to_vec());
-        let host = self.settings.host.clone();
+        let feed = self.feed();
         let tx = self.bg_tx.clone();
 
         std::thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            let xml = rt.block_on(tws::fetch_scanner_params(&host, &ports, 3));
+            let xml = tws::connection::runtime().block_on(feed.scanner_params());
             let _ = tx.send(BgMessage::ListComplete { xml, group });
         });
     }
 
+    /// Fetch historical bars in a background thread.
+    pub fn start_bars(&self, request: BarRequest) {
+        let feed = self.feed();
+        let tx = self.bg_tx.clone();
+
+        std::thread::spawn(move || {
+            let result = tws::connection::runtime()
+                .block_on(feed.bars(&request))
+                .map_err(|e| format!("{e:#}"));
+            let _ = tx.send(BgMessage::BarsComplete { request, result });
+        });
+    }
+
+    /// Fetch a news article body in a background thread.
+    pub fn start_article(&self, provider: &str, article_id: &str) {
+        let feed = self.feed();
+        let tx = self.bg_tx.clone();
+        let (provider, article_id) = (provider.to_string(), article_id.to_string());
+
+        std::thread::spawn(move || {
+            let result = tws::connection::runtime()
+                .block_on(feed.news_article(&provider, &article_id))
+                .map_err(|e| format!("{e:#}"));
+            let _ = tx.send(BgMessage::ArticleComplete { provider, article_id, result });
+        });
+    }
+
     /// Start polling. Returns true if first poll was kicked off.
     pub fn poll_on(&mut self) -> bool {
         if self.polling {
@@ -295,10 +684,11 @@ impl AlertEngine {
         let count = self.alert_seen.len();
         self.alert_seen.clear();
         self.alert_rows.clear();
+        self.criteria_matched.clear();
         self.streaming_set.clear();
         let _ = self.enrich_tx.send(EnrichRequest {
             symbol: String::new(),
-            scanner_hits: 0,
+            score: 0.0,
         });
         // Send sentinel to market data worker to cancel all subscriptions
         if let Some(ref tx) = self.mktdata_tx {
@@ -318,41 +708,52 @@ impl AlertEngine {
         }
         self.poll_busy = true;
 
-        let ports: Vec<u16> = self
-            .settings
-            .port
-            .map(|p| vec![p])
-            .unwrap_or_else(|| DEFAULT_PORTS.to_vec());
-        let host = self.settings.host.clone();
+        let feed = self.feed();
+        let set = self.settings.scanner_set.clone();
         let tx = self.bg_tx.clone();
 
         std::thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
             let start = std::time::Instant::now();
 
-            let (symbol_scanners, symbol_data, connected_port) = rt.block_on(
-                tws::run_poll_scan(ALERT_SCANNERS, &host, &ports, 10, 50, Some(1.0), Some(20.0)),
-            );
+            let (symbol_scanners, symbol_data, scanner_ranks, connected_port) =
+                tws::connection::runtime().block_on(feed.poll(&set));
 
-            let scanners_run = ALERT_SCANNERS.len();
+            let scanners_run = set.len();
             let elapsed_secs = start.elapsed().as_secs_f64();
             info!(unique_stocks = symbol_data.len(), scanners_run, elapsed_secs, "poll scan complete");
 
             let _ = tx.send(BgMessage::PollComplete {
                 symbol_data,
                 symbol_scanners,
+                scanner_ranks,
                 port: connected_port,
                 scanners_run,
                 elapsed_secs,
+                at: chrono::Utc::now(),
             });
         });
     }
 
+    /// Append `msg` to the session recording, if one is running. A failed
+    /// write stops the recording rather than the engine.
+    fn record(&mut self, msg: &BgMessage) {
+        if let Some(recorder) = &mut self.recorder
+            && let Err(e) = recorder.record(msg)
+        {
+            warn!("session recording stopped: {e:#}");
+            self.recorder = None;
+        }
+    }
+
     /// Drain bg_rx, process messages, return events for consumers.
     pub fn tick(&mut self, rt: &tokio::runtime::Handle) -> Vec<EngineEvent> {
         let mut events = Vec::new();
+        let mut received = false;
+        let mut halts: Vec<(String, HaltChange)> = Vec::new();
 
         while let Ok(msg) = self.bg_rx.try_recv() {
+            received = true;
+            self.record(&msg);
             match msg {
                 BgMessage::ScanComplete {
                     scanner_code,
@@ -367,8 +768,12 @@ impl AlertEngine {
                     }
                     self.scan_busy = false;
                     // Queue enrichment for scan results
+                    let now = chrono::Utc::now().timestamp();
+                    let total = self.settings.scanner_set.len();
                     for r in &results {
-                        self.queue_enrich(&r.symbol, 1);
+                        let inputs = MomentumInputs::from(r).with_scanner_total(total);
+                        let score = momentum_score(&inputs, &self.settings.criteria, now);
+                        self.queue_enrich(&r.symbol, score);
                     }
                     events.push(EngineEvent::ScanComplete {
                         scanner_code,
@@ -382,10 +787,13 @@ impl AlertEngine {
                 BgMessage::PollComplete {
                     symbol_data,
                     symbol_scanners,
+                    scanner_ranks,
                     port,
                     scanners_run,
                     elapsed_secs,
+                    at,
                 } => {
+                    self.market_time = Some(at);
                     if let Some(p) = port {
                         self.connected_port = Some(p);
                         events.push(EngineEvent::PortDiscovered { port: p });
@@ -393,9 +801,9 @@ impl AlertEngine {
                         self.connected_port = None;
                     }
 
-                    // Write to Supabase (background, non-blocking)
+                    // Write to history store (background, non-blocking)
                     if let Some(ref self_db) = self.db {
-                        let batch: HashMap<String, (serde_json::Value, Vec<String>)> = symbol_data
+                        let batch: StockBatch = symbol_data
                             .iter()
                             .map(|(sym, r)| {
                                 let data = serde_json::json!({
@@ -416,16 +824,16 @@ impl AlertEngine {
                                 )
                             })
                             .collect();
-                        let mut db = self_db.clone();
+                        let db = SharedStore::clone(self_db);
                         rt.spawn(async move {
                             if let Err(e) = db.record_stocks_batch(&batch).await {
-                                warn!("Supabase write error: {e}");
+                                warn!("History write error: {e}");
                             }
                         });
                     }
 
                     // Detect new symbols
-                    let now = chrono::Local::now().format("%H:%M:%S").to_string();
+                    let now = crate::session::clock_time(at);
                     let new_syms: Vec<String> = symbol_data
                         .keys()
                         .filter(|s| !self.alert_seen.contains(*s))
@@ -447,6 +855,13 @@ impl AlertEngine {
                                 .get(sym)
                                 .cloned()
                                 .unwrap_or_default();
+                            let mut levels = IntradayLevels {
+                                prior_close: r.close,
+                                ..Default::default()
+                            };
+                            if let Some(l) = r.last {
+                                levels.on_price(l, at);
+                            }
                             self.alert_rows.push(AlertRow {
                                 symbol: sym.clone(),
                                 alert_time: now.clone(),
@@ -468,32 +883,38 @@ impl AlertEngine {
                                 enriched: false,
                                 avg_volume: None,
                                 avg_volume_10d: None,
+                                levels,
+                                ..Default::default()
                             });
                             // Subscribe to streaming market data for live price updates
                             self.subscribe_market_data(sym, &r.currency);
-                            self.queue_enrich(sym, hits);
+                            let score = self.streaming_priority(sym);
+                            self.queue_enrich(sym, score);
                         }
                     }
 
                     // Update price/volume for already-seen symbols
                     for row in &mut self.alert_rows {
                         if let Some(r) = symbol_data.get(&row.symbol) {
-                            if r.last.is_some() {
-                                row.last = r.last;
+                            if let Some(l) = r.last {
+                                row.last = Some(l);
+                                row.levels.on_price(l, at);
+                            }
+                            if r.close.is_some() {
+                                row.levels.prior_close = r.close;
                             }
                             if r.change_pct.is_some() {
                                 row.change_pct = r.change_pct;
                             }
                             if let Some(v) = r.volume {
                                 row.volume = Some(v);
-                                // Recompute rvol if avg_volume is known
-                                let avg = row.avg_volume_10d.or(row.avg_volume);
-                                if let Some(avg) = avg {
-                                    if avg > 0 {
-                                        // v is IB round lots (×100), avg is Yahoo raw shares
-                                        row.rvol = Some(v as f64 * 100.0 / avg as f64);
-                                    }
-                                }
+                            }
+                            row.quote.on_quote(r.bid, r.ask, r.bid_size, r.ask_size);
+                            if let Some(change) = r
+                                .halted
+                                .and_then(|h| row.quote.on_halt(h, at, row.last))
+                            {
+                                halts.push((row.symbol.clone(), change));
                             }
                             // Update scanner hits and list
                             if let Some(new_scanners) = symbol_scanners.get(&row.symbol) {
@@ -510,17 +931,11 @@ impl AlertEngine {
                         }
                     }
 
-                    // Sort alert rows
-                    self.alert_rows.sort_by(|a, b| {
-                        b.scanner_hits
-                            .cmp(&a.scanner_hits)
-                            .then_with(|| {
-                                b.change_pct
-                                    .unwrap_or(0.0)
-                                    .partial_cmp(&a.change_pct.unwrap_or(0.0))
-                                    .unwrap_or(std::cmp::Ordering::Equal)
-                            })
-                    });
+                    self.refresh_derived();
+                    self.record_sightings(&symbol_data, &scanner_ranks, at, rt);
+
+                    self.update_scores();
+                    self.sort_alert_rows();
 
                     self.poll_busy = false;
                     events.push(EngineEvent::PollCycleComplete {
@@ -531,15 +946,17 @@ impl AlertEngine {
                     });
                 }
                 BgMessage::EnrichComplete { symbol, data } => {
-                    // Write enrichment to Supabase (background, non-blocking)
+                    // Write enrichment to history store (background, non-blocking)
                     if let Some(ref self_db) = self.db {
                         let headlines_json = serde_json::to_string(&data.news_headlines)
                             .unwrap_or_else(|_| "[]".to_string());
-                        let supa_data = serde_json::json!({
+                        let cache_data = serde_json::json!({
                             "name": &data.name,
                             "sector": &data.sector,
                             "catalyst": &data.catalyst,
                             "catalyst_time": data.catalyst_time,
+                            "catalyst_kind": data.catalyst_kind,
+                            "catalyst_sentiment": data.catalyst_sentiment,
                             "float_shares": data.float_shares,
                             "industry": &data.industry,
                             "short_pct": data.short_pct,
@@ -548,14 +965,14 @@ impl AlertEngine {
                             "news_headlines": headlines_json,
                             "enriched_at": chrono::Utc::now().to_rfc3339(),
                         });
-                        let batch: HashMap<String, (serde_json::Value, Vec<String>)> =
-                            [(symbol.clone(), (supa_data, vec![]))]
+                        let batch: StockBatch =
+                            [(symbol.clone(), (cache_data, vec![]))]
                                 .into_iter()
                                 .collect();
-                        let mut db = self_db.clone();
+                        let db = SharedStore::clone(self_db);
                         rt.spawn(async move {
                             if let Err(e) = db.record_stocks_batch(&batch).await {
-                                warn!("Supabase enrich write error: {e}");
+                                warn!("History enrich write error: {e}");
                             }
                         });
                     }
@@ -576,21 +993,18 @@ impl AlertEngine {
                         row.industry = data.industry;
                         row.country = data.country;
                         row.float_shares = data.float_shares;
+                        row.shares_outstanding = data.shares_outstanding;
                         row.short_pct = data.short_pct;
                         row.catalyst = data.catalyst;
                         row.catalyst_time = data.catalyst_time;
-                        row.news_headlines = data.news_headlines;
                         row.avg_volume = data.avg_volume;
                         row.avg_volume_10d = data.avg_volume_10d;
-                        // Prefer 10d avg for RVOL (more relevant for momentum), fall back to 3mo
-                        let avg_for_rvol = data.avg_volume_10d.or(data.avg_volume);
-                        if let (Some(vol), Some(avg)) = (row.volume, avg_for_rvol) {
-                            if avg > 0 {
-                                // vol is IB round lots (×100), avg is Yahoo raw shares
-                                row.rvol = Some(vol as f64 * 100.0 / avg as f64);
-                            }
-                        }
+                        row.provenance = data.provenance;
+                        row.catalyst_kind = data.catalyst_kind;
+                        row.catalyst_sentiment = data.catalyst_sentiment;
+                        row.filings = data.filings;
                         row.enriched = true;
+                        merge_row_news(row, &data.news_headlines);
                     }
 
                     events.push(EngineEvent::EnrichComplete { symbol, data: data_clone });
@@ -603,37 +1017,89 @@ impl AlertEngine {
                         if update.catalyst.is_some() {
                             row.catalyst = update.catalyst.clone();
                             row.catalyst_time = update.catalyst_time;
+                            row.catalyst_kind = update.catalyst_kind;
+                            row.catalyst_sentiment = update.catalyst_sentiment;
                         }
-                        if !update.news_headlines.is_empty() {
-                            row.news_headlines = update.news_headlines.clone();
+                        if !update.filings.is_empty() {
+                            row.filings = update.filings.clone();
                         }
+                        merge_row_news(row, &update.news_headlines);
                     }
                     events.push(EngineEvent::NewsRefresh { symbol, update });
                 }
+                BgMessage::NewsTick { headline } => {
+                    events.extend(self.apply_news_tick(headline));
+                }
+                BgMessage::Connection { role, state } => {
+                    match state {
+                        ConnectionState::Connected { port } => self.connected_port = Some(port),
+                        // Only the scanner connection decides whether TWS is down;
+                        // a streaming, news or fundamentals client dropping doesn't
+                        _ if matches!(role, ClientRole::Poll | ClientRole::Probe) => {
+                            self.connected_port = None;
+                        }
+                        _ => {}
+                    }
+                    events.push(EngineEvent::ConnectionChanged { role, state });
+                }
+                BgMessage::BarsComplete { request, result } => {
+                    events.push(EngineEvent::BarsComplete { request, result });
+                }
+                BgMessage::ArticleComplete { provider, article_id, result } => {
+                    events.push(EngineEvent::ArticleComplete { provider, article_id, result });
+                }
+                BgMessage::IntradayBars { symbol, bars } => {
+                    if let Some(row) = self.alert_rows.iter_mut().find(|r| r.symbol == symbol) {
+                        row.levels.merge(IntradayLevels::from_bars(&bars));
+                    }
+                }
+                BgMessage::VolumeProfile { symbol, profile } => {
+                    info!(symbol = %symbol, sessions = profile.sessions, "RVOL volume profile loaded");
+                    self.volume_profiles.insert(symbol, profile);
+                }
                 BgMessage::MarketDataTick {
                     symbol,
                     last,
                     close,
-                    bid: _,
-                    ask: _,
+                    bid,
+                    ask,
                     volume,
+                    vwap,
+                    high,
+                    bid_size,
+                    ask_size,
+                    halted,
+                    borrow,
+                    shortable_shares,
+                    at,
                 } => {
+                    self.market_time = Some(at);
                     if let Some(row) =
                         self.alert_rows.iter_mut().find(|r| r.symbol == symbol)
                     {
+                        row.quote.on_quote(bid, ask, bid_size, ask_size);
+                        row.short.on_shortable(borrow, shortable_shares);
+                        // Before `last` is updated, so a halt keeps the last trade before it
+                        if let Some(change) = halted
+                            .and_then(|h| row.quote.on_halt(h, at, row.last))
+                        {
+                            halts.push((symbol.clone(), change));
+                        }
                         if let Some(l) = last {
                             row.last = Some(l);
+                            row.levels.on_price(l, at);
+                        }
+                        if close.is_some() {
+                            row.levels.prior_close = close;
+                        }
+                        if let Some(v) = vwap {
+                            row.levels.on_vwap(v);
+                        }
+                        if let Some(h) = high {
+                            row.levels.on_high(h);
                         }
                         if let Some(v) = volume {
                             row.volume = Some(v);
-                            // Recompute rvol: prefer 10d avg, fall back to 3mo
-                            // v is IB round lots (×100 to get shares), avg is Yahoo raw shares
-                            let avg = row.avg_volume_10d.or(row.avg_volume);
-                            if let Some(avg) = avg {
-                                if avg > 0 {
-                                    row.rvol = Some(v as f64 * 100.0 / avg as f64);
-                                }
-                            }
                         }
                         // Compute change_pct from last and close
                         let effective_close = close.or_else(|| {
@@ -650,30 +1116,41 @@ impl AlertEngine {
             }
         }
 
+        for (symbol, change) in halts {
+            events.push(self.report_halt(symbol, change, rt));
+        }
+
+        if received {
+            self.refresh_derived();
+            self.update_scores();
+            for symbol in self.evaluate_pillars() {
+                info!(symbol = %symbol, criteria = %self.settings.criteria.name, "criteria match");
+                events.push(EngineEvent::CriteriaMatch {
+                    symbol,
+                    criteria: self.settings.criteria.name.clone(),
+                });
+            }
+        }
+
         events
     }
 
     /// Probe TWS to discover the connected port.
     pub fn probe_port(&mut self) {
-        let ports: Vec<u16> = self
-            .settings
-            .port
-            .map(|p| vec![p])
-            .unwrap_or_else(|| DEFAULT_PORTS.to_vec());
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        if let Some(port) = rt.block_on(tws::probe_port(&self.settings.host, &ports)) {
+        let feed = self.feed();
+        if let Some(port) = tws::connection::runtime().block_on(feed.probe()) {
             self.connected_port = Some(port);
         }
     }
 
-    /// Load today's tws_scans from Supabase and populate alert state.
+    /// Load today's tws_scans from the history store and populate alert state.
     /// Returns (loaded_count, needs_enrichment_count).
     pub fn init_from_tws_scans(&mut self, rt: &tokio::runtime::Handle) -> (usize, usize) {
         if let Some(ref db) = self.db {
             let result = rt.block_on(db.get_today());
             if let Err(ref e) = result {
-                warn!("Failed to load today's scans from Supabase: {e}");
-                eprintln!("Supabase error: {e}");
+                warn!("Failed to load today's scans from {}: {e}", db.backend());
+                eprintln!("History error ({}): {e}", db.backend());
             }
             if let Ok(today) = result {
                 let loaded = today.len();
@@ -695,29 +1172,15 @@ impl AlertEngine {
                             .unwrap_or(false)
                     });
 
-                    // Deserialize news_headlines with backwards compat for old string-only format
-                    let news_headlines: Vec<crate::models::NewsHeadline> = s
+                    let news_headlines = s
                         .news_headlines
                         .as_deref()
-                        .and_then(|h| {
-                            // Try new format first: Vec<NewsHeadline>
-                            serde_json::from_str::<Vec<crate::models::NewsHeadline>>(h)
-                                .ok()
-                                .or_else(|| {
-                                    // Fallback: old Vec<String> format
-                                    serde_json::from_str::<Vec<String>>(h).ok().map(|titles| {
-                                        titles.into_iter().map(|title| crate::models::NewsHeadline {
-                                            title,
-                                            published: None,
-                                        }).collect()
-                                    })
-                                })
-                        })
+                        .map(crate::history::parse_news_headlines)
                         .unwrap_or_default();
 
                     self.alert_rows.push(AlertRow {
                         symbol: s.symbol.clone(),
-                        alert_time: crate::history::local_time_str(&s.first_seen),
+                        alert_time: crate::history::eastern_time_str(&s.first_seen),
                         last: s.last_price,
                         change_pct: s.change_pct,
                         volume: None,
@@ -730,18 +1193,26 @@ impl AlertEngine {
                         country: None,
                         catalyst: s.catalyst.clone(),
                         catalyst_time: s.catalyst_time,
+                        catalyst_kind: s.catalyst_kind,
+                        catalyst_sentiment: s.catalyst_sentiment,
                         scanner_hits: n_scans,
                         scanners: scanners_str.split(',').filter(|s| !s.is_empty()).map(String::from).collect(),
                         news_headlines,
                         enriched: enrichment_fresh,
                         avg_volume: s.avg_volume,
                         avg_volume_10d: s.avg_volume_10d,
+                        ..Default::default()
                     });
                     if !enrichment_fresh {
                         needs_enrich += 1;
-                        self.queue_enrich(&s.symbol, n_scans);
+                        let score = self.streaming_priority(&s.symbol);
+                        self.queue_enrich(&s.symbol, score);
                     }
                 }
+                self.update_scores();
+                self.sort_alert_rows();
+                // Rows already passing at startup are not re-reported
+                self.evaluate_pillars();
                 info!(loaded, needs_enrich, "tws_scans loaded");
                 return (loaded, needs_enrich);
             }
@@ -750,21 +1221,38 @@ impl AlertEngine {
     }
 }
 
+/// Headlines kept per alert row as broad-tape news arrives.
+const MAX_ROW_HEADLINES: usize = 20;
+
+/// Merge `fresh` headlines into a row's news, keeping the broad-tape
+/// headlines it already has, and reclassify its catalyst over the merged
+/// headlines and its filings. A catalyst nothing classifies is kept.
+fn merge_row_news(row: &mut AlertRow, fresh: &[NewsHeadline]) {
+    row.news_headlines = crate::enrichment::merge_kept_headlines(fresh, &row.news_headlines);
+    row.news_headlines.truncate(MAX_ROW_HEADLINES);
+    if let Some(catalyst) = crate::catalyst::classify_news(&row.news_headlines, &row.filings) {
+        row.catalyst = Some(catalyst.headline);
+        row.catalyst_time = catalyst.published;
+        row.catalyst_kind = Some(catalyst.kind);
+        row.catalyst_sentiment = Some(catalyst.sentiment);
+    }
+}
+
 /// Cache TTL for enrichment data (15 minutes).
 const ENRICH_CACHE_TTL: Duration = Duration::from_secs(15 * 60);
 
-/// Spawn the enrichment worker thread with optional Supabase cache.
+/// Spawn the enrichment worker thread, fetching through `providers` with
+/// optional history-store cache.
 pub fn spawn_enrichment_worker(
     bg_tx: mpsc::Sender<BgMessage>,
     enrich_rx: mpsc::Receiver<EnrichRequest>,
     rt_handle: tokio::runtime::Handle,
-    db: Option<SupabaseClient>,
+    db: Option<SharedStore>,
+    providers: ProviderChain,
 ) -> std::thread::JoinHandle<()> {
     std::thread::spawn(move || {
-        let client = reqwest::Client::new();
         let mut heap = BinaryHeap::<EnrichRequest>::new();
         let mut enriched_set = HashSet::<String>::new();
-        let mut yahoo_auth: Option<YahooAuth> = None;
         let mut last_news_refresh = Instant::now();
         let mut news_refresh_idx: usize = 0;
 
@@ -795,29 +1283,24 @@ pub fn spawn_enrichment_worker(
                 }
                 enriched_set.insert(req.symbol.clone());
 
-                // Try Supabase cache first
+                // Try the history store's enrichment cache first
                 let cached = db.as_ref().and_then(|db| {
                     rt_handle
                         .block_on(db.get_enrichment_cache(&req.symbol, ENRICH_CACHE_TTL))
                 });
 
-                let data = if let Some(cached_data) = cached {
+                let data = if let Some(mut cached_data) = cached {
                     info!(symbol = %req.symbol, "enrichment cache hit");
+                    cached_data.mark_source("cache");
                     cached_data
                 } else {
-                    // Fetch or reuse Yahoo auth
-                    if yahoo_auth.is_none() {
-                        yahoo_auth = rt_handle.block_on(fetch_yahoo_auth(&client)).ok();
-                        if yahoo_auth.is_none() {
-                            warn!("Yahoo auth failed, skipping enrichment");
-                        }
-                    }
-                    if let Some(ref auth) = yahoo_auth {
-                        info!(symbol = %req.symbol, priority = req.scanner_hits, "enriching via Yahoo");
-                        rt_handle.block_on(fetch_enrichment_with_auth(&client, &req.symbol, auth))
-                    } else {
-                        EnrichmentData::default()
-                    }
+                    info!(
+                        symbol = %req.symbol,
+                        priority = req.score,
+                        providers = %providers.describe(),
+                        "enriching"
+                    );
+                    rt_handle.block_on(providers.fetch(&req.symbol))
                 };
 
                 let _ = bg_tx.send(BgMessage::EnrichComplete {
@@ -827,7 +1310,7 @@ pub fn spawn_enrichment_worker(
             } else if !enriched_set.is_empty()
                 && last_news_refresh.elapsed() >= Duration::from_secs(5 * 60)
             {
-                // News refresh cycle: fetch RSS news for enriched symbols
+                // News refresh cycle: fetch news for enriched symbols
                 // Process one symbol per loop iteration to stay responsive
                 // to new enrichment requests
                 let symbols: Vec<String> = enriched_set.iter().cloned().collect();
@@ -836,9 +1319,7 @@ pub fn spawn_enrichment_worker(
                     last_news_refresh = Instant::now();
                 } else {
                     let sym = &symbols[news_refresh_idx];
-                    if let Some(update) =
-                        rt_handle.block_on(crate::enrichment::fetch_news_only(&client, sym))
-                    {
+                    if let Some(update) = rt_handle.block_on(providers.fetch_news(sym)) {
                         let _ = bg_tx.send(BgMessage::NewsRefresh {
                             symbol: sym.clone(),
                             update,
@@ -875,50 +1356,51 @@ pub fn spawn_enrichment_worker(
     })
 }
 
+/// Spawn an enrichment worker that answers from the simulator instead of
+/// Yahoo, whose lookups would fail for simulated symbols.
+pub fn spawn_sim_enrichment_worker(
+    bg_tx: mpsc::Sender<BgMessage>,
+    enrich_rx: mpsc::Receiver<EnrichRequest>,
+    sim: Simulator,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        for req in enrich_rx {
+            // Empty symbol is the clear sentinel; nothing is cached here
+            if req.symbol.is_empty() {
+                continue;
+            }
+            let data = sim.enrichment(&req.symbol).unwrap_or_default();
+            if bg_tx.send(BgMessage::EnrichComplete { symbol: req.symbol, data }).is_err() {
+                return;
+            }
+        }
+    })
+}
+
 /// Spawn the market data streaming worker thread.
 ///
-/// Holds a persistent TWS connection and subscribes to real-time market data
-/// for symbols sent via `mktdata_rx`. Each subscription gets its own tokio task
-/// that forwards price/volume ticks to the engine via `bg_tx`.
+/// Subscribes to real-time market data from `source` for symbols sent via
+/// `mktdata_rx`. Each subscription gets its own tokio task that forwards
+/// price/volume ticks to the engine via `bg_tx`. When the feed drops
+/// (including the TWS nightly restart) the worker reconnects with backoff
+/// and re-subscribes every symbol.
 pub fn spawn_market_data_worker(
     bg_tx: mpsc::Sender<BgMessage>,
     mktdata_rx: mpsc::Receiver<MktDataRequest>,
-    host: String,
-    ports: Vec<u16>,
+    source: Arc<dyn MarketDataSource>,
 ) -> std::thread::JoinHandle<()> {
     std::thread::spawn(move || {
-        use ibapi::market_data::realtime::TickTypes;
-        use std::sync::Arc;
-
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        rt.block_on(async move {
-            // Connect to TWS (client_id 30 for streaming)
-            let ports_ref = if ports.is_empty() { DEFAULT_PORTS } else { &ports };
-            let mut client_opt = None;
-            for &port in ports_ref {
-                let addr = format!("{host}:{port}");
-                match ibapi::Client::connect(&addr, 30).await {
-                    Ok(c) => {
-                        info!(port, "market data stream connected");
-                        client_opt = Some(c);
-                        break;
-                    }
-                    Err(e) => {
-                        warn!(port, "market data stream connect failed: {e}");
-                    }
-                }
-            }
-            let client = match client_opt {
-                Some(c) => Arc::new(c),
-                None => {
-                    warn!("market data worker: could not connect to TWS");
-                    return;
-                }
-            };
+        tws::connection::runtime().block_on(async move {
+            let mut backoff = Backoff::default();
+            source.reconnect_streaming(&mut backoff).await;
 
-            let mut subscribed: HashSet<String> = HashSet::new();
-            // Cancel senders keyed by symbol — drop the sender to signal task cancellation.
+            // Symbols that should be streaming, with their currency
+            let mut subscribed: HashMap<String, String> = HashMap::new();
+            // Cancel senders keyed by symbol — send to signal task cancellation.
             let mut cancel_txs: HashMap<String, tokio::sync::oneshot::Sender<()>> = HashMap::new();
+            // Streams TWS ended without a cancel (e.g. after a connection reset)
+            let (ended_tx, mut ended_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+            let mut retry_at: HashMap<String, Instant> = HashMap::new();
 
             loop {
                 // Drain new subscription requests
@@ -932,6 +1414,7 @@ pub fn spawn_market_data_worker(
                                     info!(symbol = %sym, "cancelled streaming (clear all)");
                                 }
                                 subscribed.clear();
+                                retry_at.clear();
                                 continue;
                             }
                             if req.cancel {
@@ -941,133 +1424,58 @@ pub fn spawn_market_data_worker(
                                     info!(symbol = %req.symbol, "cancelled streaming (evicted)");
                                 }
                                 subscribed.remove(&req.symbol);
+                                retry_at.remove(&req.symbol);
                                 continue;
                             }
-                            if subscribed.contains(&req.symbol) {
+                            if subscribed.contains_key(&req.symbol) {
                                 continue;
                             }
-                            subscribed.insert(req.symbol.clone());
-
-                            let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel::<()>();
-                            cancel_txs.insert(req.symbol.clone(), cancel_tx);
-
-                            // Spawn an async task per symbol to stream ticks
-                            let client = Arc::clone(&client);
-                            let tx = bg_tx.clone();
-                            let symbol = req.symbol.clone();
-                            let currency = req.currency.clone();
-                            tokio::spawn(async move {
-                                let contract = ibapi::contracts::Contract::stock(&symbol);
-                                let cur = if currency.is_empty() { "USD" } else { &currency };
-                                let contract = ibapi::contracts::Contract {
-                                    currency: ibapi::contracts::Currency::from(cur),
-                                    ..contract.build()
-                                };
-
-                                let mut subscription = match client
-                                    .market_data(&contract)
-                                    .generic_ticks(&["233"]) // RTVolume for streaming volume updates
-                                    .subscribe()
-                                    .await
-                                {
-                                    Ok(s) => s,
-                                    Err(e) => {
-                                        warn!(symbol = %symbol, "market data subscribe failed: {e}");
-                                        return;
-                                    }
-                                };
-
-                                info!(symbol = %symbol, "streaming market data subscribed");
-                                let mut stored_close: Option<f64> = None;
-                                let mut cancel_rx = cancel_rx;
-
-                                loop {
-                                    tokio::select! {
-                                        _ = &mut cancel_rx => {
-                                            subscription.cancel().await;
-                                            info!(symbol = %symbol, "streaming market data cancelled");
-                                            break;
-                                        }
-                                        tick_opt = subscription.next() => {
-                                            let tick = match tick_opt {
-                                                Some(Ok(t)) => t,
-                                                _ => break,
-                                            };
-
-                                            let mut last = None;
-                                            let mut close = None;
-                                            let mut bid = None;
-                                            let mut ask = None;
-                                            let mut volume = None;
-
-                                            match tick {
-                                                TickTypes::Price(tp) => match tp.tick_type {
-                                                    ibapi::contracts::tick_types::TickType::Last => last = Some(tp.price),
-                                                    ibapi::contracts::tick_types::TickType::Close => {
-                                                        close = Some(tp.price);
-                                                        stored_close = close;
-                                                    }
-                                                    ibapi::contracts::tick_types::TickType::Bid => bid = Some(tp.price),
-                                                    ibapi::contracts::tick_types::TickType::Ask => ask = Some(tp.price),
-                                                    _ => continue,
-                                                },
-                                                TickTypes::PriceSize(tp) => {
-                                                    match tp.price_tick_type {
-                                                        ibapi::contracts::tick_types::TickType::Last => last = Some(tp.price),
-                                                        ibapi::contracts::tick_types::TickType::Close => {
-                                                            close = Some(tp.price);
-                                                            stored_close = close;
-                                                        }
-                                                        ibapi::contracts::tick_types::TickType::Bid => bid = Some(tp.price),
-                                                        ibapi::contracts::tick_types::TickType::Ask => ask = Some(tp.price),
-                                                        _ => {}
-                                                    }
-                                                    if tp.size_tick_type == ibapi::contracts::tick_types::TickType::Volume {
-                                                        volume = Some(tp.size as i64);
-                                                    }
-                                                }
-                                                TickTypes::Size(ts) => {
-                                                    if ts.tick_type == ibapi::contracts::tick_types::TickType::Volume {
-                                                        volume = Some(ts.size as i64);
-                                                    } else {
-                                                        continue;
-                                                    }
-                                                }
-                                                // RTVolume (tick 233): "price;size;time;totalVolume;vwap;single"
-                                                TickTypes::String(ts) if ts.tick_type == ibapi::contracts::tick_types::TickType::RtVolume => {
-                                                    let parts: Vec<&str> = ts.value.split(';').collect();
-                                                    if parts.len() >= 4 {
-                                                        if let Ok(tv) = parts[3].parse::<f64>() {
-                                                            volume = Some(tv as i64);
-                                                        }
-                                                        if let Ok(p) = parts[0].parse::<f64>() {
-                                                            if p > 0.0 {
-                                                                last = Some(p);
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                                _ => continue,
-                                            }
-
-                                            let _ = tx.send(BgMessage::MarketDataTick {
-                                                symbol: symbol.clone(),
-                                                last,
-                                                close: close.or(stored_close),
-                                                bid,
-                                                ask,
-                                                volume,
-                                            });
-                                        }
-                                    }
-                                }
-                            });
+                            subscribed.insert(req.symbol.clone(), req.currency.clone());
+                            let cancel_tx =
+                                spawn_stream(&source, &req.symbol, &req.currency, &bg_tx, &ended_tx);
+                            cancel_txs.insert(req.symbol, cancel_tx);
                         }
                         Err(mpsc::TryRecvError::Empty) => break,
                         Err(mpsc::TryRecvError::Disconnected) => return,
                     }
                 }
 
+                if !source.is_streaming() {
+                    warn!(source = source.name(), "market data stream lost its connection");
+                    for (_, cancel_tx) in cancel_txs.drain() {
+                        let _ = cancel_tx.send(());
+                    }
+                    retry_at.clear();
+                    source.reconnect_streaming(&mut backoff).await;
+                    info!(count = subscribed.len(), "re-subscribing streaming market data");
+                    for (sym, cur) in &subscribed {
+                        cancel_txs.insert(sym.clone(), spawn_stream(&source, sym, cur, &bg_tx, &ended_tx));
+                    }
+                    continue;
+                }
+
+                // Re-subscribe streams TWS ended while the connection stayed up,
+                // after a pause so a rejected symbol doesn't spin
+                while let Ok(sym) = ended_rx.try_recv() {
+                    if subscribed.contains_key(&sym) {
+                        cancel_txs.remove(&sym);
+                        retry_at.insert(sym, Instant::now() + Duration::from_secs(5));
+                    }
+                }
+                let due: Vec<String> = retry_at
+                    .iter()
+                    .filter(|(_, at)| **at <= Instant::now())
+                    .map(|(sym, _)| sym.clone())
+                    .collect();
+                for sym in due {
+                    retry_at.remove(&sym);
+                    if let Some(cur) = subscribed.get(&sym) {
+                        info!(symbol = %sym, "re-subscribing ended stream");
+                        let cancel_tx = spawn_stream(&source, &sym, cur, &bg_tx, &ended_tx);
+                        cancel_txs.insert(sym, cancel_tx);
+                    }
+                }
+
                 // Sleep briefly before checking for new requests
                 tokio::time::sleep(Duration::from_millis(50)).await;
             }
@@ -1075,24 +1483,144 @@ pub fn spawn_market_data_worker(
     })
 }
 
+/// Stream one symbol's ticks to the engine until cancelled. Returns the
+/// cancel sender. A stream that ends on its own reports on `ended_tx`.
+fn spawn_stream(
+    source: &Arc<dyn MarketDataSource>,
+    symbol: &str,
+    currency: &str,
+    bg_tx: &mpsc::Sender<BgMessage>,
+    ended_tx: &tokio::sync::mpsc::UnboundedSender<String>,
+) -> tokio::sync::oneshot::Sender<()> {
+    use futures::StreamExt;
+
+    let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    let source = Arc::clone(source);
+    let tx = bg_tx.clone();
+    let ended_tx = ended_tx.clone();
+    let symbol = symbol.to_string();
+    let currency = currency.to_string();
+
+    tokio::spawn(async move {
+        let mut ticks = match source.subscribe(&symbol, &currency).await {
+            Ok(s) => s,
+            Err(e) => {
+                warn!(symbol = %symbol, "market data subscribe failed: {e}");
+                return;
+            }
+        };
+
+        info!(symbol = %symbol, "streaming market data subscribed");
+        let mut stored_close: Option<f64> = None;
+
+        loop {
+            tokio::select! {
+                _ = &mut cancel_rx => {
+                    // Dropping the stream cancels the subscription
+                    info!(symbol = %symbol, "streaming market data cancelled");
+                    return;
+                }
+                tick = ticks.next() => {
+                    let Some(MarketTick {
+                        last,
+                        close,
+                        bid,
+                        ask,
+                        volume,
+                        vwap,
+                        high,
+                        bid_size,
+                        ask_size,
+                        halted,
+                        borrow,
+                        shortable_shares,
+                    }) = tick
+                    else {
+                        break;
+                    };
+                    if close.is_some() {
+                        stored_close = close;
+                    }
+                    let _ = tx.send(BgMessage::MarketDataTick {
+                        symbol: symbol.clone(),
+                        last,
+                        close: close.or(stored_close),
+                        bid,
+                        ask,
+                        volume,
+                        vwap,
+                        high,
+                        bid_size,
+                        ask_size,
+                        halted,
+                        borrow,
+                        shortable_shares,
+                        at: chrono::Utc::now(),
+                    });
+                }
+            }
+        }
+
+        warn!(symbol = %symbol, "streaming market data ended");
+        let _ = ended_tx.send(symbol);
+    });
+
+    cancel_tx
+}
+
+/// Spawn the broad-tape news worker thread.
+///
+/// Forwards every headline from `source.subscribe_news` to the engine,
+/// which matches it to alert rows. If the subscription fails or ends
+/// (no news permissions, connection reset) it is retried with backoff.
+pub fn spawn_news_worker(
+    bg_tx: mpsc::Sender<BgMessage>,
+    source: Arc<dyn MarketDataSource>,
+) -> std::thread::JoinHandle<()> {
+    use futures::StreamExt;
+
+    std::thread::spawn(move || {
+        tws::connection::runtime().block_on(async move {
+            let mut backoff = Backoff::new(Duration::from_secs(30), Duration::from_secs(30 * 60));
+            loop {
+                match source.subscribe_news().await {
+                    Ok(mut headlines) => {
+                        info!(source = source.name(), "broad-tape news subscribed");
+                        backoff.reset();
+                        while let Some(headline) = headlines.next().await {
+                            if bg_tx.send(BgMessage::NewsTick { headline }).is_err() {
+                                return;
+                            }
+                        }
+                        warn!("broad-tape news ended");
+                    }
+                    Err(e) => warn!("broad-tape news unavailable: {e}"),
+                }
+                tokio::time::sleep(backoff.next_delay()).await;
+            }
+        });
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::criteria::Pillar;
     use std::collections::BinaryHeap;
 
     #[test]
     fn test_enrich_request_priority_ordering() {
         let low = EnrichRequest {
             symbol: "LOW".to_string(),
-            scanner_hits: 1,
+            score: 10.0,
         };
         let mid = EnrichRequest {
             symbol: "MID".to_string(),
-            scanner_hits: 4,
+            score: 40.0,
         };
         let high = EnrichRequest {
             symbol: "HIGH".to_string(),
-            scanner_hits: 8,
+            score: 80.0,
         };
         assert!(high > mid);
         assert!(mid > low);
@@ -1156,6 +1684,7 @@ mod tests {
             enriched: false,
             avg_volume: None,
             avg_volume_10d: None,
+            ..Default::default()
         });
         let count = engine.poll_clear();
         assert_eq!(count, 2);
@@ -1172,13 +1701,554 @@ mod tests {
         assert!(events.is_empty());
     }
 
+    #[test]
+    fn test_connection_state_events() {
+        let (tx, _rx) = mpsc::channel();
+        let mut engine = AlertEngine::new(tx, Settings::default(), None);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        engine
+            .bg_tx
+            .send(BgMessage::Connection {
+                role: ClientRole::Streaming,
+                state: ConnectionState::Connected { port: 7497 },
+            })
+            .unwrap();
+        let events = engine.tick(rt.handle());
+        assert_eq!(engine.connected_port, Some(7497));
+        assert!(matches!(
+            events.as_slice(),
+            [EngineEvent::ConnectionChanged { role: ClientRole::Streaming, .. }]
+        ));
+
+        engine
+            .bg_tx
+            .send(BgMessage::Connection {
+                role: ClientRole::Streaming,
+                state: ConnectionState::Reconnecting { attempt: 1, delay: Duration::from_secs(1) },
+            })
+            .unwrap();
+        engine.tick(rt.handle());
+        // The poll connection is still up
+        assert_eq!(engine.connected_port, Some(7497));
+
+        engine
+            .bg_tx
+            .send(BgMessage::Connection {
+                role: ClientRole::Poll,
+                state: ConnectionState::Reconnecting { attempt: 1, delay: Duration::from_secs(1) },
+            })
+            .unwrap();
+        engine.tick(rt.handle());
+        assert_eq!(engine.connected_port, None);
+    }
+
+    #[test]
+    fn test_poll_cycle_from_simulator() {
+        let (tx, _rx) = mpsc::channel();
+        let sim = Simulator::new(crate::market_data::SimConfig {
+            step: Duration::from_secs(3600),
+            ..Default::default()
+        });
+        sim.advance(20);
+        let mut engine =
+            AlertEngine::new(tx, Settings::default(), None).with_source(Arc::new(sim));
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        engine.run_poll_scanners();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let total = loop {
+            let events = engine.tick(rt.handle());
+            if let Some(EngineEvent::PollCycleComplete { total_stocks, .. }) = events
+                .iter()
+                .find(|e| matches!(e, EngineEvent::PollCycleComplete { .. }))
+            {
+                break *total_stocks;
+            }
+            assert!(Instant::now() < deadline, "poll cycle did not complete");
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert!(total > 0);
+        assert_eq!(engine.connected_port, Some(crate::market_data::sim::SIM_PORT));
+        assert!(!engine.alert_rows.is_empty());
+    }
+
+    #[test]
+    fn test_poll_stamped_with_event_time() {
+        let (tx, _rx) = mpsc::channel();
+        let db = crate::history::open_memory_store().unwrap();
+        let mut engine = AlertEngine::new(tx, Settings::default(), Some(SharedStore::clone(&db)))
+            .with_source(Arc::new(Simulator::new(Default::default())));
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        // A replayed poll from 09:35 New York
+        let at = "2026-01-02T14:35:00Z".parse().unwrap();
+        let result = ScanResult {
+            symbol: "ABCD".to_string(),
+            last: Some(4.5),
+            ..Default::default()
+        };
+        engine
+            .bg_tx
+            .send(BgMessage::PollComplete {
+                symbol_data: HashMap::from([("ABCD".to_string(), result)]),
+                symbol_scanners: HashMap::from([("ABCD".to_string(), vec!["TOP_PERC_GAIN".to_string()])]),
+                scanner_ranks: HashMap::from([(("ABCD".to_string(), "TOP_PERC_GAIN".to_string()), 1)]),
+                port: None,
+                scanners_run: 1,
+                elapsed_secs: 0.5,
+                at,
+            })
+            .unwrap();
+        engine.tick(rt.handle());
+        assert_eq!(engine.alert_rows[0].alert_time, "09:35:00");
+
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 1, 2).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let sightings = loop {
+            let sightings = rt.block_on(db.get_trajectory("ABCD", date)).unwrap();
+            if !sightings.is_empty() {
+                break sightings;
+            }
+            assert!(Instant::now() < deadline, "sighting was not written");
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(sightings[0].seen_at, "2026-01-02T14:35:00Z");
+    }
+
+    #[test]
+    fn test_ticks_update_quote() {
+        let (tx, _rx) = mpsc::channel();
+        let mut engine = AlertEngine::new(tx, Settings::default(), None);
+        engine.settings.criteria = MomentumCriteria {
+            max_spread_pct: Some(1.0),
+            exclude_halted: true,
+            ..MomentumCriteria::default()
+        };
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        engine.alert_rows.push(AlertRow {
+            symbol: "ABCD".to_string(),
+            ..Default::default()
+        });
+        let tick = |bid, ask, halted| BgMessage::MarketDataTick {
+            symbol: "ABCD".to_string(),
+            last: None,
+            close: None,
+            bid,
+            ask,
+            volume: None,
+            vwap: None,
+            high: None,
+            bid_size: bid.map(|_| Shares(500)),
+            ask_size: None,
+            halted,
+            borrow: None,
+            shortable_shares: None,
+            at: chrono::Utc::now(),
+        };
+        engine.bg_tx.send(tick(Some(4.9), Some(5.1), Some(HaltState::Trading))).unwrap();
+        engine.tick(rt.handle());
+        let row = &engine.alert_rows[0];
+        assert!((row.quote.spread_pct.unwrap() - 4.0).abs() < 1e-9);
+        assert_eq!(row.quote.size(), Some(Shares(500)));
+        assert!(row.pillars.failed.contains(&Pillar::Spread));
+        assert!(row.pillars.passed.contains(&Pillar::Halted));
+
+        // A halt arrives on its own; the quote is kept
+        engine.bg_tx.send(tick(None, None, Some(HaltState::Halted))).unwrap();
+        engine.tick(rt.handle());
+        let row = &engine.alert_rows[0];
+        assert_eq!(row.quote.bid, Some(4.9));
+        assert!(row.pillars.failed.contains(&Pillar::Halted));
+    }
+
+    #[test]
+    fn test_halt_and_resume_events() {
+        let (tx, _rx) = mpsc::channel();
+        let mut engine = AlertEngine::new(tx, Settings::default(), None);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        engine.alert_rows.push(AlertRow {
+            symbol: "ABCD".to_string(),
+            ..Default::default()
+        });
+        let tick = |last, halted| BgMessage::MarketDataTick {
+            symbol: "ABCD".to_string(),
+            last,
+            close: None,
+            bid: None,
+            ask: None,
+            volume: None,
+            vwap: None,
+            high: None,
+            bid_size: None,
+            ask_size: None,
+            halted,
+            borrow: None,
+            shortable_shares: None,
+            at: chrono::Utc::now(),
+        };
+        engine.bg_tx.send(tick(Some(2.45), Some(HaltState::Trading))).unwrap();
+        assert!(engine.tick(rt.handle()).is_empty());
+
+        engine.bg_tx.send(tick(None, Some(HaltState::Volatility))).unwrap();
+        let events = engine.tick(rt.handle());
+        assert!(matches!(
+            events.as_slice(),
+            [EngineEvent::Halted { symbol, state: HaltState::Volatility, price: Some(p), .. }]
+                if symbol == "ABCD" && *p == 2.45
+        ));
+        // Repeated halted ticks are the same halt
+        engine.bg_tx.send(tick(None, Some(HaltState::Halted))).unwrap();
+        assert!(engine.tick(rt.handle()).is_empty());
+
+        engine.bg_tx.send(tick(Some(2.80), Some(HaltState::Trading))).unwrap();
+        let events = engine.tick(rt.handle());
+        assert!(matches!(
+            events.as_slice(),
+            [EngineEvent::Resumed { halt_price: Some(p), duration, .. }]
+                if *p == 2.45 && *duration >= chrono::Duration::zero()
+        ));
+        assert_eq!(engine.alert_rows[0].quote.halted_at, None);
+    }
+
+    #[test]
+    fn test_news_tick_matches_rows() {
+        let (tx, _rx) = mpsc::channel();
+        let mut engine = AlertEngine::new(tx, Settings::default(), None);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        for symbol in ["ABCD", "WXYZ"] {
+            engine.alert_rows.push(AlertRow {
+                symbol: symbol.to_string(),
+                catalyst: Some("WXYZ beats earnings".to_string()),
+                ..Default::default()
+            });
+        }
+        let headline = NewsHeadline {
+            source: Some("BRFG".to_string()),
+            article_id: Some("BRFG$1".to_string()),
+            ..NewsHeadline::new("ABCD announces merger with private peer", Some(1_700_000_000))
+        };
+        engine.bg_tx.send(BgMessage::NewsTick { headline: headline.clone() }).unwrap();
+        let events = engine.tick(rt.handle());
+        assert!(matches!(
+            events.as_slice(),
+            [EngineEvent::NewsTick { symbol, headline: h }] if symbol == "ABCD" && *h == headline
+        ));
+        assert_eq!(engine.alert_rows[0].news_headlines, vec![headline.clone()]);
+        assert_eq!(engine.alert_rows[0].catalyst.as_deref(), Some("ABCD announces merger with private peer"));
+        assert_eq!(engine.alert_rows[0].catalyst_kind, Some(crate::catalyst::CatalystKind::Merger));
+        assert!(engine.alert_rows[1].news_headlines.is_empty());
+
+        // The same headline again is not news
+        engine.bg_tx.send(BgMessage::NewsTick { headline }).unwrap();
+        assert!(engine.tick(rt.handle()).is_empty());
+        assert_eq!(engine.alert_rows[0].news_headlines.len(), 1);
+    }
+
+    #[test]
+    fn test_news_refresh_keeps_tick_headlines() {
+        let (tx, _rx) = mpsc::channel();
+        let mut engine = AlertEngine::new(tx, Settings::default(), None);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        engine.alert_rows.push(AlertRow {
+            symbol: "ABCD".to_string(),
+            ..Default::default()
+        });
+        let tick = NewsHeadline {
+            source: Some("BRFG".to_string()),
+            article_id: Some("BRFG$1".to_string()),
+            ..NewsHeadline::new("ABCD announces merger with private peer", Some(1_700_000_000))
+        };
+        engine.bg_tx.send(BgMessage::NewsTick { headline: tick.clone() }).unwrap();
+        engine.tick(rt.handle());
+
+        // The refresh has older news, and the tick headline again as Yahoo has it
+        let update = crate::enrichment::NewsUpdate::from_news(
+            vec![
+                NewsHeadline::new("ABCD Announces Merger With Private Peer", Some(1_700_000_000)),
+                NewsHeadline::new("ABCD to present at investor conference", Some(1_600_000_000)),
+            ],
+            Vec::new(),
+        );
+        engine.bg_tx.send(BgMessage::NewsRefresh { symbol: "ABCD".to_string(), update }).unwrap();
+        engine.tick(rt.handle());
+        let row = &engine.alert_rows[0];
+        let titles: Vec<&str> = row.news_headlines.iter().map(|h| h.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["ABCD announces merger with private peer", "ABCD to present at investor conference"]
+        );
+        assert_eq!(row.news_headlines[0].article(), Some(("BRFG", "BRFG$1")));
+        assert_eq!(row.catalyst_kind, Some(crate::catalyst::CatalystKind::Merger));
+
+        // A refresh without it keeps the tick headline
+        let update = crate::enrichment::NewsUpdate::from_news(
+            vec![NewsHeadline::new("ABCD to present at investor conference", Some(1_600_000_000))],
+            Vec::new(),
+        );
+        engine.bg_tx.send(BgMessage::NewsRefresh { symbol: "ABCD".to_string(), update }).unwrap();
+        engine.tick(rt.handle());
+        let row = &engine.alert_rows[0];
+        assert_eq!(row.news_headlines.len(), 2);
+        assert_eq!(row.catalyst.as_deref(), Some("ABCD announces merger with private peer"));
+    }
+
+    #[test]
+    fn test_ticks_update_levels() {
+        let (tx, _rx) = mpsc::channel();
+        let mut engine = AlertEngine::new(tx, Settings::default(), None);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        engine.alert_rows.push(AlertRow {
+            symbol: "ABCD".to_string(),
+            ..Default::default()
+        });
+        engine
+            .bg_tx
+            .send(BgMessage::MarketDataTick {
+                symbol: "ABCD".to_string(),
+                last: Some(5.5),
+                close: Some(5.0),
+                bid: None,
+                ask: None,
+                volume: None,
+                vwap: Some(5.0),
+                high: Some(6.0),
+                bid_size: None,
+                ask_size: None,
+                halted: None,
+                borrow: None,
+                shortable_shares: None,
+                // After the open, so the tick is no premarket trade
+                at: crate::session::eastern_time(crate::session::today(), 10, 0),
+            })
+            .unwrap();
+        engine.tick(rt.handle());
+
+        let levels = &engine.alert_rows[0].levels;
+        assert_eq!(levels.prior_close, Some(5.0));
+        assert_eq!(levels.high_of_day, Some(6.0));
+        assert!((levels.from_vwap_pct.unwrap() - 10.0).abs() < 1e-9);
+        assert!(levels.gap_pct.is_some());
+        assert_eq!(engine.alert_rows[0].short.ssr, Some(false));
+
+        let open = crate::session::eastern_time(crate::session::today(), 9, 30);
+        let bar = |time, high| crate::market_data::Bar {
+            time,
+            open: 5.2,
+            high,
+            low: 5.1,
+            close: 5.2,
+            volume: 100.0,
+        };
+        engine
+            .bg_tx
+            .send(BgMessage::IntradayBars {
+                symbol: "ABCD".to_string(),
+                bars: vec![bar(open - chrono::Duration::minutes(5), 5.3), bar(open, 6.5)],
+            })
+            .unwrap();
+        engine.tick(rt.handle());
+
+        let levels = &engine.alert_rows[0].levels;
+        assert_eq!(levels.premarket_high, Some(5.3));
+        assert_eq!(levels.high_of_day, Some(6.5));
+        assert_eq!(levels.open, Some(5.2));
+        // The feed's VWAP is kept over the bars' estimate
+        assert_eq!(levels.vwap, Some(5.0));
+        assert!((levels.gap_pct.unwrap() - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ticks_use_event_time() {
+        let (tx, _rx) = mpsc::channel();
+        let mut engine = AlertEngine::new(tx, Settings::default(), None);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        engine.alert_rows.push(AlertRow {
+            symbol: "ABCD".to_string(),
+            ..Default::default()
+        });
+        let tick = |last, at: &str| BgMessage::MarketDataTick {
+            symbol: "ABCD".to_string(),
+            last: Some(last),
+            close: None,
+            bid: None,
+            ask: None,
+            volume: None,
+            vwap: None,
+            high: None,
+            bid_size: None,
+            ask_size: None,
+            halted: None,
+            borrow: None,
+            shortable_shares: None,
+            at: at.parse().unwrap(),
+        };
+        // A replayed session: 08:00 and 10:00 New York, whenever it is now
+        engine.bg_tx.send(tick(5.2, "2026-01-02T13:00:00Z")).unwrap();
+        engine.tick(rt.handle());
+        let levels = &engine.alert_rows[0].levels;
+        assert_eq!(levels.premarket_high, Some(5.2));
+        assert_eq!(levels.open, None);
+
+        engine.bg_tx.send(tick(5.6, "2026-01-02T15:00:00Z")).unwrap();
+        engine.tick(rt.handle());
+        let levels = &engine.alert_rows[0].levels;
+        assert_eq!(levels.premarket_high, Some(5.2));
+        assert_eq!(levels.open, Some(5.6));
+    }
+
+    #[test]
+    fn test_scores_use_market_time() {
+        let (tx, _rx) = mpsc::channel();
+        let mut engine = AlertEngine::new(tx, Settings::default(), None);
+        let published = "2026-01-02T14:00:00Z".parse::<chrono::DateTime<chrono::Utc>>().unwrap();
+        engine.alert_rows.push(AlertRow {
+            symbol: "ABCD".to_string(),
+            catalyst: Some("ABCD wins FDA approval".to_string()),
+            catalyst_time: Some(published.timestamp()),
+            ..Default::default()
+        });
+
+        // A replay scores the catalyst's freshness as of the recorded session
+        engine.market_time = Some(published);
+        engine.update_scores();
+        let fresh = engine.alert_rows[0].score;
+        assert_eq!(engine.streaming_priority("ABCD"), fresh);
+        engine.market_time = Some(published + chrono::Duration::hours(24));
+        engine.update_scores();
+        assert!((fresh - engine.alert_rows[0].score - 7.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rvol_prefers_time_of_day() {
+        let (tx, _rx) = mpsc::channel();
+        let mut engine = AlertEngine::new(tx, Settings::default(), None);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        engine.alert_rows.push(AlertRow {
+            symbol: "ABCD".to_string(),
+            volume: Some(Shares(1_000_000)),
+            avg_volume_10d: Some(Shares(10_000_000)),
+            ..Default::default()
+        });
+        engine.refresh_derived();
+        assert_eq!(engine.alert_rows[0].rvol, Some(0.1));
+        assert_eq!(engine.alert_rows[0].rvol_time_of_day, None);
+
+        // Prior sessions had traded 250K shares by every time of day
+        let profile = VolumeProfile {
+            cumulative: vec![250_000.0; 288],
+            sessions: 10,
+        };
+        engine
+            .bg_tx
+            .send(BgMessage::VolumeProfile { symbol: "ABCD".to_string(), profile })
+            .unwrap();
+        engine.tick(rt.handle());
+        let row = &engine.alert_rows[0];
+        assert_eq!(row.rvol_full_day, Some(0.1));
+        assert!(row.rvol_time_of_day.unwrap() >= 4.0);
+        assert_eq!(row.rvol, row.rvol_time_of_day);
+
+        // RVOL is taken at the latest event's time, e.g. a replayed 10:00,
+        // by which 1.2M shares had traded at 10K per five minutes
+        let ramp = VolumeProfile {
+            cumulative: (1..=288).map(|slot| slot as f64 * 10_000.0).collect(),
+            sessions: 10,
+        };
+        engine.volume_profiles.insert("ABCD".to_string(), ramp);
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 1, 2).unwrap();
+        engine.market_time = Some(crate::session::eastern_time(date, 10, 0));
+        engine.refresh_derived();
+        let tod = engine.alert_rows[0].rvol_time_of_day.unwrap();
+        assert!((tod - 1_000_000.0 / 1_200_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_criteria_match_after_enrichment() {
+        let (tx, _rx) = mpsc::channel();
+        let mut engine = AlertEngine::new(tx, Settings::default(), None);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        engine.alert_rows.push(AlertRow {
+            symbol: "ABCD".to_string(),
+            last: Some(5.0),
+            change_pct: Some(25.0),
+            volume: Some(Shares(10_000_000)),
+            ..Default::default()
+        });
+
+        let data = EnrichmentData {
+            float_shares: Some(4_000_000.0),
+            avg_volume_10d: Some(Shares(1_000_000)),
+            catalyst: Some("FDA approval".to_string()),
+            ..Default::default()
+        };
+        engine
+            .bg_tx
+            .send(BgMessage::EnrichComplete {
+                symbol: "ABCD".to_string(),
+                data: data.clone(),
+            })
+            .unwrap();
+        let events = engine.tick(rt.handle());
+        let matches = events
+            .iter()
+            .filter(|e| matches!(e, EngineEvent::CriteriaMatch { symbol, .. } if symbol == "ABCD"))
+            .count();
+        assert_eq!(matches, 1);
+        assert!(engine.alert_rows[0].pillars.passes_all());
+
+        // A second update for an already-matched symbol does not re-announce it
+        engine
+            .bg_tx
+            .send(BgMessage::EnrichComplete {
+                symbol: "ABCD".to_string(),
+                data,
+            })
+            .unwrap();
+        let events = engine.tick(rt.handle());
+        assert!(!events.iter().any(|e| matches!(e, EngineEvent::CriteriaMatch { .. })));
+    }
+
+    #[test]
+    fn test_set_criteria_reevaluates_rows() {
+        let (tx, _rx) = mpsc::channel();
+        let mut engine = AlertEngine::new(tx, Settings::default(), None);
+        engine.alert_rows.push(AlertRow {
+            symbol: "BIG".to_string(),
+            last: Some(150.0),
+            change_pct: Some(5.0),
+            ..Default::default()
+        });
+        engine.set_criteria(MomentumCriteria::default());
+        assert!(!engine.alert_rows[0].pillars.passes_all());
+
+        engine.set_criteria(MomentumCriteria {
+            name: "gappers".to_string(),
+            min_price: Some(20.0),
+            max_price: None,
+            min_change_pct: Some(4.0),
+            min_rvol: None,
+            max_float: None,
+            min_short_pct: None,
+            require_catalyst: false,
+            max_spread_pct: None,
+            min_quote_size: None,
+            exclude_halted: false,
+            max_shortable_shares: None,
+            exclude_ssr: false,
+            pass_if_unknown: vec![],
+        });
+        assert!(engine.alert_rows[0].pillars.passes_all());
+        assert!(engine.criteria_matched.contains("BIG"));
+    }
+
     #[test]
     fn test_streaming_priority() {
         let (tx, _rx) = mpsc::channel();
         let mut engine = AlertEngine::new(tx, Settings::default(), None);
 
         // Unknown symbol gets 0
-        assert_eq!(engine.streaming_priority("UNKNOWN"), 0);
+        assert_eq!(engine.streaming_priority("UNKNOWN"), 0.0);
 
         // Symbol with scanner_hits but no catalyst
         engine.alert_rows.push(AlertRow {
@@ -1202,10 +2272,12 @@ mod tests {
             enriched: false,
             avg_volume: None,
             avg_volume_10d: None,
+            ..Default::default()
         });
-        assert_eq!(engine.streaming_priority("AAPL"), 4);
+        let aapl = engine.streaming_priority("AAPL");
+        assert!(aapl > 0.0);
 
-        // Symbol with catalyst gets +3
+        // Catalyst and a bigger move outrank more scanner hits
         engine.alert_rows.push(AlertRow {
             symbol: "TSLA".to_string(),
             alert_time: "10:00:00".to_string(),
@@ -1227,8 +2299,13 @@ mod tests {
             enriched: false,
             avg_volume: None,
             avg_volume_10d: None,
+            ..Default::default()
         });
-        assert_eq!(engine.streaming_priority("TSLA"), 5); // 2 + 3
+        assert!(engine.streaming_priority("TSLA") > aapl);
+
+        engine.update_scores();
+        engine.sort_alert_rows();
+        assert_eq!(engine.alert_rows[0].symbol, "TSLA");
     }
 
     #[test]
@@ -1264,6 +2341,7 @@ mod tests {
                 enriched: false,
                 avg_volume: None,
                 avg_volume_10d: None,
+                ..Default::default()
             });
             engine.subscribe_market_data(&sym, "USD");
         }
@@ -1291,6 +2369,7 @@ mod tests {
             enriched: false,
             avg_volume: None,
             avg_volume_10d: None,
+            ..Default::default()
         });
         engine.subscribe_market_data("LOWPRI", "USD");
         assert!(!engine.streaming_set.contains("LOWPRI"));
@@ -1318,6 +2397,7 @@ mod tests {
             enriched: false,
             avg_volume: None,
             avg_volume_10d: None,
+            ..Default::default()
         });
         engine.subscribe_market_data("HIGHPRI", "USD");
         assert!(engine.streaming_set.contains("HIGHPRI"));
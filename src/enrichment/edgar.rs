@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate};
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::debug;
+
+use super::{EnrichmentData, EnrichmentProvider, NewsUpdate};
+use crate::config::{EdgarConfig, ProviderKind};
+use crate::models::Filing;
+
+const TICKERS_URL: &str = "https://www.sec.gov/files/company_tickers.json";
+const SUBMISSIONS_URL: &str = "https://data.sec.gov/submissions";
+const ARCHIVES_URL: &str = "https://www.sec.gov/Archives/edgar/data";
+
+/// Most filings kept per symbol.
+const MAX_FILINGS: usize = 10;
+
+/// Ownership reports (insider forms 3/4/5, Rule 144 notices); frequent and
+/// rarely move a stock.
+const SKIPPED_FORMS: &[&str] = &["3", "4", "5", "3/A", "4/A", "5/A", "144", "144/A"];
+
+/// One entry of `company_tickers.json`, keyed "0", "1", ...
+#[derive(Deserialize)]
+struct TickerEntry {
+    cik_str: u64,
+    ticker: String,
+}
+
+/// `submissions/CIK##########.json`, reduced to the recent filings, which
+/// EDGAR lists newest first as parallel arrays.
+#[derive(Deserialize)]
+struct Submissions {
+    filings: SubmissionFilings,
+}
+
+#[derive(Deserialize)]
+struct SubmissionFilings {
+    recent: RecentFilings,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecentFilings {
+    accession_number: Vec<String>,
+    filing_date: Vec<String>,
+    #[serde(default)]
+    acceptance_date_time: Vec<String>,
+    form: Vec<String>,
+    #[serde(default)]
+    items: Vec<String>,
+    #[serde(default)]
+    primary_document: Vec<String>,
+    #[serde(default)]
+    primary_doc_description: Vec<String>,
+}
+
+/// Parse `company_tickers.json` into ticker -> CIK.
+fn parse_tickers(json: &str) -> Result<HashMap<String, u64>> {
+    let entries: HashMap<String, TickerEntry> =
+        serde_json::from_str(json).context("unexpected company_tickers.json")?;
+    Ok(entries.into_values().map(|e| (e.ticker.to_uppercase(), e.cik_str)).collect())
+}
+
+/// What an 8-K item reports.
+fn item_label(item: &str) -> Option<&'static str> {
+    Some(match item {
+        "1.01" => "Material agreement",
+        "1.02" => "Agreement terminated",
+        "1.03" => "Bankruptcy",
+        "2.01" => "Acquisition or disposition completed",
+        "2.02" => "Results of operations",
+        "2.03" => "Financial obligation",
+        "3.01" => "Delisting notice",
+        "3.02" => "Unregistered sale of equity",
+        "3.03" => "Rights of holders modified",
+        "4.01" => "Auditor change",
+        "4.02" => "Non-reliance on financials",
+        "5.01" => "Change in control",
+        "5.02" => "Officer or director change",
+        "5.03" => "Bylaws amended",
+        "5.07" => "Shareholder vote",
+        "7.01" => "Regulation FD disclosure",
+        "8.01" => "Other events",
+        "9.01" => "Financial statements and exhibits",
+        _ => return None,
+    })
+}
+
+/// What a form is, for forms whose type says more than EDGAR's
+/// document description.
+fn form_label(form: &str) -> Option<&'static str> {
+    let form = form.trim_end_matches("/A");
+    Some(match form {
+        "S-1" | "F-1" => "Registration statement",
+        "S-3" | "F-3" | "S-3ASR" => "Shelf registration",
+        "S-8" => "Employee plan registration",
+        "EFFECT" => "Registration effective",
+        "10-Q" => "Quarterly report",
+        "10-K" | "20-F" => "Annual report",
+        "6-K" => "Foreign issuer report",
+        "SC 13D" => "Activist stake",
+        "SC 13G" => "Passive stake",
+        "DEF 14A" => "Proxy statement",
+        f if f.starts_with("424B") => "Prospectus",
+        _ => return None,
+    })
+}
+
+/// Title for a filing: an 8-K's items (the 9.01 exhibits item only if
+/// alone), else the form's label, else EDGAR's description.
+fn filing_title(form: &str, items: &[String], description: &str) -> String {
+    let labels: Vec<&str> = items.iter().filter_map(|i| item_label(i)).collect();
+    let material: Vec<&str> = labels
+        .iter()
+        .copied()
+        .filter(|l| *l != "Financial statements and exhibits")
+        .collect();
+    if !material.is_empty() {
+        return material.join("; ");
+    }
+    if let Some(label) = labels.first().copied().or_else(|| form_label(form)) {
+        return label.to_string();
+    }
+    if description.is_empty() { form.to_string() } else { description.to_string() }
+}
+
+/// Filing time: the acceptance timestamp, else midnight UTC of the filing
+/// date.
+fn filed_at(acceptance: Option<&str>, filing_date: &str) -> Option<i64> {
+    acceptance
+        .and_then(|a| DateTime::parse_from_rfc3339(a).ok())
+        .map(|t| t.timestamp())
+        .or_else(|| {
+            let date = NaiveDate::parse_from_str(filing_date, "%Y-%m-%d").ok()?;
+            Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp())
+        })
+}
+
+/// Recent filings of company `cik` filed at or after `since`, newest first,
+/// ownership reports skipped, at most `MAX_FILINGS`.
+fn parse_filings(json: &str, cik: u64, since: i64) -> Result<Vec<Filing>> {
+    let submissions: Submissions =
+        serde_json::from_str(json).context("unexpected EDGAR submissions JSON")?;
+    let recent = submissions.filings.recent;
+    fn at(v: &[String], i: usize) -> &str {
+        v.get(i).map(String::as_str).unwrap_or("")
+    }
+
+    let mut filings = Vec::new();
+    for (i, form) in recent.form.iter().enumerate() {
+        if SKIPPED_FORMS.contains(&form.as_str()) {
+            continue;
+        }
+        let acceptance = recent.acceptance_date_time.get(i).map(String::as_str);
+        let filed = filed_at(acceptance, at(&recent.filing_date, i));
+        if filed.is_some_and(|t| t < since) {
+            // Newest first: everything after is older still
+            break;
+        }
+        let items: Vec<String> = at(&recent.items, i)
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        let accession = at(&recent.accession_number, i).to_string();
+        let document = at(&recent.primary_document, i);
+        let url = (!document.is_empty()).then(|| {
+            format!("{ARCHIVES_URL}/{cik}/{}/{document}", accession.replace('-', ""))
+        });
+        filings.push(Filing {
+            title: filing_title(form, &items, at(&recent.primary_doc_description, i)),
+            form: form.clone(),
+            filed,
+            items,
+            accession,
+            url,
+        });
+        if filings.len() == MAX_FILINGS {
+            break;
+        }
+    }
+    Ok(filings)
+}
+
+/// Recent SEC filings from EDGAR's JSON API: tickers are mapped to CIKs
+/// with `company_tickers.json` (fetched once), then each company's
+/// submissions give form types, filing times and 8-K items.
+pub struct EdgarProvider {
+    client: Client,
+    tickers_url: String,
+    submissions_url: String,
+    user_agent: String,
+    lookback: chrono::Duration,
+    ciks: tokio::sync::OnceCell<HashMap<String, u64>>,
+}
+
+impl EdgarProvider {
+    pub fn new(config: &EdgarConfig) -> Self {
+        let (tickers_url, submissions_url) = match config.base_url.as_deref() {
+            Some(base) => {
+                let base = base.trim_end_matches('/');
+                (format!("{base}/files/company_tickers.json"), format!("{base}/submissions"))
+            }
+            None => (TICKERS_URL.to_string(), SUBMISSIONS_URL.to_string()),
+        };
+        Self {
+            client: Client::new(),
+            tickers_url,
+            submissions_url,
+            user_agent: config.user_agent.clone(),
+            lookback: chrono::Duration::days(config.lookback_days.into()),
+            ciks: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    async fn get(&self, url: &str) -> Result<String> {
+        let resp = self
+            .client
+            .get(url)
+            .header("User-Agent", &self.user_agent)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(resp.text().await?)
+    }
+
+    async fn cik(&self, symbol: &str) -> Result<Option<u64>> {
+        let ciks = self
+            .ciks
+            .get_or_try_init(|| async {
+                let map = parse_tickers(&self.get(&self.tickers_url).await?)?;
+                debug!(tickers = map.len(), "EDGAR ticker map loaded");
+                anyhow::Ok(map)
+            })
+            .await?;
+        Ok(ciks.get(&symbol.to_uppercase()).copied())
+    }
+
+    /// Recent filings, newest first; none if EDGAR doesn't know the ticker.
+    pub async fn filings(&self, symbol: &str) -> Result<Vec<Filing>> {
+        let Some(cik) = self.cik(symbol).await? else {
+            debug!(symbol, "no CIK for ticker");
+            return Ok(Vec::new());
+        };
+        let json = self.get(&format!("{}/CIK{cik:010}.json", self.submissions_url)).await?;
+        let since = (chrono::Utc::now() - self.lookback).timestamp();
+        parse_filings(&json, cik, since)
+    }
+}
+
+#[async_trait]
+impl EnrichmentProvider for EdgarProvider {
+    fn name(&self) -> &'static str {
+        ProviderKind::Edgar.label()
+    }
+
+    async fn fetch(&self, symbol: &str) -> Result<EnrichmentData> {
+        let mut data = EnrichmentData::default();
+        data.apply_news(NewsUpdate::from_news(Vec::new(), self.filings(symbol).await?));
+        Ok(data)
+    }
+
+    async fn fetch_news(&self, symbol: &str) -> Result<Option<NewsUpdate>> {
+        let filings = self.filings(symbol).await?;
+        Ok((!filings.is_empty()).then(|| NewsUpdate::from_news(Vec::new(), filings)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    const TICKERS: &str = include_str!("testdata/edgar/files/company_tickers.json");
+    const ACME: &str = include_str!("testdata/edgar/submissions/CIK0001234567.json");
+
+    /// Serve `routes` (path -> body) over HTTP on a local port, returning
+    /// the base URL; other paths get a 404.
+    async fn serve(routes: Vec<(&'static str, &'static str)>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let routes = routes.clone();
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 8192];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    let path = request.split_whitespace().nth(1).unwrap_or("");
+                    let (status, body) = match routes.iter().find(|(p, _)| *p == path) {
+                        Some((_, body)) => ("200 OK", *body),
+                        None => ("404 Not Found", ""),
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        base
+    }
+
+    #[test]
+    fn test_parse_filings() {
+        assert_eq!(parse_tickers(TICKERS).unwrap()["ACME"], 1234567);
+
+        let filings = parse_filings(ACME, 1234567, 0).unwrap();
+        let forms: Vec<&str> = filings.iter().map(|f| f.form.as_str()).collect();
+        assert_eq!(forms, vec!["424B5", "8-K", "S-3", "8-K", "10-Q", "8-K"]);
+        assert_eq!(filings[0].title, "Prospectus");
+        assert_eq!(filings[0].filed, Some(1721074532));
+        assert_eq!(
+            filings[0].url.as_deref(),
+            Some("https://www.sec.gov/Archives/edgar/data/1234567/000110465924080001/tm2419861-1_424b5.htm")
+        );
+        assert_eq!(filings[1].title, "Material agreement");
+        assert_eq!(filings[1].items, vec!["1.01", "9.01"]);
+        assert_eq!(filings[3].title, "Financial statements and exhibits");
+        // No acceptance time: midnight of the filing date
+        assert_eq!(filings[4].filed, Some(1720051200));
+        assert_eq!(filings[5].title, "Results of operations");
+
+        // Stops at the first filing before `since`
+        assert_eq!(parse_filings(ACME, 1234567, 1721000000).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_edgar_provider_fixture_server() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let base = rt.block_on(serve(vec![
+            ("/files/company_tickers.json", TICKERS),
+            ("/submissions/CIK0001234567.json", ACME),
+        ]));
+        let config = EdgarConfig {
+            base_url: Some(format!("{base}/")),
+            lookback_days: 100_000,
+            ..Default::default()
+        };
+        let edgar = EdgarProvider::new(&config);
+
+        let data = rt.block_on(edgar.fetch("acme")).unwrap();
+        assert_eq!(data.filings.len(), 6);
+        assert_eq!(data.catalyst.as_deref(), Some("424B5: Prospectus"));
+        assert_eq!(data.catalyst_time, Some(1721074532));
+
+        // Known ticker without submissions, unknown ticker
+        assert!(rt.block_on(edgar.fetch("BOLT")).is_err());
+        assert!(rt.block_on(edgar.fetch_news("NOPE")).unwrap().is_none());
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+use super::{EnrichmentData, EnrichmentProvider, NewsUpdate};
+use crate::config::ProviderKind;
+
+/// Enrichment read from a JSON file mapping symbols to `EnrichmentData`,
+/// for offline runs and tests. Unknown symbols enrich to nothing.
+#[derive(Debug, Clone, Default)]
+pub struct FixtureProvider {
+    symbols: HashMap<String, EnrichmentData>,
+}
+
+impl FixtureProvider {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Self::from_json(&text).with_context(|| format!("invalid enrichment fixture {}", path.display()))
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        Ok(Self { symbols: serde_json::from_str(text)? })
+    }
+}
+
+#[async_trait]
+impl EnrichmentProvider for FixtureProvider {
+    fn name(&self) -> &'static str {
+        ProviderKind::Fixture.label()
+    }
+
+    async fn fetch(&self, symbol: &str) -> Result<EnrichmentData> {
+        Ok(self.symbols.get(symbol).cloned().unwrap_or_default())
+    }
+
+    async fn fetch_news(&self, symbol: &str) -> Result<Option<NewsUpdate>> {
+        Ok(self.symbols.get(symbol).and_then(|d| {
+            (!d.news_headlines.is_empty() || !d.filings.is_empty()).then(|| NewsUpdate {
+                catalyst: d.catalyst.clone(),
+                catalyst_time: d.catalyst_time,
+                catalyst_kind: d.catalyst_kind,
+                catalyst_sentiment: d.catalyst_sentiment,
+                news_headlines: d.news_headlines.clone(),
+                filings: d.filings.clone(),
+            })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::Shares;
+
+    #[test]
+    fn test_fixture_provider() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let fixture = FixtureProvider::from_json(include_str!("testdata/fixtures.json")).unwrap();
+        let acme = rt.block_on(fixture.fetch("ACME")).unwrap();
+        assert_eq!(acme.name.as_deref(), Some("Acme Therapeutics"));
+        assert_eq!(acme.float_shares, Some(4_200_000.0));
+        assert_eq!(acme.avg_volume, Some(Shares(850_000)));
+        assert!(acme.sector.is_none());
+
+        let news = rt.block_on(fixture.fetch_news("ACME")).unwrap().unwrap();
+        assert_eq!(news.news_headlines.len(), 2);
+        assert!(rt.block_on(fixture.fetch_news("BOLT")).unwrap().is_none());
+        assert!(rt.block_on(fixture.fetch("NOPE")).unwrap().name.is_none());
+
+        assert!(FixtureProvider::from_json("[]").is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::warn;
+
+use super::{EnrichmentData, EnrichmentProvider, NewsUpdate};
+use crate::config::ProviderKind;
+use crate::market_data::MarketDataSource;
+
+/// Shares outstanding from IB fundamental data and headlines from the IB
+/// news providers, over the market data source's TWS connections (see
+/// `tws::fundamentals` and `tws::news`).
+pub struct IbProvider {
+    source: Arc<dyn MarketDataSource>,
+}
+
+impl IbProvider {
+    pub fn new(source: Arc<dyn MarketDataSource>) -> Self {
+        Self { source }
+    }
+}
+
+#[async_trait]
+impl EnrichmentProvider for IbProvider {
+    fn name(&self) -> &'static str {
+        ProviderKind::Ib.label()
+    }
+
+    /// Fails only if both fundamentals and news do.
+    async fn fetch(&self, symbol: &str) -> Result<EnrichmentData> {
+        let (fundamentals, news) =
+            futures::join!(self.source.fundamentals(symbol), self.source.news(symbol));
+        let news = match (&fundamentals, news) {
+            (Err(_), Err(e)) => return Err(e),
+            (_, Ok(news)) => news,
+            (Ok(_), Err(e)) => {
+                warn!(symbol, "IB news failed: {e}");
+                Vec::new()
+            }
+        };
+        let fundamentals = fundamentals.unwrap_or_else(|e| {
+            warn!(symbol, "IB fundamentals failed: {e}");
+            Default::default()
+        });
+        let news = NewsUpdate::from_news(news, Vec::new());
+        let mut data = EnrichmentData {
+            shares_outstanding: fundamentals.shares_outstanding,
+            ..Default::default()
+        };
+        data.apply_news(news);
+        Ok(data)
+    }
+
+    async fn fetch_news(&self, symbol: &str) -> Result<Option<NewsUpdate>> {
+        let headlines = self.source.news(symbol).await?;
+        Ok((!headlines.is_empty()).then(|| NewsUpdate::from_news(headlines, Vec::new())))
+    }
+}
@@ -0,0 +1,847 @@
+//! Fundamentals and news for scanned symbols, from pluggable providers.
+//!
+//! An `EnrichmentProvider` fetches what it knows about a symbol (Yahoo
+//! Finance, IB fundamentals and news over TWS, SEC EDGAR filings, or a
+//! JSON fixture for offline runs).
+//! A `ProviderChain` queries its providers and takes each field from the
+//! first one in that field's order that has it, recording the source in
+//! `EnrichmentData::provenance`. News is the exception: headlines from
+//! every provider are merged newest first and the catalyst is classified
+//! over all of them and the filings. The merged float is then checked against shares
+//! outstanding (see `EnrichmentData::reconcile_float`).
+
+mod edgar;
+mod fixture;
+mod ib;
+mod yahoo;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::catalyst::{classify_catalyst, classify_news, Catalyst, CatalystKind, Sentiment};
+use crate::config::{EnrichmentConfig, ProviderKind};
+use crate::market_data::MarketDataSource;
+use crate::models::{Filing, NewsHeadline, ScanResult};
+use crate::units::Shares;
+
+pub use edgar::EdgarProvider;
+pub use fixture::FixtureProvider;
+pub use ib::IbProvider;
+pub use yahoo::YahooProvider;
+
+/// A float more than this factor above shares outstanding is stale, e.g.
+/// from before a reverse split.
+pub const FLOAT_TOLERANCE: f64 = 1.05;
+
+/// An enrichment field that can be routed to a provider and carries
+/// provenance. `Catalyst` includes its time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    Name,
+    Sector,
+    Industry,
+    Country,
+    Float,
+    SharesOutstanding,
+    ShortPct,
+    AvgVolume,
+    #[serde(rename = "avg_volume_10d")]
+    AvgVolume10d,
+    Catalyst,
+    News,
+    Filings,
+}
+
+impl Field {
+    pub const ALL: [Field; 12] = [
+        Field::Name,
+        Field::Sector,
+        Field::Industry,
+        Field::Country,
+        Field::Float,
+        Field::SharesOutstanding,
+        Field::ShortPct,
+        Field::AvgVolume,
+        Field::AvgVolume10d,
+        Field::Catalyst,
+        Field::News,
+        Field::Filings,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Field::Name => "name",
+            Field::Sector => "sector",
+            Field::Industry => "industry",
+            Field::Country => "country",
+            Field::Float => "float",
+            Field::SharesOutstanding => "shares out",
+            Field::ShortPct => "short%",
+            Field::AvgVolume => "avg vol",
+            Field::AvgVolume10d => "avg vol 10d",
+            Field::Catalyst => "catalyst",
+            Field::News => "news",
+            Field::Filings => "filings",
+        }
+    }
+}
+
+/// Which provider each enrichment field came from ("cache" for values
+/// read back from the history store).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Provenance(pub BTreeMap<Field, String>);
+
+impl Provenance {
+    pub fn get(&self, field: Field) -> Option<&str> {
+        self.0.get(&field).map(|s| s.as_str())
+    }
+
+    pub fn set(&mut self, field: Field, source: &str) {
+        self.0.insert(field, source.to_string());
+    }
+
+    /// Whether no provider supplied any field.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Fields grouped by source, e.g. "yahoo: name, sector; fixture: float".
+    /// `None` if nothing was enriched.
+    pub fn summary(&self) -> Option<String> {
+        let mut groups: Vec<(&str, Vec<&str>)> = Vec::new();
+        for (field, source) in &self.0 {
+            match groups.iter_mut().find(|(s, _)| s == source) {
+                Some((_, fields)) => fields.push(field.label()),
+                None => groups.push((source, vec![field.label()])),
+            }
+        }
+        (!groups.is_empty()).then(|| {
+            groups
+                .iter()
+                .map(|(source, fields)| format!("{source}: {}", fields.join(", ")))
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+    }
+}
+
+/// Enrichment data for a symbol, merged from one or more providers.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnrichmentData {
+    pub name: Option<String>,
+    pub sector: Option<String>,
+    pub industry: Option<String>,
+    pub country: Option<String>,
+    pub float_shares: Option<f64>,
+    pub shares_outstanding: Option<f64>,
+    pub short_pct: Option<f64>,
+    pub avg_volume: Option<Shares>,
+    pub avg_volume_10d: Option<Shares>,
+    pub catalyst: Option<String>,
+    pub catalyst_time: Option<i64>,
+    /// Category and sentiment of `catalyst`, kept from its classification
+    /// (a filing catalyst classifies by form, not by its headline text).
+    pub catalyst_kind: Option<CatalystKind>,
+    pub catalyst_sentiment: Option<Sentiment>,
+    pub news_headlines: Vec<NewsHeadline>,
+    /// Recent SEC filings, newest first.
+    pub filings: Vec<Filing>,
+    /// Source of each field that has a value.
+    pub provenance: Provenance,
+}
+
+impl EnrichmentData {
+    pub fn has(&self, field: Field) -> bool {
+        match field {
+            Field::Name => self.name.is_some(),
+            Field::Sector => self.sector.is_some(),
+            Field::Industry => self.industry.is_some(),
+            Field::Country => self.country.is_some(),
+            Field::Float => self.float_shares.is_some(),
+            Field::SharesOutstanding => self.shares_outstanding.is_some(),
+            Field::ShortPct => self.short_pct.is_some(),
+            Field::AvgVolume => self.avg_volume.is_some(),
+            Field::AvgVolume10d => self.avg_volume_10d.is_some(),
+            Field::Catalyst => self.catalyst.is_some(),
+            Field::News => !self.news_headlines.is_empty(),
+            Field::Filings => !self.filings.is_empty(),
+        }
+    }
+
+    /// Take `field` from `other`.
+    fn copy_field(&mut self, field: Field, other: &Self) {
+        match field {
+            Field::Name => self.name.clone_from(&other.name),
+            Field::Sector => self.sector.clone_from(&other.sector),
+            Field::Industry => self.industry.clone_from(&other.industry),
+            Field::Country => self.country.clone_from(&other.country),
+            Field::Float => self.float_shares = other.float_shares,
+            Field::SharesOutstanding => self.shares_outstanding = other.shares_outstanding,
+            Field::ShortPct => self.short_pct = other.short_pct,
+            Field::AvgVolume => self.avg_volume = other.avg_volume,
+            Field::AvgVolume10d => self.avg_volume_10d = other.avg_volume_10d,
+            Field::Catalyst => {
+                self.catalyst.clone_from(&other.catalyst);
+                self.catalyst_time = other.catalyst_time;
+                self.catalyst_kind = other.catalyst_kind;
+                self.catalyst_sentiment = other.catalyst_sentiment;
+            }
+            Field::News => self.news_headlines.clone_from(&other.news_headlines),
+            Field::Filings => self.filings.clone_from(&other.filings),
+        }
+    }
+
+    /// Record `source` as the provenance of every field with a value.
+    pub fn mark_source(&mut self, source: &str) {
+        for field in Field::ALL {
+            if self.has(field) {
+                self.provenance.set(field, source);
+            }
+        }
+    }
+
+    /// Shares outstanding bounds the float. A float above it (beyond
+    /// `FLOAT_TOLERANCE`) is replaced by shares outstanding and the float's
+    /// provenance then reads "<source> shares out". A missing float stays
+    /// unknown: shares outstanding is only an upper bound, and filling it
+    /// in would fail the float pillar instead of leaving it unknown.
+    /// Returns the replaced float, if any.
+    pub fn reconcile_float(&mut self) -> Option<f64> {
+        let shares_out = self.shares_outstanding?;
+        let stale = self.float_shares.filter(|float| *float > shares_out * FLOAT_TOLERANCE)?;
+        let source = self.provenance.get(Field::SharesOutstanding).unwrap_or("unknown");
+        let source = format!("{source} shares out");
+        self.float_shares = Some(shares_out);
+        self.provenance.set(Field::Float, &source);
+        Some(stale)
+    }
+
+    pub fn apply_news(&mut self, update: NewsUpdate) {
+        self.catalyst = update.catalyst;
+        self.catalyst_time = update.catalyst_time;
+        self.catalyst_kind = update.catalyst_kind;
+        self.catalyst_sentiment = update.catalyst_sentiment;
+        self.news_headlines = update.news_headlines;
+        self.filings = update.filings;
+    }
+}
+
+/// News-only refresh result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewsUpdate {
+    pub catalyst: Option<String>,
+    pub catalyst_time: Option<i64>,
+    #[serde(default)]
+    pub catalyst_kind: Option<CatalystKind>,
+    #[serde(default)]
+    pub catalyst_sentiment: Option<Sentiment>,
+    pub news_headlines: Vec<NewsHeadline>,
+    #[serde(default)]
+    pub filings: Vec<Filing>,
+}
+
+impl NewsUpdate {
+    /// From news items with `title` and `providerPublishTime` keys, the
+    /// catalyst being the first that classifies as one.
+    pub fn from_items(news: &[Value]) -> Self {
+        let catalyst = classify_catalyst(news);
+        let news_headlines = news
+            .iter()
+            .filter_map(|item| {
+                let title = item.get("title")?.as_str()?.to_string();
+                let published = item.get("providerPublishTime").and_then(|t| t.as_i64());
+                Some(NewsHeadline::new(title, published))
+            })
+            .collect();
+        Self::classified(catalyst, news_headlines, Vec::new())
+    }
+
+    /// From already parsed headlines and filings, both newest first.
+    pub fn from_news(news_headlines: Vec<NewsHeadline>, filings: Vec<Filing>) -> Self {
+        let catalyst = classify_news(&news_headlines, &filings);
+        Self::classified(catalyst, news_headlines, filings)
+    }
+
+    fn classified(catalyst: Option<Catalyst>, news_headlines: Vec<NewsHeadline>, filings: Vec<Filing>) -> Self {
+        Self {
+            catalyst_time: catalyst.as_ref().and_then(|c| c.published),
+            catalyst_kind: catalyst.as_ref().map(|c| c.kind),
+            catalyst_sentiment: catalyst.as_ref().map(|c| c.sentiment),
+            catalyst: catalyst.map(|c| c.headline),
+            news_headlines,
+            filings,
+        }
+    }
+}
+
+/// Whether two headlines are the same story: the same article of the same
+/// provider, or the same title.
+fn same_story(a: &NewsHeadline, b: &NewsHeadline) -> bool {
+    (a.article().is_some() && a.article() == b.article()) || a.title.eq_ignore_ascii_case(&b.title)
+}
+
+/// Merge headline lists from several providers: headlines without a source
+/// get their provider's name, repeated stories keep the first, and the
+/// result is sorted newest first (undated last). Each headline comes back
+/// with the provider it was taken from.
+pub fn merge_headlines<'a>(
+    lists: impl IntoIterator<Item = (&'static str, &'a [NewsHeadline])>,
+) -> Vec<(&'static str, NewsHeadline)> {
+    let mut merged: Vec<(&'static str, NewsHeadline)> = Vec::new();
+    for (provider, headlines) in lists {
+        for h in headlines {
+            if merged.iter().any(|(_, m)| same_story(m, h)) {
+                continue;
+            }
+            let mut h = h.clone();
+            h.source.get_or_insert_with(|| provider.to_string());
+            merged.push((provider, h));
+        }
+    }
+    merged.sort_by_key(|(_, h)| std::cmp::Reverse(h.published));
+    merged
+}
+
+/// Fold `fresh` headlines into `kept` (e.g. a row's broad-tape news):
+/// repeated stories keep the copy already kept, newest first (undated
+/// last).
+pub fn merge_kept_headlines(fresh: &[NewsHeadline], kept: &[NewsHeadline]) -> Vec<NewsHeadline> {
+    let mut merged = kept.to_vec();
+    for h in fresh {
+        if !merged.iter().any(|m| same_story(m, h)) {
+            merged.push(h.clone());
+        }
+    }
+    merged.sort_by_key(|h| std::cmp::Reverse(h.published));
+    merged
+}
+
+/// A source of fundamentals and news.
+#[async_trait]
+pub trait EnrichmentProvider: Send + Sync {
+    /// Short provider name, recorded as provenance ("yahoo", "fixture").
+    fn name(&self) -> &'static str;
+
+    /// Everything the provider knows about `symbol`; missing fields stay
+    /// `None`. Provenance is filled in by the chain.
+    async fn fetch(&self, symbol: &str) -> Result<EnrichmentData>;
+
+    /// Fresh news for a periodic refresh. `None` if there is none or the
+    /// provider has no news.
+    async fn fetch_news(&self, _symbol: &str) -> Result<Option<NewsUpdate>> {
+        Ok(None)
+    }
+}
+
+/// Providers queried together, with a per-field order of preference.
+#[derive(Clone)]
+pub struct ProviderChain {
+    providers: Vec<Arc<dyn EnrichmentProvider>>,
+    /// Fields routed to specific providers; others use `providers` order.
+    routes: BTreeMap<Field, Vec<&'static str>>,
+}
+
+impl ProviderChain {
+    pub fn new(providers: Vec<Arc<dyn EnrichmentProvider>>) -> Self {
+        Self { providers, routes: BTreeMap::new() }
+    }
+
+    /// Take `field` only from the named providers, first one with a value
+    /// wins.
+    pub fn route(mut self, field: Field, order: &[&'static str]) -> Self {
+        self.routes.insert(field, order.to_vec());
+        self
+    }
+
+    /// The chain configured in `[enrichment]`, with `source` serving the
+    /// IB provider; fails if the fixture file cannot be read.
+    pub fn from_config(config: &EnrichmentConfig, source: Arc<dyn MarketDataSource>) -> Result<Self> {
+        let providers = config
+            .providers
+            .iter()
+            .map(|kind| -> Result<Arc<dyn EnrichmentProvider>> {
+                Ok(match kind {
+                    ProviderKind::Yahoo => Arc::new(YahooProvider::new()),
+                    ProviderKind::Ib => Arc::new(IbProvider::new(Arc::clone(&source))),
+                    ProviderKind::Edgar => Arc::new(EdgarProvider::new(&config.edgar)),
+                    ProviderKind::Fixture => {
+                        let path = config
+                            .fixture_path
+                            .as_deref()
+                            .context("the fixture enrichment provider needs enrichment.fixture_path")?;
+                        Arc::new(FixtureProvider::load(path)?)
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut chain = Self::new(providers);
+        for (field, kinds) in &config.fields {
+            let order: Vec<&'static str> = kinds.iter().map(|k| k.label()).collect();
+            chain = chain.route(*field, &order);
+        }
+        Ok(chain)
+    }
+
+    /// Provider names in order, for logs ("yahoo, fixture").
+    pub fn describe(&self) -> String {
+        let names: Vec<&str> = self.providers.iter().map(|p| p.name()).collect();
+        if names.is_empty() { "no providers".to_string() } else { names.join(", ") }
+    }
+
+    fn order(&self, field: Field) -> Vec<&'static str> {
+        match self.routes.get(&field) {
+            Some(order) => order.clone(),
+            None => self.providers.iter().map(|p| p.name()).collect(),
+        }
+    }
+
+    /// Query every provider concurrently, merge field by field and
+    /// reconcile the float. A failing provider is logged and skipped.
+    pub async fn fetch(&self, symbol: &str) -> EnrichmentData {
+        let results =
+            futures::future::join_all(self.providers.iter().map(|p| p.fetch(symbol))).await;
+        let fetched: Vec<(&'static str, EnrichmentData)> = self
+            .providers
+            .iter()
+            .zip(results)
+            .filter_map(|(p, result)| match result {
+                Ok(data) => Some((p.name(), data)),
+                Err(e) => {
+                    warn!(symbol, provider = p.name(), "enrichment failed: {e}");
+                    None
+                }
+            })
+            .collect();
+
+        let mut data = EnrichmentData::default();
+        for field in Field::ALL {
+            let source = self.order(field).into_iter().find_map(|name| {
+                fetched.iter().find(|(p, d)| *p == name && d.has(field))
+            });
+            if let Some((name, from)) = source {
+                data.copy_field(field, from);
+                data.provenance.set(field, name);
+            }
+        }
+        self.merge_news(&mut data, &fetched);
+        if let Some(stale) = data.reconcile_float() {
+            warn!(
+                symbol,
+                float = stale,
+                shares_outstanding = data.shares_outstanding,
+                "float exceeds shares outstanding, using shares outstanding"
+            );
+        }
+        data
+    }
+
+    /// Replace the first-wins news with headlines merged from every
+    /// provider in the news order, and classify the catalyst over them and
+    /// the filings. News provenance reads e.g. "yahoo+ib"; a catalyst
+    /// neither classifies (as a fixture may have) is kept.
+    fn merge_news(&self, data: &mut EnrichmentData, fetched: &[(&'static str, EnrichmentData)]) {
+        let order = self.order(Field::News);
+        let lists = order.iter().filter_map(|name| {
+            fetched
+                .iter()
+                .find(|(p, _)| p == name)
+                .map(|(p, d)| (*p, d.news_headlines.as_slice()))
+        });
+        let merged = merge_headlines(lists);
+        if !merged.is_empty() {
+            let sources: Vec<&str> = order
+                .iter()
+                .copied()
+                .filter(|name| merged.iter().any(|(p, _)| p == name))
+                .collect();
+            data.provenance.set(Field::News, &sources.join("+"));
+            data.news_headlines = merged.iter().map(|(_, h)| h.clone()).collect();
+        }
+        if let Some(catalyst) = classify_news(&data.news_headlines, &data.filings) {
+            let source = match merged.iter().find(|(_, h)| h.title == catalyst.headline) {
+                Some((p, _)) => Some(*p),
+                None => data.provenance.get(Field::Filings),
+            };
+            if let Some(source) = source.map(str::to_string) {
+                data.provenance.set(Field::Catalyst, &source);
+            }
+            data.catalyst_time = catalyst.published;
+            data.catalyst_kind = Some(catalyst.kind);
+            data.catalyst_sentiment = Some(catalyst.sentiment);
+            data.catalyst = Some(catalyst.headline);
+        }
+    }
+
+    /// Fresh news from every provider in the news order, merged, with the
+    /// filings of the first provider in the filings order that has any.
+    pub async fn fetch_news(&self, symbol: &str) -> Option<NewsUpdate> {
+        let (news, filings) = (self.order(Field::News), self.order(Field::Filings));
+        let providers: Vec<&Arc<dyn EnrichmentProvider>> = self
+            .providers
+            .iter()
+            .filter(|p| news.contains(&p.name()) || filings.contains(&p.name()))
+            .collect();
+        let results =
+            futures::future::join_all(providers.iter().map(|p| p.fetch_news(symbol))).await;
+        let updates: Vec<(&'static str, NewsUpdate)> = providers
+            .iter()
+            .zip(results)
+            .filter_map(|(p, result)| match result {
+                Ok(update) => update.map(|u| (p.name(), u)),
+                Err(e) => {
+                    warn!(symbol, provider = p.name(), "news refresh failed: {e}");
+                    None
+                }
+            })
+            .collect();
+        if updates.is_empty() {
+            return None;
+        }
+        let update_of = |name: &&str| updates.iter().find(|(p, _)| p == name);
+        let merged = merge_headlines(
+            news.iter().filter_map(update_of).map(|(p, u)| (*p, u.news_headlines.as_slice())),
+        );
+        let filings = filings
+            .iter()
+            .filter_map(update_of)
+            .find(|(_, u)| !u.filings.is_empty())
+            .map(|(_, u)| u.filings.clone())
+            .unwrap_or_default();
+        let mut update = NewsUpdate::from_news(merged.into_iter().map(|(_, h)| h).collect(), filings);
+        if update.catalyst.is_none() {
+            // A provider's catalyst need not come from its headlines
+            if let Some((_, u)) = updates.iter().find(|(_, u)| u.catalyst.is_some()) {
+                update.catalyst.clone_from(&u.catalyst);
+                update.catalyst_time = u.catalyst_time;
+                update.catalyst_kind = u.catalyst_kind;
+                update.catalyst_sentiment = u.catalyst_sentiment;
+            }
+        }
+        Some(update)
+    }
+}
+
+/// Enrich a list of scan results through `chain`, all symbols at once. A
+/// result is marked enriched when any provider returned data for it, so a
+/// missing catalyst then reads as none rather than unknown.
+pub async fn enrich_results(chain: &ProviderChain, results: &mut [ScanResult]) {
+    let fetches = results.iter().map(|r| chain.fetch(&r.symbol));
+    let enriched = futures::future::join_all(fetches).await;
+
+    for (r, data) in results.iter_mut().zip(enriched) {
+        r.enriched = !data.provenance.is_empty();
+        r.name = data.name;
+        r.sector = data.sector;
+        r.industry = data.industry;
+        r.country = data.country;
+        r.float_shares = data.float_shares;
+        r.short_pct = data.short_pct;
+        r.avg_volume = data.avg_volume;
+        r.avg_volume_10d = data.avg_volume_10d;
+        r.catalyst = data.catalyst;
+        r.catalyst_sentiment = data.catalyst_sentiment;
+        if let Some(v) = crate::rvol::scan_rvol(r) {
+            r.rvol = Some(v);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::{SimConfig, Simulator};
+
+    /// A provider that always fails.
+    struct Down;
+
+    #[async_trait]
+    impl EnrichmentProvider for Down {
+        fn name(&self) -> &'static str {
+            "down"
+        }
+
+        async fn fetch(&self, _symbol: &str) -> Result<EnrichmentData> {
+            anyhow::bail!("unreachable")
+        }
+    }
+
+    fn fixture() -> Arc<dyn EnrichmentProvider> {
+        Arc::new(FixtureProvider::from_json(include_str!("testdata/fixtures.json")).unwrap())
+    }
+
+    /// A second provider with a different float and a sector.
+    fn other() -> Arc<dyn EnrichmentProvider> {
+        struct Other;
+
+        #[async_trait]
+        impl EnrichmentProvider for Other {
+            fn name(&self) -> &'static str {
+                "other"
+            }
+
+            async fn fetch(&self, _symbol: &str) -> Result<EnrichmentData> {
+                Ok(EnrichmentData {
+                    sector: Some("Healthcare".to_string()),
+                    float_shares: Some(5_000_000.0),
+                    ..Default::default()
+                })
+            }
+        }
+        Arc::new(Other)
+    }
+
+    #[test]
+    fn test_enrichment_data_default() {
+        let d = EnrichmentData::default();
+        assert!(d.name.is_none());
+        assert!(d.sector.is_none());
+        assert!(d.catalyst.is_none());
+        assert_eq!(d.provenance.summary(), None);
+    }
+
+    #[test]
+    fn test_chain_merges_by_field() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        // Chain order: the fixture wins where it has a value
+        let chain = ProviderChain::new(vec![fixture(), other()]);
+        let d = rt.block_on(chain.fetch("ACME"));
+        assert_eq!(d.float_shares, Some(4_200_000.0));
+        assert_eq!(d.sector.as_deref(), Some("Healthcare"));
+        assert_eq!(d.provenance.get(Field::Float), Some("fixture"));
+        assert_eq!(d.provenance.get(Field::Sector), Some("other"));
+        assert_eq!(d.provenance.get(Field::Country), None);
+        assert_eq!(
+            d.provenance.summary().as_deref(),
+            Some("fixture: name, float, short%, avg vol, catalyst, news; other: sector")
+        );
+
+        // Routed fields take only the named providers
+        let chain = ProviderChain::new(vec![fixture(), other()])
+            .route(Field::Float, &["other", "fixture"])
+            .route(Field::Sector, &["fixture"]);
+        let d = rt.block_on(chain.fetch("ACME"));
+        assert_eq!(d.float_shares, Some(5_000_000.0));
+        assert_eq!(d.provenance.get(Field::Float), Some("other"));
+        assert_eq!(d.sector, None);
+    }
+
+    #[test]
+    fn test_chain_merges_news() {
+        struct Wire;
+
+        #[async_trait]
+        impl EnrichmentProvider for Wire {
+            fn name(&self) -> &'static str {
+                "wire"
+            }
+
+            async fn fetch(&self, _symbol: &str) -> Result<EnrichmentData> {
+                let ib = |title: &str, published, id: &str| NewsHeadline {
+                    source: Some("BRFG".to_string()),
+                    article_id: Some(id.to_string()),
+                    ..NewsHeadline::new(title, Some(published))
+                };
+                Ok(EnrichmentData {
+                    news_headlines: vec![
+                        ib("Acme Therapeutics wins FDA approval", 1721100000, "BRFG$2"),
+                        ib("Acme Therapeutics to present at healthcare conference", 1720963800, "BRFG$1"),
+                    ],
+                    ..Default::default()
+                })
+            }
+
+            async fn fetch_news(&self, symbol: &str) -> Result<Option<NewsUpdate>> {
+                let data = self.fetch(symbol).await?;
+                Ok(Some(NewsUpdate::from_news(data.news_headlines, Vec::new())))
+            }
+        }
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let chain = ProviderChain::new(vec![fixture(), Arc::new(Wire)]);
+        let d = rt.block_on(chain.fetch("ACME"));
+        let titles: Vec<(&str, Option<&str>)> =
+            d.news_headlines.iter().map(|h| (h.title.as_str(), h.source.as_deref())).collect();
+        assert_eq!(
+            titles,
+            vec![
+                ("Acme Therapeutics wins FDA approval", Some("BRFG")),
+                ("Acme Therapeutics announces positive Phase 2 results", Some("fixture")),
+                ("Acme Therapeutics to present at healthcare conference", Some("fixture")),
+            ]
+        );
+        assert_eq!(d.news_headlines[0].article(), Some(("BRFG", "BRFG$2")));
+        assert_eq!(d.catalyst.as_deref(), Some("Acme Therapeutics wins FDA approval"));
+        assert_eq!(d.catalyst_time, Some(1721100000));
+        assert_eq!(d.provenance.get(Field::News), Some("fixture+wire"));
+        assert_eq!(d.provenance.get(Field::Catalyst), Some("wire"));
+
+        let update = rt.block_on(chain.fetch_news("ACME")).unwrap();
+        assert_eq!(update.news_headlines.len(), 3);
+        assert_eq!(update.catalyst.as_deref(), Some("Acme Therapeutics wins FDA approval"));
+
+        // Only the fixture has news routed: its own catalyst stands
+        let chain = ProviderChain::new(vec![fixture(), Arc::new(Wire)]).route(Field::News, &["fixture"]);
+        let update = rt.block_on(chain.fetch_news("ACME")).unwrap();
+        assert_eq!(update.news_headlines.len(), 2);
+        assert_eq!(
+            update.catalyst.as_deref(),
+            Some("Acme Therapeutics announces positive Phase 2 results")
+        );
+    }
+
+    #[test]
+    fn test_chain_skips_failing_provider() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let chain = ProviderChain::new(vec![Arc::new(Down), fixture()]);
+        let d = rt.block_on(chain.fetch("BOLT"));
+        assert_eq!(d.name.as_deref(), Some("Bolt Mobility"));
+        assert_eq!(d.avg_volume_10d, Some(Shares(4_800_000)));
+        assert_eq!(d.provenance.get(Field::Name), Some("fixture"));
+
+        assert_eq!(
+            rt.block_on(chain.fetch_news("ACME")).map(|n| n.news_headlines.len()),
+            Some(2)
+        );
+        assert!(rt.block_on(chain.fetch_news("BOLT")).is_none());
+    }
+
+    #[test]
+    fn test_chain_from_config() {
+        let source: Arc<dyn MarketDataSource> = Arc::new(Simulator::new(SimConfig::default()));
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/enrichment/testdata/fixtures.json");
+        let config = EnrichmentConfig {
+            providers: vec![ProviderKind::Fixture, ProviderKind::Ib],
+            fixture_path: Some(path),
+            ..Default::default()
+        };
+        let chain = ProviderChain::from_config(&config, Arc::clone(&source)).unwrap();
+        assert_eq!(chain.describe(), "fixture, ib");
+
+        let config = EnrichmentConfig { fixture_path: Some("missing.json".into()), ..config };
+        assert!(ProviderChain::from_config(&config, source).is_err());
+    }
+
+    #[test]
+    fn test_reconcile_float() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let sim = Simulator::new(SimConfig::default());
+        let symbol = sim.symbols()[0].clone();
+        let shares_out = rt.block_on(sim.fundamentals(&symbol)).unwrap().shares_outstanding.unwrap();
+
+        // IB alone: shares outstanding, but the float stays unknown
+        let chain = ProviderChain::new(vec![Arc::new(IbProvider::new(Arc::new(sim)))]);
+        let d = rt.block_on(chain.fetch(&symbol));
+        assert_eq!(d.shares_outstanding, Some(shares_out));
+        assert_eq!(d.float_shares, None);
+        assert_eq!(d.provenance.get(Field::Float), None);
+
+        // A float within shares outstanding stands
+        let mut d = EnrichmentData {
+            float_shares: Some(8e6),
+            shares_outstanding: Some(10e6),
+            ..Default::default()
+        };
+        d.mark_source("yahoo");
+        assert_eq!(d.reconcile_float(), None);
+        assert_eq!(d.float_shares, Some(8e6));
+
+        // A stale float above it (e.g. before a reverse split) is replaced
+        d.float_shares = Some(80e6);
+        assert_eq!(d.reconcile_float(), Some(80e6));
+        assert_eq!(d.float_shares, Some(10e6));
+        assert_eq!(d.provenance.get(Field::Float), Some("yahoo shares out"));
+    }
+
+    /// A provider with a small float, volume history and one headline.
+    struct Headline(&'static str);
+
+    #[async_trait]
+    impl EnrichmentProvider for Headline {
+        fn name(&self) -> &'static str {
+            "headline"
+        }
+
+        async fn fetch(&self, _symbol: &str) -> Result<EnrichmentData> {
+            Ok(EnrichmentData {
+                float_shares: Some(4_000_000.0),
+                avg_volume_10d: Some(Shares(1_000_000)),
+                news_headlines: vec![NewsHeadline::new(self.0, Some(1_700_000_000))],
+                ..Default::default()
+            })
+        }
+    }
+
+    fn scan_result() -> ScanResult {
+        ScanResult {
+            symbol: "ABCD".to_string(),
+            last: Some(5.0),
+            change_pct: Some(25.0),
+            volume: Some(Shares(10_000_000)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_enrich_results_bearish_catalyst() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let criteria = crate::criteria::MomentumCriteria::default();
+
+        let chain = ProviderChain::new(vec![Arc::new(Headline("ABCD wins FDA approval"))]);
+        let mut results = vec![scan_result()];
+        rt.block_on(enrich_results(&chain, &mut results));
+        assert_eq!(results[0].catalyst_sentiment, Some(Sentiment::Bullish));
+        assert_eq!(crate::scanner::filter_momentum(&results, &criteria).len(), 1);
+
+        // A dilutive offering explains the move but is no catalyst
+        let chain = ProviderChain::new(vec![Arc::new(Headline(
+            "ABCD announces $10 million registered direct offering",
+        ))]);
+        let mut results = vec![scan_result()];
+        rt.block_on(enrich_results(&chain, &mut results));
+        assert_eq!(results[0].catalyst_sentiment, Some(Sentiment::Bearish));
+        assert!(crate::scanner::filter_momentum(&results, &criteria).is_empty());
+    }
+
+    #[test]
+    fn test_enrich_results_marks_enriched() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let has_catalyst = |r: &ScanResult| crate::criteria::MomentumInputs::from(r).has_catalyst;
+
+        // Enriched without news: no catalyst
+        let chain = ProviderChain::new(vec![other()]);
+        let mut results = vec![scan_result()];
+        rt.block_on(enrich_results(&chain, &mut results));
+        assert!(results[0].enriched);
+        assert_eq!(has_catalyst(&results[0]), Some(false));
+
+        // Every provider failed: unknown, left to `pass_if_unknown`
+        let chain = ProviderChain::new(vec![Arc::new(Down)]);
+        let mut results = vec![scan_result()];
+        rt.block_on(enrich_results(&chain, &mut results));
+        assert!(!results[0].enriched);
+        assert_eq!(has_catalyst(&results[0]), None);
+    }
+
+    #[test]
+    fn test_news_update_from_items() {
+        let items = vec![
+            serde_json::json!({"title": "Acme to present at investor conference", "providerPublishTime": 100}),
+            serde_json::json!({"title": "Acme receives FDA approval", "providerPublishTime": 200}),
+        ];
+        let update = NewsUpdate::from_items(&items);
+        assert_eq!(update.news_headlines.len(), 2);
+        assert_eq!(update.catalyst.as_deref(), Some("Acme receives FDA approval"));
+        assert_eq!(update.catalyst_time, Some(200));
+    }
+}
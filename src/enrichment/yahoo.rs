@@ -1,10 +1,13 @@
 use anyhow::Result;
+use async_trait::async_trait;
 use reqwest::Client;
 use serde_json::Value;
+use tokio::sync::Mutex;
 use tracing::{debug, warn};
 
-use crate::catalyst::classify_catalyst;
-use crate::models::{NewsHeadline, ScanResult};
+use super::{EnrichmentData, EnrichmentProvider, NewsUpdate};
+use crate::config::ProviderKind;
+use crate::units::{Shares, VolumeSource};
 
 const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
 
@@ -26,13 +29,13 @@ fn percent_encode(s: &str) -> String {
 
 /// Yahoo Finance auth credentials (cookie + crumb).
 #[derive(Debug, Clone)]
-pub struct YahooAuth {
-    pub cookie: String,
-    pub crumb: String,
+struct YahooAuth {
+    cookie: String,
+    crumb: String,
 }
 
 /// Fetch Yahoo Finance auth (cookie + crumb) required for API access.
-pub async fn fetch_yahoo_auth(client: &Client) -> Result<YahooAuth> {
+async fn fetch_yahoo_auth(client: &Client) -> Result<YahooAuth> {
     // Step 1: Hit fc.yahoo.com to get set-cookie
     let resp = client
         .get("https://fc.yahoo.com")
@@ -112,7 +115,7 @@ async fn fetch_yahoo_news(client: &Client, symbol: &str, auth: &YahooAuth) -> Re
 }
 
 /// Fetch recent news via Yahoo Finance RSS feed (no auth required, more reliable).
-pub async fn fetch_yahoo_news_rss(client: &Client, symbol: &str) -> Result<Vec<Value>> {
+async fn fetch_yahoo_news_rss(client: &Client, symbol: &str) -> Result<Vec<Value>> {
     let url = format!(
         "https://feeds.finance.yahoo.com/rss/2.0/headline?s={}&region=US&lang=en-US",
         symbol
@@ -198,166 +201,113 @@ fn extract_str(data: &Value, module: &str, field: &str) -> Option<String> {
     .map(|s| s.to_string())
 }
 
-/// Enrichment data fetched from Yahoo Finance.
-#[derive(Debug, Clone, Default)]
-pub struct EnrichmentData {
-    pub name: Option<String>,
-    pub sector: Option<String>,
-    pub industry: Option<String>,
-    pub country: Option<String>,
-    pub float_shares: Option<f64>,
-    pub short_pct: Option<f64>,
-    pub avg_volume: Option<i64>,
-    pub avg_volume_10d: Option<i64>,
-    pub catalyst: Option<String>,
-    pub catalyst_time: Option<i64>,
-    pub news_headlines: Vec<NewsHeadline>,
+/// Fundamentals from a quoteSummary response; news is left empty.
+fn from_quote_summary(info: &Value) -> EnrichmentData {
+    EnrichmentData {
+        name: extract_str(info, "price", "shortName"),
+        sector: extract_str(info, "summaryProfile", "sector"),
+        industry: extract_str(info, "summaryProfile", "industry"),
+        country: extract_str(info, "summaryProfile", "country"),
+        float_shares: extract_raw(info, "defaultKeyStatistics", "floatShares")
+            .and_then(|v| v.as_f64()),
+        short_pct: extract_raw(info, "defaultKeyStatistics", "shortPercentOfFloat")
+            .and_then(|v| v.as_f64()),
+        avg_volume: extract_raw(info, "price", "averageDailyVolume3Month")
+            .and_then(|v| v.as_f64())
+            .map(|v| Shares::from_reported(v, VolumeSource::Yahoo)),
+        avg_volume_10d: extract_raw(info, "price", "averageDailyVolume10Day")
+            .and_then(|v| v.as_f64())
+            .map(|v| Shares::from_reported(v, VolumeSource::Yahoo)),
+        ..Default::default()
+    }
 }
 
-/// Fetch enrichment data for a single symbol (requires pre-fetched auth).
-pub async fn fetch_enrichment_with_auth(
-    client: &Client,
-    symbol: &str,
-    auth: &YahooAuth,
-) -> EnrichmentData {
-    let mut data = EnrichmentData::default();
-
-    let (info_result, rss_result, search_result) = tokio::join!(
-        fetch_yahoo_info(client, symbol, auth),
-        fetch_yahoo_news_rss(client, symbol),
-        fetch_yahoo_news(client, symbol, auth)
-    );
+/// Yahoo Finance: quoteSummary for fundamentals, the RSS feed (falling
+/// back to the search API) for news.
+///
+/// The cookie + crumb handshake is done on first use and reused. When it
+/// fails only the fundamentals are missing; RSS news needs no auth.
+pub struct YahooProvider {
+    client: Client,
+    auth: Mutex<Option<YahooAuth>>,
+}
 
-    if let Ok(info) = info_result {
-        data.name = extract_str(&info, "price", "shortName");
-        data.sector = extract_str(&info, "summaryProfile", "sector");
-        data.industry = extract_str(&info, "summaryProfile", "industry");
-        data.country = extract_str(&info, "summaryProfile", "country");
-        data.float_shares = extract_raw(&info, "defaultKeyStatistics", "floatShares")
-            .and_then(|v| v.as_f64());
-        data.short_pct = extract_raw(&info, "defaultKeyStatistics", "shortPercentOfFloat")
-            .and_then(|v| v.as_f64());
-        data.avg_volume = extract_raw(&info, "price", "averageDailyVolume3Month")
-            .and_then(|v| v.as_i64());
-        data.avg_volume_10d = extract_raw(&info, "price", "averageDailyVolume10Day")
-            .and_then(|v| v.as_i64());
-    } else if let Err(e) = info_result {
-        warn!("Yahoo Finance info fetch failed for {symbol}: {e}");
+impl YahooProvider {
+    pub fn new() -> Self {
+        Self { client: Client::new(), auth: Mutex::new(None) }
     }
 
-    // Prefer RSS feed for news (more reliable), fall back to search API
-    let news = match rss_result {
-        Ok(rss) if !rss.is_empty() => rss,
-        _ => {
-            debug!("RSS news empty for {symbol}, trying search API");
-            search_result.unwrap_or_default()
+    /// Cached auth, or a fresh handshake (retried on every call until it
+    /// succeeds).
+    async fn auth(&self) -> Result<YahooAuth> {
+        let mut auth = self.auth.lock().await;
+        if let Some(a) = auth.as_ref() {
+            return Ok(a.clone());
         }
-    };
-
-    if let Some((cat_title, cat_time)) = classify_catalyst(&news) {
-        data.catalyst = Some(cat_title);
-        data.catalyst_time = cat_time;
+        let fresh = fetch_yahoo_auth(&self.client).await?;
+        *auth = Some(fresh.clone());
+        Ok(fresh)
     }
-    data.news_headlines = news
-        .iter()
-        .filter_map(|item| {
-            let title = item.get("title")?.as_str()?.to_string();
-            let published = item.get("providerPublishTime").and_then(|t| t.as_i64());
-            Some(NewsHeadline { title, published })
-        })
-        .collect();
-
-    data
-}
-
-/// News-only refresh result.
-#[derive(Debug, Clone)]
-pub struct NewsUpdate {
-    pub catalyst: Option<String>,
-    pub catalyst_time: Option<i64>,
-    pub news_headlines: Vec<NewsHeadline>,
-}
 
-/// Fetch only news for a symbol via RSS (fast, no auth needed).
-/// Returns None if no news found.
-pub async fn fetch_news_only(client: &Client, symbol: &str) -> Option<NewsUpdate> {
-    let news = fetch_yahoo_news_rss(client, symbol).await.ok()?;
-    if news.is_empty() {
-        return None;
+    /// RSS headlines, or the search API's when the feed is empty.
+    async fn news(&self, symbol: &str, auth: Option<&YahooAuth>) -> Vec<Value> {
+        match fetch_yahoo_news_rss(&self.client, symbol).await {
+            Ok(rss) if !rss.is_empty() => rss,
+            _ => {
+                let Some(auth) = auth else {
+                    return Vec::new();
+                };
+                debug!("RSS news empty for {symbol}, trying search API");
+                fetch_yahoo_news(&self.client, symbol, auth).await.unwrap_or_default()
+            }
+        }
     }
-    let (catalyst, catalyst_time) = classify_catalyst(&news)
-        .map(|(c, t)| (Some(c), t))
-        .unwrap_or((None, None));
-    let headlines: Vec<NewsHeadline> = news
-        .iter()
-        .filter_map(|item| {
-            let title = item.get("title")?.as_str()?.to_string();
-            let published = item.get("providerPublishTime").and_then(|t| t.as_i64());
-            Some(NewsHeadline { title, published })
-        })
-        .collect();
-    Some(NewsUpdate {
-        catalyst,
-        catalyst_time,
-        news_headlines: headlines,
-    })
 }
 
-/// Fetch enrichment data for a single symbol (fetches auth internally).
-pub async fn fetch_enrichment(client: &Client, symbol: &str) -> EnrichmentData {
-    match fetch_yahoo_auth(client).await {
-        Ok(auth) => fetch_enrichment_with_auth(client, symbol, &auth).await,
-        Err(e) => {
-            warn!("Yahoo auth failed: {e}");
-            EnrichmentData::default()
-        }
+impl Default for YahooProvider {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-/// Enrich a list of scan results with Yahoo Finance data.
-/// Fetches auth once and reuses for all symbols.
-pub async fn enrich_results(results: &mut [ScanResult]) {
-    let client = Client::new();
-
-    let auth = match fetch_yahoo_auth(&client).await {
-        Ok(a) => a,
-        Err(e) => {
-            warn!("Yahoo auth failed, skipping enrichment: {e}");
-            return;
-        }
-    };
-
-    let mut handles = Vec::new();
-    for r in results.iter() {
-        let client = client.clone();
-        let symbol = r.symbol.clone();
-        let auth = auth.clone();
-        handles.push(tokio::spawn(async move {
-            fetch_enrichment_with_auth(&client, &symbol, &auth).await
-        }));
+#[async_trait]
+impl EnrichmentProvider for YahooProvider {
+    fn name(&self) -> &'static str {
+        ProviderKind::Yahoo.label()
     }
 
-    for (i, handle) in handles.into_iter().enumerate() {
-        if let Ok(data) = handle.await {
-            let r = &mut results[i];
-            r.name = data.name;
-            r.sector = data.sector;
-            r.industry = data.industry;
-            r.country = data.country;
-            r.float_shares = data.float_shares;
-            r.short_pct = data.short_pct;
-            r.avg_volume = data.avg_volume;
-            r.avg_volume_10d = data.avg_volume_10d;
-            r.catalyst = data.catalyst;
-            // Prefer 10d avg for RVOL (more relevant for momentum), fall back to 3mo
-            let avg_for_rvol = data.avg_volume_10d.or(data.avg_volume);
-            if let (Some(vol), Some(avg)) = (r.volume, avg_for_rvol) {
-                if avg > 0 {
-                    // vol is IB round lots (×100), avg is Yahoo raw shares
-                    r.rvol = Some(vol as f64 * 100.0 / avg as f64);
-                }
+    async fn fetch(&self, symbol: &str) -> Result<EnrichmentData> {
+        let auth = match self.auth().await {
+            Ok(a) => Some(a),
+            Err(e) => {
+                warn!("Yahoo auth failed, fetching {symbol} news only: {e}");
+                None
             }
-        }
+        };
+        let info = async {
+            match &auth {
+                Some(auth) => fetch_yahoo_info(&self.client, symbol, auth).await.map(Some),
+                None => Ok(None),
+            }
+        };
+        let (info_result, news) = tokio::join!(info, self.news(symbol, auth.as_ref()));
+
+        let mut data = match info_result {
+            Ok(Some(info)) => from_quote_summary(&info),
+            Ok(None) => EnrichmentData::default(),
+            Err(e) => {
+                warn!("Yahoo Finance info fetch failed for {symbol}: {e}");
+                EnrichmentData::default()
+            }
+        };
+        data.apply_news(NewsUpdate::from_items(&news));
+        Ok(data)
+    }
+
+    /// RSS only: fast and needs no auth.
+    async fn fetch_news(&self, symbol: &str) -> Result<Option<NewsUpdate>> {
+        let news = fetch_yahoo_news_rss(&self.client, symbol).await?;
+        Ok((!news.is_empty()).then(|| NewsUpdate::from_items(&news)))
     }
 }
 
@@ -365,14 +315,6 @@ pub async fn enrich_results(results: &mut [ScanResult]) {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_enrichment_data_default() {
-        let d = EnrichmentData::default();
-        assert!(d.name.is_none());
-        assert!(d.sector.is_none());
-        assert!(d.catalyst.is_none());
-    }
-
     #[test]
     fn test_extract_raw_missing() {
         let data = serde_json::json!({});
@@ -392,4 +334,26 @@ mod tests {
         });
         assert_eq!(extract_str(&data, "price", "shortName"), Some("Apple Inc.".to_string()));
     }
+
+    #[test]
+    fn test_from_quote_summary() {
+        let data = serde_json::json!({
+            "quoteSummary": {
+                "result": [{
+                    "price": {
+                        "shortName": "Acme Bio",
+                        "averageDailyVolume10Day": {"raw": 2500000}
+                    },
+                    "defaultKeyStatistics": {
+                        "floatShares": {"raw": 8200000}
+                    }
+                }]
+            }
+        });
+        let d = from_quote_summary(&data);
+        assert_eq!(d.name.as_deref(), Some("Acme Bio"));
+        assert_eq!(d.float_shares, Some(8_200_000.0));
+        assert_eq!(d.avg_volume_10d, Some(Shares(2_500_000)));
+        assert!(d.sector.is_none() && d.news_headlines.is_empty());
+    }
 }
@@ -1,15 +1,20 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use iced::keyboard;
 use iced::widget::{container, row};
 use iced::{Element, Font, Length, Subscription, Task, Theme};
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::config::SupabaseConfig;
+use crate::config::ScannerConfig;
+use crate::criteria::MomentumCriteria;
 use crate::engine::{AlertEngine, EngineEvent};
-use crate::history::SupabaseClient;
+use crate::enrichment::{ProviderChain, YahooProvider};
+use crate::market_data::{Feed, Simulator};
 use crate::models::*;
+use crate::rvol::scan_rvol;
 use crate::tws;
+use crate::tws::bars::{self, BarDuration, BarRequest, BarSize};
 
 use super::components::side_rail::side_rail_view;
 use super::theme;
@@ -48,9 +53,19 @@ pub enum Message {
     SplitRight,
     ScanCategory(String),
     RunScan(String),
+    /// Fetch and show the article behind a headline, or hide it if shown.
+    OpenArticle(NewsHeadline),
     FontLoaded(Result<(), iced::font::Error>),
 }
 
+/// A news article opened from the detail panel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Article {
+    pub article_id: String,
+    /// Plain text, or the fetch error; `None` while loading.
+    pub body: Option<Result<String, String>>,
+}
+
 /// Application state for the GUI.
 pub struct App {
     pub engine: AlertEngine,
@@ -77,6 +92,12 @@ pub struct App {
     pub scan_results: Vec<ScanResult>,
     pub selected_scan_row: usize,
     pub scan_results_code: String,
+    /// Loaded scanner.toml (criteria profiles available to `criteria <name>`).
+    pub scanner_config: ScannerConfig,
+    /// Title shown in place of host:port for simulated or replayed feeds.
+    pub feed_label: Option<String>,
+    /// Article shown under its headline in the detail panel.
+    pub article: Option<Article>,
 }
 
 impl App {
@@ -108,48 +129,101 @@ impl App {
             scan_results: Vec::new(),
             selected_scan_row: 0,
             scan_results_code: String::new(),
+            scanner_config: ScannerConfig::default(),
+            article: None,
+            feed_label: None,
         }
     }
 
     /// Entry point for iced. Creates the app with engine setup.
-    pub fn new_gui(host: String, port: Option<u16>) -> (Self, Task<Message>) {
+    pub fn new_gui(
+        host: String,
+        port: Option<u16>,
+        scanner_config: ScannerConfig,
+        criteria: MomentumCriteria,
+        feed: Feed,
+    ) -> (Self, Task<Message>) {
         crate::config::load_env();
 
         let (enrich_tx, enrich_rx) = std::sync::mpsc::channel::<crate::engine::EnrichRequest>();
 
-        let db = if let Ok(config) = SupabaseConfig::from_env() {
-            info!("Connected to Supabase");
-            Some(SupabaseClient::new(config))
-        } else {
-            None
+        // Simulated and replayed sessions keep their history in memory
+        let store = match feed {
+            Feed::Tws => crate::history::open_store(&scanner_config.history),
+            Feed::Sim(_) | Feed::Replay { .. } => crate::history::open_memory_store(),
+        };
+        let db = match store {
+            Ok(db) => {
+                info!(backend = db.backend(), "History store opened");
+                Some(db)
+            }
+            Err(e) => {
+                warn!("History store unavailable: {e}");
+                None
+            }
         };
 
         let mut settings = Settings::default();
         settings.host = host;
         settings.port = port;
+        settings.criteria = criteria;
+        settings.scanner_set = scanner_config.scanner_set(None).unwrap_or_default();
         let mut app = App::new(AlertEngine::new(enrich_tx, settings, db));
-
-        // Spawn enrichment worker
-        let _worker = crate::engine::spawn_enrichment_worker(
-            app.engine.bg_tx.clone(),
-            enrich_rx,
-            app.rt_handle.clone(),
-            app.engine.db.clone(),
-        );
+        app.scanner_config = scanner_config;
+        info!("Market data: {}", feed.describe());
+
+        match feed {
+            Feed::Replay { recording, speed } => {
+                // Everything, enrichment included, comes from the recording
+                drop(enrich_rx);
+                if let Some(set) = app.scanner_config.scanner_sets.get(&recording.header.scanner_set) {
+                    app.engine.settings.scanner_set = set.clone();
+                }
+                app.feed_label = Some(format!(
+                    "replay {} ({speed}x)",
+                    recording.header.started_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                ));
+                let _replay = crate::recording::spawn_replay(recording, speed, app.engine.bg_tx.clone());
+                app.update_title();
+                return (app, Task::none());
+            }
+            Feed::Sim(config) => {
+                let sim = Simulator::new(config);
+                app.engine = app.engine.with_source(Arc::new(sim.clone()));
+                app.feed_label = Some("simulated feed".to_string());
+                let _worker = crate::engine::spawn_sim_enrichment_worker(
+                    app.engine.bg_tx.clone(),
+                    enrich_rx,
+                    sim,
+                );
+            }
+            Feed::Tws => {
+                let source = Arc::clone(&app.engine.source);
+                let providers = ProviderChain::from_config(&app.scanner_config.enrichment, source)
+                    .unwrap_or_else(|e| {
+                        warn!("Enrichment config unusable, falling back to Yahoo: {e}");
+                        ProviderChain::new(vec![Arc::new(YahooProvider::new())])
+                    });
+                info!("Enrichment: {}", providers.describe());
+                let _worker = crate::engine::spawn_enrichment_worker(
+                    app.engine.bg_tx.clone(),
+                    enrich_rx,
+                    app.rt_handle.clone(),
+                    app.engine.db.clone(),
+                    providers,
+                );
+            }
+        }
 
         // Spawn market data streaming worker
         let (mktdata_tx, mktdata_rx) = std::sync::mpsc::channel::<crate::engine::MktDataRequest>();
-        let mktdata_host = app.engine.settings.host.clone();
-        let mktdata_ports: Vec<u16> = app.engine.settings.port
-            .map(|p| vec![p])
-            .unwrap_or_else(|| crate::models::DEFAULT_PORTS.to_vec());
         let _mktdata_worker = crate::engine::spawn_market_data_worker(
             app.engine.bg_tx.clone(),
             mktdata_rx,
-            mktdata_host,
-            mktdata_ports,
+            app.engine.feed(),
         );
         app.engine.mktdata_tx = Some(mktdata_tx);
+        let _news_worker = crate::engine::spawn_news_worker(app.engine.bg_tx.clone(), app.engine.feed());
 
         // Probe TWS port
         app.engine.probe_port();
@@ -183,10 +257,11 @@ impl App {
             Mode::Scan => "[SCAN] Tab=Log",
             Mode::Log => "[LOG] Tab=Alert",
         };
-        self.title = format!(
-            "Scanner REPL -- {}:{} {}",
-            self.engine.settings.host, port, mode_tag
-        );
+        let endpoint = self
+            .feed_label
+            .clone()
+            .unwrap_or_else(|| format!("{}:{}", self.engine.settings.host, port));
+        self.title = format!("Scanner REPL -- {endpoint} {mode_tag}");
     }
 
     fn push_output(&mut self, line: &str) {
@@ -233,12 +308,14 @@ impl App {
             "help" => self.cmd_help(),
             "scan" => self.cmd_scan(args),
             "list" => self.cmd_list(args),
+            "bars" => self.cmd_bars(args),
             "set" => self.cmd_set(args),
             "show" => self.cmd_show(),
             "aliases" => self.cmd_aliases(),
             "poll" => self.cmd_poll(args, rt),
             "history" => self.cmd_history(args, rt),
             "mode" => self.cmd_mode(args),
+            "criteria" => self.cmd_criteria(args),
             _ => {
                 self.push_output(&format!("Unknown command: {cmd} -- type help"));
             }
@@ -251,12 +328,18 @@ impl App {
             "  scan <alias|code> [--rows N] [--min-price N] [--max-price N]",
             "  list                  Show scanner groups",
             "  list <group>          Expand group (fuzzy match)",
+            "  bars SYM [size] [duration] [--rth]  Historical bars, e.g. bars AAPL 5m 1d",
             "  poll                  Show polling status",
             "  poll on|off           Start/stop background polling",
             "  poll clear            Clear seen-set (re-alert)",
+            "  poll set              List scanner sets",
+            "  poll set <name>       Switch the polled scanner set",
             "  history               Show today's tracked stocks",
             "  history all           Show all historical stocks",
             "  history clear         Clear entire history",
+            "  history trajectory SYM  Show today's sightings for a symbol",
+            "  criteria              List momentum criteria profiles",
+            "  criteria <name>       Switch active criteria profile",
             "  set <key> <value>     Change setting",
             "  show                  Current settings",
             "  aliases               Alias map",
@@ -335,12 +418,36 @@ impl App {
         self.engine.start_list(group);
     }
 
+    fn cmd_bars(&mut self, args: &[&str]) {
+        let usage = "Usage: bars SYM [size] [duration] [--rth]";
+        let Some(symbol) = args.first() else {
+            self.push_output(usage);
+            return;
+        };
+        let rth = args.contains(&"--rth");
+        let rest: Vec<&str> = args[1..].iter().copied().filter(|a| *a != "--rth").collect();
+        let size = rest.first().map_or(Ok(BarSize::Min5), |s| bars::parse_bar_size(s));
+        let duration = rest.get(1).map_or(Ok(BarDuration::DAY), |s| bars::parse_duration(s));
+        match (size, duration) {
+            (Ok(size), Ok(duration)) => {
+                self.push_output(&format!("Fetching {} bars for {}...", size, symbol.to_uppercase()));
+                self.engine.start_bars(BarRequest::new(symbol, size, duration).rth(rth));
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                self.push_output(&format!("{e}"));
+                self.push_output(usage);
+            }
+        }
+    }
+
     fn cmd_poll(&mut self, args: &[&str], _rt: &tokio::runtime::Handle) {
         if args.is_empty() {
             let status = if self.engine.polling { "on" } else { "off" };
             self.push_output(&format!(
-                "  Polling: {}  |  Seen: {} symbols",
+                "  Polling: {}  |  Set: {} ({} scanners)  |  Seen: {} symbols",
                 status,
+                self.engine.settings.scanner_set.name,
+                self.engine.settings.scanner_set.len(),
                 self.engine.alert_seen.len()
             ));
             return;
@@ -365,17 +472,45 @@ impl App {
                 let count = self.engine.poll_clear();
                 self.push_output(&format!("Cleared {count} seen symbols and alert table"));
             }
+            "set" => self.cmd_poll_set(args.get(1).copied()),
             _ => {
-                self.push_output("Usage: poll [on|off|clear]");
+                self.push_output("Usage: poll [on|off|clear|set <name>]");
             }
         }
     }
 
+    fn cmd_poll_set(&mut self, name: Option<&str>) {
+        if let Some(name) = name {
+            match self.scanner_config.scanner_set(Some(name)) {
+                Ok(set) => {
+                    self.push_output(&format!("Scanner set: {} -- {}", set.name, set.describe()));
+                    self.engine.set_scanner_set(set);
+                }
+                Err(e) => self.push_output(&format!("{e}")),
+            }
+            return;
+        }
+
+        self.push_output(&format!(
+            "Active scanner set: {}",
+            self.engine.settings.scanner_set.name
+        ));
+        let sets: Vec<String> = self
+            .scanner_config
+            .scanner_sets
+            .values()
+            .map(|s| format!("  {:<12}  {}", s.name, s.describe()))
+            .collect();
+        for line in sets {
+            self.push_output(&line);
+        }
+    }
+
     fn cmd_history(&mut self, args: &[&str], rt: &tokio::runtime::Handle) {
         let db = match &self.engine.db {
             Some(db) => db,
             None => {
-                self.push_output("Supabase not connected");
+                self.push_output("History store not configured");
                 return;
             }
         };
@@ -386,6 +521,17 @@ impl App {
             return;
         }
 
+        if args.first().map(|s| s.to_lowercase()) == Some("trajectory".to_string()) {
+            let Some(symbol) = args.get(1).map(|s| s.to_uppercase()) else {
+                self.push_output("Usage: history trajectory SYMBOL");
+                return;
+            };
+            let date = crate::session::today();
+            let sightings = rt.block_on(db.get_trajectory(&symbol, date)).unwrap_or_default();
+            self.show_trajectory(&symbol, date, &sightings);
+            return;
+        }
+
         let (stocks, label) =
             if args.first().map(|s| s.to_lowercase()) == Some("all".to_string()) {
                 (
@@ -408,12 +554,13 @@ impl App {
 
         self.push_output(&format!("{label} -- {} stocks", stocks.len()));
         self.push_output(&format!(
-            "{:<10}  {:<6}  {:>8}  {:>8}  {:>6}  {:<30}  {:>4}  {}",
-            "Time", "Symbol", "Last", "Chg%", "RVol", "Scanners", "Hits", "Catalyst"
+            "{:<10}  {:<10}  {:<6}  {:>8}  {:>8}  {:>6}  {:<30}  {:>4}  {}",
+            "Date", "Time", "Symbol", "Last", "Chg%", "RVol", "Scanners", "Hits", "Catalyst"
         ));
-        self.push_output(&"-".repeat(100));
+        self.push_output(&"-".repeat(112));
 
         for s in &stocks {
+            let date_str = crate::history::scan_date_str(s);
             let time_str = crate::history::local_time_str(&s.first_seen);
             let price = s
                 .last_price
@@ -435,12 +582,79 @@ impl App {
                 cat.to_string()
             };
             self.push_output(&format!(
-                "{:<10}  {:<6}  {:>8}  {:>8}  {:>6}  {:<30}  {:>4}  {}",
-                time_str, s.symbol, price, chg, rvol, s.scanners, hits, cat
+                "{:<10}  {:<10}  {:<6}  {:>8}  {:>8}  {:>6}  {:<30}  {:>4}  {}",
+                date_str, time_str, s.symbol, price, chg, rvol, s.scanners, hits, cat
+            ));
+        }
+    }
+
+    fn show_trajectory(
+        &mut self,
+        symbol: &str,
+        date: chrono::NaiveDate,
+        sightings: &[crate::models::Sighting],
+    ) {
+        if sightings.is_empty() {
+            self.push_output(&format!("{symbol} on {date}: no sightings"));
+            return;
+        }
+        self.push_output(&format!("{symbol} on {date} -- {} sightings", sightings.len()));
+        self.push_output(&format!(
+            "{:<10}  {:<20}  {:>4}  {:>8}  {:>8}  {:>6}",
+            "Time", "Scanner", "Rank", "Last", "Chg%", "RVol"
+        ));
+        self.push_output(&"-".repeat(66));
+        for s in sightings {
+            self.push_output(&format!(
+                "{:<10}  {:<20}  {:>4}  {:>8}  {:>8}  {:>6}",
+                crate::history::local_time_str(&s.seen_at),
+                s.scanner,
+                s.rank,
+                crate::scanner::fmt_price(s.price),
+                crate::scanner::fmt_change_pct(s.change_pct),
+                crate::scanner::fmt_rvol(s.rvol),
             ));
         }
     }
 
+    fn cmd_criteria(&mut self, args: &[&str]) {
+        if let Some(name) = args.first() {
+            match self.scanner_config.criteria(Some(name)) {
+                Ok(criteria) => {
+                    self.push_output(&format!(
+                        "Criteria: {} -- {}",
+                        criteria.name,
+                        criteria.describe()
+                    ));
+                    self.engine.set_criteria(criteria);
+                    self.reevaluate_scan_results();
+                }
+                Err(e) => self.push_output(&format!("{e}")),
+            }
+            return;
+        }
+
+        self.push_output(&format!(
+            "Active criteria: {}",
+            self.engine.settings.criteria.name
+        ));
+        let profiles: Vec<String> = self
+            .scanner_config
+            .criteria
+            .values()
+            .map(|c| format!("  {:<12}  {}", c.name, c.describe()))
+            .collect();
+        for line in profiles {
+            self.push_output(&line);
+        }
+    }
+
+    /// Re-score the scanner view results against the active criteria.
+    fn reevaluate_scan_results(&mut self) {
+        let settings = &self.engine.settings;
+        crate::scanner::score_results(&mut self.scan_results, &settings.criteria, settings.scanner_set.len());
+    }
+
     fn cmd_set(&mut self, args: &[&str]) {
         if args.len() < 2 {
             self.push_output("Usage: set <key> <value>");
@@ -521,6 +735,14 @@ impl App {
             "  maxstreaming = {}",
             self.engine.settings.max_streaming
         ));
+        self.push_output(&format!(
+            "  criteria  = {}",
+            self.engine.settings.criteria.name
+        ));
+        self.push_output(&format!(
+            "  scannerset = {}",
+            self.engine.settings.scanner_set.name
+        ));
     }
 
     fn cmd_aliases(&mut self) {
@@ -594,18 +816,15 @@ impl App {
                         sr.avg_volume = ar.avg_volume;
                         sr.avg_volume_10d = ar.avg_volume_10d;
                         sr.catalyst = ar.catalyst.clone();
+                        sr.catalyst_sentiment = ar.catalyst_sentiment;
                         sr.news_headlines = ar.news_headlines.clone();
                         sr.enriched = true;
-                        // Prefer 10d avg for RVOL, fall back to 3mo
-                        let avg_for_rvol = ar.avg_volume_10d.or(ar.avg_volume);
-                        if let (Some(vol), Some(avg)) = (sr.volume, avg_for_rvol) {
-                            if avg > 0 {
-                                // vol is IB round lots (×100), avg is Yahoo raw shares
-                                sr.rvol = Some(vol as f64 * 100.0 / avg as f64);
-                            }
+                        if let Some(v) = scan_rvol(sr) {
+                            sr.rvol = Some(v);
                         }
                     }
                 }
+                self.reevaluate_scan_results();
 
                 if results.is_empty() {
                     self.push_output("No results.");
@@ -770,35 +989,93 @@ impl App {
                     sr.float_shares = data.float_shares;
                     sr.short_pct = data.short_pct;
                     sr.catalyst = data.catalyst;
+                    sr.catalyst_sentiment = data.catalyst_sentiment;
                     sr.news_headlines = data.news_headlines;
                     sr.avg_volume = data.avg_volume;
                     sr.avg_volume_10d = data.avg_volume_10d;
                     sr.enriched = true;
-                    // Prefer 10d avg for RVOL, fall back to 3mo
-                    let avg_for_rvol = data.avg_volume_10d.or(data.avg_volume);
-                    if let (Some(vol), Some(avg)) = (sr.volume, avg_for_rvol) {
-                        if avg > 0 {
-                            // vol is IB round lots (×100), avg is Yahoo raw shares
-                            sr.rvol = Some(vol as f64 * 100.0 / avg as f64);
-                        }
+                    if let Some(v) = scan_rvol(sr) {
+                        sr.rvol = Some(v);
                     }
                 }
+                self.reevaluate_scan_results();
             }
             EngineEvent::NewsRefresh { symbol, update } => {
                 // Update scan results
                 if let Some(sr) = self.scan_results.iter_mut().find(|r| r.symbol == symbol) {
                     if update.catalyst.is_some() {
                         sr.catalyst = update.catalyst.clone();
+                        sr.catalyst_sentiment = update.catalyst_sentiment;
                     }
                     if !update.news_headlines.is_empty() {
                         sr.news_headlines = update.news_headlines;
                     }
                 }
+                self.reevaluate_scan_results();
+            }
+            EngineEvent::NewsTick { symbol, headline } => {
+                let source = headline.source.as_deref().unwrap_or("-");
+                self.push_log("news", &format!("{symbol} -- {} ({source})", headline.title));
+                let row = self.engine.alert_rows.iter().find(|r| r.symbol == symbol);
+                if let (Some(row), Some(sr)) =
+                    (row, self.scan_results.iter_mut().find(|r| r.symbol == symbol))
+                {
+                    sr.catalyst.clone_from(&row.catalyst);
+                    sr.catalyst_sentiment = row.catalyst_sentiment;
+                    sr.news_headlines.clone_from(&row.news_headlines);
+                }
+                self.reevaluate_scan_results();
+            }
+            EngineEvent::ArticleComplete { provider, article_id, result } => {
+                if let Err(e) = &result {
+                    self.push_log("news", &format!("Article {provider}/{article_id} failed: {e}"));
+                }
+                if let Some(article) = self.article.as_mut().filter(|a| a.article_id == article_id) {
+                    article.body = Some(result.map(|body| tws::news::plain_text(&body)));
+                }
             }
             EngineEvent::PortDiscovered { port } => {
                 self.push_log("tws", &format!("Connected: port {port}"));
                 self.update_title();
             }
+            EngineEvent::CriteriaMatch { symbol, criteria } => {
+                self.push_log("criteria", &format!("{symbol} passes all pillars of '{criteria}'"));
+            }
+            EngineEvent::ConnectionChanged { role, state } => {
+                self.push_log("tws", &format!("{}: {state}", role.label()));
+                self.update_title();
+            }
+            EngineEvent::Halted { symbol, state, price, .. } => {
+                let at = price.map(|p| format!(" at ${p:.2}")).unwrap_or_default();
+                self.push_log("halt", &format!("{symbol} {}{at}", state.label()));
+            }
+            EngineEvent::Resumed { symbol, duration, .. } => {
+                self.push_log(
+                    "halt",
+                    &format!(
+                        "{symbol} resumed after {}",
+                        crate::scanner::fmt_halt_duration(duration.num_seconds())
+                    ),
+                );
+            }
+            EngineEvent::BarsComplete { request, result } => {
+                self.clear_output();
+                match result {
+                    Ok(bars) => {
+                        self.push_output(&format!(
+                            "{} -- {} x {} bars{}",
+                            request.symbol,
+                            bars.len(),
+                            request.bar_size,
+                            if request.rth { " (RTH)" } else { "" }
+                        ));
+                        for line in crate::scanner::bar_table(&bars) {
+                            self.push_output(&line);
+                        }
+                    }
+                    Err(e) => self.push_output(&format!("Bars for {} failed: {e}", request.symbol)),
+                }
+            }
         }
     }
 }
@@ -850,6 +1127,9 @@ impl App {
                 self.handle_input(&input, &handle);
             }
             Message::SelectAlert(i) => {
+                if i != self.selected_alert_row {
+                    self.article = None;
+                }
                 self.selected_alert_row = i;
             }
             Message::SelectScanResult(i) => {
@@ -893,6 +1173,17 @@ impl App {
                 let handle = self.rt_handle.clone();
                 self.handle_input(&format!("scan {code}"), &handle);
             }
+            Message::OpenArticle(headline) => {
+                let Some((provider, article_id)) = headline.article() else {
+                    return Task::none();
+                };
+                if self.article.as_ref().is_some_and(|a| a.article_id == article_id) {
+                    self.article = None;
+                } else {
+                    self.article = Some(Article { article_id: article_id.to_string(), body: None });
+                    self.engine.start_article(provider, article_id);
+                }
+            }
             Message::FontLoaded(_) => {}
         }
         Task::none()
@@ -944,8 +1235,20 @@ impl App {
 }
 
 /// Launch the iced GUI application.
-pub fn run_gui(host: String, port: Option<u16>) -> iced::Result {
-    iced::application(move || App::new_gui(host.clone(), port), App::update, App::view)
+pub fn run_gui(
+    host: String,
+    port: Option<u16>,
+    scanner_config: ScannerConfig,
+    criteria: MomentumCriteria,
+    feed: Feed,
+) -> iced::Result {
+    iced::application(
+        move || {
+            App::new_gui(host.clone(), port, scanner_config.clone(), criteria.clone(), feed.clone())
+        },
+        App::update,
+        App::view,
+    )
         .title(App::iced_title)
         .subscription(App::subscription)
         .theme(App::iced_theme)
@@ -1255,7 +1558,52 @@ mod tests {
         assert!(app
             .output_lines
             .iter()
-            .any(|l| l.contains("Supabase not connected")));
+            .any(|l| l.contains("History store not configured")));
+    }
+
+    #[test]
+    fn test_criteria_list() {
+        let (mut app, rt) = app_with_rt();
+        let handle = rt.handle().clone();
+        app.handle_input("criteria", &handle);
+        assert!(app.output_lines.iter().any(|l| l.contains("Active criteria: momentum")));
+    }
+
+    #[test]
+    fn test_criteria_switch() {
+        let (mut app, rt) = app_with_rt();
+        let handle = rt.handle().clone();
+        app.scanner_config = ScannerConfig::from_toml(
+            "[criteria.runners]\nmax_price = 5.0\nmin_change_pct = 20.0\n",
+        )
+        .unwrap();
+        app.handle_input("criteria runners", &handle);
+        assert_eq!(app.engine.settings.criteria.name, "runners");
+        assert_eq!(app.engine.settings.criteria.max_price, Some(5.0));
+
+        app.handle_input("criteria nope", &handle);
+        assert!(app.output_lines.iter().any(|l| l.contains("unknown criteria profile")));
+        assert_eq!(app.engine.settings.criteria.name, "runners");
+    }
+
+    #[test]
+    fn test_poll_set_switch() {
+        let (mut app, rt) = app_with_rt();
+        let handle = rt.handle().clone();
+        app.scanner_config = ScannerConfig::from_toml(
+            "[scanner_sets.gappers]\ncodes = [\"HIGH_OPEN_GAP\", \"TOP_PERC_GAIN\"]\n",
+        )
+        .unwrap();
+        app.handle_input("poll set", &handle);
+        assert!(app.output_lines.iter().any(|l| l.contains("Active scanner set: default")));
+
+        app.handle_input("poll set gappers", &handle);
+        assert_eq!(app.engine.settings.scanner_set.name, "gappers");
+        assert_eq!(app.engine.settings.scanner_set.len(), 2);
+
+        app.handle_input("poll set nope", &handle);
+        assert!(app.output_lines.iter().any(|l| l.contains("unknown scanner set")));
+        assert_eq!(app.engine.settings.scanner_set.name, "gappers");
     }
 
     #[test]
@@ -1263,14 +1611,8 @@ mod tests {
         use crate::models::NewsHeadline;
         let data = crate::enrichment::EnrichmentData {
             news_headlines: vec![
-                NewsHeadline {
-                    title: "Headline 1".to_string(),
-                    published: Some(1700000000),
-                },
-                NewsHeadline {
-                    title: "Headline 2".to_string(),
-                    published: None,
-                },
+                NewsHeadline::new("Headline 1", Some(1700000000)),
+                NewsHeadline::new("Headline 2", None),
             ],
             ..Default::default()
         };
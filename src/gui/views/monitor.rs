@@ -1,8 +1,10 @@
 use iced::widget::{button, column, container, row, scrollable, text, Space};
 use iced::{Element, Length};
 
+use crate::catalyst::Sentiment;
 use crate::gui::app::{App, Message};
 use crate::gui::theme::{self, Colors};
+use crate::scanner::{fmt_borrow, fmt_ssr, fmt_volume};
 
 impl App {
     pub fn alerts_view(&self) -> Element<Message> {
@@ -93,10 +95,22 @@ impl App {
                 .size(fs)
                 .width(Length::FillPortion(2))
                 .style(theme::text_color(Colors::YELLOW)),
+            text("Spread")
+                .size(fs)
+                .width(Length::FillPortion(2))
+                .style(theme::text_color(Colors::YELLOW)),
             text("Hits")
                 .size(fs)
                 .width(Length::FillPortion(1))
                 .style(theme::text_color(Colors::YELLOW)),
+            text("Score")
+                .size(fs)
+                .width(Length::FillPortion(1))
+                .style(theme::text_color(Colors::YELLOW)),
+            text("Pillars")
+                .size(fs)
+                .width(Length::FillPortion(1))
+                .style(theme::text_color(Colors::YELLOW)),
             text("Name")
                 .size(fs)
                 .width(Length::FillPortion(4))
@@ -120,7 +134,7 @@ impl App {
                     .change_pct
                     .map(|c| format!("{c:+.1}%"))
                     .unwrap_or("-".into());
-                let hits = format!("{}/8", r.scanner_hits);
+                let hits = format!("{}/{}", r.scanner_hits, self.engine.settings.scanner_set.len());
                 let name = if r.enriched {
                     r.name.as_deref().unwrap_or("-")
                 } else {
@@ -138,18 +152,60 @@ impl App {
                     Colors::RED
                 };
 
+                // Halted rows are badged red and show how long they have
+                // been halted instead of a stale spread
+                let halted = r.quote.is_halted().unwrap_or(false);
+                let (spread_str, spread_color) = if halted {
+                    let mins = r
+                        .quote
+                        .halted_at
+                        .map(|t| (chrono::Utc::now() - t).num_minutes());
+                    let badge = match mins {
+                        Some(m) => format!("HALT {m}m"),
+                        None => "HALT".to_string(),
+                    };
+                    (badge, Colors::RED)
+                } else {
+                    (
+                        r.quote.spread_pct.map(|s| format!("{s:.1}%")).unwrap_or("-".into()),
+                        Colors::TEXT,
+                    )
+                };
+                let symbol_color = if halted { Colors::RED } else { Colors::CYAN };
+
+                let total = r.pillars.passed.len() + r.pillars.failed.len();
+                let pillars = if total == 0 {
+                    "-".to_string()
+                } else {
+                    format!("{}/{total}", r.pillars.passed.len())
+                };
+                let pillar_color = if total > 0 && r.pillars.passes_all() {
+                    Colors::GREEN
+                } else {
+                    Colors::TEXT
+                };
+
                 let row_content = row![
                     text(&r.alert_time).size(fs).width(Length::FillPortion(3)),
                     text(&r.symbol)
                         .size(fs)
                         .width(Length::FillPortion(2))
-                        .style(theme::text_color(Colors::CYAN)),
+                        .style(theme::text_color(symbol_color)),
                     text(price).size(fs).width(Length::FillPortion(2)),
                     text(chg_str)
                         .size(fs)
                         .width(Length::FillPortion(2))
                         .style(theme::text_color(chg_color)),
+                    text(spread_str)
+                        .size(fs)
+                        .width(Length::FillPortion(2))
+                        .style(theme::text_color(spread_color)),
                     text(hits).size(fs).width(Length::FillPortion(1)),
+                    text(format!("{:.0}", r.score)).size(fs).width(Length::FillPortion(1)),
+                    text(pillars)
+                        .size(fs)
+                        .width(Length::FillPortion(1))
+                        .style(theme::text_color(pillar_color)),
                     text(name).size(fs).width(Length::FillPortion(4)),
                 ]
                 .spacing(4)
@@ -234,19 +290,56 @@ impl App {
                 .style(theme::text_color(chg_color))
         ]);
 
+        // Level-1 quote
+        let q = &r.quote;
+        let side = |price: Option<f64>, size| match price {
+            Some(p) => format!("${p:.2} x {}", fmt_volume(size)),
+            None => "-".to_string(),
+        };
+        lines = lines.push(row![label!("Bid"), val!(side(q.bid, q.bid_size))]);
+        lines = lines.push(row![label!("Ask"), val!(side(q.ask, q.ask_size))]);
+        let spread_str = match (q.spread, q.spread_pct) {
+            (Some(s), Some(p)) => format!("${s:.2} ({p:.2}%)"),
+            _ => "-".to_string(),
+        };
+        lines = lines.push(row![label!("Spread"), val!(spread_str)]);
+        let (halt_str, halt_color) = match q.halted {
+            Some(h) if h.is_halted() => {
+                let since = q
+                    .halted_at
+                    .map(|t| format!(" since {}", t.with_timezone(&chrono::Local).format("%H:%M:%S")))
+                    .unwrap_or_default();
+                let at = q.halt_price.map(|p| format!(" at ${p:.2}")).unwrap_or_default();
+                (format!("{}{since}{at}", h.label()), Colors::RED)
+            }
+            Some(h) => (h.label().to_string(), Colors::GREEN),
+            None => ("-".to_string(), Colors::TEXT),
+        };
+        lines = lines.push(row![
+            label!("Status"),
+            text(halt_str)
+                .size(fs)
+                .width(Length::FillPortion(3))
+                .style(theme::text_color(halt_color))
+        ]);
+
         // Volume
-        let vol_str = r.volume.map(format_volume).unwrap_or("-".into());
+        let vol_str = fmt_volume(r.volume);
         lines = lines.push(row![label!("Volume"), val!(vol_str)]);
 
         // Avg Volume (10d and 3mo) — Yahoo values are already in raw shares
-        let avg_vol_10d_str = fmt_or_dots(r.enriched, r.avg_volume_10d.map(format_raw_shares));
-        let avg_vol_3mo_str = fmt_or_dots(r.enriched, r.avg_volume.map(format_raw_shares));
+        let avg_vol_10d_str = fmt_or_dots(r.enriched, r.avg_volume_10d.map(|v| v.to_string()));
+        let avg_vol_3mo_str = fmt_or_dots(r.enriched, r.avg_volume.map(|v| v.to_string()));
         lines = lines.push(row![label!("Avg Vol 10d"), val!(avg_vol_10d_str)]);
         lines = lines.push(row![label!("Avg Vol 3mo"), val!(avg_vol_3mo_str)]);
 
         // RVol
         let rvol_str = fmt_or_dots(r.enriched, r.rvol.map(|v| format!("{v:.1}x")));
         lines = lines.push(row![label!("RVol"), val!(rvol_str)]);
+        let rvol_tod_str = r.rvol_time_of_day.map(|v| format!("{v:.1}x")).unwrap_or("-".into());
+        let rvol_day_str = fmt_or_dots(r.enriched, r.rvol_full_day.map(|v| format!("{v:.1}x")));
+        lines = lines.push(row![label!("RVol (time)"), val!(rvol_tod_str)]);
+        lines = lines.push(row![label!("RVol (day)"), val!(rvol_day_str)]);
 
         // Float
         let float_str = fmt_or_dots(
@@ -264,11 +357,73 @@ impl App {
             }),
         );
         lines = lines.push(row![label!("Float"), val!(float_str)]);
+        let shares_out_str =
+            fmt_or_dots(r.enriched, r.shares_outstanding.map(|v| format!("{:.1}M", v / 1e6)));
+        lines = lines.push(row![label!("Shares Out"), val!(shares_out_str)]);
 
         // Short%
         let short_str =
             fmt_or_dots(r.enriched, r.short_pct.map(|v| format!("{:.1}%", v * 100.0)));
         lines = lines.push(row![label!("Short%"), val!(short_str)]);
+        lines = lines.push(row![label!("Borrow"), val!(fmt_borrow(&r.short))]);
+        let ssr_color = if r.short.ssr == Some(true) { Colors::RED } else { Colors::TEXT };
+        lines = lines.push(row![
+            label!("SSR"),
+            text(fmt_ssr(r.short.ssr))
+                .size(fs)
+                .width(Length::FillPortion(3))
+                .style(theme::text_color(ssr_color))
+        ]);
+
+        // Where each enriched value came from
+        lines = lines.push(row![
+            label!("Sources"),
+            val!(fmt_or_dots(r.enriched, r.provenance.summary()))
+        ]);
+
+        // Intraday levels and the last price's distance from each
+        let lv = &r.levels;
+        let level = |price: Option<f64>, dist: Option<f64>| match (price, dist) {
+            (Some(p), Some(d)) => format!("${p:.2} ({d:+.1}%)"),
+            (Some(p), None) => format!("${p:.2}"),
+            _ => "-".to_string(),
+        };
+        lines = lines.push(row![label!("VWAP"), val!(level(lv.vwap, lv.from_vwap_pct))]);
+        lines = lines.push(row![label!("HOD"), val!(level(lv.high_of_day, lv.from_high_pct))]);
+        lines = lines.push(row![
+            label!("PM High"),
+            val!(level(lv.premarket_high, lv.from_premarket_high_pct))
+        ]);
+        lines = lines.push(row![
+            label!("PM Low"),
+            val!(level(lv.premarket_low, lv.from_premarket_low_pct))
+        ]);
+        let gap_str = lv.gap_pct.map(|g| format!("{g:+.1}%")).unwrap_or("-".into());
+        lines = lines.push(row![label!("Gap"), val!(gap_str)]);
+
+        // Composite momentum score and pillars (active criteria profile)
+        lines = lines.push(row![label!("Score"), val!(format!("{:.1}", r.score))]);
+        let labels = |ps: &[crate::criteria::Pillar]| {
+            if ps.is_empty() {
+                "-".to_string()
+            } else {
+                ps.iter().map(|p| p.label()).collect::<Vec<_>>().join(", ")
+            }
+        };
+        lines = lines.push(row![
+            label!("Passed"),
+            text(labels(&r.pillars.passed))
+                .size(fs)
+                .width(Length::FillPortion(3))
+                .style(theme::text_color(Colors::GREEN))
+        ]);
+        lines = lines.push(row![
+            label!("Failed"),
+            text(labels(&r.pillars.failed))
+                .size(fs)
+                .width(Length::FillPortion(3))
+                .style(theme::text_color(Colors::RED))
+        ]);
 
         lines = lines.push(Space::new().height(4));
 
@@ -285,12 +440,33 @@ impl App {
         let country_str = fmt_or_dots(r.enriched, r.country.clone());
         lines = lines.push(row![label!("Country"), val!(country_str)]);
 
+        // Catalyst category, colored by sentiment
+        let catalyst_str = fmt_or_dots(
+            r.enriched,
+            r.catalyst_kind.map(|kind| match r.catalyst_sentiment {
+                Some(sentiment) => format!("{} ({})", kind.label(), sentiment.label()),
+                None => kind.label().to_string(),
+            }),
+        );
+        let catalyst_color = match r.catalyst_sentiment {
+            Some(Sentiment::Bullish) => Colors::GREEN,
+            Some(Sentiment::Bearish) => Colors::RED,
+            _ => Colors::TEXT,
+        };
+        lines = lines.push(row![
+            label!("Catalyst"),
+            text(catalyst_str)
+                .size(fs)
+                .width(Length::FillPortion(3))
+                .style(theme::text_color(catalyst_color))
+        ]);
+
         lines = lines.push(Space::new().height(4));
 
         // Scanner Hits
         lines = lines.push(row![
             label!("Scanners"),
-            val!(format!("{}/8", r.scanner_hits))
+            val!(format!("{}/{}", r.scanner_hits, self.engine.settings.scanner_set.len()))
         ]);
         if !r.scanners.is_empty() {
             let scan_size = if fs > 9 { fs - 1 } else { fs };
@@ -338,10 +514,35 @@ impl App {
                             .style(theme::text_dim),
                     );
                 }
+                let title = match headline.source.as_deref() {
+                    Some(source) => format!("  {} ({source})", headline.title),
+                    None => format!("  {}", headline.title),
+                };
+                if headline.article().is_none() {
+                    lines = lines.push(text(title).size(news_size));
+                    continue;
+                }
+                // IB headlines open their article
                 lines = lines.push(
-                    text(format!("  {}", headline.title))
-                        .size(news_size),
+                    button(text(title).size(news_size).style(theme::text_color(Colors::CYAN)))
+                        .on_press(Message::OpenArticle(headline.clone()))
+                        .padding(0)
+                        .style(theme::category_btn_style),
                 );
+                let open = self
+                    .article
+                    .as_ref()
+                    .filter(|a| Some(a.article_id.as_str()) == headline.article_id.as_deref());
+                if let Some(article) = open {
+                    let body = match &article.body {
+                        None => text("    Loading article...").style(theme::text_dim),
+                        Some(Ok(body)) => text(body.clone()),
+                        Some(Err(e)) => {
+                            text(format!("    Article unavailable: {e}")).style(theme::text_color(Colors::RED))
+                        }
+                    };
+                    lines = lines.push(container(body.size(news_size)).padding([4, 12]));
+                }
             }
         } else if !r.enriched {
             lines = lines.push(row![
@@ -353,6 +554,39 @@ impl App {
             ]);
         }
 
+        // SEC Filings
+        if !r.filings.is_empty() {
+            lines = lines.push(Space::new().height(4));
+            lines = lines.push(
+                text("Filings")
+                    .size(fs)
+                    .style(theme::text_color(Colors::YELLOW)),
+            );
+            let filing_size = if fs > 9 { fs - 1 } else { fs };
+            for filing in r.filings.iter().take(5) {
+                let filed = filing
+                    .filed
+                    .and_then(|epoch| chrono::DateTime::from_timestamp(epoch, 0))
+                    .map(|dt| dt.with_timezone(&chrono::Local).format("%b %d %H:%M").to_string())
+                    .unwrap_or_else(|| "-".to_string());
+                let color = if crate::catalyst::is_catalyst_filing(filing) {
+                    Colors::CYAN
+                } else {
+                    Colors::TEXT
+                };
+                lines = lines.push(
+                    text(format!("  {} ({filed})", filing.form))
+                        .size(if filing_size > 2 { filing_size - 2 } else { filing_size })
+                        .style(theme::text_dim),
+                );
+                lines = lines.push(
+                    text(format!("  {}", filing.title))
+                        .size(filing_size)
+                        .style(theme::text_color(color)),
+                );
+            }
+        }
+
         container(scrollable(lines).height(Length::Fill))
             .width(Length::FillPortion(pct))
             .height(Length::Fill)
@@ -361,28 +595,6 @@ impl App {
     }
 }
 
-/// IB TWS reports volume in round lots (100 shares). Convert to shares for display.
-fn format_volume(vol: i64) -> String {
-    // IB volume is in round lots (×100 to get shares)
-    let shares = vol as f64 * 100.0;
-    format_shares_f64(shares)
-}
-
-/// Format a value already in raw shares (e.g. Yahoo Finance avg volume).
-fn format_raw_shares(vol: i64) -> String {
-    format_shares_f64(vol as f64)
-}
-
-fn format_shares_f64(shares: f64) -> String {
-    if shares >= 1_000_000.0 {
-        format!("{:.1}M", shares / 1_000_000.0)
-    } else if shares >= 1_000.0 {
-        format!("{:.1}K", shares / 1_000.0)
-    } else {
-        format!("{:.0}", shares)
-    }
-}
-
 fn fmt_or_dots(enriched: bool, val: Option<String>) -> String {
     match val {
         Some(v) if !v.is_empty() => v,
@@ -3,12 +3,15 @@ use iced::{Element, Length};
 
 use crate::gui::app::{App, Message};
 use crate::gui::theme::{self, Colors};
+use crate::scanner::fmt_volume;
+use crate::scanner_set::ScannerSet;
 
-/// Scanner categories for the sidebar.
+/// Scanner categories for the sidebar. The Alerts entry is labelled and
+/// filled from the polled scanner set.
 const SCANNER_CATEGORIES: &[(&str, &str, &[(&str, &str)])] = &[
     (
         "__alert__",
-        "Alerts (8)",
+        "Alerts",
         &[],
     ),
     (
@@ -53,7 +56,7 @@ const SCANNER_CATEGORIES: &[(&str, &str, &[(&str, &str)])] = &[
     ),
 ];
 
-/// The 8 alert scanners with detailed descriptions.
+/// The default set's 8 alert scanners with detailed descriptions.
 const ALERT_SCANNERS_INFO: &[(&str, &str)] = &[
     (
         "HOT_BY_VOLUME",
@@ -208,6 +211,47 @@ const HIGHS_SCANNERS_INFO: &[(&str, &str)] = &[
     ),
 ];
 
+/// Description of a scanner code from the category tables.
+fn scanner_description(code: &str) -> Option<&'static str> {
+    [
+        ALERT_SCANNERS_INFO,
+        MOMENTUM_SCANNERS_INFO,
+        PREMARKET_GAPS_INFO,
+        EXTENDED_HOURS_INFO,
+        HIGHS_SCANNERS_INFO,
+    ]
+    .into_iter()
+    .flatten()
+    .find(|(c, _)| *c == code)
+    .map(|(_, desc)| *desc)
+}
+
+/// Alerts panel rows for the polled scanner set: each code with its
+/// description and filter tags.
+fn alert_scanner_rows(set: &ScannerSet) -> Vec<(String, String)> {
+    set.codes
+        .iter()
+        .map(|code| {
+            let mut desc = scanner_description(code)
+                .unwrap_or("Custom scanner from scanner.toml.")
+                .to_string();
+            let filters = set.filters_for(code);
+            if !filters.is_empty() {
+                let tags: Vec<String> = filters.iter().map(|(k, v)| format!("{k}={v}")).collect();
+                desc.push_str(&format!(" Filters: {}.", tags.join(", ")));
+            }
+            (code.clone(), desc)
+        })
+        .collect()
+}
+
+/// Owned rows of a static category table.
+fn info_rows(scanners: &[(&str, &str)]) -> Vec<(String, String)> {
+    scanners
+        .iter()
+        .map(|(code, desc)| (code.to_string(), desc.to_string()))
+        .collect()
+}
 
 impl App {
     pub fn scanner_view(&self) -> Element<Message> {
@@ -223,7 +267,13 @@ impl App {
         .spacing(2)
         .padding(8);
 
+        let scanner_set = &self.engine.settings.scanner_set;
         for &(key, label, _) in SCANNER_CATEGORIES {
+            let label = if key == "__alert__" {
+                format!("Alerts ({})", scanner_set.len())
+            } else {
+                label.to_string()
+            };
             let has_scanners = matches!(
                 key,
                 "__alert__" | "__momentum__" | "__premarket_gaps__" | "__extended_hours__" | "__highs__"
@@ -265,32 +315,36 @@ impl App {
                 "__alert__" => self.scanner_table_panel(
                     fs,
                     "Alerts",
-                    "These 8 scanners run every poll cycle to detect momentum stocks.",
-                    ALERT_SCANNERS_INFO,
+                    format!(
+                        "Scanner set '{}' ({}) runs every poll cycle to detect momentum stocks.",
+                        scanner_set.name,
+                        scanner_set.describe()
+                    ),
+                    alert_scanner_rows(scanner_set),
                 ),
                 "__momentum__" => self.scanner_table_panel(
                     fs,
                     "Momentum",
-                    "Gainers, losers, volume, and price action scanners.",
-                    MOMENTUM_SCANNERS_INFO,
+                    "Gainers, losers, volume, and price action scanners.".to_string(),
+                    info_rows(MOMENTUM_SCANNERS_INFO),
                 ),
                 "__premarket_gaps__" => self.scanner_table_panel(
                     fs,
                     "Premarket Gaps",
-                    "Stocks gapping up or down from previous close at the open.",
-                    PREMARKET_GAPS_INFO,
+                    "Stocks gapping up or down from previous close at the open.".to_string(),
+                    info_rows(PREMARKET_GAPS_INFO),
                 ),
                 "__extended_hours__" => self.scanner_table_panel(
                     fs,
                     "Extended Hours",
-                    "After-hours and pre-market scanners for outside RTH activity.",
-                    EXTENDED_HOURS_INFO,
+                    "After-hours and pre-market scanners for outside RTH activity.".to_string(),
+                    info_rows(EXTENDED_HOURS_INFO),
                 ),
                 "__highs__" => self.scanner_table_panel(
                     fs,
                     "Highs & Lows",
-                    "52-week high and low breakout scanners.",
-                    HIGHS_SCANNERS_INFO,
+                    "52-week high and low breakout scanners.".to_string(),
+                    info_rows(HIGHS_SCANNERS_INFO),
                 ),
                 _ => self.category_output_panel(fs),
             }
@@ -379,7 +433,7 @@ impl App {
                 .change_pct
                 .map(|c| format!("{c:+.1}%"))
                 .unwrap_or("-".into());
-            let vol_str = r.volume.map(format_volume).unwrap_or("-".into());
+            let vol_str = fmt_volume(r.volume);
             let name = r.name.as_deref().unwrap_or("-");
             let name = if name.len() > 18 {
                 format!("{}..", &name[..16])
@@ -495,12 +549,12 @@ impl App {
         ]);
 
         // Volume
-        let vol_str = r.volume.map(format_volume).unwrap_or("-".into());
+        let vol_str = fmt_volume(r.volume);
         lines = lines.push(row![label!("Volume"), val!(vol_str)]);
 
         // Avg Volume (10d and 3mo)
-        let avg_vol_10d_str = fmt_or_dots(r.enriched, r.avg_volume_10d.map(format_raw_shares));
-        let avg_vol_3mo_str = fmt_or_dots(r.enriched, r.avg_volume.map(format_raw_shares));
+        let avg_vol_10d_str = fmt_or_dots(r.enriched, r.avg_volume_10d.map(|v| v.to_string()));
+        let avg_vol_3mo_str = fmt_or_dots(r.enriched, r.avg_volume.map(|v| v.to_string()));
         lines = lines.push(row![label!("Avg Vol 10d"), val!(avg_vol_10d_str)]);
         lines = lines.push(row![label!("Avg Vol 3mo"), val!(avg_vol_3mo_str)]);
 
@@ -530,6 +584,15 @@ impl App {
             fmt_or_dots(r.enriched, r.short_pct.map(|v| format!("{:.1}%", v * 100.0)));
         lines = lines.push(row![label!("Short%"), val!(short_str)]);
 
+        // Pillars (active criteria profile)
+        let pillars_str = if r.pillars.passed.is_empty() && r.pillars.failed.is_empty() {
+            "-".to_string()
+        } else {
+            r.pillars.summary()
+        };
+        lines = lines.push(row![label!("Pillars"), val!(pillars_str)]);
+        lines = lines.push(row![label!("Score"), val!(format!("{:.1}", r.score))]);
+
         lines = lines.push(Space::new().height(4));
 
         // Name, Sector, Industry, Country
@@ -625,8 +688,8 @@ impl App {
         &self,
         fs: u32,
         title: &'static str,
-        subtitle: &'static str,
-        scanners: &'static [(&'static str, &'static str)],
+        subtitle: String,
+        scanners: Vec<(String, String)>,
     ) -> Element<Message> {
         let desc_size = if fs > 3 { fs - 3 } else { fs };
 
@@ -634,13 +697,13 @@ impl App {
             text("Scanner Code")
                 .size(fs)
                 .style(theme::text_color(Colors::YELLOW)),
-            move |r: (&str, &str)| -> Element<Message> {
+            move |r: (String, String)| -> Element<Message> {
                 button(
-                    text(r.0)
+                    text(r.0.clone())
                         .size(fs)
                         .style(theme::text_color(Colors::GREEN)),
                 )
-                .on_press(Message::RunScan(r.0.to_string()))
+                .on_press(Message::RunScan(r.0))
                 .padding([2, 4])
                 .style(theme::category_btn_style)
                 .into()
@@ -652,7 +715,7 @@ impl App {
             text("Description")
                 .size(fs)
                 .style(theme::text_color(Colors::YELLOW)),
-            move |r: (&str, &str)| -> Element<Message> {
+            move |r: (String, String)| -> Element<Message> {
                 text(r.1)
                     .size(desc_size)
                     .style(theme::text_dim)
@@ -661,7 +724,7 @@ impl App {
         )
         .width(Length::FillPortion(5));
 
-        let tbl = table::table([code_col, desc_col], scanners.to_vec())
+        let tbl = table::table([code_col, desc_col], scanners)
             .padding(8)
             .separator(1);
 
@@ -716,28 +779,6 @@ impl App {
     }
 }
 
-/// IB TWS reports volume in round lots (100 shares). Convert to shares for display.
-fn format_volume(vol: i64) -> String {
-    // IB volume is in round lots (×100 to get shares)
-    let shares = vol as f64 * 100.0;
-    format_shares_f64(shares)
-}
-
-/// Format a value already in raw shares (e.g. Yahoo Finance avg volume).
-fn format_raw_shares(vol: i64) -> String {
-    format_shares_f64(vol as f64)
-}
-
-fn format_shares_f64(shares: f64) -> String {
-    if shares >= 1_000_000.0 {
-        format!("{:.1}M", shares / 1_000_000.0)
-    } else if shares >= 1_000.0 {
-        format!("{:.1}K", shares / 1_000.0)
-    } else {
-        format!("{:.0}", shares)
-    }
-}
-
 fn fmt_or_dots(enriched: bool, val: Option<String>) -> String {
     match val {
         Some(v) if !v.is_empty() => v,
@@ -69,11 +69,14 @@ impl App {
                 .unwrap_or("none".into()))
         ]);
         lines = lines.push(row![
-            label!("Supabase"),
-            val!(if self.engine.db.is_some() {
-                "connected".into()
-            } else {
-                String::from("not configured")
+            label!("Criteria"),
+            val!(format!("{} -- {}", s.criteria.name, s.criteria.describe()))
+        ]);
+        lines = lines.push(row![
+            label!("History"),
+            val!(match &self.engine.db {
+                Some(db) => db.backend().to_string(),
+                None => String::from("not configured"),
             })
         ]);
         lines = lines.push(row![
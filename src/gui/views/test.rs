@@ -3,6 +3,7 @@ use iced::{Element, Length};
 
 use crate::gui::app::{App, Message};
 use crate::gui::theme::{self, Colors};
+use crate::units::Shares;
 
 /// Sample row data for the test table.
 #[derive(Debug, Clone)]
@@ -10,37 +11,26 @@ struct StockRow {
     symbol: String,
     price: f64,
     change_pct: f64,
-    volume: i64,
+    volume: Shares,
     scanner_hits: u32,
     sector: String,
 }
 
 fn sample_data() -> Vec<StockRow> {
     vec![
-        StockRow { symbol: "AAPL".into(), price: 178.50, change_pct: 2.3, volume: 45_000_000, scanner_hits: 5, sector: "Technology".into() },
-        StockRow { symbol: "TSLA".into(), price: 245.80, change_pct: -1.5, volume: 62_000_000, scanner_hits: 7, sector: "Consumer Cyclical".into() },
-        StockRow { symbol: "NVDA".into(), price: 890.25, change_pct: 4.1, volume: 38_000_000, scanner_hits: 8, sector: "Technology".into() },
-        StockRow { symbol: "GME".into(), price: 22.40, change_pct: 15.8, volume: 120_000_000, scanner_hits: 6, sector: "Consumer Cyclical".into() },
-        StockRow { symbol: "AMC".into(), price: 5.12, change_pct: 8.4, volume: 85_000_000, scanner_hits: 4, sector: "Communication".into() },
-        StockRow { symbol: "PLTR".into(), price: 42.30, change_pct: 3.2, volume: 28_000_000, scanner_hits: 3, sector: "Technology".into() },
-        StockRow { symbol: "SOFI".into(), price: 9.85, change_pct: -2.1, volume: 15_000_000, scanner_hits: 2, sector: "Financial".into() },
-        StockRow { symbol: "MARA".into(), price: 18.70, change_pct: 12.5, volume: 42_000_000, scanner_hits: 5, sector: "Financial".into() },
-        StockRow { symbol: "RIOT".into(), price: 11.30, change_pct: 9.7, volume: 31_000_000, scanner_hits: 4, sector: "Financial".into() },
-        StockRow { symbol: "NIO".into(), price: 6.45, change_pct: -3.8, volume: 55_000_000, scanner_hits: 3, sector: "Consumer Cyclical".into() },
+        StockRow { symbol: "AAPL".into(), price: 178.50, change_pct: 2.3, volume: Shares(45_000_000), scanner_hits: 5, sector: "Technology".into() },
+        StockRow { symbol: "TSLA".into(), price: 245.80, change_pct: -1.5, volume: Shares(62_000_000), scanner_hits: 7, sector: "Consumer Cyclical".into() },
+        StockRow { symbol: "NVDA".into(), price: 890.25, change_pct: 4.1, volume: Shares(38_000_000), scanner_hits: 8, sector: "Technology".into() },
+        StockRow { symbol: "GME".into(), price: 22.40, change_pct: 15.8, volume: Shares(120_000_000), scanner_hits: 6, sector: "Consumer Cyclical".into() },
+        StockRow { symbol: "AMC".into(), price: 5.12, change_pct: 8.4, volume: Shares(85_000_000), scanner_hits: 4, sector: "Communication".into() },
+        StockRow { symbol: "PLTR".into(), price: 42.30, change_pct: 3.2, volume: Shares(28_000_000), scanner_hits: 3, sector: "Technology".into() },
+        StockRow { symbol: "SOFI".into(), price: 9.85, change_pct: -2.1, volume: Shares(15_000_000), scanner_hits: 2, sector: "Financial".into() },
+        StockRow { symbol: "MARA".into(), price: 18.70, change_pct: 12.5, volume: Shares(42_000_000), scanner_hits: 5, sector: "Financial".into() },
+        StockRow { symbol: "RIOT".into(), price: 11.30, change_pct: 9.7, volume: Shares(31_000_000), scanner_hits: 4, sector: "Financial".into() },
+        StockRow { symbol: "NIO".into(), price: 6.45, change_pct: -3.8, volume: Shares(55_000_000), scanner_hits: 3, sector: "Consumer Cyclical".into() },
     ]
 }
 
-fn fmt_volume(vol: i64) -> String {
-    let shares = vol as f64 * 100.0;
-    if shares >= 1_000_000.0 {
-        format!("{:.1}M", shares / 1_000_000.0)
-    } else if shares >= 1_000.0 {
-        format!("{:.1}K", shares / 1_000.0)
-    } else {
-        format!("{:.0}", shares)
-    }
-}
-
 impl App {
     pub fn test_view(&self) -> Element<Message> {
         let fs = self.font_size;
@@ -84,7 +74,7 @@ impl App {
         let vol_col = table::column(
             text("Volume").size(fs).style(theme::text_color(Colors::YELLOW)),
             move |row: StockRow| -> Element<Message> {
-                text(fmt_volume(row.volume)).size(fs).into()
+                text(row.volume.to_string()).size(fs).into()
             },
         )
         .width(Length::FillPortion(2));
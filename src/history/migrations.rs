@@ -0,0 +1,124 @@
+//! Numbered schema migrations for the history stores.
+//!
+//! Each migration has a SQLite and a Postgres (Supabase) script under
+//! `sql/migrations/`. Stores record applied versions in `schema_migrations`;
+//! the engine refuses to start against a store behind `latest()`.
+
+use crate::config::HistoryBackend;
+
+/// One schema migration, embedded at compile time.
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub sqlite: &'static str,
+    pub postgres: &'static str,
+    /// Checked by the SQLite store before the script runs.
+    pub precondition: Option<Precondition>,
+}
+
+/// A condition existing rows must meet before a migration can run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precondition {
+    /// Every `tws_scans.first_seen` parses, so the trading date backfill
+    /// can derive a date for each row.
+    DatableScans,
+}
+
+impl Migration {
+    /// File name under `sql/migrations/<backend>/`.
+    pub fn file_name(&self) -> String {
+        format!("{:04}_{}.sql", self.version, self.name)
+    }
+
+    /// The script for a backend.
+    pub fn sql(&self, backend: HistoryBackend) -> &'static str {
+        match backend {
+            HistoryBackend::Sqlite => self.sqlite,
+            HistoryBackend::Supabase => self.postgres,
+        }
+    }
+}
+
+/// All migrations, in version order.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "tws_scans",
+        sqlite: include_str!("../../sql/migrations/sqlite/0001_tws_scans.sql"),
+        postgres: include_str!("../../sql/migrations/postgres/0001_tws_scans.sql"),
+        precondition: None,
+    },
+    Migration {
+        version: 2,
+        name: "tws_sightings",
+        sqlite: include_str!("../../sql/migrations/sqlite/0002_tws_sightings.sql"),
+        postgres: include_str!("../../sql/migrations/postgres/0002_tws_sightings.sql"),
+        precondition: None,
+    },
+    Migration {
+        version: 3,
+        name: "trading_date",
+        sqlite: include_str!("../../sql/migrations/sqlite/0003_trading_date.sql"),
+        postgres: include_str!("../../sql/migrations/postgres/0003_trading_date.sql"),
+        precondition: Some(Precondition::DatableScans),
+    },
+    Migration {
+        version: 4,
+        name: "volume_shares",
+        sqlite: include_str!("../../sql/migrations/sqlite/0004_volume_shares.sql"),
+        postgres: include_str!("../../sql/migrations/postgres/0004_volume_shares.sql"),
+        precondition: None,
+    },
+    Migration {
+        version: 5,
+        name: "tws_halts",
+        sqlite: include_str!("../../sql/migrations/sqlite/0005_tws_halts.sql"),
+        postgres: include_str!("../../sql/migrations/postgres/0005_tws_halts.sql"),
+        precondition: None,
+    },
+    Migration {
+        version: 6,
+        name: "catalyst_class",
+        sqlite: include_str!("../../sql/migrations/sqlite/0006_catalyst_class.sql"),
+        postgres: include_str!("../../sql/migrations/postgres/0006_catalyst_class.sql"),
+        precondition: None,
+    },
+];
+
+/// Schema version this build reads and writes.
+pub fn latest() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Migrations not yet applied to a store at `current`.
+pub fn pending(current: u32) -> impl Iterator<Item = &'static Migration> {
+    MIGRATIONS.iter().filter(move |m| m.version > current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_are_sequential() {
+        for (i, m) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(m.version, i as u32 + 1, "{}", m.name);
+            assert!(m.postgres.contains(&format!("VALUES ({}, '{}')", m.version, m.name)));
+            // A script deriving trading dates must declare the check for undatable rows
+            assert_eq!(
+                m.precondition == Some(Precondition::DatableScans),
+                m.sqlite.contains("eastern_date("),
+                "{}",
+                m.name
+            );
+        }
+        assert_eq!(latest(), MIGRATIONS.len() as u32);
+    }
+
+    #[test]
+    fn test_pending() {
+        assert_eq!(pending(0).count(), MIGRATIONS.len());
+        assert_eq!(pending(latest()).count(), 0);
+        assert_eq!(pending(1).next().map(|m| m.file_name()), Some("0002_tws_sightings.sql".into()));
+    }
+}
@@ -0,0 +1,388 @@
+pub mod migrations;
+mod sqlite;
+mod supabase;
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{Local, NaiveDate, Utc};
+use serde_json::Value;
+
+use crate::config::{HistoryBackend, HistoryConfig, SupabaseConfig};
+use crate::enrichment::EnrichmentData;
+use crate::models::{HaltEvent, NewsHeadline, Sighting, TwsScan};
+use crate::session;
+
+pub use sqlite::SqliteStore;
+pub use supabase::SupabaseClient;
+
+/// A batch of stock sightings: symbol -> (data, scanners list).
+///
+/// `data` keys are the `DATA_COLUMNS` data keys (`last`, `change_pct`, ...);
+/// null or missing values never overwrite stored ones.
+pub type StockBatch = HashMap<String, (Value, Vec<String>)>;
+
+/// Persistence for the tws_scans sightings history and enrichment cache.
+#[async_trait]
+pub trait HistoryStore: Send + Sync {
+    /// Short backend name for status display ("supabase", "sqlite").
+    fn backend(&self) -> &'static str;
+
+    /// Record a batch of stock sightings into today's per-symbol rows
+    /// (insert new, update existing).
+    async fn record_stocks_batch(&self, stocks: &StockBatch) -> Result<()>;
+
+    /// Check enrichment cache for a symbol. Returns Some(EnrichmentData) if
+    /// the symbol has been enriched within `max_age`.
+    async fn get_enrichment_cache(&self, symbol: &str, max_age: Duration)
+    -> Option<EnrichmentData>;
+
+    /// Get history (one row per symbol per trading date, ordered by first_seen DESC).
+    async fn get_history(&self, limit: u32) -> Result<Vec<TwsScan>>;
+
+    /// Get the current trading date's tws_scans.
+    async fn get_today(&self) -> Result<Vec<TwsScan>>;
+
+    /// Get tws_scans for trading dates `from..=to`, oldest first.
+    async fn get_scans_between(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<TwsScan>>;
+
+    /// Clear all history, sightings and halts included. Returns count of deleted summary rows.
+    async fn clear_history(&self) -> Result<u32>;
+
+    /// Get symbols that are not already in the store.
+    async fn get_new_symbols(&self, symbols: &[String]) -> Result<HashSet<String>>;
+
+    /// Append one poll cycle's sightings. Never updates existing rows.
+    async fn record_sightings(&self, sightings: &[Sighting]) -> Result<()>;
+
+    /// A symbol's sightings on a trading date, oldest first.
+    async fn get_trajectory(&self, symbol: &str, date: NaiveDate) -> Result<Vec<Sighting>>;
+
+    /// Append a halt or resume.
+    async fn record_halt(&self, event: &HaltEvent) -> Result<()>;
+
+    /// A symbol's halts and resumes on a trading date, oldest first.
+    async fn get_halts(&self, symbol: &str, date: NaiveDate) -> Result<Vec<HaltEvent>>;
+
+    /// Highest applied migration version (0 for an empty store).
+    async fn schema_version(&self) -> Result<u32>;
+
+    /// Apply pending migrations in order. Returns the versions applied.
+    async fn migrate(&self) -> Result<Vec<u32>>;
+}
+
+/// A history store shared between the engine, workers and UI.
+pub type SharedStore = Arc<dyn HistoryStore>;
+
+/// Open the history backend selected in `scanner.toml`.
+pub fn open_store(config: &HistoryConfig) -> Result<SharedStore> {
+    match config.backend {
+        HistoryBackend::Supabase => {
+            let supa = SupabaseConfig::from_env()?;
+            Ok(Arc::new(SupabaseClient::new(supa)))
+        }
+        HistoryBackend::Sqlite => Ok(Arc::new(SqliteStore::open(&config.path)?)),
+    }
+}
+
+/// A throwaway in-memory SQLite store, e.g. for simulated sessions that
+/// must not touch real history.
+pub fn open_memory_store() -> Result<SharedStore> {
+    Ok(Arc::new(SqliteStore::open_in_memory()?))
+}
+
+/// Fail unless the store's schema matches this build.
+pub async fn ensure_schema(store: &dyn HistoryStore) -> Result<()> {
+    let current = store
+        .schema_version()
+        .await
+        .with_context(|| format!("failed to read {} schema version", store.backend()))?;
+    let latest = migrations::latest();
+    if current < latest {
+        bail!(
+            "{} history schema is at version {current}, this build needs {latest}; \
+             run `scanner db migrate` first",
+            store.backend()
+        );
+    }
+    if current > latest {
+        bail!(
+            "{} history schema is at version {current}, newer than this build ({latest}); \
+             upgrade scanner",
+            store.backend()
+        );
+    }
+    Ok(())
+}
+
+/// Updatable tws_scans columns and the batch data keys they are filled from.
+const DATA_COLUMNS: &[(&str, &str)] = &[
+    ("last_price", "last"),
+    ("change_pct", "change_pct"),
+    ("rvol", "rvol"),
+    ("float_shares", "float_shares"),
+    ("catalyst", "catalyst"),
+    ("catalyst_time", "catalyst_time"),
+    ("catalyst_kind", "catalyst_kind"),
+    ("catalyst_sentiment", "catalyst_sentiment"),
+    ("name", "name"),
+    ("sector", "sector"),
+    ("industry", "industry"),
+    ("short_pct", "short_pct"),
+    ("avg_volume", "avg_volume"),
+    ("avg_volume_10d", "avg_volume_10d"),
+    ("news_headlines", "news_headlines"),
+    ("enriched_at", "enriched_at"),
+];
+
+/// Merge a stored comma-separated scanner list with newly seen scanners.
+fn merge_scanners(existing: Option<&str>, new: &[String]) -> String {
+    let mut set: BTreeSet<&str> = new.iter().map(|s| s.as_str()).collect();
+    if let Some(existing) = existing {
+        set.extend(existing.split(',').filter(|s| !s.is_empty()));
+    }
+    set.into_iter().collect::<Vec<_>>().join(",")
+}
+
+/// Timestamp format for sightings: UTC with a `Z` suffix, so values sort
+/// lexically and survive URL query strings unescaped.
+pub const SIGHTING_TS_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+/// UTC bounds `[start, end)` of a trading date, in `SIGHTING_TS_FORMAT`.
+fn day_bounds_utc(date: NaiveDate) -> (String, String) {
+    let fmt = |d: chrono::DateTime<Utc>| d.format(SIGHTING_TS_FORMAT).to_string();
+    let next = date.succ_opt().unwrap_or(date);
+    (fmt(session::day_start_utc(date)), fmt(session::day_start_utc(next)))
+}
+
+/// True if an `enriched_at` timestamp is within `max_age` of now.
+fn is_fresh(enriched_at: &str, max_age: Duration) -> bool {
+    let Ok(enriched_at) = chrono::DateTime::parse_from_rfc3339(enriched_at) else {
+        return false;
+    };
+    let age = Utc::now().signed_duration_since(enriched_at.with_timezone(&Utc));
+    chrono::Duration::from_std(max_age).is_ok_and(|max| age <= max)
+}
+
+/// Parse stored news headlines JSON.
+/// Backwards compat: try Vec<NewsHeadline> first, then Vec<String>.
+pub fn parse_news_headlines(json: &str) -> Vec<NewsHeadline> {
+    serde_json::from_str::<Vec<NewsHeadline>>(json)
+        .ok()
+        .or_else(|| {
+            serde_json::from_str::<Vec<String>>(json).ok().map(|titles| {
+                titles
+                    .into_iter()
+                    .map(|title| NewsHeadline::new(title, None))
+                    .collect()
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Print tws_scans as a formatted history table.
+pub fn print_history(scans: &[TwsScan], label: &str) {
+    if scans.is_empty() {
+        println!("{label}: no stocks in history");
+        return;
+    }
+
+    println!("{label} -- {} stocks", scans.len());
+    println!(
+        "{:<10}  {:<10}  {:<6}  {:>8}  {:>8}  {:>6}  {:<30}  {:>4}  {}",
+        "Date", "Time", "Symbol", "Last", "Chg%", "RVol", "Scanners", "Hits", "Catalyst"
+    );
+    println!("{}", "-".repeat(112));
+
+    for s in scans {
+        let date_str = scan_date_str(s);
+        let time_str = local_time_str(&s.first_seen);
+        let price = match s.last_price {
+            Some(p) => format!("{p:.2}"),
+            None => "-".to_string(),
+        };
+        let chg = match s.change_pct {
+            Some(c) => format!("{c:+.1}%"),
+            None => "-".to_string(),
+        };
+        let rvol = match s.rvol {
+            Some(r) => format!("{r:.1}x"),
+            None => "-".to_string(),
+        };
+        let hits = s.hit_count.unwrap_or(0);
+        let catalyst = s.catalyst.as_deref().unwrap_or("");
+        let catalyst = if catalyst.len() > 30 {
+            format!("{}..", &catalyst[..28])
+        } else {
+            catalyst.to_string()
+        };
+
+        println!(
+            "{:<10}  {:<10}  {:<6}  {:>8}  {:>8}  {:>6}  {:<30}  {:>4}  {}",
+            date_str, time_str, s.symbol, price, chg, rvol, s.scanners, hits, catalyst
+        );
+    }
+}
+
+/// Print a symbol's sightings as a trajectory table.
+pub fn print_trajectory(symbol: &str, date: NaiveDate, sightings: &[Sighting]) {
+    if sightings.is_empty() {
+        println!("{symbol} on {date}: no sightings");
+        return;
+    }
+
+    println!("{symbol} on {date} -- {} sightings", sightings.len());
+    println!(
+        "{:<10}  {:<20}  {:>4}  {:>8}  {:>8}  {:>12}  {:>6}",
+        "Time", "Scanner", "Rank", "Last", "Chg%", "Volume", "RVol"
+    );
+    println!("{}", "-".repeat(80));
+
+    for s in sightings {
+        println!(
+            "{:<10}  {:<20}  {:>4}  {:>8}  {:>8}  {:>12}  {:>6}",
+            local_time_str(&s.seen_at),
+            s.scanner,
+            s.rank,
+            crate::scanner::fmt_price(s.price),
+            crate::scanner::fmt_change_pct(s.change_pct),
+            crate::scanner::fmt_volume(s.volume),
+            crate::scanner::fmt_rvol(s.rvol),
+        );
+    }
+}
+
+/// Print a symbol's halts and resumes under its trajectory.
+pub fn print_halts(halts: &[HaltEvent]) {
+    if halts.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{:<10}  {:<12}  {:>8}  {:>10}", "Time", "Event", "Halt $", "Duration");
+    println!("{}", "-".repeat(46));
+
+    for h in halts {
+        let event = if h.state.is_halted() { h.state.label() } else { "resumed" };
+        println!(
+            "{:<10}  {:<12}  {:>8}  {:>10}",
+            local_time_str(&h.at),
+            event,
+            crate::scanner::fmt_price(h.halt_price),
+            h.duration_secs.map_or("-".to_string(), crate::scanner::fmt_halt_duration),
+        );
+    }
+}
+
+/// Trading date of a history row, derived from `first_seen` for legacy rows.
+pub fn scan_date_str(scan: &TwsScan) -> String {
+    scan.trading_date
+        .or_else(|| {
+            chrono::DateTime::parse_from_rfc3339(&scan.first_seen)
+                .ok()
+                .map(|dt| session::trading_date(dt.with_timezone(&Utc)))
+        })
+        .map(|d| d.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn parse_ts(iso_ts: &str) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    chrono::DateTime::parse_from_rfc3339(iso_ts)
+        .or_else(|_| chrono::DateTime::parse_from_str(iso_ts, "%Y-%m-%dT%H:%M:%S%:z"))
+        .ok()
+}
+
+/// Convert an ISO timestamp to local HH:MM:SS.
+pub fn local_time_str(iso_ts: &str) -> String {
+    parse_ts(iso_ts)
+        .map(|dt| dt.with_timezone(&Local).format("%H:%M:%S").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Convert an ISO timestamp to Eastern HH:MM:SS, as alert times are shown.
+pub fn eastern_time_str(iso_ts: &str) -> String {
+    parse_ts(iso_ts)
+        .map(|dt| session::clock_time(dt.with_timezone(&Utc)))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_time_str_valid() {
+        // Should not panic on valid ISO timestamp
+        let result = local_time_str("2024-01-15T14:30:00+00:00");
+        assert!(!result.is_empty());
+        assert_ne!(result, "-");
+    }
+
+    #[test]
+    fn test_local_time_str_invalid() {
+        assert_eq!(local_time_str("not-a-date"), "-");
+    }
+
+    #[test]
+    fn test_local_time_str_short() {
+        assert_eq!(local_time_str("abc"), "-");
+    }
+
+    #[test]
+    fn test_day_bounds_utc_span_one_day() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let (start, end) = day_bounds_utc(date);
+        let start = chrono::DateTime::parse_from_rfc3339(&start).unwrap();
+        let end = chrono::DateTime::parse_from_rfc3339(&end).unwrap();
+        assert_eq!((end - start).num_hours(), 24);
+        assert_eq!(session::trading_date(start.with_timezone(&Utc)), date);
+    }
+
+    #[test]
+    fn test_parse_news_headlines_legacy_format() {
+        let h = parse_news_headlines(r#"["Old style headline"]"#);
+        assert_eq!(h.len(), 1);
+        assert_eq!(h[0].published, None);
+        assert!(parse_news_headlines("not json").is_empty());
+    }
+
+    #[test]
+    fn test_print_history_empty() {
+        // Should not panic
+        print_history(&[], "Test");
+    }
+
+    #[test]
+    fn test_print_history_with_data() {
+        let scans = vec![TwsScan {
+            id: Some(1),
+            symbol: "AAPL".to_string(),
+            trading_date: chrono::NaiveDate::from_ymd_opt(2024, 1, 15),
+            first_seen: "2024-01-15T14:30:00+00:00".to_string(),
+            last_seen: "2024-01-15T14:35:00+00:00".to_string(),
+            scanners: "HOT_BY_VOLUME,TOP_PERC_GAIN".to_string(),
+            hit_count: Some(3),
+            last_price: Some(15.50),
+            change_pct: Some(12.5),
+            rvol: Some(6.3),
+            float_shares: Some(5_000_000.0),
+            catalyst: Some("FDA approval for new drug".to_string()),
+            name: Some("Apple Inc".to_string()),
+            sector: Some("Technology".to_string()),
+            enriched_at: None,
+            industry: None,
+            short_pct: None,
+            avg_volume: None,
+            avg_volume_10d: None,
+            news_headlines: None,
+            catalyst_time: None,
+            catalyst_kind: Some(crate::catalyst::CatalystKind::Fda),
+            catalyst_sentiment: Some(crate::catalyst::Sentiment::Bullish),
+        }];
+        // Should not panic
+        print_history(&scans, "Today");
+    }
+}
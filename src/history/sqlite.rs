@@ -0,0 +1,815 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde_json::Value;
+
+use super::migrations::{self, Precondition, MIGRATIONS};
+use super::{
+    day_bounds_utc, is_fresh, merge_scanners, parse_news_headlines, HistoryStore, StockBatch,
+    DATA_COLUMNS,
+};
+use crate::catalyst::{CatalystKind, Sentiment};
+use crate::config::HistoryBackend;
+use crate::enrichment::EnrichmentData;
+use crate::models::{HaltEvent, Sighting, TwsScan};
+use crate::quote::HaltState;
+use crate::session;
+use crate::units::Shares;
+
+const MIGRATIONS_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    applied_at TEXT NOT NULL
+);
+";
+
+/// Embedded SQLite history store, for running without Supabase.
+///
+/// Timestamps are stored as UTC RFC 3339 strings so they sort lexically.
+/// Opening does not touch the schema; `scanner db migrate` applies it.
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Open (or create) the database file, creating parent directories.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        Self::init(conn)
+    }
+
+    /// In-memory store at the latest schema (used by tests).
+    pub fn open_in_memory() -> Result<Self> {
+        let store = Self::init(Connection::open_in_memory()?)?;
+        store.apply_pending()?;
+        Ok(store)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        // Used by migrations that derive trading dates from stored timestamps;
+        // NULL for a timestamp that doesn't parse (see `undatable_scans`)
+        conn.create_scalar_function(
+            "eastern_date",
+            1,
+            FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+            |ctx| {
+                let ts: String = ctx.get(0)?;
+                let date = chrono::DateTime::parse_from_rfc3339(&ts)
+                    .ok()
+                    .map(|dt| session::trading_date(dt.with_timezone(&Utc)).to_string());
+                Ok(date)
+            },
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn current_version(conn: &Connection) -> Result<u32> {
+        let tracked = conn
+            .query_row(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations'",
+                [],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if tracked {
+            let version: Option<u32> =
+                conn.query_row("SELECT MAX(version) FROM schema_migrations", [], |r| r.get(0))?;
+            return Ok(version.unwrap_or(0));
+        }
+        legacy_version(conn)
+    }
+
+    /// Apply pending migrations, each in its own transaction.
+    fn apply_pending(&self) -> Result<Vec<u32>> {
+        let mut conn = self.conn();
+        let current = Self::current_version(&conn)?;
+        conn.execute_batch(MIGRATIONS_TABLE)?;
+        // Stores created before migrations were tracked get their inferred baseline recorded
+        for m in MIGRATIONS.iter().filter(|m| m.version <= current) {
+            conn.execute(
+                "INSERT OR IGNORE INTO schema_migrations (version, name, applied_at)
+                 VALUES (?1, ?2, ?3)",
+                params![m.version, m.name, now_utc()],
+            )?;
+        }
+
+        let mut applied = Vec::new();
+        for m in migrations::pending(current) {
+            if m.precondition == Some(Precondition::DatableScans) {
+                let undatable = undatable_scans(&conn)?;
+                if !undatable.is_empty() {
+                    let rows: Vec<String> =
+                        undatable.iter().map(|(id, ts)| format!("id {id} ('{ts}')")).collect();
+                    anyhow::bail!(
+                        "migration {} cannot derive a trading date for {} tws_scans rows with unparseable first_seen: {}",
+                        m.file_name(),
+                        rows.len(),
+                        rows.join(", ")
+                    );
+                }
+            }
+            let tx = conn.transaction()?;
+            tx.execute_batch(m.sql(HistoryBackend::Sqlite))
+                .with_context(|| format!("migration {} failed", m.file_name()))?;
+            tx.execute(
+                "INSERT INTO schema_migrations (version, name, applied_at) VALUES (?1, ?2, ?3)",
+                params![m.version, m.name, now_utc()],
+            )?;
+            tx.commit()?;
+            applied.push(m.version);
+        }
+        Ok(applied)
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        // A panic mid-statement leaves SQLite consistent, so a poisoned lock is still usable
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn query_scans(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<TwsScan>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(sql)?;
+        let scans = stmt
+            .query_map(params, scan_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(scans)
+    }
+}
+
+/// Version of a store created before `schema_migrations` existed,
+/// inferred from which tables and columns are present.
+fn legacy_version(conn: &Connection) -> Result<u32> {
+    let columns: Vec<String> = conn
+        .prepare("SELECT name FROM pragma_table_info('tws_scans')")?
+        .query_map([], |r| r.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    if columns.is_empty() {
+        return Ok(0);
+    }
+    if columns.iter().any(|c| c == "trading_date") {
+        return Ok(3);
+    }
+    let has_sightings = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'tws_sightings'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    Ok(if has_sightings { 2 } else { 1 })
+}
+
+/// `tws_scans` rows whose `first_seen` `eastern_date` can't parse, as
+/// (id, first_seen). Checked before a migration that dates rows by it, so
+/// they are reported instead of being given a made-up date.
+fn undatable_scans(conn: &Connection) -> Result<Vec<(i64, String)>> {
+    let mut stmt =
+        conn.prepare("SELECT id, first_seen FROM tws_scans WHERE eastern_date(first_seen) IS NULL")?;
+    let rows = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(rows)
+}
+
+fn now_utc() -> String {
+    Utc::now().format("%Y-%m-%dT%H:%M:%S%:z").to_string()
+}
+
+/// Convert a JSON batch value to a SQLite value.
+fn sql_value(v: &Value) -> SqlValue {
+    match v {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Number(n) => n
+            .as_i64()
+            .map(SqlValue::Integer)
+            .or_else(|| n.as_f64().map(SqlValue::Real))
+            .unwrap_or(SqlValue::Null),
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+fn sighting_from_row(row: &Row) -> rusqlite::Result<Sighting> {
+    Ok(Sighting {
+        symbol: row.get("symbol")?,
+        seen_at: row.get("seen_at")?,
+        scanner: row.get("scanner")?,
+        rank: row.get("rank")?,
+        price: row.get("price")?,
+        change_pct: row.get("change_pct")?,
+        volume: row.get::<_, Option<i64>>("volume")?.map(Shares),
+        rvol: row.get("rvol")?,
+    })
+}
+
+fn halt_from_row(row: &Row) -> rusqlite::Result<HaltEvent> {
+    let state: String = row.get("state")?;
+    Ok(HaltEvent {
+        symbol: row.get("symbol")?,
+        at: row.get("at")?,
+        state: HaltState::parse(&state).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(
+                0,
+                rusqlite::types::Type::Text,
+                format!("unknown halt state '{state}'").into(),
+            )
+        })?,
+        halt_price: row.get("halt_price")?,
+        duration_secs: row.get("duration_secs")?,
+    })
+}
+
+fn scan_from_row(row: &Row) -> rusqlite::Result<TwsScan> {
+    Ok(TwsScan {
+        id: row.get("id")?,
+        symbol: row.get("symbol")?,
+        trading_date: row
+            .get::<_, Option<String>>("trading_date")?
+            .and_then(|d| d.parse().ok()),
+        first_seen: row.get("first_seen")?,
+        last_seen: row.get("last_seen")?,
+        scanners: row.get("scanners")?,
+        hit_count: row.get("hit_count")?,
+        last_price: row.get("last_price")?,
+        change_pct: row.get("change_pct")?,
+        rvol: row.get("rvol")?,
+        float_shares: row.get("float_shares")?,
+        catalyst: row.get("catalyst")?,
+        catalyst_time: row.get("catalyst_time")?,
+        catalyst_kind: row
+            .get::<_, Option<String>>("catalyst_kind")?
+            .as_deref()
+            .and_then(CatalystKind::parse),
+        catalyst_sentiment: row
+            .get::<_, Option<String>>("catalyst_sentiment")?
+            .as_deref()
+            .and_then(Sentiment::parse),
+        name: row.get("name")?,
+        sector: row.get("sector")?,
+        enriched_at: row.get("enriched_at")?,
+        industry: row.get("industry")?,
+        short_pct: row.get("short_pct")?,
+        avg_volume: row.get::<_, Option<i64>>("avg_volume")?.map(Shares),
+        avg_volume_10d: row.get::<_, Option<i64>>("avg_volume_10d")?.map(Shares),
+        news_headlines: row.get("news_headlines")?,
+    })
+}
+
+#[async_trait]
+impl HistoryStore for SqliteStore {
+    fn backend(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn record_stocks_batch(&self, stocks: &StockBatch) -> Result<()> {
+        if stocks.is_empty() {
+            return Ok(());
+        }
+        let now = now_utc();
+        let trading_date = session::today().to_string();
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+
+        for (sym, (data, scanner_list)) in stocks {
+            let existing: Option<(String, i64)> = tx
+                .query_row(
+                    "SELECT scanners, COALESCE(hit_count, 0) FROM tws_scans
+                     WHERE symbol = ?1 AND trading_date = ?2",
+                    params![sym, trading_date],
+                    |r| Ok((r.get(0)?, r.get(1)?)),
+                )
+                .optional()?;
+            let scanners_str =
+                merge_scanners(existing.as_ref().map(|(s, _)| s.as_str()), scanner_list);
+
+            // Only non-null values are written, so enrichment never gets erased
+            let fields: Vec<(&str, SqlValue)> = DATA_COLUMNS
+                .iter()
+                .filter_map(|(col, key)| {
+                    data.get(key)
+                        .filter(|v| !v.is_null())
+                        .map(|v| (*col, sql_value(v)))
+                })
+                .collect();
+
+            let mut values: Vec<SqlValue> = vec![
+                SqlValue::Text(now.clone()),
+                SqlValue::Text(scanners_str),
+            ];
+            let sql = if let Some((_, old_hits)) = existing {
+                values.push(SqlValue::Integer(old_hits + scanner_list.len() as i64));
+                let mut sets = vec!["last_seen = ?1", "scanners = ?2", "hit_count = ?3"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<Vec<_>>();
+                for (col, val) in fields {
+                    values.push(val);
+                    sets.push(format!("{col} = ?{}", values.len()));
+                }
+                values.push(SqlValue::Text(sym.clone()));
+                values.push(SqlValue::Text(trading_date.clone()));
+                format!(
+                    "UPDATE tws_scans SET {} WHERE symbol = ?{} AND trading_date = ?{}",
+                    sets.join(", "),
+                    values.len() - 1,
+                    values.len()
+                )
+            } else {
+                values.push(SqlValue::Integer(scanner_list.len() as i64));
+                values.push(SqlValue::Text(sym.clone()));
+                values.push(SqlValue::Text(trading_date.clone()));
+                let mut cols = vec!["last_seen", "scanners", "hit_count", "symbol", "trading_date"];
+                for (col, val) in fields {
+                    values.push(val);
+                    cols.push(col);
+                }
+                let placeholders: Vec<String> =
+                    (1..=values.len()).map(|i| format!("?{i}")).collect();
+                format!(
+                    "INSERT INTO tws_scans (first_seen, {}) VALUES (?1, {})",
+                    cols.join(", "),
+                    placeholders.join(", ")
+                )
+            };
+            tx.execute(&sql, rusqlite::params_from_iter(values))?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn get_enrichment_cache(
+        &self,
+        symbol: &str,
+        max_age: Duration,
+    ) -> Option<EnrichmentData> {
+        let scan = self
+            .query_scans(
+                "SELECT * FROM tws_scans WHERE symbol = ?1 ORDER BY enriched_at DESC LIMIT 1",
+                params![symbol],
+            )
+            .ok()?
+            .into_iter()
+            .next()?;
+        if !is_fresh(scan.enriched_at.as_deref()?, max_age) {
+            return None;
+        }
+        Some(EnrichmentData {
+            name: scan.name,
+            sector: scan.sector,
+            industry: scan.industry,
+            country: None,
+            float_shares: scan.float_shares,
+            short_pct: scan.short_pct,
+            avg_volume: scan.avg_volume,
+            avg_volume_10d: scan.avg_volume_10d,
+            catalyst: scan.catalyst,
+            catalyst_time: scan.catalyst_time,
+            catalyst_kind: scan.catalyst_kind,
+            catalyst_sentiment: scan.catalyst_sentiment,
+            news_headlines: scan
+                .news_headlines
+                .as_deref()
+                .map(parse_news_headlines)
+                .unwrap_or_default(),
+            ..Default::default()
+        })
+    }
+
+    async fn get_history(&self, limit: u32) -> Result<Vec<TwsScan>> {
+        self.query_scans(
+            "SELECT * FROM tws_scans ORDER BY first_seen DESC LIMIT ?1",
+            params![limit],
+        )
+    }
+
+    async fn get_today(&self) -> Result<Vec<TwsScan>> {
+        self.query_scans(
+            "SELECT * FROM tws_scans WHERE trading_date = ?1 ORDER BY first_seen DESC",
+            params![session::today().to_string()],
+        )
+    }
+
+    async fn get_scans_between(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<TwsScan>> {
+        self.query_scans(
+            "SELECT * FROM tws_scans WHERE trading_date BETWEEN ?1 AND ?2
+             ORDER BY trading_date, first_seen",
+            params![from.to_string(), to.to_string()],
+        )
+    }
+
+    async fn clear_history(&self) -> Result<u32> {
+        let conn = self.conn();
+        let count = conn.execute("DELETE FROM tws_scans", [])?;
+        conn.execute("DELETE FROM tws_sightings", [])?;
+        conn.execute("DELETE FROM tws_halts", [])?;
+        Ok(count as u32)
+    }
+
+    async fn get_new_symbols(&self, symbols: &[String]) -> Result<HashSet<String>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT 1 FROM tws_scans WHERE symbol = ?1")?;
+        let mut new = HashSet::new();
+        for sym in symbols {
+            if !stmt.exists(params![sym])? {
+                new.insert(sym.clone());
+            }
+        }
+        Ok(new)
+    }
+
+    async fn record_sightings(&self, sightings: &[Sighting]) -> Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO tws_sightings
+                    (symbol, seen_at, scanner, rank, price, change_pct, volume, rvol)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for s in sightings {
+                stmt.execute(params![
+                    s.symbol,
+                    s.seen_at,
+                    s.scanner,
+                    s.rank,
+                    s.price,
+                    s.change_pct,
+                    s.volume.map(Shares::get),
+                    s.rvol
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn schema_version(&self) -> Result<u32> {
+        Self::current_version(&self.conn())
+    }
+
+    async fn migrate(&self) -> Result<Vec<u32>> {
+        self.apply_pending()
+    }
+
+    async fn get_trajectory(&self, symbol: &str, date: NaiveDate) -> Result<Vec<Sighting>> {
+        let (start, end) = day_bounds_utc(date);
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT * FROM tws_sightings
+             WHERE symbol = ?1 AND seen_at >= ?2 AND seen_at < ?3
+             ORDER BY seen_at, id",
+        )?;
+        let sightings = stmt
+            .query_map(params![symbol, start, end], sighting_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(sightings)
+    }
+
+    async fn record_halt(&self, event: &HaltEvent) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO tws_halts (symbol, at, state, halt_price, duration_secs)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                event.symbol,
+                event.at,
+                event.state.as_str(),
+                event.halt_price,
+                event.duration_secs
+            ],
+        )?;
+        Ok(())
+    }
+
+    async fn get_halts(&self, symbol: &str, date: NaiveDate) -> Result<Vec<HaltEvent>> {
+        let (start, end) = day_bounds_utc(date);
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT * FROM tws_halts
+             WHERE symbol = ?1 AND at >= ?2 AND at < ?3
+             ORDER BY at, id",
+        )?;
+        let halts = stmt
+            .query_map(params![symbol, start, end], halt_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(halts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn batch(entries: &[(&str, Value, &[&str])]) -> StockBatch {
+        entries
+            .iter()
+            .map(|(sym, data, scanners)| {
+                (
+                    sym.to_string(),
+                    (data.clone(), scanners.iter().map(|s| s.to_string()).collect()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_record_insert_then_update() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let store = SqliteStore::open_in_memory().unwrap();
+
+        rt.block_on(store.record_stocks_batch(&batch(&[(
+            "ABCD",
+            json!({"last": 4.5, "change_pct": 22.0, "name": "Abcd Corp"}),
+            &["TOP_PERC_GAIN"],
+        )])))
+        .unwrap();
+        rt.block_on(store.record_stocks_batch(&batch(&[(
+            "ABCD",
+            json!({"last": 5.0, "name": null}),
+            &["HOT_BY_VOLUME", "TOP_PERC_GAIN"],
+        )])))
+        .unwrap();
+
+        let today = rt.block_on(store.get_today()).unwrap();
+        assert_eq!(today.len(), 1);
+        let s = &today[0];
+        assert_eq!(s.scanners, "HOT_BY_VOLUME,TOP_PERC_GAIN");
+        assert_eq!(s.hit_count, Some(3));
+        assert_eq!(s.last_price, Some(5.0));
+        assert_eq!(s.change_pct, Some(22.0));
+        // Null does not overwrite a stored value
+        assert_eq!(s.name.as_deref(), Some("Abcd Corp"));
+    }
+
+    #[test]
+    fn test_enrichment_cache_freshness() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let store = SqliteStore::open_in_memory().unwrap();
+        let headlines = r#"[{"title":"FDA approves drug","published":1700000000}]"#;
+        rt.block_on(store.record_stocks_batch(&batch(&[
+            (
+                "FRSH",
+                json!({
+                    "float_shares": 3_000_000.0,
+                    "catalyst": "FDA approves drug",
+                    "news_headlines": headlines,
+                    "enriched_at": Utc::now().to_rfc3339(),
+                }),
+                &[],
+            ),
+            (
+                "STAL",
+                json!({"enriched_at": "2020-01-01T00:00:00+00:00"}),
+                &[],
+            ),
+        ])))
+        .unwrap();
+
+        let cached = rt
+            .block_on(store.get_enrichment_cache("FRSH", Duration::from_secs(900)))
+            .unwrap();
+        assert_eq!(cached.float_shares, Some(3_000_000.0));
+        assert_eq!(cached.news_headlines.len(), 1);
+        assert_eq!(cached.news_headlines[0].published, Some(1_700_000_000));
+
+        assert!(rt
+            .block_on(store.get_enrichment_cache("STAL", Duration::from_secs(900)))
+            .is_none());
+        assert!(rt
+            .block_on(store.get_enrichment_cache("NONE", Duration::from_secs(900)))
+            .is_none());
+    }
+
+    #[test]
+    fn test_catalyst_class_round_trip() {
+        // A filing catalyst's class can't be recovered from its headline text
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let store = SqliteStore::open_in_memory().unwrap();
+        rt.block_on(store.record_stocks_batch(&batch(&[(
+            "DILU",
+            json!({
+                "catalyst": "424B5: Prospectus",
+                "catalyst_kind": CatalystKind::Offering,
+                "catalyst_sentiment": Sentiment::Bearish,
+                "enriched_at": Utc::now().to_rfc3339(),
+            }),
+            &[],
+        )])))
+        .unwrap();
+
+        let cached = rt
+            .block_on(store.get_enrichment_cache("DILU", Duration::from_secs(900)))
+            .unwrap();
+        assert_eq!(cached.catalyst_kind, Some(CatalystKind::Offering));
+        assert_eq!(cached.catalyst_sentiment, Some(Sentiment::Bearish));
+        let rows = rt.block_on(store.get_history(10)).unwrap();
+        assert_eq!(rows[0].catalyst_kind, Some(CatalystKind::Offering));
+        assert_eq!(rows[0].catalyst_sentiment, Some(Sentiment::Bearish));
+    }
+
+    #[test]
+    fn test_sightings_are_append_only() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let store = SqliteStore::open_in_memory().unwrap();
+        let today = session::today();
+        let (start, _) = day_bounds_utc(today);
+        let at = |mins: i64| {
+            let t = chrono::DateTime::parse_from_rfc3339(&start).unwrap()
+                + chrono::Duration::minutes(mins);
+            t.with_timezone(&Utc).format(crate::history::SIGHTING_TS_FORMAT).to_string()
+        };
+        let sighting = |mins: i64, scanner: &str, price: f64| Sighting {
+            symbol: "ABCD".to_string(),
+            seen_at: at(mins),
+            scanner: scanner.to_string(),
+            rank: 3,
+            price: Some(price),
+            change_pct: Some(price * 10.0),
+            volume: Some(Shares(1_200_000)),
+            rvol: None,
+        };
+
+        rt.block_on(store.record_sightings(&[
+            sighting(600, "TOP_PERC_GAIN", 2.0),
+            sighting(600, "HOT_BY_VOLUME", 2.0),
+        ]))
+        .unwrap();
+        rt.block_on(store.record_sightings(&[sighting(615, "TOP_PERC_GAIN", 2.6)]))
+            .unwrap();
+        // Previous day is excluded
+        rt.block_on(store.record_sightings(&[sighting(-30, "TOP_PERC_GAIN", 1.5)]))
+            .unwrap();
+
+        let path = rt.block_on(store.get_trajectory("ABCD", today)).unwrap();
+        assert_eq!(path.len(), 3);
+        assert_eq!(path[0].price, Some(2.0));
+        assert_eq!(path[2].price, Some(2.6));
+        assert_eq!(path[2].scanner, "TOP_PERC_GAIN");
+        assert!(rt.block_on(store.get_trajectory("ZZZZ", today)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_halts_round_trip() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let store = SqliteStore::open_in_memory().unwrap();
+        let today = session::today();
+        let at = |mins: i64| {
+            (session::day_start_utc(today) + chrono::Duration::minutes(mins))
+                .format(crate::history::SIGHTING_TS_FORMAT)
+                .to_string()
+        };
+        let halt = HaltEvent {
+            symbol: "ABCD".to_string(),
+            at: at(600),
+            state: HaltState::Volatility,
+            halt_price: Some(2.45),
+            duration_secs: None,
+        };
+        let resume = HaltEvent {
+            at: at(605),
+            state: HaltState::Trading,
+            duration_secs: Some(300),
+            ..halt.clone()
+        };
+        rt.block_on(store.record_halt(&halt)).unwrap();
+        rt.block_on(store.record_halt(&resume)).unwrap();
+
+        assert_eq!(rt.block_on(store.get_halts("ABCD", today)).unwrap(), vec![halt, resume]);
+        assert!(rt.block_on(store.get_halts("ZZZZ", today)).unwrap().is_empty());
+        rt.block_on(store.clear_history()).unwrap();
+        assert!(rt.block_on(store.get_halts("ABCD", today)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_new_symbols_and_clear() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let store = SqliteStore::open_in_memory().unwrap();
+        rt.block_on(store.record_stocks_batch(&batch(&[
+            ("AAA", json!({}), &["HOT_BY_VOLUME"]),
+            ("BBB", json!({}), &["HOT_BY_VOLUME"]),
+        ])))
+        .unwrap();
+
+        let new = rt
+            .block_on(store.get_new_symbols(&["AAA".to_string(), "CCC".to_string()]))
+            .unwrap();
+        assert_eq!(new, HashSet::from(["CCC".to_string()]));
+
+        assert_eq!(rt.block_on(store.get_history(1)).unwrap().len(), 1);
+        assert_eq!(rt.block_on(store.clear_history()).unwrap(), 2);
+        assert!(rt.block_on(store.get_history(10)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_one_row_per_trading_date() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let store = SqliteStore::open_in_memory().unwrap();
+        store
+            .conn()
+            .execute(
+                "INSERT INTO tws_scans (symbol, trading_date, first_seen, last_seen, scanners)
+                 VALUES ('ABCD', '2024-01-02', '2024-01-02T14:30:00+00:00',
+                         '2024-01-02T15:00:00+00:00', 'HOT_BY_VOLUME')",
+                [],
+            )
+            .unwrap();
+        rt.block_on(store.record_stocks_batch(&batch(&[("ABCD", json!({}), &["TOP_PERC_GAIN"])])))
+            .unwrap();
+
+        let all = rt.block_on(store.get_history(10)).unwrap();
+        assert_eq!(all.len(), 2);
+        let today = rt.block_on(store.get_today()).unwrap();
+        assert_eq!(today.len(), 1);
+        assert_eq!(today[0].trading_date, Some(session::today()));
+        assert_eq!(today[0].scanners, "TOP_PERC_GAIN");
+        assert_eq!(today[0].hit_count, Some(1));
+
+        let jan2 = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let range = rt.block_on(store.get_scans_between(jan2, jan2)).unwrap();
+        assert_eq!(range.len(), 1);
+        assert_eq!(range[0].scanners, "HOT_BY_VOLUME");
+        let range = rt.block_on(store.get_scans_between(jan2, session::today())).unwrap();
+        assert_eq!(range.len(), 2);
+        assert_eq!(range[1].trading_date, Some(session::today()));
+    }
+
+    #[test]
+    fn test_migrates_untracked_symbol_keyed_table() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0].sqlite).unwrap();
+        // 01:30 UTC on Jan 3 is still Jan 2 in New York
+        conn.execute(
+            "INSERT INTO tws_scans (symbol, first_seen, last_seen, scanners, last_price)
+             VALUES ('LATE', '2024-01-03T01:30:00+00:00', '2024-01-03T01:45:00+00:00',
+                     'HOT_BY_VOLUME', 3.25)",
+            [],
+        )
+        .unwrap();
+
+        let store = SqliteStore::init(conn).unwrap();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        assert_eq!(rt.block_on(store.schema_version()).unwrap(), 1);
+        assert!(rt.block_on(crate::history::ensure_schema(&store)).is_err());
+
+        assert_eq!(rt.block_on(store.migrate()).unwrap(), vec![2, 3, 4, 5, 6]);
+        assert_eq!(rt.block_on(store.schema_version()).unwrap(), migrations::latest());
+        assert!(rt.block_on(store.migrate()).unwrap().is_empty());
+        rt.block_on(crate::history::ensure_schema(&store)).unwrap();
+
+        let rows = rt.block_on(store.get_history(10)).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].symbol, "LATE");
+        assert_eq!(rows[0].trading_date, "2024-01-02".parse().ok());
+        assert_eq!(rows[0].last_price, Some(3.25));
+    }
+
+    #[test]
+    fn test_migration_reports_unparseable_timestamps() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0].sqlite).unwrap();
+        conn.execute(
+            "INSERT INTO tws_scans (symbol, first_seen, last_seen, scanners)
+             VALUES ('BAD', '01/02/2024 09:31', '01/02/2024 09:45', 'HOT_BY_VOLUME')",
+            [],
+        )
+        .unwrap();
+
+        let store = SqliteStore::init(conn).unwrap();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let err = rt.block_on(store.migrate()).unwrap_err().to_string();
+        assert!(err.contains("0003_trading_date"), "{err}");
+        assert!(err.contains("id 1 ('01/02/2024 09:31')"), "{err}");
+        // The migrations before it are kept; the row is left undated
+        assert_eq!(rt.block_on(store.schema_version()).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_empty_store_needs_migration() {
+        let store = SqliteStore::init(Connection::open_in_memory().unwrap()).unwrap();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        assert_eq!(rt.block_on(store.schema_version()).unwrap(), 0);
+        let err = rt.block_on(crate::history::ensure_schema(&store)).unwrap_err();
+        assert!(err.to_string().contains("scanner db migrate"));
+        assert_eq!(rt.block_on(store.migrate()).unwrap().len(), MIGRATIONS.len());
+    }
+}
@@ -1,14 +1,28 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
 use anyhow::{Context, Result};
-use chrono::{Local, Utc};
+use async_trait::async_trait;
+use chrono::{Local, NaiveDate};
 use reqwest::Client;
 use serde_json::{json, Value};
 use tracing::{info, warn};
 
+use super::{
+    day_bounds_utc, is_fresh, merge_scanners, parse_news_headlines, HistoryStore, StockBatch,
+    DATA_COLUMNS,
+};
+use crate::catalyst::{CatalystKind, Sentiment};
 use crate::config::SupabaseConfig;
 use crate::enrichment::EnrichmentData;
-use crate::models::{NewsHeadline, TwsScan};
+use crate::models::{HaltEvent, Sighting, TwsScan};
+use crate::session;
+use crate::units::Shares;
 
 const TABLE: &str = "tws_scans";
+const SIGHTINGS_TABLE: &str = "tws_sightings";
+const HALTS_TABLE: &str = "tws_halts";
+const MIGRATIONS_TABLE: &str = "schema_migrations";
 
 /// Supabase REST API client for the tws_scans table.
 #[derive(Clone)]
@@ -32,7 +46,11 @@ impl SupabaseClient {
     }
 
     fn base_url(&self) -> String {
-        format!("{}/rest/v1/{TABLE}", self.config.url)
+        self.table_url(TABLE)
+    }
+
+    fn table_url(&self, table: &str) -> String {
+        format!("{}/rest/v1/{table}", self.config.url)
     }
 
     fn auth_headers(&self) -> Vec<(&str, String)> {
@@ -44,7 +62,11 @@ impl SupabaseClient {
 
     /// SELECT rows with optional filters.
     async fn select(&self, query: &str) -> Result<Vec<Value>> {
-        let url = format!("{}?{query}", self.base_url());
+        self.select_from(TABLE, query).await
+    }
+
+    async fn select_from(&self, table: &str, query: &str) -> Result<Vec<Value>> {
+        let url = format!("{}?{query}", self.table_url(table));
         let mut req = self.client.get(&url);
         for (k, v) in self.auth_headers() {
             req = req.header(k, v);
@@ -60,7 +82,11 @@ impl SupabaseClient {
 
     /// INSERT rows.
     async fn insert(&self, rows: &[Value]) -> Result<()> {
-        let mut req = self.client.post(&self.base_url());
+        self.insert_into(TABLE, rows).await
+    }
+
+    async fn insert_into<T: serde::Serialize + Sync>(&self, table: &str, rows: &[T]) -> Result<()> {
+        let mut req = self.client.post(self.table_url(table));
         for (k, v) in self.auth_headers() {
             req = req.header(k, v);
         }
@@ -91,7 +117,11 @@ impl SupabaseClient {
 
     /// DELETE rows matching a filter.
     async fn delete(&self, filter: &str) -> Result<()> {
-        let url = format!("{}?{filter}", self.base_url());
+        self.delete_from(TABLE, filter).await
+    }
+
+    async fn delete_from(&self, table: &str, filter: &str) -> Result<()> {
+        let url = format!("{}?{filter}", self.table_url(table));
         let mut req = self.client.delete(&url);
         for (k, v) in self.auth_headers() {
             req = req.header(k, v);
@@ -100,47 +130,12 @@ impl SupabaseClient {
         Ok(())
     }
 
-    /// Record a batch of stock sightings (insert new, update existing).
-    /// stocks: map of symbol -> (data, scanners list)
-    pub async fn record_stocks_batch(
-        &mut self,
-        stocks: &std::collections::HashMap<String, (Value, Vec<String>)>,
-    ) -> Result<()> {
-        if stocks.is_empty() {
-            return Ok(());
-        }
-
-        let symbols: Vec<&str> = stocks.keys().map(|s| s.as_str()).collect();
-        let now = Local::now().format("%Y-%m-%dT%H:%M:%S%:z").to_string();
-
-        for attempt in 0..3 {
-            match self.try_record_batch(&symbols, stocks, &now).await {
-                Ok(()) => return Ok(()),
-                Err(e) => {
-                    let msg = format!("{e}");
-                    if attempt < 2
-                        && (msg.contains("connection")
-                            || msg.contains("Connection")
-                            || msg.contains("reset"))
-                    {
-                        warn!("Supabase connection dropped, reconnecting (attempt {})...", attempt + 1);
-                        self.reconnect();
-                        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-                        continue;
-                    }
-                    warn!("Supabase record_stocks_batch failed: {e}");
-                    return Ok(()); // Don't crash
-                }
-            }
-        }
-        Ok(())
-    }
-
     async fn try_record_batch(
         &self,
         symbols: &[&str],
-        stocks: &std::collections::HashMap<String, (Value, Vec<String>)>,
+        stocks: &StockBatch,
         now: &str,
+        trading_date: NaiveDate,
     ) -> Result<()> {
         // Bulk SELECT existing symbols
         let symbols_param = symbols
@@ -148,10 +143,12 @@ impl SupabaseClient {
             .map(|s| format!("\"{s}\""))
             .collect::<Vec<_>>()
             .join(",");
-        let query = format!("select=id,symbol,scanners,hit_count&symbol=in.({symbols_param})");
+        let query = format!(
+            "select=id,symbol,scanners,hit_count&symbol=in.({symbols_param})&trading_date=eq.{trading_date}"
+        );
         let existing = self.select(&query).await?;
 
-        let existing_map: std::collections::HashMap<String, Value> = existing
+        let existing_map: HashMap<String, Value> = existing
             .into_iter()
             .filter_map(|row| {
                 let sym = row.get("symbol")?.as_str()?.to_string();
@@ -162,22 +159,13 @@ impl SupabaseClient {
         // Separate inserts and updates
         let mut inserts = Vec::new();
         for (sym, (data, scanner_list)) in stocks {
-            let scanners_str = {
-                let mut set: std::collections::BTreeSet<&str> =
-                    scanner_list.iter().map(|s| s.as_str()).collect();
-                // Merge with existing scanners if present
-                if let Some(existing_row) = existing_map.get(sym) {
-                    if let Some(existing_scanners) = existing_row.get("scanners").and_then(|s| s.as_str()) {
-                        for s in existing_scanners.split(',') {
-                            set.insert(s);
-                        }
-                    }
-                }
-                set.into_iter().collect::<Vec<_>>().join(",")
-            };
+            let existing_scanners = existing_map
+                .get(sym)
+                .and_then(|row| row.get("scanners"))
+                .and_then(|s| s.as_str());
+            let scanners_str = merge_scanners(existing_scanners, scanner_list);
 
-            if existing_map.contains_key(sym) {
-                let existing_row = &existing_map[sym];
+            if let Some(existing_row) = existing_map.get(sym) {
                 let old_hits = existing_row
                     .get("hit_count")
                     .and_then(|h| h.as_i64())
@@ -190,34 +178,20 @@ impl SupabaseClient {
                 });
 
                 // Only update fields with non-null values
-                for (db_col, data_key) in &[
-                    ("last_price", "last"),
-                    ("change_pct", "change_pct"),
-                    ("rvol", "rvol"),
-                    ("float_shares", "float_shares"),
-                    ("catalyst", "catalyst"),
-                    ("catalyst_time", "catalyst_time"),
-                    ("name", "name"),
-                    ("sector", "sector"),
-                    ("industry", "industry"),
-                    ("short_pct", "short_pct"),
-                    ("avg_volume", "avg_volume"),
-                    ("avg_volume_10d", "avg_volume_10d"),
-                    ("news_headlines", "news_headlines"),
-                    ("enriched_at", "enriched_at"),
-                ] {
-                    if let Some(val) = data.get(data_key) {
-                        if !val.is_null() {
-                            update[db_col] = val.clone();
-                        }
+                for (db_col, data_key) in DATA_COLUMNS {
+                    if let Some(val) = data.get(data_key)
+                        && !val.is_null()
+                    {
+                        update[db_col] = val.clone();
                     }
                 }
 
-                let filter = format!("symbol=eq.{sym}");
+                let filter = format!("symbol=eq.{sym}&trading_date=eq.{trading_date}");
                 self.update(&filter, &update).await?;
             } else {
                 let mut insert = json!({
                     "symbol": sym,
+                    "trading_date": trading_date.to_string(),
                     "first_seen": now,
                     "last_seen": now,
                     "scanners": scanners_str,
@@ -230,11 +204,11 @@ impl SupabaseClient {
                     "name": data.get("name").cloned().unwrap_or(Value::Null),
                     "sector": data.get("sector").cloned().unwrap_or(Value::Null),
                 });
-                for key in &["industry", "short_pct", "avg_volume", "avg_volume_10d", "catalyst_time", "news_headlines", "enriched_at"] {
-                    if let Some(val) = data.get(key) {
-                        if !val.is_null() {
-                            insert[key] = val.clone();
-                        }
+                for key in &["industry", "short_pct", "avg_volume", "avg_volume_10d", "catalyst_time", "catalyst_kind", "catalyst_sentiment", "news_headlines", "enriched_at"] {
+                    if let Some(val) = data.get(key)
+                        && !val.is_null()
+                    {
+                        insert[key] = val.clone();
                     }
                 }
                 inserts.push(insert);
@@ -247,45 +221,69 @@ impl SupabaseClient {
 
         Ok(())
     }
+}
+
+#[async_trait]
+impl HistoryStore for SupabaseClient {
+    fn backend(&self) -> &'static str {
+        "supabase"
+    }
+
+    async fn record_stocks_batch(&self, stocks: &StockBatch) -> Result<()> {
+        if stocks.is_empty() {
+            return Ok(());
+        }
 
-    /// Check enrichment cache for a symbol. Returns Some(EnrichmentData) if
-    /// the symbol has been enriched within `max_age`.
-    pub async fn get_enrichment_cache(
+        let symbols: Vec<&str> = stocks.keys().map(|s| s.as_str()).collect();
+        let now = Local::now().format("%Y-%m-%dT%H:%M:%S%:z").to_string();
+        let trading_date = session::today();
+
+        // Retries reconnect on a private copy so concurrent writers are unaffected
+        let mut db = self.clone();
+        for attempt in 0..3 {
+            match db.try_record_batch(&symbols, stocks, &now, trading_date).await {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    let msg = format!("{e}");
+                    if attempt < 2
+                        && (msg.contains("connection")
+                            || msg.contains("Connection")
+                            || msg.contains("reset"))
+                    {
+                        warn!("Supabase connection dropped, reconnecting (attempt {})...", attempt + 1);
+                        db.reconnect();
+                        tokio::time::sleep(Duration::from_millis(500)).await;
+                        continue;
+                    }
+                    warn!("Supabase record_stocks_batch failed: {e}");
+                    return Ok(()); // Don't crash
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_enrichment_cache(
         &self,
         symbol: &str,
-        max_age: std::time::Duration,
+        max_age: Duration,
     ) -> Option<EnrichmentData> {
         let query = format!(
-            "select=name,sector,industry,float_shares,short_pct,avg_volume,avg_volume_10d,catalyst,catalyst_time,news_headlines,enriched_at&symbol=eq.{symbol}&limit=1"
+            "select=name,sector,industry,float_shares,short_pct,avg_volume,avg_volume_10d,catalyst,catalyst_time,catalyst_kind,catalyst_sentiment,news_headlines,enriched_at&symbol=eq.{symbol}&order=enriched_at.desc.nullslast&limit=1"
         );
         let rows = self.select(&query).await.ok()?;
         let row = rows.into_iter().next()?;
 
         // Check enriched_at freshness
-        let enriched_at_str = row.get("enriched_at")?.as_str()?;
-        let enriched_at = chrono::DateTime::parse_from_rfc3339(enriched_at_str).ok()?;
-        let age = Utc::now().signed_duration_since(enriched_at.with_timezone(&Utc));
-        if age > chrono::Duration::from_std(max_age).ok()? {
+        if !is_fresh(row.get("enriched_at")?.as_str()?, max_age) {
             return None;
         }
 
         // Reconstruct EnrichmentData from cached fields
-        // Backwards compat: try Vec<NewsHeadline> first, then Vec<String>
-        let news_headlines: Vec<NewsHeadline> = row
+        let news_headlines = row
             .get("news_headlines")
             .and_then(|v| v.as_str())
-            .and_then(|s| {
-                serde_json::from_str::<Vec<NewsHeadline>>(s)
-                    .ok()
-                    .or_else(|| {
-                        serde_json::from_str::<Vec<String>>(s).ok().map(|titles| {
-                            titles.into_iter().map(|title| NewsHeadline {
-                                title,
-                                published: None,
-                            }).collect()
-                        })
-                    })
-            })
+            .map(parse_news_headlines)
             .unwrap_or_default();
 
         Some(EnrichmentData {
@@ -295,16 +293,21 @@ impl SupabaseClient {
             country: None,
             float_shares: row.get("float_shares").and_then(|v| v.as_f64()),
             short_pct: row.get("short_pct").and_then(|v| v.as_f64()),
-            avg_volume: row.get("avg_volume").and_then(|v| v.as_i64()),
-            avg_volume_10d: row.get("avg_volume_10d").and_then(|v| v.as_i64()),
+            avg_volume: row.get("avg_volume").and_then(|v| v.as_i64()).map(Shares),
+            avg_volume_10d: row.get("avg_volume_10d").and_then(|v| v.as_i64()).map(Shares),
             catalyst: row.get("catalyst").and_then(|v| v.as_str()).map(String::from),
             catalyst_time: row.get("catalyst_time").and_then(|v| v.as_i64()),
+            catalyst_kind: row.get("catalyst_kind").and_then(|v| v.as_str()).and_then(CatalystKind::parse),
+            catalyst_sentiment: row
+                .get("catalyst_sentiment")
+                .and_then(|v| v.as_str())
+                .and_then(Sentiment::parse),
             news_headlines,
+            ..Default::default()
         })
     }
 
-    /// Get history (all tws_scans, ordered by first_seen DESC).
-    pub async fn get_history(&self, limit: u32) -> Result<Vec<TwsScan>> {
+    async fn get_history(&self, limit: u32) -> Result<Vec<TwsScan>> {
         let query = format!("select=*&order=first_seen.desc&limit={limit}");
         let rows = self.select(&query).await?;
         let scans = rows
@@ -314,21 +317,11 @@ impl SupabaseClient {
         Ok(scans)
     }
 
-    /// Get today's tws_scans (first_seen >= today midnight).
-    pub async fn get_today(&self) -> Result<Vec<TwsScan>> {
-        let today = Local::now()
-            .date_naive()
-            .and_hms_opt(0, 0, 0)
-            .unwrap();
-        let midnight = Local::now()
-            .timezone()
-            .from_local_datetime(&today)
-            .single()
-            .unwrap()
-            .format("%Y-%m-%dT%H:%M:%S%:z")
-            .to_string();
-
-        let query = format!("select=*&first_seen=gte.{midnight}&order=first_seen.desc");
+    async fn get_today(&self) -> Result<Vec<TwsScan>> {
+        let query = format!(
+            "select=*&trading_date=eq.{}&order=first_seen.desc",
+            session::today()
+        );
         let rows = self.select(&query).await?;
         let scans = rows
             .into_iter()
@@ -337,8 +330,20 @@ impl SupabaseClient {
         Ok(scans)
     }
 
-    /// Clear all history. Returns count of deleted rows.
-    pub async fn clear_history(&self) -> Result<u32> {
+    async fn get_scans_between(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<TwsScan>> {
+        let query = format!(
+            "select=*&trading_date=gte.{from}&trading_date=lte.{to}\
+             &order=trading_date.asc,first_seen.asc"
+        );
+        let rows = self.select(&query).await?;
+        let scans = rows
+            .into_iter()
+            .filter_map(|v| serde_json::from_value(v).ok())
+            .collect();
+        Ok(scans)
+    }
+
+    async fn clear_history(&self) -> Result<u32> {
         // Count first
         let count_query = "select=id&limit=10000";
         let rows = self.select(count_query).await?;
@@ -346,13 +351,14 @@ impl SupabaseClient {
 
         // Delete all
         self.delete("symbol=neq.").await?;
+        self.delete_from(SIGHTINGS_TABLE, "symbol=neq.").await?;
+        self.delete_from(HALTS_TABLE, "symbol=neq.").await?;
         Ok(count)
     }
 
-    /// Get symbols that are not already in the database.
-    pub async fn get_new_symbols(&self, symbols: &[String]) -> Result<std::collections::HashSet<String>> {
+    async fn get_new_symbols(&self, symbols: &[String]) -> Result<HashSet<String>> {
         if symbols.is_empty() {
-            return Ok(std::collections::HashSet::new());
+            return Ok(HashSet::new());
         }
         let symbols_param = symbols
             .iter()
@@ -361,121 +367,74 @@ impl SupabaseClient {
             .join(",");
         let query = format!("select=symbol&symbol=in.({symbols_param})");
         let rows = self.select(&query).await?;
-        let existing: std::collections::HashSet<String> = rows
+        let existing: HashSet<String> = rows
             .iter()
             .filter_map(|r| r.get("symbol")?.as_str().map(|s| s.to_string()))
             .collect();
-        let all: std::collections::HashSet<String> = symbols.iter().cloned().collect();
+        let all: HashSet<String> = symbols.iter().cloned().collect();
         Ok(all.difference(&existing).cloned().collect())
     }
-}
 
-/// Print tws_scans as a formatted history table.
-pub fn print_history(scans: &[TwsScan], label: &str) {
-    if scans.is_empty() {
-        println!("{label}: no stocks in history");
-        return;
+    async fn record_sightings(&self, sightings: &[Sighting]) -> Result<()> {
+        if sightings.is_empty() {
+            return Ok(());
+        }
+        self.insert_into(SIGHTINGS_TABLE, sightings).await
     }
 
-    println!("{label} -- {} stocks", scans.len());
-    println!(
-        "{:<10}  {:<6}  {:>8}  {:>8}  {:>6}  {:<30}  {:>4}  {}",
-        "Time", "Symbol", "Last", "Chg%", "RVol", "Scanners", "Hits", "Catalyst"
-    );
-    println!("{}", "-".repeat(100));
-
-    for s in scans {
-        let time_str = local_time_str(&s.first_seen);
-        let price = match s.last_price {
-            Some(p) => format!("{p:.2}"),
-            None => "-".to_string(),
-        };
-        let chg = match s.change_pct {
-            Some(c) => format!("{c:+.1}%"),
-            None => "-".to_string(),
-        };
-        let rvol = match s.rvol {
-            Some(r) => format!("{r:.1}x"),
-            None => "-".to_string(),
-        };
-        let hits = s.hit_count.unwrap_or(0);
-        let catalyst = s.catalyst.as_deref().unwrap_or("");
-        let catalyst = if catalyst.len() > 30 {
-            format!("{}..", &catalyst[..28])
-        } else {
-            catalyst.to_string()
-        };
-
-        println!(
-            "{:<10}  {:<6}  {:>8}  {:>8}  {:>6}  {:<30}  {:>4}  {}",
-            time_str, s.symbol, price, chg, rvol, s.scanners, hits, catalyst
+    async fn schema_version(&self) -> Result<u32> {
+        let url = format!(
+            "{}?select=version&order=version.desc&limit=1",
+            self.table_url(MIGRATIONS_TABLE)
         );
-    }
-}
-
-/// Convert an ISO timestamp to local HH:MM:SS.
-pub fn local_time_str(iso_ts: &str) -> String {
-    chrono::DateTime::parse_from_rfc3339(iso_ts)
-        .or_else(|_| chrono::DateTime::parse_from_str(iso_ts, "%Y-%m-%dT%H:%M:%S%:z"))
-        .map(|dt| dt.with_timezone(&Local).format("%H:%M:%S").to_string())
-        .unwrap_or_else(|_| "-".to_string())
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_local_time_str_valid() {
-        // Should not panic on valid ISO timestamp
-        let result = local_time_str("2024-01-15T14:30:00+00:00");
-        assert!(!result.is_empty());
-        assert_ne!(result, "-");
+        let mut req = self.client.get(&url);
+        for (k, v) in self.auth_headers() {
+            req = req.header(k, v);
+        }
+        let resp = req.send().await.context("Supabase SELECT failed")?;
+        // No schema_migrations table yet: nothing has been applied
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(0);
+        }
+        let rows: Vec<Value> = resp.json().await.context("Supabase response parse failed")?;
+        Ok(rows
+            .first()
+            .and_then(|r| r["version"].as_u64())
+            .unwrap_or(0) as u32)
     }
 
-    #[test]
-    fn test_local_time_str_invalid() {
-        assert_eq!(local_time_str("not-a-date"), "-");
+    async fn migrate(&self) -> Result<Vec<u32>> {
+        anyhow::bail!(
+            "the Supabase REST API cannot run DDL; print the pending SQL with \
+             `scanner db migrate --print` and run it in the Supabase SQL editor"
+        )
     }
 
-    #[test]
-    fn test_local_time_str_short() {
-        assert_eq!(local_time_str("abc"), "-");
+    async fn get_trajectory(&self, symbol: &str, date: NaiveDate) -> Result<Vec<Sighting>> {
+        let (start, end) = day_bounds_utc(date);
+        let query = format!(
+            "select=*&symbol=eq.{symbol}&seen_at=gte.{start}&seen_at=lt.{end}&order=seen_at.asc"
+        );
+        let rows = self.select_from(SIGHTINGS_TABLE, &query).await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|v| serde_json::from_value(v).ok())
+            .collect())
     }
 
-    #[test]
-    fn test_print_history_empty() {
-        // Should not panic
-        print_history(&[], "Test");
+    async fn record_halt(&self, event: &HaltEvent) -> Result<()> {
+        self.insert_into(HALTS_TABLE, std::slice::from_ref(event)).await
     }
 
-    #[test]
-    fn test_print_history_with_data() {
-        let scans = vec![TwsScan {
-            id: Some(1),
-            symbol: "AAPL".to_string(),
-            first_seen: "2024-01-15T14:30:00+00:00".to_string(),
-            last_seen: "2024-01-15T14:35:00+00:00".to_string(),
-            scanners: "HOT_BY_VOLUME,TOP_PERC_GAIN".to_string(),
-            hit_count: Some(3),
-            last_price: Some(15.50),
-            change_pct: Some(12.5),
-            rvol: Some(6.3),
-            float_shares: Some(5_000_000.0),
-            catalyst: Some("FDA approval for new drug".to_string()),
-            name: Some("Apple Inc".to_string()),
-            sector: Some("Technology".to_string()),
-            enriched_at: None,
-            industry: None,
-            short_pct: None,
-            avg_volume: None,
-            avg_volume_10d: None,
-            news_headlines: None,
-            catalyst_time: None,
-        }];
-        // Should not panic
-        print_history(&scans, "Today");
+    async fn get_halts(&self, symbol: &str, date: NaiveDate) -> Result<Vec<HaltEvent>> {
+        let (start, end) = day_bounds_utc(date);
+        let query = format!(
+            "select=*&symbol=eq.{symbol}&at=gte.{start}&at=lt.{end}&order=at.asc"
+        );
+        let rows = self.select_from(HALTS_TABLE, &query).await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|v| serde_json::from_value(v).ok())
+            .collect())
     }
 }
-
-use chrono::TimeZone;
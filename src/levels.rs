@@ -0,0 +1,202 @@
+//! Intraday price levels for alert rows: VWAP, high and low of day,
+//! premarket range and gap from the prior close.
+//!
+//! Levels are seeded from today's 5-minute bars when a symbol starts
+//! streaming, then follow every tick. Distances from each level are
+//! recomputed from the last price by `refresh`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::market_data::Bar;
+use crate::session;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IntradayLevels {
+    /// Volume-weighted average price for the day.
+    pub vwap: Option<f64>,
+    /// Highest trade of the day, premarket included.
+    pub high_of_day: Option<f64>,
+    /// Lowest trade of the day, premarket included.
+    pub low_of_day: Option<f64>,
+    pub premarket_high: Option<f64>,
+    pub premarket_low: Option<f64>,
+    /// First regular-session trade (09:30 ET).
+    pub open: Option<f64>,
+    pub prior_close: Option<f64>,
+    /// Open vs prior close; before the open, last vs prior close.
+    pub gap_pct: Option<f64>,
+    /// Last price relative to each level, in percent.
+    pub from_vwap_pct: Option<f64>,
+    pub from_high_pct: Option<f64>,
+    pub from_premarket_high_pct: Option<f64>,
+    pub from_premarket_low_pct: Option<f64>,
+}
+
+/// The 09:30 ET open of the session `at` falls in.
+fn market_open(at: DateTime<Utc>) -> DateTime<Utc> {
+    session::eastern_time(session::trading_date(at), 9, 30)
+}
+
+/// `last` relative to `level`, in percent.
+fn pct_from(last: Option<f64>, level: Option<f64>) -> Option<f64> {
+    let (last, level) = (last?, level?);
+    (level > 0.0).then(|| (last - level) / level * 100.0)
+}
+
+fn max(a: Option<f64>, b: f64) -> Option<f64> {
+    Some(a.map_or(b, |a| a.max(b)))
+}
+
+fn min(a: Option<f64>, b: f64) -> Option<f64> {
+    Some(a.map_or(b, |a| a.min(b)))
+}
+
+impl IntradayLevels {
+    /// Levels from one trading date's bars, extended hours included.
+    pub fn from_bars(bars: &[Bar]) -> Self {
+        let mut levels = Self::default();
+        let (mut value, mut volume) = (0.0, 0.0);
+        for b in bars {
+            levels.observe(b.high, b.low, b.time);
+            if levels.open.is_none() && b.time >= market_open(b.time) {
+                levels.open = Some(b.open);
+            }
+            value += (b.high + b.low + b.close) / 3.0 * b.volume;
+            volume += b.volume;
+        }
+        levels.vwap = (volume > 0.0).then(|| value / volume);
+        levels
+    }
+
+    fn observe(&mut self, high: f64, low: f64, at: DateTime<Utc>) {
+        self.high_of_day = max(self.high_of_day, high);
+        self.low_of_day = min(self.low_of_day, low);
+        if at < market_open(at) {
+            self.premarket_high = max(self.premarket_high, high);
+            self.premarket_low = min(self.premarket_low, low);
+        }
+    }
+
+    /// Fold in a trade at `price` seen at `at`.
+    pub fn on_price(&mut self, price: f64, at: DateTime<Utc>) {
+        if price <= 0.0 {
+            return;
+        }
+        self.observe(price, price, at);
+        if self.open.is_none() && at >= market_open(at) {
+            self.open = Some(price);
+        }
+    }
+
+    /// The day's high as reported by the feed.
+    pub fn on_high(&mut self, high: f64) {
+        if high > 0.0 {
+            self.high_of_day = max(self.high_of_day, high);
+        }
+    }
+
+    /// The day's VWAP as reported by the feed (RTVolume).
+    pub fn on_vwap(&mut self, vwap: f64) {
+        if vwap > 0.0 {
+            self.vwap = Some(vwap);
+        }
+    }
+
+    /// Merge levels seeded from bars. Ranges widen to cover both; the bars'
+    /// open wins over a tick seen after the open, and the feed's VWAP wins
+    /// over the bars' estimate.
+    pub fn merge(&mut self, seed: Self) {
+        if let Some(h) = seed.high_of_day {
+            self.high_of_day = max(self.high_of_day, h);
+        }
+        if let Some(l) = seed.low_of_day {
+            self.low_of_day = min(self.low_of_day, l);
+        }
+        if let Some(h) = seed.premarket_high {
+            self.premarket_high = max(self.premarket_high, h);
+        }
+        if let Some(l) = seed.premarket_low {
+            self.premarket_low = min(self.premarket_low, l);
+        }
+        self.open = seed.open.or(self.open);
+        self.vwap = self.vwap.or(seed.vwap);
+    }
+
+    /// Recompute the gap and the distances from `last`.
+    pub fn refresh(&mut self, last: Option<f64>) {
+        self.gap_pct = pct_from(self.open.or(last), self.prior_close);
+        self.from_vwap_pct = pct_from(last, self.vwap);
+        self.from_high_pct = pct_from(last, self.high_of_day);
+        self.from_premarket_high_pct = pct_from(last, self.premarket_high);
+        self.from_premarket_low_pct = pct_from(last, self.premarket_low);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        session::eastern_time(NaiveDate::from_ymd_opt(2024, 7, 15).unwrap(), hour, minute)
+    }
+
+    fn bar(time: DateTime<Utc>, open: f64, high: f64, low: f64, close: f64, volume: f64) -> Bar {
+        Bar { time, open, high, low, close, volume }
+    }
+
+    #[test]
+    fn test_from_bars() {
+        let bars = vec![
+            bar(at(8, 0), 2.0, 2.4, 1.9, 2.3, 1000.0),
+            bar(at(9, 25), 2.3, 2.6, 2.1, 2.5, 1000.0),
+            bar(at(9, 30), 2.55, 3.0, 2.5, 2.9, 2000.0),
+        ];
+        let levels = IntradayLevels::from_bars(&bars);
+        assert_eq!(levels.premarket_high, Some(2.6));
+        assert_eq!(levels.premarket_low, Some(1.9));
+        assert_eq!(levels.high_of_day, Some(3.0));
+        assert_eq!(levels.low_of_day, Some(1.9));
+        assert_eq!(levels.open, Some(2.55));
+        // Typical prices 2.2, 2.4, 2.8 weighted 1:1:2
+        assert!((levels.vwap.unwrap() - 2.55).abs() < 1e-9);
+        assert_eq!(IntradayLevels::from_bars(&[]).vwap, None);
+    }
+
+    #[test]
+    fn test_ticks_merge_and_refresh() {
+        let mut levels = IntradayLevels {
+            prior_close: Some(2.0),
+            ..Default::default()
+        };
+        levels.on_price(2.5, at(9, 0));
+        levels.on_price(2.4, at(9, 10));
+        levels.refresh(Some(2.4));
+        // Before the open the gap follows the last price
+        assert!((levels.gap_pct.unwrap() - 20.0).abs() < 1e-9);
+        assert_eq!(levels.from_vwap_pct, None);
+
+        levels.on_price(2.8, at(10, 0));
+        levels.on_vwap(2.6);
+        assert_eq!((levels.premarket_high, levels.premarket_low), (Some(2.5), Some(2.4)));
+        assert_eq!((levels.high_of_day, levels.open), (Some(2.8), Some(2.8)));
+
+        // Bars fetched later correct the open and widen the ranges
+        levels.merge(IntradayLevels::from_bars(&[
+            bar(at(7, 0), 2.1, 2.2, 2.05, 2.2, 500.0),
+            bar(at(9, 30), 2.5, 2.9, 2.5, 2.7, 500.0),
+        ]));
+        assert_eq!(levels.open, Some(2.5));
+        assert_eq!(levels.premarket_low, Some(2.05));
+        assert_eq!((levels.high_of_day, levels.low_of_day), (Some(2.9), Some(2.05)));
+        assert_eq!(levels.vwap, Some(2.6));
+
+        levels.refresh(Some(2.6));
+        assert!((levels.gap_pct.unwrap() - 25.0).abs() < 1e-9);
+        assert_eq!(levels.from_vwap_pct, Some(0.0));
+        assert!((levels.from_high_pct.unwrap() - (2.6 - 2.9) / 2.9 * 100.0).abs() < 1e-9);
+        assert!((levels.from_premarket_high_pct.unwrap() - 4.0).abs() < 1e-9);
+    }
+}
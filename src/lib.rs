@@ -1,11 +1,22 @@
+pub mod backtest;
 pub mod catalyst;
 pub mod cli;
 pub mod config;
+pub mod criteria;
 pub mod engine;
 pub mod enrichment;
 pub mod error;
 pub mod history;
+pub mod levels;
+pub mod market_data;
+pub mod recording;
+pub mod rvol;
 pub mod models;
+pub mod quote;
 pub mod scanner;
+pub mod scanner_set;
+pub mod session;
+pub mod short;
 pub mod gui;
 pub mod tws;
+pub mod units;
@@ -1,9 +1,15 @@
+use std::path::PathBuf;
+
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
 use scanner_rs::cli;
 use scanner_rs::config;
 use scanner_rs::gui;
+use scanner_rs::market_data::{Feed, SimConfig, SimScript};
+use scanner_rs::recording::{self, Recording};
+use scanner_rs::scanner_set::ScanFilters;
+use scanner_rs::tws::bars::{self, BarRequest};
 
 #[derive(Parser)]
 #[command(name = "scanner", about = "TWS Momentum Stock Scanner")]
@@ -33,14 +39,53 @@ enum Commands {
         /// Maximum price filter
         #[arg(long)]
         max_price: Option<f64>,
+        /// Minimum volume filter
+        #[arg(long, default_value = "100000")]
+        min_volume: i32,
+        /// Minimum market cap filter
+        #[arg(long)]
+        min_market_cap: Option<f64>,
+        /// Maximum market cap filter
+        #[arg(long)]
+        max_market_cap: Option<f64>,
+        /// Scanner location code (e.g., STK.US.MAJOR, STK.US, STK.NASDAQ)
+        #[arg(long, default_value = "STK.US.MAJOR")]
+        location: String,
+        /// Scanner instrument type
+        #[arg(long, default_value = "STK")]
+        instrument: String,
+        /// Stock type filter (CORP, ADR, ETF, REIT, CEF)
+        #[arg(long)]
+        stock_type: Option<String>,
+        /// Extra TWS filter tag, repeatable (e.g., --filter changePercAbove=10)
+        #[arg(long = "filter", value_name = "TAG=VALUE", value_parser = parse_filter_tag)]
+        filters: Vec<(String, String)>,
         /// List scanner parameters instead of running a scan
         #[arg(long)]
         list: bool,
+        /// Momentum criteria profile from scanner.toml (default: default_criteria)
+        #[arg(long)]
+        criteria: Option<String>,
+        /// Only show results passing every pillar of the criteria profile
+        #[arg(long)]
+        momentum: bool,
     },
-    /// List available scanners from TWS
+    /// List available scanners from TWS (cached in var/scanner_params.json)
     List {
         /// Group to expand (fuzzy match), or omit for summary
         group: Option<String>,
+        /// Search scan types by code or name
+        #[arg(long)]
+        search: Option<String>,
+        /// Show the filter tags a scan type supports
+        #[arg(long, value_name = "CODE")]
+        filters: Option<String>,
+        /// Print the full scanner parameters model as JSON
+        #[arg(long)]
+        json: bool,
+        /// Refetch from TWS instead of using the cache
+        #[arg(long)]
+        refresh: bool,
         /// TWS host
         #[arg(long, default_value = "127.0.0.1")]
         host: String,
@@ -48,15 +93,61 @@ enum Commands {
         #[arg(long)]
         port: Option<u16>,
     },
-    /// Query Supabase sightings history
+    /// Query sightings history (Supabase or SQLite, per scanner.toml)
     History {
-        /// Subcommand: today (default), all, clear, or a number
+        /// Subcommand: today (default), all, clear, trajectory, or a number
         what: Option<String>,
+        /// Symbol for `trajectory`
+        symbol: Option<String>,
+        /// Trading day for `trajectory` (YYYY-MM-DD, default today in US/Eastern)
+        #[arg(long)]
+        date: Option<chrono::NaiveDate>,
+    },
+    /// Inspect or migrate the history store schema
+    Db {
+        /// Subcommand: status (default) or migrate
+        what: Option<String>,
+        /// With `migrate`, print the pending SQL instead of applying it
+        #[arg(long)]
+        print: bool,
+    },
+    /// Backtest a criteria profile over recorded sightings
+    Backtest {
+        /// First trading date (YYYY-MM-DD)
+        #[arg(long)]
+        from: chrono::NaiveDate,
+        /// Last trading date (default: today in US/Eastern)
+        #[arg(long)]
+        to: Option<chrono::NaiveDate>,
+        /// Momentum criteria profile from scanner.toml
+        #[arg(long)]
+        criteria: Option<String>,
+        /// Scanner set the history was polled with (for scanner-hit scoring)
+        #[arg(long)]
+        set: Option<String>,
+        /// Only count entries with at least this momentum score (0-100)
+        #[arg(long)]
+        min_score: Option<f64>,
+        /// Print outcomes and summary as JSON
+        #[arg(long)]
+        json: bool,
+        /// TWS host (for historical bars)
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// TWS port
+        #[arg(long)]
+        port: Option<u16>,
     },
-    /// Enrich symbols with Yahoo Finance data (for testing)
+    /// Enrich symbols through the configured providers (for testing)
     Enrich {
         /// Symbols to enrich
         symbols: Vec<String>,
+        /// TWS host (IB fundamentals)
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// TWS port
+        #[arg(long)]
+        port: Option<u16>,
     },
     /// Show current configuration
     Config {
@@ -71,6 +162,43 @@ enum Commands {
         /// TWS port (auto-detects 7500/7497 if omitted)
         #[arg(long)]
         port: Option<u16>,
+        /// Momentum criteria profile from scanner.toml
+        #[arg(long)]
+        criteria: Option<String>,
+        #[command(flatten)]
+        sim: SimArgs,
+    },
+    /// Historical bars from TWS (cached in var/bars)
+    Bars {
+        /// Symbol
+        symbol: String,
+        /// Bar size: 1s, 5s, ..., 1m, 5m, 15m, 1h, 1d, 1w, 1mo
+        #[arg(long, default_value = "5m")]
+        size: String,
+        /// How far back: e.g. 1d, 2w, 3600s, 6mo
+        #[arg(long, default_value = "1d")]
+        duration: String,
+        /// trades, midpoint, bid, ask, bid_ask, ...
+        #[arg(long, default_value = "trades")]
+        what: String,
+        /// Regular trading hours only (default includes pre/post market)
+        #[arg(long)]
+        rth: bool,
+        /// Trading date the bars end on (default: now)
+        #[arg(long)]
+        date: Option<chrono::NaiveDate>,
+        /// Output format: table, csv or json
+        #[arg(long, default_value = "table")]
+        format: cli::BarsFormat,
+        /// Refetch from TWS even if the cache is fresh
+        #[arg(long)]
+        refresh: bool,
+        /// TWS host
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// TWS port
+        #[arg(long)]
+        port: Option<u16>,
     },
     /// Cross-check volume: compare tick volume vs summed 5-min bar volume
     Volume {
@@ -85,18 +213,107 @@ enum Commands {
     },
     /// Stream momentum alerts to stdout (headless mode)
     Alert {
-        /// TWS host
-        #[arg(long, default_value = "127.0.0.1")]
-        host: String,
-        /// TWS port (auto-detects 7500/7497 if omitted)
-        #[arg(long)]
-        port: Option<u16>,
+        #[command(flatten)]
+        args: AlertArgs,
+    },
+    /// Run alert mode and record every market input to a session file
+    Record {
+        #[command(flatten)]
+        args: AlertArgs,
+        /// Session file (default: var/sessions/<date>-<time>.jsonl)
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+    },
+    /// Replay a recorded session through the alert engine
+    Replay {
+        /// Session file written by `scanner record`
+        file: PathBuf,
+        /// Playback speed (e.g. 10 replays ten times faster than recorded)
+        #[arg(long, default_value = "1")]
+        speed: f64,
         /// Output alerts as JSON lines
         #[arg(long)]
         json: bool,
+        /// Momentum criteria profile from scanner.toml
+        #[arg(long)]
+        criteria: Option<String>,
+        /// Replay in the GUI instead of printing alerts
+        #[arg(long)]
+        gui: bool,
     },
 }
 
+/// Options shared by `alert` and `record`.
+#[derive(Args)]
+struct AlertArgs {
+    /// TWS host
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+    /// TWS port (auto-detects 7500/7497 if omitted)
+    #[arg(long)]
+    port: Option<u16>,
+    /// Output alerts as JSON lines
+    #[arg(long)]
+    json: bool,
+    /// Momentum criteria profile from scanner.toml
+    #[arg(long)]
+    criteria: Option<String>,
+    /// Scanner set from scanner.toml to poll (default: default_scanner_set)
+    #[arg(long)]
+    set: Option<String>,
+    #[command(flatten)]
+    sim: SimArgs,
+}
+
+/// Simulated feed options for `alert`, `record` and `gui`.
+#[derive(Args)]
+struct SimArgs {
+    /// Use the simulated market data feed instead of TWS
+    #[arg(long)]
+    sim: bool,
+    /// Seed for the simulated feed
+    #[arg(long, default_value = "42", requires = "sim")]
+    seed: u64,
+    /// Scripted price paths for the simulated feed (TOML)
+    #[arg(long, value_name = "PATH", requires = "sim")]
+    script: Option<PathBuf>,
+}
+
+impl SimArgs {
+    fn feed(self) -> Result<Feed> {
+        if !self.sim {
+            return Ok(Feed::Tws);
+        }
+        let script = self.script.as_deref().map(SimScript::load).transpose()?;
+        Ok(Feed::Sim(SimConfig { seed: self.seed, script, ..SimConfig::default() }))
+    }
+}
+
+impl AlertArgs {
+    fn into_options(self, scanner_config: &config::ScannerConfig) -> Result<cli::AlertOptions> {
+        Ok(cli::AlertOptions {
+            criteria: scanner_config.criteria(self.criteria.as_deref())?,
+            scanner_set: scanner_config.scanner_set(self.set.as_deref())?,
+            host: self.host,
+            port: self.port,
+            json: self.json,
+            feed: self.sim.feed()?,
+            record: None,
+            enrichment: scanner_config.enrichment.clone(),
+        })
+    }
+}
+
+/// Parse a `TAG=VALUE` scanner filter option.
+fn parse_filter_tag(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((tag, value)) if !tag.is_empty() && !value.is_empty() => {
+            Ok((tag.to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected TAG=VALUE, got '{s}'")),
+    }
+}
+
 fn init_logging() -> tracing_appender::non_blocking::WorkerGuard {
     let var_dir = std::path::Path::new("var");
     if !var_dir.exists() {
@@ -119,32 +336,67 @@ fn main() -> Result<()> {
     config::load_env();
 
     let cli_args = Cli::parse();
+    let scanner_config = config::ScannerConfig::load()?;
 
     match cli_args.command {
         // GUI mode: runs its own tokio runtime internally
-        Some(Commands::Gui { host, port }) => {
-            gui::run_gui(host, port).map_err(|e| anyhow::anyhow!("{e}"))?;
+        Some(Commands::Gui { host, port, criteria, sim }) => {
+            let criteria = scanner_config.criteria(criteria.as_deref())?;
+            let feed = sim.feed()?;
+            if matches!(feed, Feed::Tws) {
+                cli::check_history_schema(&scanner_config.history)?;
+            }
+            gui::run_gui(host, port, scanner_config, criteria, feed)
+                .map_err(|e| anyhow::anyhow!("{e}"))?;
         }
         None => {
-            gui::run_gui("127.0.0.1".to_string(), None).map_err(|e| anyhow::anyhow!("{e}"))?;
+            let criteria = scanner_config.criteria(None)?;
+            cli::check_history_schema(&scanner_config.history)?;
+            gui::run_gui("127.0.0.1".to_string(), None, scanner_config, criteria, Feed::Tws)
+                .map_err(|e| anyhow::anyhow!("{e}"))?;
         }
 
         // Alert mode: runs its own tokio runtime internally
-        Some(Commands::Alert { host, port, json }) => {
-            cli::run_alert(&host, port, json)?;
+        Some(Commands::Alert { args }) => {
+            let opts = args.into_options(&scanner_config)?;
+            cli::run_alert(opts, &scanner_config.history)?;
+        }
+        Some(Commands::Record { args, out }) => {
+            let mut opts = args.into_options(&scanner_config)?;
+            opts.record = Some(out.unwrap_or_else(|| recording::default_path(chrono::Local::now())));
+            cli::run_alert(opts, &scanner_config.history)?;
+        }
+        Some(Commands::Replay { file, speed, json, criteria, gui }) => {
+            if !(speed.is_finite() && speed > 0.0) {
+                anyhow::bail!("--speed must be a positive number, got {speed}");
+            }
+            let criteria = scanner_config.criteria(criteria.as_deref())?;
+            let recording = Recording::load(&file)?;
+            // Score hits against the set the session polled, if still defined
+            let scanner_set = scanner_config
+                .scanner_set(Some(&recording.header.scanner_set))
+                .unwrap_or_default();
+            let feed = Feed::Replay { recording, speed };
+            if gui {
+                gui::run_gui("127.0.0.1".to_string(), None, scanner_config, criteria, feed)
+                    .map_err(|e| anyhow::anyhow!("{e}"))?;
+            } else {
+                let opts = cli::AlertOptions { json, criteria, scanner_set, feed, ..Default::default() };
+                cli::run_alert(opts, &scanner_config.history)?;
+            }
         }
 
         // All other commands use a tokio runtime
         other => {
             let rt = tokio::runtime::Runtime::new()?;
-            rt.block_on(run_command(other.unwrap()))?;
+            rt.block_on(run_command(other.unwrap(), &scanner_config))?;
         }
     }
 
     Ok(())
 }
 
-async fn run_command(cmd: Commands) -> Result<()> {
+async fn run_command(cmd: Commands, scanner_config: &config::ScannerConfig) -> Result<()> {
     match cmd {
         Commands::Scan {
             code,
@@ -153,25 +405,100 @@ async fn run_command(cmd: Commands) -> Result<()> {
             rows,
             min_price,
             max_price,
+            min_volume,
+            min_market_cap,
+            max_market_cap,
+            location,
+            instrument,
+            stock_type,
+            filters,
             list: _,
+            criteria,
+            momentum,
+        } => {
+            let criteria = scanner_config.criteria(criteria.as_deref())?;
+            let filters = ScanFilters {
+                min_price: Some(min_price),
+                max_price,
+                min_volume: Some(min_volume),
+                min_market_cap,
+                max_market_cap,
+                location,
+                instrument,
+                stock_type,
+                tags: filters.into_iter().collect(),
+            };
+            let opts = cli::ScanOptions {
+                code: &code,
+                rows,
+                filters: &filters,
+                criteria: &criteria,
+                scanner_total: scanner_config.scanner_set(None)?.len(),
+                momentum_only: momentum,
+            };
+            cli::cmd_scan(&opts, &host, port, &scanner_config.enrichment).await?;
+        }
+
+        Commands::List {
+            group,
+            search,
+            filters,
+            json,
+            refresh,
+            host,
+            port,
         } => {
-            cli::cmd_scan(&code, &host, port, rows, min_price, max_price).await?;
+            let opts = cli::ListOptions {
+                group: group.as_deref(),
+                search: search.as_deref(),
+                filters: filters.as_deref(),
+                json,
+                refresh,
+            };
+            cli::cmd_list(&opts, &host, port).await?;
+        }
+
+        Commands::History { what, symbol, date } => {
+            cli::cmd_history(what.as_deref(), symbol.as_deref(), date, &scanner_config.history)
+                .await?;
         }
 
-        Commands::List { group, host, port } => {
-            cli::cmd_list(group.as_deref(), &host, port).await?;
+        Commands::Db { what, print } => {
+            cli::cmd_db(what.as_deref(), print, &scanner_config.history).await?;
         }
 
-        Commands::History { what } => {
-            cli::cmd_history(what.as_deref()).await?;
+        Commands::Backtest { from, to, criteria, set, min_score, json, host, port } => {
+            let criteria = scanner_config.criteria(criteria.as_deref())?;
+            let scanner_set = scanner_config.scanner_set(set.as_deref())?;
+            let opts = cli::BacktestOptions {
+                from,
+                to: to.unwrap_or_else(scanner_rs::session::today),
+                criteria: &criteria,
+                scanner_set: &scanner_set,
+                min_score,
+                json,
+            };
+            cli::cmd_backtest(&opts, &host, port, &scanner_config.history).await?;
         }
 
-        Commands::Enrich { symbols } => {
-            cli::cmd_enrich(&symbols).await?;
+        Commands::Enrich { symbols, host, port } => {
+            cli::cmd_enrich(&symbols, &host, port, &scanner_config.enrichment).await?;
         }
 
         Commands::Config { what: _ } => {
-            cli::cmd_config();
+            cli::cmd_config(scanner_config);
+        }
+
+        Commands::Bars { symbol, size, duration, what, rth, date, format, refresh, host, port } => {
+            // A past date's window ends at that session's close
+            let end = date
+                .filter(|d| *d < scanner_rs::session::today())
+                .map(|d| scanner_rs::session::eastern_time(d, if rth { 16 } else { 20 }, 0));
+            let req = BarRequest::new(&symbol, bars::parse_bar_size(&size)?, bars::parse_duration(&duration)?)
+                .what_to_show(bars::parse_what_to_show(&what)?)
+                .rth(rth)
+                .ending(end);
+            cli::cmd_bars(&req, format, refresh, &host, port).await?;
         }
 
         Commands::Volume {
@@ -182,7 +509,10 @@ async fn run_command(cmd: Commands) -> Result<()> {
             cli::cmd_volume(&symbols, &host, port).await?;
         }
 
-        Commands::Gui { .. } | Commands::Alert { .. } => unreachable!(),
+        Commands::Gui { .. }
+        | Commands::Alert { .. }
+        | Commands::Record { .. }
+        | Commands::Replay { .. } => unreachable!(),
     }
 
     Ok(())
@@ -0,0 +1,178 @@
+//! Market data sources behind the engine: live TWS or the offline simulator.
+//!
+//! `MarketDataSource` covers everything the engine asks of TWS: scanner
+//! subscriptions, snapshots, streaming ticks, historical bars, fundamentals,
+//! news and the scanner parameters XML.
+
+pub mod sim;
+pub mod tws;
+
+use std::collections::HashMap;
+use std::pin::Pin;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{NewsHeadline, ScanResult};
+use crate::quote::HaltState;
+use crate::recording::Recording;
+use crate::scanner_set::{ScanFilters, ScannerSet};
+use crate::short::Borrow;
+use crate::tws::bars::BarRequest;
+use crate::tws::connection::Backoff;
+use crate::tws::fundamentals::Fundamentals;
+use crate::tws::ScannerRanks;
+use crate::units::Shares;
+
+pub use sim::{SimConfig, SimScript, Simulator};
+pub use tws::TwsSource;
+
+/// Where an alert session's market data comes from.
+#[derive(Debug, Clone, Default)]
+pub enum Feed {
+    /// Live TWS connections.
+    #[default]
+    Tws,
+    /// The deterministic simulator.
+    Sim(SimConfig),
+    /// A recorded session, played back at `speed` times real time.
+    Replay { recording: Recording, speed: f64 },
+}
+
+impl Feed {
+    /// One-line summary for startup logs.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Tws => "TWS".to_string(),
+            Self::Sim(config) => format!("simulated feed ({})", config.describe()),
+            Self::Replay { recording, speed } => format!(
+                "replay of {} session from {} ({} events, {}s, {speed}x)",
+                recording.header.source,
+                recording.header.started_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+                recording.events.len(),
+                recording.duration().as_secs()
+            ),
+        }
+    }
+}
+
+/// One streaming update. Fields a tick doesn't carry are `None`.
+/// Volumes and sizes are in shares (see `units`).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MarketTick {
+    pub last: Option<f64>,
+    pub close: Option<f64>,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    pub volume: Option<Shares>,
+    /// Day VWAP (RTVolume).
+    pub vwap: Option<f64>,
+    /// Day high.
+    pub high: Option<f64>,
+    pub bid_size: Option<Shares>,
+    pub ask_size: Option<Shares>,
+    pub halted: Option<HaltState>,
+    pub borrow: Option<Borrow>,
+    pub shortable_shares: Option<Shares>,
+}
+
+impl MarketTick {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Overwrite fields with those present in a later `update`.
+    pub fn merge(&mut self, update: MarketTick) {
+        self.last = update.last.or(self.last);
+        self.close = update.close.or(self.close);
+        self.bid = update.bid.or(self.bid);
+        self.ask = update.ask.or(self.ask);
+        self.volume = update.volume.or(self.volume);
+        self.vwap = update.vwap.or(self.vwap);
+        self.high = update.high.or(self.high);
+        self.bid_size = update.bid_size.or(self.bid_size);
+        self.ask_size = update.ask_size.or(self.ask_size);
+        self.halted = update.halted.or(self.halted);
+        self.borrow = update.borrow.or(self.borrow);
+        self.shortable_shares = update.shortable_shares.or(self.shortable_shares);
+    }
+}
+
+/// A historical OHLCV bar. `volume` is in shares.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bar {
+    pub time: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+/// Streaming ticks for one symbol; dropping the stream unsubscribes.
+pub type TickStream = Pin<Box<dyn Stream<Item = MarketTick> + Send>>;
+
+/// Broad-tape news headlines for every symbol, as they arrive.
+pub type NewsStream = Pin<Box<dyn Stream<Item = NewsHeadline> + Send>>;
+
+/// Results of polling a scanner set:
+/// (symbol_scanners, symbol_data, scanner_ranks, connected_port).
+pub type PollScan = (
+    HashMap<String, Vec<String>>,
+    HashMap<String, ScanResult>,
+    ScannerRanks,
+    Option<u16>,
+);
+
+#[async_trait]
+pub trait MarketDataSource: Send + Sync {
+    /// Short name for logs, e.g. "tws" or "sim".
+    fn name(&self) -> &'static str;
+
+    /// Port of a reachable feed, if any.
+    async fn probe(&self) -> Option<u16>;
+
+    /// One-shot scan with snapshot prices. Returns results and the port used.
+    async fn scan(&self, code: &str, rows: u32, filters: &ScanFilters) -> (Vec<ScanResult>, Option<u16>);
+
+    /// Run every scanner of a set.
+    async fn poll(&self, set: &ScannerSet) -> PollScan;
+
+    /// Fill last/bid/ask/close/volume/change for up to `max_symbols` results.
+    async fn snapshots(&self, results: &mut HashMap<String, ScanResult>, max_symbols: usize);
+
+    /// Stream one symbol's ticks. The stream ends if the feed drops it.
+    async fn subscribe(&self, symbol: &str, currency: &str) -> Result<TickStream>;
+
+    /// Whether streaming subscriptions are currently being served.
+    fn is_streaming(&self) -> bool;
+
+    /// Wait until the streaming feed is up, retrying with `backoff`.
+    async fn reconnect_streaming(&self, backoff: &mut Backoff);
+
+    /// Historical bars for one request (see `tws::bars`).
+    async fn bars(&self, req: &BarRequest) -> Result<Vec<Bar>>;
+
+    /// 5-minute bars for one trading date, including extended hours.
+    async fn intraday_bars(&self, symbol: &str, date: NaiveDate) -> Result<Vec<Bar>> {
+        self.bars(&BarRequest::intraday(symbol, date)).await
+    }
+
+    /// Scanner parameters XML (see `tws::params`).
+    async fn scanner_params(&self) -> Option<String>;
+
+    /// Shares outstanding and market cap (see `tws::fundamentals`).
+    async fn fundamentals(&self, symbol: &str) -> Result<Fundamentals>;
+
+    /// Recent headlines for one symbol, newest first (see `tws::news`).
+    async fn news(&self, symbol: &str) -> Result<Vec<NewsHeadline>>;
+
+    /// Body of the article a headline's provider and article id point to.
+    async fn news_article(&self, provider: &str, article_id: &str) -> Result<String>;
+
+    /// Broad-tape headlines across all symbols.
+    async fn subscribe_news(&self) -> Result<NewsStream>;
+}
@@ -0,0 +1,802 @@
+//! Deterministic simulated TWS feed.
+//!
+//! Generates a universe of symbols from a seed (or loads scripted price
+//! paths) and advances it one step per `SimConfig::step` of wall-clock
+//! time. The same seed or script always produces the same sequence of
+//! scanner hits, quotes and bars, so the full pipeline can run offline.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use super::{Bar, MarketDataSource, MarketTick, NewsStream, PollScan, TickStream};
+use crate::catalyst::Catalyst;
+use crate::enrichment::EnrichmentData;
+use crate::models::{NewsHeadline, ScanResult, ALERT_SCANNERS};
+use crate::quote::HaltState;
+use crate::scanner_set::{ScanFilters, ScannerSet};
+use crate::short::Borrow;
+use crate::tws::bars::{BarRequest, BarSize};
+use crate::tws::connection::Backoff;
+use crate::tws::fundamentals::Fundamentals;
+use crate::units::{Shares, ROUND_LOT};
+
+/// Port reported by the simulator in place of a TWS port.
+pub const SIM_PORT: u16 = 0;
+
+/// Provider code on simulated news headlines.
+const SIM_NEWS_PROVIDER: &str = "SIM";
+
+#[derive(Debug, Clone)]
+pub struct SimConfig {
+    pub seed: u64,
+    /// Size of the generated universe (ignored with a script).
+    pub symbols: usize,
+    /// Wall-clock time per simulated step.
+    pub step: Duration,
+    pub script: Option<SimScript>,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            symbols: 40,
+            step: Duration::from_secs(1),
+            script: None,
+        }
+    }
+}
+
+impl SimConfig {
+    /// One-line summary, e.g. "seed 42, 40 symbols".
+    pub fn describe(&self) -> String {
+        match &self.script {
+            Some(script) => format!("scripted, {} symbols", script.symbols.len()),
+            None => format!("seed {}, {} symbols", self.seed, self.symbols),
+        }
+    }
+}
+
+/// Scripted price paths, loaded from TOML:
+///
+/// ```toml
+/// [[symbols]]
+/// symbol = "ABCD"
+/// prev_close = 2.0
+/// prices = [2.1, 2.4, 2.9, 3.5]
+/// float_shares = 5000000
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct SimScript {
+    #[serde(default)]
+    pub symbols: Vec<ScriptedSymbol>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ScriptedSymbol {
+    pub symbol: String,
+    pub name: Option<String>,
+    pub prev_close: f64,
+    /// Last price at each step; the final price holds afterwards.
+    pub prices: Vec<f64>,
+    /// Cumulative shares traded at each step (default: 50K more per step).
+    #[serde(default)]
+    pub volumes: Vec<i64>,
+    pub float_shares: Option<f64>,
+    pub avg_volume: Option<i64>,
+    pub catalyst: Option<String>,
+}
+
+impl SimScript {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let script: Self =
+            toml::from_str(&text).with_context(|| format!("invalid sim script {}", path.display()))?;
+        if script.symbols.is_empty() {
+            anyhow::bail!("sim script {} has no symbols", path.display());
+        }
+        if let Some(s) = script.symbols.iter().find(|s| s.prices.is_empty()) {
+            anyhow::bail!("sim script symbol {} has no prices", s.symbol);
+        }
+        Ok(script)
+    }
+}
+
+/// SplitMix64: small, seedable and stable across platforms.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1).
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn range(&mut self, lo: f64, hi: f64) -> f64 {
+        lo + (hi - lo) * self.next_f64()
+    }
+
+    /// Standard normal (Box-Muller).
+    fn normal(&mut self) -> f64 {
+        let u1 = self.next_f64().max(f64::MIN_POSITIVE);
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+fn cents(price: f64) -> f64 {
+    (price * 100.0).round() / 100.0
+}
+
+/// Simulated shares outstanding: the float plus a quarter held by insiders.
+fn sim_shares_outstanding(s: &SimSymbol) -> Option<f64> {
+    s.float_shares.map(|f| (f * 1.25).round())
+}
+
+#[derive(Debug, Clone)]
+struct SimSymbol {
+    symbol: String,
+    name: String,
+    con_id: i64,
+    prev_close: f64,
+    last: f64,
+    /// Cumulative shares traded today.
+    volume: i64,
+    float_shares: Option<f64>,
+    avg_volume: Option<i64>,
+    catalyst: Option<String>,
+    /// Mean and standard deviation of the per-step log return.
+    drift: f64,
+    sigma: f64,
+    /// Mean shares traded per step.
+    volume_rate: f64,
+    script: Option<ScriptedSymbol>,
+    /// One bar per completed step: (open, high, low, close, volume).
+    bars: Vec<(f64, f64, f64, f64, i64)>,
+}
+
+impl SimSymbol {
+    fn change_pct(&self) -> f64 {
+        (self.last - self.prev_close) / self.prev_close * 100.0
+    }
+
+    fn relative_volume(&self) -> f64 {
+        self.avg_volume
+            .filter(|a| *a > 0)
+            .map_or(0.0, |a| self.volume as f64 / a as f64)
+    }
+
+    /// Displayed size on each side: a few seconds of trading, in round lots.
+    fn quote_size(&self) -> Shares {
+        Shares(((self.volume_rate / 20.0) as i64 / ROUND_LOT).max(1) * ROUND_LOT)
+    }
+
+    /// Borrow availability and shortable shares, fixed per symbol.
+    fn borrow(&self) -> (Borrow, Shares) {
+        match self.con_id % 4 {
+            0 => (Borrow::Unavailable, Shares(0)),
+            1 => (Borrow::Hard, Shares(40_000)),
+            _ => (Borrow::Easy, Shares(2_000_000)),
+        }
+    }
+
+    fn scripted(s: &ScriptedSymbol, con_id: i64) -> Self {
+        Self {
+            symbol: s.symbol.clone(),
+            name: s.name.clone().unwrap_or_else(|| format!("{} Scripted", s.symbol)),
+            con_id,
+            prev_close: s.prev_close,
+            last: s.prices[0],
+            volume: s.volumes.first().copied().unwrap_or(50_000),
+            float_shares: s.float_shares,
+            avg_volume: s.avg_volume,
+            catalyst: s.catalyst.clone(),
+            drift: 0.0,
+            sigma: 0.0,
+            volume_rate: 50_000.0,
+            script: Some(s.clone()),
+            bars: Vec::new(),
+        }
+    }
+
+    /// Every fifth symbol is a low-float runner gapping up on news.
+    fn generated(rng: &mut Rng, symbol: String, index: usize) -> Self {
+        let runner = index.is_multiple_of(5);
+        let prev_close = cents(rng.range(0.5, 30.0));
+        let avg_volume = rng.range(300_000.0, 5_000_000.0) as i64;
+        let gap = if runner { rng.range(0.05, 0.4) } else { rng.range(-0.03, 0.03) };
+        let volume_rate = avg_volume as f64 / 390.0 * if runner { rng.range(5.0, 15.0) } else { 1.0 };
+        Self {
+            name: format!("{symbol} Simulated Corp"),
+            con_id: 100_000 + index as i64,
+            prev_close,
+            last: cents((prev_close * (1.0 + gap)).max(0.05)),
+            volume: (volume_rate * rng.range(5.0, 20.0)) as i64,
+            float_shares: Some(if runner {
+                rng.range(2e6, 20e6).round()
+            } else {
+                rng.range(20e6, 500e6).round()
+            }),
+            avg_volume: Some(avg_volume),
+            catalyst: runner.then(|| format!("{symbol} announces simulated FDA approval")),
+            drift: if runner { rng.range(0.002, 0.01) } else { rng.range(-0.002, 0.002) },
+            sigma: if runner { 0.02 } else { 0.005 },
+            volume_rate,
+            script: None,
+            bars: Vec::new(),
+            symbol,
+        }
+    }
+
+    /// Move to step `step` (1-based count of completed steps).
+    fn advance(&mut self, rng: &mut Rng, step: usize) {
+        let open = self.last;
+        let before = self.volume;
+        match &self.script {
+            Some(s) => {
+                self.last = s.prices[step.min(s.prices.len() - 1)];
+                self.volume = match s.volumes.get(step) {
+                    Some(v) => *v,
+                    None if s.volumes.is_empty() => self.volume + 50_000,
+                    None => self.volume,
+                };
+            }
+            None => {
+                let ret = self.drift + self.sigma * rng.normal();
+                self.last = cents((self.last * ret.exp()).max(0.05));
+                self.volume += (self.volume_rate * rng.range(0.5, 1.5)) as i64;
+            }
+        }
+        self.bars.push((
+            open,
+            open.max(self.last),
+            open.min(self.last),
+            self.last,
+            self.volume - before,
+        ));
+    }
+
+    fn result(&self, rank: u32) -> ScanResult {
+        ScanResult {
+            rank,
+            symbol: self.symbol.clone(),
+            con_id: self.con_id,
+            exchange: "NASDAQ".to_string(),
+            currency: "USD".to_string(),
+            last: Some(self.last),
+            change: Some(cents(self.last - self.prev_close)),
+            change_pct: Some(self.change_pct()),
+            bid: Some(cents(self.last - 0.01)),
+            ask: Some(cents(self.last + 0.01)),
+            bid_size: Some(self.quote_size()),
+            ask_size: Some(self.quote_size()),
+            halted: Some(HaltState::Trading),
+            volume: Some(Shares(self.volume)),
+            close: Some(self.prev_close),
+            name: Some(self.name.clone()),
+            ..Default::default()
+        }
+    }
+
+    fn tick(&self) -> MarketTick {
+        let (value, volume) = self.bars.iter().fold((0.0, 0i64), |(value, volume), &(_, h, l, c, v)| {
+            (value + (h + l + c) / 3.0 * v as f64, volume + v)
+        });
+        let high = self.bars.iter().fold(self.last, |high, &(_, h, ..)| high.max(h));
+        let (borrow, shortable_shares) = self.borrow();
+        MarketTick {
+            last: Some(self.last),
+            close: Some(self.prev_close),
+            bid: Some(cents(self.last - 0.01)),
+            ask: Some(cents(self.last + 0.01)),
+            volume: Some(Shares(self.volume)),
+            vwap: (volume > 0).then(|| cents(value / volume as f64)),
+            high: Some(high),
+            bid_size: Some(self.quote_size()),
+            ask_size: Some(self.quote_size()),
+            halted: Some(HaltState::Trading),
+            borrow: Some(borrow),
+            shortable_shares: Some(shortable_shares),
+        }
+    }
+}
+
+/// How a scanner code ranks symbols: higher is better, `None` excludes.
+fn scan_metric(code: &str, s: &SimSymbol) -> Option<f64> {
+    let code = code.to_uppercase();
+    let chg = s.change_pct();
+    if code.contains("HALT") {
+        None
+    } else if code.contains("LOSE") {
+        (chg < 0.0).then_some(-chg)
+    } else if ["GAIN", "GAP", "OPEN_PERC", "HIGH_VS"].iter().any(|w| code.contains(w)) {
+        (chg > 0.0).then_some(chg)
+    } else if code.contains("ACTIVE") {
+        Some(s.volume as f64 * s.last)
+    } else if code.contains("VOLUME_RATE") || code.contains("HOT_BY_PRICE") {
+        Some(s.relative_volume())
+    } else if code.contains("VOLUME") || code.contains("TRADE") {
+        Some(s.volume as f64)
+    } else {
+        Some(s.relative_volume())
+    }
+}
+
+struct SimState {
+    /// Completed steps.
+    step: u64,
+    /// Steps added by `advance` on top of elapsed time.
+    offset: u64,
+    rng: Rng,
+    symbols: Vec<SimSymbol>,
+}
+
+struct SimInner {
+    step: Duration,
+    started: Instant,
+    state: Mutex<SimState>,
+}
+
+impl SimInner {
+    /// Lock the state, first advancing it to the current step.
+    fn sync(&self) -> MutexGuard<'_, SimState> {
+        let mut state = self.state.lock().unwrap();
+        let elapsed = (self.started.elapsed().as_millis() / self.step.as_millis().max(1)) as u64;
+        let target = elapsed + state.offset;
+        while state.step < target {
+            state.step += 1;
+            let step = state.step as usize;
+            let SimState { rng, symbols, .. } = &mut *state;
+            for s in symbols.iter_mut() {
+                s.advance(rng, step);
+            }
+        }
+        state
+    }
+}
+
+/// Simulated `MarketDataSource`. Cheap to clone; clones share one market.
+#[derive(Clone)]
+pub struct Simulator {
+    inner: Arc<SimInner>,
+}
+
+impl Simulator {
+    pub fn new(config: SimConfig) -> Self {
+        let mut rng = Rng(config.seed);
+        let symbols = match &config.script {
+            Some(script) => script
+                .symbols
+                .iter()
+                .enumerate()
+                .map(|(i, s)| SimSymbol::scripted(s, 100_000 + i as i64))
+                .collect(),
+            None => {
+                let mut seen = HashSet::new();
+                let mut symbols = Vec::new();
+                while symbols.len() < config.symbols {
+                    let len = if rng.next_f64() < 0.5 { 3 } else { 4 };
+                    let ticker: String =
+                        (0..len).map(|_| (b'A' + (rng.next_u64() % 26) as u8) as char).collect();
+                    if seen.insert(ticker.clone()) {
+                        let index = symbols.len();
+                        symbols.push(SimSymbol::generated(&mut rng, ticker, index));
+                    }
+                }
+                symbols
+            }
+        };
+        Self {
+            inner: Arc::new(SimInner {
+                step: config.step,
+                started: Instant::now(),
+                state: Mutex::new(SimState { step: 0, offset: 0, rng, symbols }),
+            }),
+        }
+    }
+
+    /// Jump `steps` ahead of wall-clock time.
+    pub fn advance(&self, steps: u64) {
+        self.inner.state.lock().unwrap().offset += steps;
+    }
+
+    /// Completed steps so far.
+    pub fn step(&self) -> u64 {
+        self.inner.sync().step
+    }
+
+    pub fn symbols(&self) -> Vec<String> {
+        self.inner.sync().symbols.iter().map(|s| s.symbol.clone()).collect()
+    }
+
+    /// Current quote for a symbol, as a snapshot-filled scan result.
+    pub fn quote(&self, symbol: &str) -> Option<ScanResult> {
+        let state = self.inner.sync();
+        state.symbols.iter().find(|s| s.symbol == symbol).map(|s| s.result(0))
+    }
+
+    /// Top `rows` symbols for a scanner code, after the price/volume filters.
+    /// Filter tags are not simulated.
+    pub fn scan_results(&self, code: &str, rows: u32, filters: &ScanFilters) -> Vec<ScanResult> {
+        let state = self.inner.sync();
+        let mut hits: Vec<(f64, &SimSymbol)> = state
+            .symbols
+            .iter()
+            .filter(|s| filters.min_price.is_none_or(|p| s.last >= p))
+            .filter(|s| filters.max_price.is_none_or(|p| s.last <= p))
+            .filter(|s| filters.min_volume.is_none_or(|v| s.volume >= v as i64))
+            .filter_map(|s| scan_metric(code, s).map(|m| (m, s)))
+            .collect();
+        hits.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.symbol.cmp(&b.1.symbol)));
+        hits.iter()
+            .take(rows as usize)
+            .enumerate()
+            .map(|(i, (_, s))| s.result(i as u32 + 1))
+            .collect()
+    }
+
+    /// Fundamentals and news standing in for Yahoo enrichment.
+    pub fn enrichment(&self, symbol: &str) -> Option<EnrichmentData> {
+        let state = self.inner.sync();
+        let s = state.symbols.iter().find(|s| s.symbol == symbol)?;
+        let published = chrono::Utc::now().timestamp() - 600;
+        let class = s.catalyst.as_deref().and_then(|c| Catalyst::from_headline(c, Some(published)));
+        let mut data = EnrichmentData {
+            name: Some(s.name.clone()),
+            sector: Some("Healthcare".to_string()),
+            industry: Some("Biotechnology".to_string()),
+            country: Some("United States".to_string()),
+            float_shares: s.float_shares,
+            shares_outstanding: sim_shares_outstanding(s),
+            short_pct: Some(0.05),
+            avg_volume: s.avg_volume.map(Shares),
+            avg_volume_10d: s.avg_volume.map(Shares),
+            catalyst: s.catalyst.clone(),
+            catalyst_time: s.catalyst.as_ref().map(|_| published),
+            catalyst_kind: class.as_ref().map(|c| c.kind),
+            catalyst_sentiment: class.as_ref().map(|c| c.sentiment),
+            news_headlines: s
+                .catalyst
+                .iter()
+                .map(|c| NewsHeadline::new(c.clone(), Some(published)))
+                .collect(),
+            ..Default::default()
+        };
+        data.mark_source("sim");
+        Some(data)
+    }
+
+    /// The catalyst headline, if the symbol has one, as an IB-style
+    /// headline with a fetchable article.
+    fn news_headlines(&self, symbol: &str) -> Result<Vec<NewsHeadline>> {
+        let state = self.inner.sync();
+        let s = state
+            .symbols
+            .iter()
+            .find(|s| s.symbol == symbol)
+            .with_context(|| format!("unknown simulated symbol {symbol}"))?;
+        let published = chrono::Utc::now().timestamp() - 600;
+        Ok(s.catalyst
+            .iter()
+            .map(|c| NewsHeadline {
+                source: Some(SIM_NEWS_PROVIDER.to_string()),
+                article_id: Some(format!("{SIM_NEWS_PROVIDER}${symbol}")),
+                ..NewsHeadline::new(c.clone(), Some(published))
+            })
+            .collect())
+    }
+
+    /// Scanner parameters XML advertising the alert scanners and basic filters.
+    pub fn scanner_params_xml() -> String {
+        let scan_types: String = ALERT_SCANNERS
+            .iter()
+            .map(|(code, _)| code)
+            .chain(&["TOP_PERC_LOSE"])
+            .map(|code| {
+                format!(
+                    "<ScanType><displayName>{code} (sim)</displayName><scanCode>{code}</scanCode>\
+                     <instruments>STK</instruments><vendor></vendor></ScanType>"
+                )
+            })
+            .collect();
+        let range = |id: &str, category: &str, tag: &str| {
+            format!(
+                "<RangeFilter><id>{id}</id><category>{category}</category>\
+                 <AbstractField type=\"scanner.filter.DoubleField\"><code>{tag}Above</code></AbstractField>\
+                 <AbstractField type=\"scanner.filter.DoubleField\"><code>{tag}Below</code></AbstractField>\
+                 </RangeFilter>"
+            )
+        };
+        format!(
+            "<?xml version=\"1.0\"?><ScanParameterResponse>\
+             <InstrumentList><Instrument><name>US Stocks</name><type>STK</type>\
+             <filters>PRICE,VOLUME,CHANGEPERC</filters></Instrument></InstrumentList>\
+             <LocationTree><Location><displayName>US Stocks</displayName><locationCode>STK.US</locationCode>\
+             <instruments>STK</instruments><LocationTree><Location><displayName>Listed/NASDAQ</displayName>\
+             <locationCode>STK.US.MAJOR</locationCode><instruments>STK</instruments></Location>\
+             </LocationTree></Location></LocationTree>\
+             <ScanTypeList>{scan_types}</ScanTypeList>\
+             <FilterList>{}{}{}</FilterList></ScanParameterResponse>",
+            range("PRICE", "Price", "price"),
+            range("VOLUME", "Volume", "volume"),
+            range("CHANGEPERC", "Price", "changePerc"),
+        )
+    }
+
+    fn tick(&self, symbol: &str) -> Option<MarketTick> {
+        let state = self.inner.sync();
+        state.symbols.iter().find(|s| s.symbol == symbol).map(|s| s.tick())
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for Simulator {
+    fn name(&self) -> &'static str {
+        "sim"
+    }
+
+    async fn probe(&self) -> Option<u16> {
+        Some(SIM_PORT)
+    }
+
+    async fn scan(&self, code: &str, rows: u32, filters: &ScanFilters) -> (Vec<ScanResult>, Option<u16>) {
+        (self.scan_results(code, rows, filters), Some(SIM_PORT))
+    }
+
+    async fn poll(&self, set: &ScannerSet) -> PollScan {
+        let mut symbol_scanners: HashMap<String, Vec<String>> = HashMap::new();
+        let mut symbol_data: HashMap<String, ScanResult> = HashMap::new();
+        let mut scanner_ranks = HashMap::new();
+        for code in &set.codes {
+            for r in self.scan_results(code, set.rows, &set.scan) {
+                symbol_scanners.entry(r.symbol.clone()).or_default().push(code.clone());
+                scanner_ranks.insert((r.symbol.clone(), code.clone()), r.rank);
+                symbol_data.entry(r.symbol.clone()).or_insert(r);
+            }
+        }
+        (symbol_scanners, symbol_data, scanner_ranks, Some(SIM_PORT))
+    }
+
+    async fn snapshots(&self, results: &mut HashMap<String, ScanResult>, max_symbols: usize) {
+        for (symbol, r) in results.iter_mut().take(max_symbols) {
+            if let Some(q) = self.quote(symbol) {
+                r.last = q.last;
+                r.bid = q.bid;
+                r.ask = q.ask;
+                r.bid_size = q.bid_size;
+                r.ask_size = q.ask_size;
+                r.halted = q.halted;
+                r.close = q.close;
+                r.volume = q.volume;
+                r.change = q.change;
+                r.change_pct = q.change_pct;
+            }
+        }
+    }
+
+    async fn subscribe(&self, symbol: &str, _currency: &str) -> Result<TickStream> {
+        if self.tick(symbol).is_none() {
+            anyhow::bail!("unknown simulated symbol {symbol}");
+        }
+        let mut interval = tokio::time::interval(self.inner.step.max(Duration::from_millis(100)));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        let state = (self.clone(), symbol.to_string(), interval);
+        Ok(Box::pin(futures::stream::unfold(state, |(sim, symbol, mut interval)| async move {
+            interval.tick().await;
+            let tick = sim.tick(&symbol)?;
+            Some((tick, (sim, symbol, interval)))
+        })))
+    }
+
+    fn is_streaming(&self) -> bool {
+        true
+    }
+
+    async fn reconnect_streaming(&self, _backoff: &mut Backoff) {}
+
+    async fn bars(&self, req: &BarRequest) -> Result<Vec<Bar>> {
+        if req.bar_size != BarSize::Min5 {
+            bail!("the simulator only has 5-minute bars");
+        }
+        if req.end.is_some_and(|end| crate::session::trading_date(end) != crate::session::today()) {
+            bail!("the simulator only has bars for today");
+        }
+        let state = self.inner.sync();
+        let s = state
+            .symbols
+            .iter()
+            .find(|s| s.symbol == req.symbol)
+            .with_context(|| format!("unknown simulated symbol {}", req.symbol))?;
+        // Bars start at the 04:00 ET premarket open, one per step
+        let open = crate::session::day_start_utc(crate::session::today()) + chrono::Duration::hours(4);
+        Ok(s.bars
+            .iter()
+            .enumerate()
+            .map(|(i, &(o, h, l, c, v))| Bar {
+                time: open + chrono::Duration::minutes(5 * i as i64),
+                open: o,
+                high: h,
+                low: l,
+                close: c,
+                volume: v as f64,
+            })
+            .collect())
+    }
+
+    async fn scanner_params(&self) -> Option<String> {
+        Some(Self::scanner_params_xml())
+    }
+
+    async fn fundamentals(&self, symbol: &str) -> Result<Fundamentals> {
+        let state = self.inner.sync();
+        let s = state
+            .symbols
+            .iter()
+            .find(|s| s.symbol == symbol)
+            .with_context(|| format!("unknown simulated symbol {symbol}"))?;
+        let shares_outstanding = sim_shares_outstanding(s);
+        Ok(Fundamentals {
+            shares_outstanding,
+            market_cap: shares_outstanding.map(|n| n * s.prev_close),
+        })
+    }
+
+    async fn news(&self, symbol: &str) -> Result<Vec<NewsHeadline>> {
+        self.news_headlines(symbol)
+    }
+
+    async fn news_article(&self, provider: &str, article_id: &str) -> Result<String> {
+        let symbol = article_id
+            .strip_prefix(SIM_NEWS_PROVIDER)
+            .and_then(|id| id.strip_prefix('$'))
+            .filter(|_| provider == SIM_NEWS_PROVIDER)
+            .with_context(|| format!("unknown simulated article {provider}/{article_id}"))?;
+        let headline = self
+            .news_headlines(symbol)?
+            .into_iter()
+            .next()
+            .with_context(|| format!("no simulated news for {symbol}"))?;
+        Ok(format!("{}\n\nSimulated article body for {symbol}.", headline.title))
+    }
+
+    /// The simulator has no broad tape; the stream never yields.
+    async fn subscribe_news(&self) -> Result<NewsStream> {
+        Ok(Box::pin(futures::stream::pending()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    /// A config whose wall clock never completes a step on its own.
+    fn frozen(seed: u64) -> SimConfig {
+        SimConfig {
+            seed,
+            step: Duration::from_secs(3600),
+            ..SimConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_market() {
+        let a = Simulator::new(frozen(7));
+        let b = Simulator::new(frozen(7));
+        a.advance(20);
+        b.advance(20);
+        assert_eq!(a.symbols(), b.symbols());
+        assert_eq!(a.symbols().len(), 40);
+        let sym = &a.symbols()[3];
+        assert_eq!(a.quote(sym).unwrap().last, b.quote(sym).unwrap().last);
+
+        let c = Simulator::new(frozen(8));
+        assert_ne!(a.symbols(), c.symbols());
+    }
+
+    #[test]
+    fn test_scan_ranks_and_filters() {
+        let sim = Simulator::new(frozen(1));
+        sim.advance(10);
+        let filters = ScanFilters {
+            min_price: Some(1.0),
+            max_price: Some(20.0),
+            ..ScanFilters::default()
+        };
+        let gainers = sim.scan_results("TOP_PERC_GAIN", 5, &filters);
+        assert!(!gainers.is_empty() && gainers.len() <= 5);
+        assert_eq!(gainers[0].rank, 1);
+        for w in gainers.windows(2) {
+            assert!(w[0].change_pct >= w[1].change_pct);
+        }
+        for r in &gainers {
+            let last = r.last.unwrap();
+            assert!((1.0..=20.0).contains(&last));
+            assert!(r.change_pct.unwrap() > 0.0);
+        }
+        assert!(sim.scan_results("HALTED", 5, &filters).is_empty());
+    }
+
+    #[test]
+    fn test_scripted_path() {
+        let script: SimScript = toml::from_str(
+            r#"
+            [[symbols]]
+            symbol = "ABCD"
+            prev_close = 2.0
+            prices = [2.2, 2.6, 3.0]
+            volumes = [100000, 400000, 900000]
+            float_shares = 4000000
+            catalyst = "ABCD wins contract"
+            "#,
+        )
+        .unwrap();
+        let sim = Simulator::new(SimConfig { script: Some(script), ..frozen(0) });
+        assert_eq!(sim.quote("ABCD").unwrap().last, Some(2.2));
+        sim.advance(1);
+        assert_eq!(sim.quote("ABCD").unwrap().last, Some(2.6));
+        sim.advance(5);
+        let q = sim.quote("ABCD").unwrap();
+        assert_eq!(q.last, Some(3.0));
+        assert_eq!(q.volume, Some(Shares(900_000)));
+        assert_eq!(sim.enrichment("ABCD").unwrap().float_shares, Some(4e6));
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let bars = rt.block_on(sim.intraday_bars("ABCD", crate::session::today())).unwrap();
+        assert_eq!(bars.len(), 6);
+        assert_eq!((bars[0].open, bars[0].close, bars[0].volume), (2.2, 2.6, 300_000.0));
+
+        let news = rt.block_on(sim.news("ABCD")).unwrap();
+        assert_eq!(news[0].title, "ABCD wins contract");
+        let (provider, id) = news[0].article().unwrap();
+        let body = rt.block_on(sim.news_article(provider, id)).unwrap();
+        assert!(body.starts_with("ABCD wins contract"));
+        assert!(rt.block_on(sim.news_article("BRFG", id)).is_err());
+    }
+
+    #[test]
+    fn test_poll_and_stream() {
+        let sim = Simulator::new(frozen(3));
+        sim.advance(5);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let set = ScannerSet::default();
+        let (scanners, data, ranks, port) = rt.block_on(sim.poll(&set));
+        assert_eq!(port, Some(SIM_PORT));
+        assert!(!data.is_empty());
+        for (sym, codes) in &scanners {
+            for code in codes {
+                assert!(ranks.contains_key(&(sym.clone(), code.clone())));
+            }
+        }
+
+        let sym = data.keys().next().unwrap().clone();
+        let tick = rt.block_on(async {
+            let mut stream = sim.subscribe(&sym, "USD").await.unwrap();
+            stream.next().await.unwrap()
+        });
+        assert_eq!(tick.last, data[&sym].last);
+        assert!(rt.block_on(sim.subscribe("NOPE", "USD")).is_err());
+    }
+
+    #[test]
+    fn test_scanner_params_validate_default_set() {
+        let params = crate::tws::params::ScannerParams::parse(&Simulator::scanner_params_xml());
+        params.validate_set(&ScannerSet::default()).unwrap();
+        assert_eq!(params.filters.len(), 3);
+    }
+}
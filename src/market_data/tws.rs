@@ -0,0 +1,96 @@
+//! Live market data from TWS / IB Gateway via ibapi.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::{Bar, MarketDataSource, NewsStream, PollScan, TickStream};
+use crate::models::{NewsHeadline, ScanResult};
+use crate::scanner_set::{ScanFilters, ScannerSet};
+use crate::tws::bars::BarRequest;
+use crate::tws::connection::{Backoff, ClientRole, ConnectionManager};
+use crate::tws::fundamentals::{self, Fundamentals};
+use crate::tws::news;
+use crate::tws;
+
+/// `MarketDataSource` backed by the shared TWS connections.
+pub struct TwsSource {
+    conn: Arc<ConnectionManager>,
+    /// Subscribed news provider codes, fetched on first use.
+    news_providers: tokio::sync::OnceCell<Vec<String>>,
+}
+
+impl TwsSource {
+    pub fn new(conn: Arc<ConnectionManager>) -> Self {
+        Self { conn, news_providers: tokio::sync::OnceCell::new() }
+    }
+
+    async fn news_providers(&self) -> Result<&[String]> {
+        let providers = self
+            .news_providers
+            .get_or_try_init(|| news::fetch_news_providers(&self.conn))
+            .await?;
+        Ok(providers)
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for TwsSource {
+    fn name(&self) -> &'static str {
+        "tws"
+    }
+
+    async fn probe(&self) -> Option<u16> {
+        tws::probe_port(&self.conn).await
+    }
+
+    async fn scan(&self, code: &str, rows: u32, filters: &ScanFilters) -> (Vec<ScanResult>, Option<u16>) {
+        tws::run_scan(&self.conn, code, rows, filters).await
+    }
+
+    async fn poll(&self, set: &ScannerSet) -> PollScan {
+        tws::run_poll_scan(&self.conn, set).await
+    }
+
+    async fn snapshots(&self, results: &mut HashMap<String, ScanResult>, max_symbols: usize) {
+        tws::fetch_snapshots(results, &self.conn, max_symbols, ClientRole::PollSnapshots).await
+    }
+
+    async fn subscribe(&self, symbol: &str, currency: &str) -> Result<TickStream> {
+        tws::subscribe_ticks(&self.conn, symbol, currency).await
+    }
+
+    fn is_streaming(&self) -> bool {
+        self.conn.is_connected(ClientRole::Streaming)
+    }
+
+    async fn reconnect_streaming(&self, backoff: &mut Backoff) {
+        self.conn.reconnect(ClientRole::Streaming, backoff).await;
+    }
+
+    async fn bars(&self, req: &BarRequest) -> Result<Vec<Bar>> {
+        tws::bars::fetch_bars(&self.conn, req, false).await
+    }
+
+    async fn scanner_params(&self) -> Option<String> {
+        tws::fetch_scanner_params(&self.conn).await
+    }
+
+    async fn fundamentals(&self, symbol: &str) -> Result<Fundamentals> {
+        fundamentals::fetch_fundamentals(&self.conn, symbol).await
+    }
+
+    async fn news(&self, symbol: &str) -> Result<Vec<NewsHeadline>> {
+        news::fetch_headlines(&self.conn, symbol, self.news_providers().await?).await
+    }
+
+    async fn news_article(&self, provider: &str, article_id: &str) -> Result<String> {
+        news::fetch_article(&self.conn, provider, article_id).await
+    }
+
+    async fn subscribe_news(&self) -> Result<NewsStream> {
+        news::subscribe_broad_tape(&self.conn, self.news_providers().await?).await
+    }
+}
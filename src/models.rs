@@ -1,10 +1,61 @@
 use serde::{Deserialize, Serialize};
 
+use crate::catalyst::{CatalystKind, Sentiment};
+use crate::criteria::{MomentumCriteria, PillarReport};
+use crate::enrichment::Provenance;
+use crate::levels::IntradayLevels;
+use crate::quote::{HaltState, Quote};
+use crate::scanner_set::ScannerSet;
+use crate::short::ShortInfo;
+use crate::units::Shares;
+
 /// A news headline with optional publish timestamp.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct NewsHeadline {
     pub title: String,
     pub published: Option<i64>,
+    /// Where the headline came from: an IB provider code ("BRFG", "DJ-N")
+    /// or an enrichment provider name ("yahoo").
+    #[serde(default)]
+    pub source: Option<String>,
+    /// IB article id, for fetching the body (`MarketDataSource::news_article`).
+    #[serde(default)]
+    pub article_id: Option<String>,
+}
+
+impl NewsHeadline {
+    pub fn new(title: impl Into<String>, published: Option<i64>) -> Self {
+        Self { title: title.into(), published, ..Default::default() }
+    }
+
+    /// Provider code and article id, when the body can be fetched.
+    pub fn article(&self) -> Option<(&str, &str)> {
+        Some((self.source.as_deref()?, self.article_id.as_deref()?))
+    }
+}
+
+/// An SEC filing from EDGAR (see `enrichment::EdgarProvider`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Filing {
+    /// Form type, e.g. "8-K", "S-3", "424B5".
+    pub form: String,
+    /// Acceptance time (Unix epoch), else the filing date.
+    pub filed: Option<i64>,
+    /// What was filed: the form's description, or an 8-K's items.
+    pub title: String,
+    /// 8-K item numbers, e.g. ["1.01", "9.01"].
+    #[serde(default)]
+    pub items: Vec<String>,
+    pub accession: String,
+    /// Primary document on sec.gov.
+    pub url: Option<String>,
+}
+
+impl Filing {
+    /// One-line form for catalysts and listings, "8-K: Material agreement".
+    pub fn headline(&self) -> String {
+        format!("{}: {}", self.form, self.title)
+    }
 }
 
 /// Result from a TWS scanner + enrichment.
@@ -20,7 +71,10 @@ pub struct ScanResult {
     pub change_pct: Option<f64>,
     pub bid: Option<f64>,
     pub ask: Option<f64>,
-    pub volume: Option<i64>,
+    pub bid_size: Option<Shares>,
+    pub ask_size: Option<Shares>,
+    pub halted: Option<HaltState>,
+    pub volume: Option<Shares>,
     pub close: Option<f64>,
     // Enrichment fields (from Yahoo Finance)
     pub name: Option<String>,
@@ -29,22 +83,31 @@ pub struct ScanResult {
     pub country: Option<String>,
     pub float_shares: Option<f64>,
     pub short_pct: Option<f64>,
-    pub avg_volume: Option<i64>,
-    pub avg_volume_10d: Option<i64>,
+    pub avg_volume: Option<Shares>,
+    pub avg_volume_10d: Option<Shares>,
     pub catalyst: Option<String>,
+    /// Whether `catalyst` reads bullish or bearish.
+    #[serde(default)]
+    pub catalyst_sentiment: Option<Sentiment>,
     pub rvol: Option<f64>,
     pub news_headlines: Vec<NewsHeadline>,
     pub enriched: bool,
+    /// Pillars passed/failed under the active criteria profile.
+    #[serde(default)]
+    pub pillars: PillarReport,
+    /// Composite momentum score (0-100), see `criteria::momentum_score`.
+    #[serde(default)]
+    pub score: f64,
 }
 
 /// Row in the alert table (accumulated during polling).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AlertRow {
     pub symbol: String,
     pub alert_time: String,
     pub last: Option<f64>,
     pub change_pct: Option<f64>,
-    pub volume: Option<i64>,
+    pub volume: Option<Shares>,
     pub rvol: Option<f64>,
     pub float_shares: Option<f64>,
     pub short_pct: Option<f64>,
@@ -58,15 +121,55 @@ pub struct AlertRow {
     pub scanners: Vec<String>,
     pub news_headlines: Vec<NewsHeadline>,
     pub enriched: bool,
-    pub avg_volume: Option<i64>,
-    pub avg_volume_10d: Option<i64>,
+    pub avg_volume: Option<Shares>,
+    pub avg_volume_10d: Option<Shares>,
+    /// Pillars passed/failed under the active criteria profile.
+    #[serde(default)]
+    pub pillars: PillarReport,
+    /// Composite momentum score (0-100), see `criteria::momentum_score`.
+    #[serde(default)]
+    pub score: f64,
+    /// Cumulative volume vs the average up to the same time of day (see
+    /// `rvol`); `rvol` uses it when known.
+    pub rvol_time_of_day: Option<f64>,
+    /// Cumulative volume vs the full-day average volume.
+    pub rvol_full_day: Option<f64>,
+    /// VWAP, high of day, premarket range and gap, updated from ticks.
+    #[serde(default)]
+    pub levels: IntradayLevels,
+    /// Spread, bid/ask size and halted state, updated from ticks.
+    #[serde(default)]
+    pub quote: Quote,
+    /// Borrow availability and SSR, updated from ticks.
+    #[serde(default)]
+    pub short: ShortInfo,
+    /// Shares outstanding; bounds `float_shares` (see
+    /// `EnrichmentData::reconcile_float`).
+    #[serde(default)]
+    pub shares_outstanding: Option<f64>,
+    /// Which provider each enrichment field came from.
+    #[serde(default)]
+    pub provenance: Provenance,
+    /// Recent SEC filings, newest first.
+    #[serde(default)]
+    pub filings: Vec<Filing>,
+    /// What `catalyst` is about, see `catalyst::Catalyst`.
+    #[serde(default)]
+    pub catalyst_kind: Option<CatalystKind>,
+    /// Whether `catalyst` reads bullish or bearish.
+    #[serde(default)]
+    pub catalyst_sentiment: Option<Sentiment>,
 }
 
 /// A TWS scan row from Supabase.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TwsScan {
     pub id: Option<i64>,
     pub symbol: String,
+    /// US/Eastern session date; one row per (symbol, trading_date).
+    /// None only for rows written before the column existed.
+    #[serde(default)]
+    pub trading_date: Option<chrono::NaiveDate>,
     pub first_seen: String,
     pub last_seen: String,
     pub scanners: String,
@@ -77,17 +180,56 @@ pub struct TwsScan {
     pub float_shares: Option<f64>,
     pub catalyst: Option<String>,
     pub catalyst_time: Option<i64>,
+    #[serde(default)]
+    pub catalyst_kind: Option<CatalystKind>,
+    #[serde(default)]
+    pub catalyst_sentiment: Option<Sentiment>,
     pub name: Option<String>,
     pub sector: Option<String>,
     // Enrichment cache fields
     pub enriched_at: Option<String>,
     pub industry: Option<String>,
     pub short_pct: Option<f64>,
-    pub avg_volume: Option<i64>,
-    pub avg_volume_10d: Option<i64>,
+    pub avg_volume: Option<Shares>,
+    pub avg_volume_10d: Option<Shares>,
     pub news_headlines: Option<String>, // JSON array string
 }
 
+/// One scanner hit for a symbol in one poll cycle.
+///
+/// Sightings are append-only, so a day's rows for a symbol trace its
+/// intraday path (the `tws_scans` row only keeps the latest values).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sighting {
+    pub symbol: String,
+    /// RFC 3339 timestamp of the poll cycle.
+    pub seen_at: String,
+    pub scanner: String,
+    /// 1-based rank within `scanner`.
+    pub rank: u32,
+    pub price: Option<f64>,
+    pub change_pct: Option<f64>,
+    pub volume: Option<Shares>,
+    pub rvol: Option<f64>,
+}
+
+/// A halt or resume of a streamed symbol.
+///
+/// Append-only like sightings: a halt and its resume are separate rows,
+/// the resume carrying how long the symbol was halted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HaltEvent {
+    pub symbol: String,
+    /// Timestamp in `SIGHTING_TS_FORMAT`.
+    pub at: String,
+    /// The halt kind, or `Trading` for a resume.
+    pub state: HaltState,
+    /// Last trade before the halt.
+    pub halt_price: Option<f64>,
+    /// Seconds halted; resumes only.
+    pub duration_secs: Option<i64>,
+}
+
 /// Application settings.
 #[derive(Debug, Clone)]
 pub struct Settings {
@@ -100,6 +242,10 @@ pub struct Settings {
     /// TWS paper accounts: 90 (100-line limit minus headroom).
     /// Live accounts: raise based on your market data subscription.
     pub max_streaming: usize,
+    /// Active momentum criteria profile.
+    pub criteria: MomentumCriteria,
+    /// Scanners polled in alert mode.
+    pub scanner_set: ScannerSet,
 }
 
 /// Default max streaming subscriptions (safe for TWS paper accounts).
@@ -114,6 +260,8 @@ impl Default for Settings {
             min_price: Some(1.0),
             max_price: None,
             max_streaming: DEFAULT_MAX_STREAMING,
+            criteria: MomentumCriteria::default(),
+            scanner_set: ScannerSet::default(),
         }
     }
 }
@@ -190,6 +338,7 @@ mod tests {
         let s = TwsScan {
             id: None,
             symbol: "TEST".to_string(),
+            trading_date: None,
             first_seen: "2024-01-01T00:00:00+00:00".to_string(),
             last_seen: "2024-01-01T00:00:00+00:00".to_string(),
             scanners: "".to_string(),
@@ -204,13 +353,15 @@ mod tests {
             enriched_at: Some("2024-01-01T00:00:00+00:00".to_string()),
             industry: Some("Biotech".to_string()),
             short_pct: Some(0.15),
-            avg_volume: Some(1_000_000),
-            avg_volume_10d: Some(500_000),
+            avg_volume: Some(Shares(1_000_000)),
+            avg_volume_10d: Some(Shares(500_000)),
             news_headlines: Some(r#"[{"title":"Headline 1","published":null}]"#.to_string()),
             catalyst_time: None,
+            catalyst_kind: None,
+            catalyst_sentiment: None,
         };
         assert_eq!(s.industry, Some("Biotech".to_string()));
-        assert_eq!(s.avg_volume, Some(1_000_000));
+        assert_eq!(s.avg_volume, Some(Shares(1_000_000)));
     }
 
     #[test]
@@ -0,0 +1,234 @@
+//! Level-1 quote quality for alert rows: spread, bid/ask size and the
+//! TWS halted state.
+//!
+//! Momentum names are often thin or halted, so the quote is kept next to
+//! the last price and can be filtered on (see `criteria`). Changes between
+//! trading and halted are reported as `HaltChange`s.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::units::Shares;
+
+/// Trading state from the TWS `Halted` tick (49).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HaltState {
+    Trading,
+    /// Regulatory or news halt.
+    Halted,
+    /// Volatility (LULD) pause.
+    Volatility,
+}
+
+impl HaltState {
+    /// From the tick value: 0 trading, 1 halted, 2 volatility pause.
+    /// TWS sends -1 when the state is unavailable.
+    pub fn from_tick(value: f64) -> Option<Self> {
+        match value as i64 {
+            0 => Some(Self::Trading),
+            1 => Some(Self::Halted),
+            2 => Some(Self::Volatility),
+            _ => None,
+        }
+    }
+
+    pub fn is_halted(self) -> bool {
+        self != Self::Trading
+    }
+
+    /// Stored name, as serialized.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Trading => "trading",
+            Self::Halted => "halted",
+            Self::Volatility => "volatility",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        [Self::Trading, Self::Halted, Self::Volatility]
+            .into_iter()
+            .find(|h| h.as_str() == s)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Trading => "trading",
+            Self::Halted => "halted",
+            Self::Volatility => "LULD pause",
+        }
+    }
+}
+
+/// Ask minus bid and the spread in percent of the midpoint. `None` unless
+/// both sides are quoted and not crossed.
+pub fn spread(bid: Option<f64>, ask: Option<f64>) -> Option<(f64, f64)> {
+    let (bid, ask) = (bid?, ask?);
+    if bid <= 0.0 || ask < bid {
+        return None;
+    }
+    let mid = (bid + ask) / 2.0;
+    Some((ask - bid, (ask - bid) / mid * 100.0))
+}
+
+/// A change between trading and halted, from `Quote::on_halt`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HaltChange {
+    Halted {
+        state: HaltState,
+        at: DateTime<Utc>,
+        /// Last trade before the halt.
+        price: Option<f64>,
+    },
+    Resumed {
+        at: DateTime<Utc>,
+        halt_price: Option<f64>,
+        duration: chrono::Duration,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Quote {
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    pub bid_size: Option<Shares>,
+    pub ask_size: Option<Shares>,
+    /// `None` until TWS sends the halted tick.
+    pub halted: Option<HaltState>,
+    /// Ask minus bid.
+    pub spread: Option<f64>,
+    /// Spread in percent of the midpoint.
+    pub spread_pct: Option<f64>,
+    /// When the current halt was first seen; `None` while trading.
+    pub halted_at: Option<DateTime<Utc>>,
+    /// Last trade before the current halt.
+    pub halt_price: Option<f64>,
+}
+
+impl Quote {
+    /// The thinner side of the book.
+    pub fn size(&self) -> Option<Shares> {
+        match (self.bid_size, self.ask_size) {
+            (Some(b), Some(a)) => Some(b.min(a)),
+            (b, a) => b.or(a),
+        }
+    }
+
+    /// Fold in quote fields from a tick or snapshot; missing ones keep
+    /// their last value.
+    pub fn on_quote(
+        &mut self,
+        bid: Option<f64>,
+        ask: Option<f64>,
+        bid_size: Option<Shares>,
+        ask_size: Option<Shares>,
+    ) {
+        self.bid = bid.or(self.bid);
+        self.ask = ask.or(self.ask);
+        self.bid_size = bid_size.or(self.bid_size);
+        self.ask_size = ask_size.or(self.ask_size);
+    }
+
+    pub fn is_halted(&self) -> Option<bool> {
+        self.halted.map(HaltState::is_halted)
+    }
+
+    /// Apply a halted tick seen at `at`, with `last` the last trade so far.
+    /// Returns the change if the symbol was halted or resumed; a switch
+    /// between halt kinds continues the same halt. A symbol first seen
+    /// halted counts as halted from `at`.
+    pub fn on_halt(
+        &mut self,
+        state: HaltState,
+        at: DateTime<Utc>,
+        last: Option<f64>,
+    ) -> Option<HaltChange> {
+        let was_halted = self.is_halted().unwrap_or(false);
+        self.halted = Some(state);
+        match (was_halted, state.is_halted()) {
+            (false, true) => {
+                self.halted_at = Some(at);
+                self.halt_price = last;
+                Some(HaltChange::Halted { state, at, price: last })
+            }
+            (true, false) => {
+                let halted_at = self.halted_at.take().unwrap_or(at);
+                Some(HaltChange::Resumed {
+                    at,
+                    halt_price: self.halt_price.take(),
+                    duration: at - halted_at,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Recompute the spread from the current bid and ask.
+    pub fn refresh(&mut self) {
+        let s = spread(self.bid, self.ask);
+        self.spread = s.map(|(abs, _)| abs);
+        self.spread_pct = s.map(|(_, pct)| pct);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spread_and_size() {
+        let mut q = Quote {
+            bid: Some(1.98),
+            ask: Some(2.02),
+            bid_size: Some(Shares(1200)),
+            ..Default::default()
+        };
+        q.refresh();
+        assert!((q.spread.unwrap() - 0.04).abs() < 1e-9);
+        assert!((q.spread_pct.unwrap() - 2.0).abs() < 1e-9);
+        assert_eq!(q.size(), Some(Shares(1200)));
+        q.ask_size = Some(Shares(300));
+        assert_eq!(q.size(), Some(Shares(300)));
+
+        // Crossed or one-sided quotes have no spread
+        q.ask = Some(1.95);
+        q.refresh();
+        assert_eq!(q.spread_pct, None);
+        assert_eq!(spread(None, Some(2.0)), None);
+    }
+
+    #[test]
+    fn test_halt_state_from_tick() {
+        assert_eq!(HaltState::from_tick(0.0), Some(HaltState::Trading));
+        assert_eq!(HaltState::from_tick(2.0), Some(HaltState::Volatility));
+        assert_eq!(HaltState::from_tick(-1.0), None);
+        assert!(HaltState::Halted.is_halted());
+        assert!(!HaltState::Trading.is_halted());
+        assert_eq!(HaltState::parse("volatility"), Some(HaltState::Volatility));
+        assert_eq!(HaltState::parse("LULD pause"), None);
+    }
+
+    #[test]
+    fn test_halt_and_resume() {
+        let t0 = DateTime::parse_from_rfc3339("2024-07-15T14:31:00Z").unwrap().to_utc();
+        let mut q = Quote::default();
+        assert_eq!(q.on_halt(HaltState::Trading, t0, Some(2.40)), None);
+
+        let halted = q.on_halt(HaltState::Volatility, t0, Some(2.45));
+        assert_eq!(halted, Some(HaltChange::Halted { state: HaltState::Volatility, at: t0, price: Some(2.45) }));
+        // Extended to a regulatory halt: still the same halt
+        let t1 = t0 + chrono::Duration::minutes(5);
+        assert_eq!(q.on_halt(HaltState::Halted, t1, Some(2.45)), None);
+        assert_eq!(q.halted_at, Some(t0));
+
+        let t2 = t0 + chrono::Duration::minutes(10);
+        assert_eq!(
+            q.on_halt(HaltState::Trading, t2, Some(2.45)),
+            Some(HaltChange::Resumed { at: t2, halt_price: Some(2.45), duration: chrono::Duration::minutes(10) })
+        );
+        assert_eq!(q.halted_at, None);
+        assert_eq!(q.is_halted(), Some(false));
+    }
+}
@@ -0,0 +1,551 @@
+//! Session recording and replay.
+//!
+//! A recording is a JSON-lines file: one header line, then one line per
+//! engine input (poll results, streaming ticks, intraday bars and volume
+//! profiles, enrichment and news refreshes, broad-tape headlines) stamped
+//! with the milliseconds since recording started.
+//! Replaying it sends the same `BgMessage`s to `AlertEngine::tick` with the
+//! original spacing, optionally sped up, stamped with the time they were
+//! recorded so levels and RVOL follow the session's clock.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::engine::BgMessage;
+use crate::enrichment::{EnrichmentData, NewsUpdate};
+use crate::market_data::Bar;
+use crate::models::{NewsHeadline, ScanResult};
+use crate::quote::HaltState;
+use crate::rvol::VolumeProfile;
+use crate::short::Borrow;
+use crate::units::{Shares, ROUND_LOT};
+
+/// Default directory for `scanner record` output.
+pub const RECORDINGS_DIR: &str = "var/sessions";
+
+/// Session file format version, bumped on incompatible changes.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Last format version that stored tick and scan volumes in IB round lots.
+/// Such files are still read; their volumes are converted to shares.
+const ROUND_LOT_VERSION: u32 = 1;
+
+/// First line of a session file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Header {
+    pub version: u32,
+    pub started_at: DateTime<Utc>,
+    /// Market data source the session was recorded from ("tws" or "sim").
+    pub source: String,
+    /// Scanner set polled during the session.
+    pub scanner_set: String,
+}
+
+impl Header {
+    pub fn new(source: &str, scanner_set: &str) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            started_at: Utc::now(),
+            source: source.to_string(),
+            scanner_set: scanner_set.to_string(),
+        }
+    }
+}
+
+/// An engine input as stored in a session file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Recorded {
+    Poll {
+        symbol_data: HashMap<String, ScanResult>,
+        symbol_scanners: HashMap<String, Vec<String>>,
+        /// `(symbol, scanner code, rank)`; JSON maps need string keys.
+        scanner_ranks: Vec<(String, String, u32)>,
+        port: Option<u16>,
+        scanners_run: usize,
+        elapsed_secs: f64,
+    },
+    Tick {
+        symbol: String,
+        last: Option<f64>,
+        close: Option<f64>,
+        bid: Option<f64>,
+        ask: Option<f64>,
+        volume: Option<Shares>,
+        #[serde(default)]
+        vwap: Option<f64>,
+        #[serde(default)]
+        high: Option<f64>,
+        #[serde(default)]
+        bid_size: Option<Shares>,
+        #[serde(default)]
+        ask_size: Option<Shares>,
+        #[serde(default)]
+        halted: Option<HaltState>,
+        #[serde(default)]
+        borrow: Option<Borrow>,
+        #[serde(default)]
+        shortable_shares: Option<Shares>,
+    },
+    Bars {
+        symbol: String,
+        bars: Vec<Bar>,
+    },
+    Profile {
+        symbol: String,
+        profile: VolumeProfile,
+    },
+    Enrich {
+        symbol: String,
+        data: EnrichmentData,
+    },
+    News {
+        symbol: String,
+        update: NewsUpdate,
+    },
+    Headline {
+        headline: NewsHeadline,
+    },
+}
+
+impl Recorded {
+    /// The recorded form of `msg`, or `None` for messages that are not
+    /// market inputs (interactive scans, connection state, ...).
+    pub fn from_message(msg: &BgMessage) -> Option<Self> {
+        Some(match msg {
+            BgMessage::PollComplete {
+                symbol_data,
+                symbol_scanners,
+                scanner_ranks,
+                port,
+                scanners_run,
+                elapsed_secs,
+                ..
+            } => {
+                let mut ranks: Vec<(String, String, u32)> = scanner_ranks
+                    .iter()
+                    .map(|((sym, code), rank)| (sym.clone(), code.clone(), *rank))
+                    .collect();
+                ranks.sort();
+                Self::Poll {
+                    symbol_data: symbol_data.clone(),
+                    symbol_scanners: symbol_scanners.clone(),
+                    scanner_ranks: ranks,
+                    port: *port,
+                    scanners_run: *scanners_run,
+                    elapsed_secs: *elapsed_secs,
+                }
+            }
+            BgMessage::MarketDataTick {
+                symbol,
+                last,
+                close,
+                bid,
+                ask,
+                volume,
+                vwap,
+                high,
+                bid_size,
+                ask_size,
+                halted,
+                borrow,
+                shortable_shares,
+                ..
+            } => Self::Tick {
+                symbol: symbol.clone(),
+                last: *last,
+                close: *close,
+                bid: *bid,
+                ask: *ask,
+                volume: *volume,
+                vwap: *vwap,
+                high: *high,
+                bid_size: *bid_size,
+                ask_size: *ask_size,
+                halted: *halted,
+                borrow: *borrow,
+                shortable_shares: *shortable_shares,
+            },
+            BgMessage::IntradayBars { symbol, bars } => Self::Bars {
+                symbol: symbol.clone(),
+                bars: bars.clone(),
+            },
+            BgMessage::VolumeProfile { symbol, profile } => Self::Profile {
+                symbol: symbol.clone(),
+                profile: profile.clone(),
+            },
+            BgMessage::EnrichComplete { symbol, data } => Self::Enrich {
+                symbol: symbol.clone(),
+                data: data.clone(),
+            },
+            BgMessage::NewsRefresh { symbol, update } => Self::News {
+                symbol: symbol.clone(),
+                update: update.clone(),
+            },
+            BgMessage::NewsTick { headline } => Self::Headline { headline: headline.clone() },
+            _ => return None,
+        })
+    }
+
+    /// The message to replay, stamped `at` (see `Event::time`).
+    pub fn into_message(self, at: DateTime<Utc>) -> BgMessage {
+        match self {
+            Self::Poll {
+                symbol_data,
+                symbol_scanners,
+                scanner_ranks,
+                port,
+                scanners_run,
+                elapsed_secs,
+            } => BgMessage::PollComplete {
+                symbol_data,
+                symbol_scanners,
+                scanner_ranks: scanner_ranks
+                    .into_iter()
+                    .map(|(sym, code, rank)| ((sym, code), rank))
+                    .collect(),
+                port,
+                scanners_run,
+                elapsed_secs,
+                at,
+            },
+            Self::Tick {
+                symbol,
+                last,
+                close,
+                bid,
+                ask,
+                volume,
+                vwap,
+                high,
+                bid_size,
+                ask_size,
+                halted,
+                borrow,
+                shortable_shares,
+            } => BgMessage::MarketDataTick {
+                symbol,
+                last,
+                close,
+                bid,
+                ask,
+                volume,
+                vwap,
+                high,
+                bid_size,
+                ask_size,
+                halted,
+                borrow,
+                shortable_shares,
+                at,
+            },
+            Self::Bars { symbol, bars } => BgMessage::IntradayBars { symbol, bars },
+            Self::Profile { symbol, profile } => BgMessage::VolumeProfile { symbol, profile },
+            Self::Enrich { symbol, data } => BgMessage::EnrichComplete { symbol, data },
+            Self::News { symbol, update } => BgMessage::NewsRefresh { symbol, update },
+            Self::Headline { headline } => BgMessage::NewsTick { headline },
+        }
+    }
+
+    /// Convert tick and scan volumes stored in round lots to shares.
+    fn round_lots_to_shares(&mut self) {
+        let convert = |v: &mut Option<Shares>| {
+            if let Some(v) = v {
+                v.0 *= ROUND_LOT;
+            }
+        };
+        match self {
+            Self::Poll { symbol_data, .. } => symbol_data.values_mut().for_each(|r| convert(&mut r.volume)),
+            Self::Tick { volume, .. } => convert(volume),
+            _ => {}
+        }
+    }
+}
+
+/// One line of a session file after the header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    /// Milliseconds since the recording started.
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub message: Recorded,
+}
+
+impl Event {
+    /// When the event was recorded, for a session started at `started_at`.
+    pub fn time(&self, started_at: DateTime<Utc>) -> DateTime<Utc> {
+        started_at + chrono::Duration::milliseconds(self.at_ms as i64)
+    }
+}
+
+/// Appends engine inputs to a session file as they arrive.
+pub struct Recorder {
+    out: LineWriter<File>,
+    path: PathBuf,
+    started: Instant,
+    events: usize,
+}
+
+impl Recorder {
+    /// Create (or truncate) `path` and write the header.
+    pub fn create(path: &Path, header: &Header) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let file = File::create(path)
+            .with_context(|| format!("failed to create {}", path.display()))?;
+        let mut out = LineWriter::new(file);
+        writeln!(out, "{}", serde_json::to_string(header)?)?;
+        Ok(Self {
+            out,
+            path: path.to_path_buf(),
+            started: Instant::now(),
+            events: 0,
+        })
+    }
+
+    /// Record `msg` if it is a market input; other messages are skipped.
+    pub fn record(&mut self, msg: &BgMessage) -> Result<()> {
+        let Some(message) = Recorded::from_message(msg) else {
+            return Ok(());
+        };
+        let event = Event {
+            at_ms: self.started.elapsed().as_millis() as u64,
+            message,
+        };
+        writeln!(self.out, "{}", serde_json::to_string(&event)?)
+            .with_context(|| format!("failed to write {}", self.path.display()))?;
+        self.events += 1;
+        Ok(())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Events written so far.
+    pub fn events(&self) -> usize {
+        self.events
+    }
+}
+
+/// `var/sessions/<local date>-<time>.jsonl` for a recording started `at`.
+pub fn default_path(at: DateTime<Local>) -> PathBuf {
+    Path::new(RECORDINGS_DIR).join(format!("{}.jsonl", at.format("%Y-%m-%d-%H%M%S")))
+}
+
+/// A session file loaded into memory.
+#[derive(Debug, Clone)]
+pub struct Recording {
+    pub header: Header,
+    pub events: Vec<Event>,
+}
+
+impl Recording {
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        let mut lines = BufReader::new(file).lines();
+
+        let Some(first) = lines.next() else {
+            bail!("{} is empty", path.display());
+        };
+        let header: Header = serde_json::from_str(&first?)
+            .with_context(|| format!("{} has no session header", path.display()))?;
+        if header.version != FORMAT_VERSION && header.version != ROUND_LOT_VERSION {
+            bail!(
+                "{} is session format version {}, this build reads version {FORMAT_VERSION}",
+                path.display(),
+                header.version
+            );
+        }
+
+        let mut events = Vec::new();
+        for (i, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut event: Event = serde_json::from_str(&line)
+                .with_context(|| format!("{}:{}: bad session event", path.display(), i + 2))?;
+            if header.version == ROUND_LOT_VERSION {
+                event.message.round_lots_to_shares();
+            }
+            events.push(event);
+        }
+        Ok(Self { header, events })
+    }
+
+    /// Recorded wall-clock length of the session.
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.events.last().map_or(0, |e| e.at_ms))
+    }
+
+    /// Number of poll cycles in the session.
+    pub fn polls(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|e| matches!(e.message, Recorded::Poll { .. }))
+            .count()
+    }
+}
+
+/// Send a recording's events to `tx` with their recorded spacing divided
+/// by `speed` (2.0 replays twice as fast), each stamped with its recorded
+/// time. The thread ends after the last event or once the engine goes away.
+pub fn spawn_replay(
+    recording: Recording,
+    speed: f64,
+    tx: mpsc::Sender<BgMessage>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        let start = Instant::now();
+        let started_at = recording.header.started_at;
+        for event in recording.events {
+            let due = Duration::from_millis(event.at_ms).div_f64(speed);
+            if let Some(wait) = due.checked_sub(start.elapsed()) {
+                std::thread::sleep(wait);
+            }
+            let at = event.time(started_at);
+            if tx.send(event.message.into_message(at)).is_err() {
+                return;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poll_message() -> BgMessage {
+        let result = ScanResult {
+            symbol: "ABCD".to_string(),
+            last: Some(4.5),
+            ..Default::default()
+        };
+        BgMessage::PollComplete {
+            symbol_data: HashMap::from([("ABCD".to_string(), result)]),
+            symbol_scanners: HashMap::from([("ABCD".to_string(), vec!["TOP_PERC_GAIN".to_string()])]),
+            scanner_ranks: HashMap::from([(("ABCD".to_string(), "TOP_PERC_GAIN".to_string()), 3)]),
+            port: Some(7497),
+            scanners_run: 8,
+            elapsed_secs: 1.5,
+            at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_record_and_load() {
+        let dir = std::env::temp_dir().join(format!("scanner-recording-{}", std::process::id()));
+        let path = dir.join("session.jsonl");
+        let mut recorder = Recorder::create(&path, &Header::new("tws", "default")).unwrap();
+        recorder.record(&poll_message()).unwrap();
+        recorder
+            .record(&BgMessage::MarketDataTick {
+                symbol: "ABCD".to_string(),
+                last: Some(4.6),
+                close: Some(4.0),
+                bid: None,
+                ask: None,
+                volume: Some(Shares(120_000)),
+                vwap: Some(4.4),
+                high: None,
+                bid_size: Some(Shares(300)),
+                ask_size: None,
+                halted: Some(HaltState::Volatility),
+                borrow: Some(Borrow::Hard),
+                shortable_shares: None,
+                at: Utc::now(),
+            })
+            .unwrap();
+        // Not a market input
+        recorder
+            .record(&BgMessage::ListComplete { xml: None, group: None })
+            .unwrap();
+        assert_eq!(recorder.events(), 2);
+        drop(recorder);
+
+        let recording = Recording::load(&path).unwrap();
+        assert_eq!(recording.header.source, "tws");
+        assert_eq!(recording.events.len(), 2);
+        assert_eq!(recording.polls(), 1);
+        match recording.events[0].message.clone().into_message(recording.header.started_at) {
+            BgMessage::PollComplete { symbol_data, scanner_ranks, port, .. } => {
+                assert_eq!(symbol_data["ABCD"].last, Some(4.5));
+                assert_eq!(scanner_ranks[&("ABCD".to_string(), "TOP_PERC_GAIN".to_string())], 3);
+                assert_eq!(port, Some(7497));
+            }
+            _ => panic!("expected a poll"),
+        }
+        assert!(matches!(
+            recording.events[1].message,
+            Recorded::Tick {
+                volume: Some(Shares(120_000)),
+                vwap: Some(4.4),
+                halted: Some(HaltState::Volatility),
+                borrow: Some(Borrow::Hard),
+                ..
+            }
+        ));
+
+        // Version 1 files stored volumes in round lots
+        std::fs::write(
+            &path,
+            "{\"version\":1,\"started_at\":\"2026-01-02T14:30:00Z\",\"source\":\"tws\",\"scanner_set\":\"default\"}\n\
+             {\"at_ms\":0,\"kind\":\"tick\",\"symbol\":\"ABCD\",\"last\":4.6,\"close\":null,\"bid\":null,\"ask\":null,\"volume\":1200}\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            Recording::load(&path).unwrap().events[0].message,
+            Recorded::Tick { volume: Some(Shares(120_000)), .. }
+        ));
+
+        std::fs::write(&path, "{\"version\":99,\"started_at\":\"2026-01-02T14:30:00Z\",\"source\":\"tws\",\"scanner_set\":\"default\"}\n").unwrap();
+        assert!(Recording::load(&path).unwrap_err().to_string().contains("version 99"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_replay_speed() {
+        let started_at = "2026-01-02T14:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        let recording = Recording {
+            header: Header {
+                started_at,
+                ..Header::new("sim", "default")
+            },
+            events: [0, 1000, 2000]
+                .into_iter()
+                .map(|at_ms| Event {
+                    at_ms,
+                    message: Recorded::from_message(&poll_message()).unwrap(),
+                })
+                .collect(),
+        };
+        assert_eq!(recording.duration(), Duration::from_secs(2));
+
+        let (tx, rx) = mpsc::channel();
+        let start = Instant::now();
+        spawn_replay(recording, 100.0, tx).join().unwrap();
+        // Two seconds of session at 100x
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        // Stamped with the recorded time, not the replay's
+        let times: Vec<_> = rx
+            .try_iter()
+            .map(|msg| match msg {
+                BgMessage::PollComplete { at, .. } => (at - started_at).num_seconds(),
+                _ => panic!("expected a poll"),
+            })
+            .collect();
+        assert_eq!(times, vec![0, 1, 2]);
+    }
+}
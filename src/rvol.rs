@@ -0,0 +1,196 @@
+//! Relative volume.
+//!
+//! Time-of-day RVOL compares today's cumulative volume with the average
+//! cumulative volume up to the same minute of recent sessions, built from
+//! their 5-minute bars. Full-day RVOL divides by the whole-day average from
+//! Yahoo and is the fallback while no volume profile is known; early in the
+//! session it badly understates activity.
+
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::market_data::Bar;
+use crate::models::ScanResult;
+use crate::session;
+use crate::tws::bars::{BarDuration, BarRequest, BarSize};
+use crate::units::Shares;
+
+/// Prior sessions averaged into a volume profile.
+pub const PROFILE_SESSIONS: i32 = 10;
+
+const SLOT_MINUTES: u32 = 5;
+const SLOTS: usize = (24 * 60 / SLOT_MINUTES) as usize;
+
+/// Average cumulative volume through each 5-minute slot of the (Eastern)
+/// day, extended hours included.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VolumeProfile {
+    /// Shares traded from midnight ET to the end of each slot.
+    pub cumulative: Vec<f64>,
+    /// Sessions averaged.
+    pub sessions: usize,
+}
+
+/// Bars for the `PROFILE_SESSIONS` sessions before `date`.
+pub fn profile_request(symbol: &str, date: NaiveDate) -> BarRequest {
+    BarRequest::new(symbol, BarSize::Min5, BarDuration::days(PROFILE_SESSIONS))
+        .ending(Some(session::day_start_utc(date)))
+}
+
+/// Minutes since midnight ET, with seconds as a fraction.
+fn minute_of_day(at: DateTime<Utc>) -> f64 {
+    let t = at.with_timezone(&session::MARKET_TZ);
+    f64::from(t.hour() * 60 + t.minute()) + f64::from(t.second()) / 60.0
+}
+
+impl VolumeProfile {
+    /// Profile from the bars of sessions before `before`. Later bars are
+    /// ignored; `None` if no earlier session has volume.
+    pub fn from_bars(bars: &[Bar], before: NaiveDate) -> Option<Self> {
+        let mut days: Vec<(NaiveDate, Vec<f64>)> = Vec::new();
+        for b in bars {
+            let date = session::trading_date(b.time);
+            if date >= before {
+                continue;
+            }
+            let slot = (minute_of_day(b.time) as u32 / SLOT_MINUTES) as usize;
+            let idx = match days.iter().position(|(d, _)| *d == date) {
+                Some(i) => i,
+                None => {
+                    days.push((date, vec![0.0; SLOTS]));
+                    days.len() - 1
+                }
+            };
+            days[idx].1[slot.min(SLOTS - 1)] += b.volume;
+        }
+        days.retain(|(_, v)| v.iter().sum::<f64>() > 0.0);
+        if days.is_empty() {
+            return None;
+        }
+
+        let mut cumulative = vec![0.0; SLOTS];
+        for (_, volumes) in &days {
+            let mut total = 0.0;
+            for (slot, v) in volumes.iter().enumerate() {
+                total += v;
+                cumulative[slot] += total;
+            }
+        }
+        let sessions = days.len();
+        for c in &mut cumulative {
+            *c /= sessions as f64;
+        }
+        Some(Self { cumulative, sessions })
+    }
+
+    /// Average shares traded by the time of day of `at`, interpolated
+    /// within the current slot.
+    pub fn expected_at(&self, at: DateTime<Utc>) -> f64 {
+        let minute = minute_of_day(at);
+        let slot = ((minute / f64::from(SLOT_MINUTES)) as usize).min(SLOTS - 1);
+        let start = if slot == 0 { 0.0 } else { self.cumulative[slot - 1] };
+        let end = self.cumulative[slot];
+        let frac = (minute - (slot as u32 * SLOT_MINUTES) as f64) / f64::from(SLOT_MINUTES);
+        start + (end - start) * frac.clamp(0.0, 1.0)
+    }
+}
+
+/// Both RVOL measures for one symbol.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RelativeVolume {
+    pub time_of_day: Option<f64>,
+    pub full_day: Option<f64>,
+}
+
+impl RelativeVolume {
+    /// Time-of-day RVOL when known, else full-day.
+    pub fn value(&self) -> Option<f64> {
+        self.time_of_day.or(self.full_day)
+    }
+}
+
+/// RVOL for cumulative `volume` at `at`. The 10-day average is preferred
+/// over the 3-month one.
+pub fn relative_volume(
+    volume: Option<Shares>,
+    avg_volume_10d: Option<Shares>,
+    avg_volume: Option<Shares>,
+    profile: Option<&VolumeProfile>,
+    at: DateTime<Utc>,
+) -> RelativeVolume {
+    let shares = volume.map(Shares::as_f64);
+    let full_day = shares
+        .zip(avg_volume_10d.or(avg_volume))
+        .filter(|(_, avg)| avg.get() > 0)
+        .map(|(shares, avg)| shares / avg.as_f64());
+    let time_of_day = shares.zip(profile).and_then(|(shares, p)| {
+        let expected = p.expected_at(at);
+        (expected > 0.0).then(|| shares / expected)
+    });
+    RelativeVolume { time_of_day, full_day }
+}
+
+/// RVOL of a one-shot scan result. Scans have no volume profile, so this
+/// is full-day RVOL.
+pub fn scan_rvol(r: &ScanResult) -> Option<f64> {
+    relative_volume(r.volume, r.avg_volume_10d, r.avg_volume, None, Utc::now()).value()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn et(date: NaiveDate, hour: u32, minute: u32) -> DateTime<Utc> {
+        session::eastern_time(date, hour, minute)
+    }
+
+    fn bar(time: DateTime<Utc>, volume: f64) -> Bar {
+        Bar { time, open: 1.0, high: 1.0, low: 1.0, close: 1.0, volume }
+    }
+
+    #[test]
+    fn test_profile_averages_sessions() {
+        let d1 = NaiveDate::from_ymd_opt(2024, 7, 11).unwrap();
+        let d2 = NaiveDate::from_ymd_opt(2024, 7, 12).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 7, 15).unwrap();
+        let bars = vec![
+            bar(et(d1, 9, 30), 1000.0),
+            bar(et(d1, 9, 35), 1000.0),
+            bar(et(d2, 9, 30), 3000.0),
+            bar(et(d2, 15, 55), 5000.0),
+            // Today's bars are not part of the profile
+            bar(et(today, 9, 30), 1e9),
+        ];
+        let p = VolumeProfile::from_bars(&bars, today).unwrap();
+        assert_eq!(p.sessions, 2);
+        assert_eq!(p.expected_at(et(today, 9, 30)), 0.0);
+        assert_eq!(p.expected_at(et(today, 9, 35)), 2000.0);
+        // Halfway through the 09:35 slot: (2000 + 2500) / 2
+        assert_eq!(p.expected_at(et(today, 9, 37) + chrono::Duration::seconds(30)), 2250.0);
+        assert_eq!(p.expected_at(et(today, 17, 0)), 5000.0);
+        assert!(VolumeProfile::from_bars(&bars[4..], today).is_none());
+    }
+
+    #[test]
+    fn test_relative_volume() {
+        let today = NaiveDate::from_ymd_opt(2024, 7, 15).unwrap();
+        let at = et(today, 9, 45);
+        // Full-day: 2M shares vs 1M average
+        let rv = relative_volume(Some(Shares(2_000_000)), Some(Shares(1_000_000)), Some(Shares(4_000_000)), None, at);
+        assert_eq!(rv.full_day, Some(2.0));
+        assert_eq!(rv.time_of_day, None);
+        assert_eq!(rv.value(), Some(2.0));
+        assert_eq!(relative_volume(Some(Shares(2_000_000)), None, Some(Shares(4_000_000)), None, at).full_day, Some(0.5));
+
+        // By 09:45 prior sessions had traded only 200K shares
+        let mut cumulative = vec![0.0; SLOTS];
+        for (slot, c) in cumulative.iter_mut().enumerate().skip(9 * 12 + 8) {
+            *c = if slot < 9 * 12 + 9 { 200_000.0 } else { 1_000_000.0 };
+        }
+        let profile = VolumeProfile { cumulative, sessions: 10 };
+        let rv = relative_volume(Some(Shares(2_000_000)), Some(Shares(1_000_000)), None, Some(&profile), at);
+        assert_eq!(rv.time_of_day, Some(10.0));
+        assert_eq!(rv.value(), Some(10.0));
+        assert_eq!(relative_volume(None, Some(Shares(1)), None, Some(&profile), at), RelativeVolume::default());
+    }
+}
@@ -1,53 +1,43 @@
+use crate::criteria::{momentum_score, MomentumCriteria, MomentumInputs, PillarReport};
+use crate::market_data::Bar;
 use crate::models::ScanResult;
+use crate::session::MARKET_TZ;
+use crate::short::{Borrow, ShortInfo};
+use crate::units::Shares;
 
-/// Filter results to only stocks passing all 5 momentum pillars.
+/// Filter results to only stocks passing every pillar enabled in `criteria`.
 ///
+/// With the default profile these are the classic 5 momentum pillars:
 /// 1. Price $1-$20
 /// 2. Change >= 10%
 /// 3. Relative Volume >= 5x
 /// 4. Float < 10M (skip if unknown)
 /// 5. Has news catalyst
-pub fn filter_momentum(results: &[ScanResult]) -> Vec<ScanResult> {
+pub fn filter_momentum(results: &[ScanResult], criteria: &MomentumCriteria) -> Vec<ScanResult> {
     results
         .iter()
-        .filter(|r| {
-            let price = match r.last {
-                Some(p) => p,
-                None => return false,
-            };
-            let chg = match r.change_pct {
-                Some(c) => c,
-                None => return false,
-            };
-            // Price: $1-$20
-            if !(1.0..=20.0).contains(&price) {
-                return false;
-            }
-            // Change: >= 10%
-            if chg < 10.0 {
-                return false;
-            }
-            // RVol: >= 5x
-            match r.rvol {
-                Some(rv) if rv >= 5.0 => {}
-                _ => return false,
-            }
-            // Float: < 10M (skip if None)
-            if let Some(flt) = r.float_shares {
-                if flt >= 10_000_000.0 {
-                    return false;
-                }
-            }
-            // Catalyst: must be present
-            if r.catalyst.is_none() {
-                return false;
-            }
-            true
-        })
+        .filter(|r| criteria.passes(&MomentumInputs::from(*r)))
         .cloned()
         .collect()
 }
 
+/// Evaluate `criteria` against each result and store the pillar report
+/// and composite momentum score on it, scoring scanner hits against a
+/// scanner set of `scanner_total` scanners.
+pub fn score_results(results: &mut [ScanResult], criteria: &MomentumCriteria, scanner_total: usize) {
+    let now = chrono::Utc::now().timestamp();
+    for r in results.iter_mut() {
+        let inputs = MomentumInputs::from(&*r).with_scanner_total(scanner_total);
+        r.pillars = criteria.evaluate(&inputs);
+        r.score = momentum_score(&inputs, criteria, now);
+    }
+}
+
+/// Sort results by composite momentum score, highest first.
+pub fn sort_by_score(results: &mut [ScanResult]) {
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+}
+
 /// Format a price value for display.
 pub fn fmt_price(price: Option<f64>) -> String {
     match price {
@@ -64,22 +54,9 @@ pub fn fmt_change_pct(pct: Option<f64>) -> String {
     }
 }
 
-/// Format volume for display. IB TWS reports volume in round lots (100 shares).
-/// Converts to shares and formats with K/M suffix.
-pub fn fmt_volume(vol: Option<i64>) -> String {
-    match vol {
-        Some(v) => {
-            let shares = v as f64 * 100.0;
-            if shares >= 1_000_000.0 {
-                format!("{:.1}M", shares / 1_000_000.0)
-            } else if shares >= 1_000.0 {
-                format!("{:.1}K", shares / 1_000.0)
-            } else {
-                format!("{:.0}", shares)
-            }
-        }
-        None => "-".to_string(),
-    }
+/// Format a volume for display with a K/M suffix.
+pub fn fmt_volume(vol: Option<Shares>) -> String {
+    vol.map_or_else(|| "-".to_string(), |v| v.to_string())
 }
 
 /// Format relative volume for display.
@@ -90,6 +67,35 @@ pub fn fmt_rvol(rvol: Option<f64>) -> String {
     }
 }
 
+/// Format a halt duration in whole seconds, e.g. `5m 05s` or `40s`.
+pub fn fmt_halt_duration(secs: i64) -> String {
+    let secs = secs.max(0);
+    if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{secs}s")
+    }
+}
+
+/// Format borrow availability with the shares available, e.g.
+/// `hard to borrow (45.0K)`.
+pub fn fmt_borrow(short: &ShortInfo) -> String {
+    match (short.borrow, short.shortable_shares) {
+        (Some(b), Some(s)) if b != Borrow::Unavailable => format!("{} ({s})", b.label()),
+        (Some(b), _) => b.label().to_string(),
+        (None, _) => "-".to_string(),
+    }
+}
+
+/// Format the SSR flag for display.
+pub fn fmt_ssr(ssr: Option<bool>) -> String {
+    match ssr {
+        Some(true) => "yes".to_string(),
+        Some(false) => "no".to_string(),
+        None => "-".to_string(),
+    }
+}
+
 /// Format float shares for display (in millions).
 pub fn fmt_float(float_shares: Option<f64>) -> String {
     match float_shares {
@@ -106,6 +112,16 @@ pub fn fmt_short_pct(pct: Option<f64>) -> String {
     }
 }
 
+/// Format a pillar report as "passed/total", or "-" if not evaluated.
+pub fn fmt_pillars(report: &PillarReport) -> String {
+    let total = report.passed.len() + report.failed.len();
+    if total == 0 {
+        "-".to_string()
+    } else {
+        format!("{}/{total}", report.passed.len())
+    }
+}
+
 /// Truncate a string to max_len, adding ".." if truncated.
 pub fn truncate(s: &str, max_len: usize) -> String {
     if s.len() > max_len {
@@ -126,17 +142,17 @@ pub fn print_results(results: &[ScanResult]) {
 
     if has_live {
         println!(
-            "{:>3}  {:<6}  {:>8}  {:>8}  {:>12}  {:>6}  {:>8}  {:>7}  {:<20}  {:<14}  {}",
-            "#", "Symbol", "Last", "Chg%", "Volume", "RVol", "Float", "Short%", "Name", "Sector", "Catalyst"
+            "{:>3}  {:<6}  {:>8}  {:>8}  {:>12}  {:>6}  {:>8}  {:>7}  {:>7}  {:>5}  {:<20}  {:<14}  {}",
+            "#", "Symbol", "Last", "Chg%", "Volume", "RVol", "Float", "Short%", "Pillars", "Score", "Name", "Sector", "Catalyst"
         );
-        println!("{}", "-".repeat(120));
+        println!("{}", "-".repeat(136));
 
         for r in results {
             let name = r.name.as_deref().unwrap_or("-");
             let sector = r.sector.as_deref().unwrap_or("-");
             let catalyst = r.catalyst.as_deref().unwrap_or("");
             println!(
-                "{:>3}  {:<6}  {:>8}  {:>8}  {:>12}  {:>6}  {:>8}  {:>7}  {:<20}  {:<14}  {}",
+                "{:>3}  {:<6}  {:>8}  {:>8}  {:>12}  {:>6}  {:>8}  {:>7}  {:>7}  {:>5.1}  {:<20}  {:<14}  {}",
                 r.rank,
                 r.symbol,
                 fmt_price(r.last),
@@ -145,6 +161,8 @@ pub fn print_results(results: &[ScanResult]) {
                 fmt_rvol(r.rvol),
                 fmt_float(r.float_shares),
                 fmt_short_pct(r.short_pct),
+                fmt_pillars(&r.pillars),
+                r.score,
                 truncate(name, 20),
                 truncate(sector, 14),
                 truncate(catalyst, 30),
@@ -167,6 +185,61 @@ pub fn print_results(results: &[ScanResult]) {
     println!("\nTotal: {} stocks", results.len());
 }
 
+/// Print which pillars excluded each result that failed the criteria.
+pub fn print_exclusions(results: &[ScanResult], criteria: &MomentumCriteria) {
+    let excluded: Vec<&ScanResult> = results.iter().filter(|r| !r.pillars.passes_all()).collect();
+    println!(
+        "\nCriteria '{}': {} ({} of {} pass)",
+        criteria.name,
+        criteria.describe(),
+        results.len() - excluded.len(),
+        results.len()
+    );
+    for r in excluded {
+        println!("  {:<6}  {}", r.symbol, r.pillars.summary());
+    }
+}
+
+/// Format bars as table lines: Eastern time, OHLC and volume in shares.
+pub fn bar_table(bars: &[Bar]) -> Vec<String> {
+    let mut lines = vec![
+        format!(
+            "{:<16}  {:>9}  {:>9}  {:>9}  {:>9}  {:>10}",
+            "Time (ET)", "Open", "High", "Low", "Close", "Volume"
+        ),
+        "-".repeat(72),
+    ];
+    for b in bars {
+        lines.push(format!(
+            "{:<16}  {:>9.2}  {:>9.2}  {:>9.2}  {:>9.2}  {:>10.0}",
+            b.time.with_timezone(&MARKET_TZ).format("%Y-%m-%d %H:%M"),
+            b.open,
+            b.high,
+            b.low,
+            b.close,
+            b.volume,
+        ));
+    }
+    lines
+}
+
+/// Format bars as CSV with a header row. Times are RFC 3339 UTC.
+pub fn bar_csv(bars: &[Bar]) -> Vec<String> {
+    let mut lines = vec!["time,open,high,low,close,volume".to_string()];
+    for b in bars {
+        lines.push(format!(
+            "{},{},{},{},{},{}",
+            b.time.to_rfc3339(),
+            b.open,
+            b.high,
+            b.low,
+            b.close,
+            b.volume
+        ));
+    }
+    lines
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -199,7 +272,7 @@ mod tests {
             Some(5_000_000.0),
             Some("FDA approval"),
         )];
-        let filtered = filter_momentum(&results);
+        let filtered = filter_momentum(&results, &MomentumCriteria::default());
         assert_eq!(filtered.len(), 1);
     }
 
@@ -212,7 +285,7 @@ mod tests {
             Some(5_000_000.0),
             Some("FDA approval"),
         )];
-        let filtered = filter_momentum(&results);
+        let filtered = filter_momentum(&results, &MomentumCriteria::default());
         assert!(filtered.is_empty());
     }
 
@@ -225,7 +298,7 @@ mod tests {
             Some(5_000_000.0),
             Some("FDA approval"),
         )];
-        let filtered = filter_momentum(&results);
+        let filtered = filter_momentum(&results, &MomentumCriteria::default());
         assert!(filtered.is_empty());
     }
 
@@ -238,7 +311,7 @@ mod tests {
             Some(5_000_000.0),
             Some("FDA approval"),
         )];
-        let filtered = filter_momentum(&results);
+        let filtered = filter_momentum(&results, &MomentumCriteria::default());
         assert!(filtered.is_empty());
     }
 
@@ -251,7 +324,7 @@ mod tests {
             Some(5_000_000.0),
             Some("FDA approval"),
         )];
-        let filtered = filter_momentum(&results);
+        let filtered = filter_momentum(&results, &MomentumCriteria::default());
         assert!(filtered.is_empty());
     }
 
@@ -264,7 +337,7 @@ mod tests {
             Some(15_000_000.0),
             Some("FDA approval"),
         )];
-        let filtered = filter_momentum(&results);
+        let filtered = filter_momentum(&results, &MomentumCriteria::default());
         assert!(filtered.is_empty());
     }
 
@@ -277,7 +350,7 @@ mod tests {
             Some(5_000_000.0),
             None,
         )];
-        let filtered = filter_momentum(&results);
+        let filtered = filter_momentum(&results, &MomentumCriteria::default());
         assert!(filtered.is_empty());
     }
 
@@ -291,7 +364,7 @@ mod tests {
             None,
             Some("FDA approval"),
         )];
-        let filtered = filter_momentum(&results);
+        let filtered = filter_momentum(&results, &MomentumCriteria::default());
         assert_eq!(filtered.len(), 1);
     }
 
@@ -304,7 +377,7 @@ mod tests {
             Some(5_000_000.0),
             Some("FDA approval"),
         )];
-        let filtered = filter_momentum(&results);
+        let filtered = filter_momentum(&results, &MomentumCriteria::default());
         assert!(filtered.is_empty());
     }
 
@@ -317,7 +390,7 @@ mod tests {
             Some(5_000_000.0),
             Some("FDA approval"),
         )];
-        let filtered = filter_momentum(&results);
+        let filtered = filter_momentum(&results, &MomentumCriteria::default());
         assert!(filtered.is_empty());
     }
 
@@ -336,10 +409,8 @@ mod tests {
 
     #[test]
     fn test_fmt_volume() {
-        // IB reports in round lots (100 shares): 1234567 lots = 123,456,700 shares = 123.5M
-        assert_eq!(fmt_volume(Some(1234567)), "123.5M");
-        // 100 lots = 10,000 shares = 10.0K
-        assert_eq!(fmt_volume(Some(100)), "10.0K");
+        assert_eq!(fmt_volume(Some(Shares(123_456_700))), "123.5M");
+        assert_eq!(fmt_volume(Some(Shares(10_000))), "10.0K");
         assert_eq!(fmt_volume(None), "-");
     }
 
@@ -361,6 +432,32 @@ mod tests {
         assert_eq!(fmt_short_pct(None), "-");
     }
 
+    #[test]
+    fn test_score_results() {
+        let mut results = vec![make_result(
+            Some(5.0),
+            Some(15.0),
+            Some(3.0),
+            None,
+            Some("FDA approval"),
+        )];
+        results.push(make_result(Some(50.0), Some(2.0), Some(0.5), None, None));
+        score_results(&mut results, &MomentumCriteria::default(), 8);
+        assert_eq!(fmt_pillars(&results[0].pillars), "4/5");
+        assert_eq!(results[0].pillars.failed, vec![crate::criteria::Pillar::RVol]);
+        assert_eq!(fmt_pillars(&PillarReport::default()), "-");
+        assert!(results[0].score > results[1].score);
+
+        results.reverse();
+        sort_by_score(&mut results);
+        assert_eq!(results[0].last, Some(5.0));
+
+        // One hit counts for more in a smaller scanner set
+        let in_eight = results[0].score;
+        score_results(&mut results, &MomentumCriteria::default(), 2);
+        assert!(results[0].score > in_eight);
+    }
+
     #[test]
     fn test_truncate() {
         assert_eq!(truncate("short", 10), "short");
@@ -377,7 +474,7 @@ mod tests {
             Some(5_000_000.0),
             Some("FDA approval"),
         )];
-        assert_eq!(filter_momentum(&results).len(), 1);
+        assert_eq!(filter_momentum(&results, &MomentumCriteria::default()).len(), 1);
 
         // Price exactly 20.0 should pass
         let results = vec![make_result(
@@ -387,7 +484,7 @@ mod tests {
             Some(5_000_000.0),
             Some("FDA approval"),
         )];
-        assert_eq!(filter_momentum(&results).len(), 1);
+        assert_eq!(filter_momentum(&results, &MomentumCriteria::default()).len(), 1);
     }
 
     #[test]
@@ -400,7 +497,7 @@ mod tests {
             Some(5_000_000.0),
             Some("FDA approval"),
         )];
-        assert_eq!(filter_momentum(&results).len(), 1);
+        assert_eq!(filter_momentum(&results, &MomentumCriteria::default()).len(), 1);
 
         // Change 9.9 should fail
         let results = vec![make_result(
@@ -410,7 +507,7 @@ mod tests {
             Some(5_000_000.0),
             Some("FDA approval"),
         )];
-        assert!(filter_momentum(&results).is_empty());
+        assert!(filter_momentum(&results, &MomentumCriteria::default()).is_empty());
     }
 
     #[test]
@@ -423,7 +520,7 @@ mod tests {
             Some(5_000_000.0),
             Some("FDA approval"),
         )];
-        assert_eq!(filter_momentum(&results).len(), 1);
+        assert_eq!(filter_momentum(&results, &MomentumCriteria::default()).len(), 1);
 
         // RVol 4.9 should fail
         let results = vec![make_result(
@@ -433,6 +530,29 @@ mod tests {
             Some(5_000_000.0),
             Some("FDA approval"),
         )];
-        assert!(filter_momentum(&results).is_empty());
+        assert!(filter_momentum(&results, &MomentumCriteria::default()).is_empty());
+    }
+
+    #[test]
+    fn test_bar_formats() {
+        let bars = vec![Bar {
+            time: chrono::DateTime::parse_from_rfc3339("2024-07-15T13:30:00Z").unwrap().to_utc(),
+            open: 2.5,
+            high: 2.75,
+            low: 2.4,
+            close: 2.6,
+            volume: 125_000.0,
+        }];
+        let table = bar_table(&bars);
+        assert_eq!(table.len(), 3);
+        assert!(table[2].starts_with("2024-07-15 09:30"));
+        assert!(table[2].ends_with("125000"));
+        assert_eq!(
+            bar_csv(&bars),
+            vec![
+                "time,open,high,low,close,volume".to_string(),
+                "2024-07-15T13:30:00+00:00,2.5,2.75,2.4,2.6,125000".to_string(),
+            ]
+        );
     }
 }
@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::ALERT_SCANNERS;
+
+/// Name of the built-in scanner set (the classic eight alert scanners).
+pub const DEFAULT_SCANNER_SET: &str = "default";
+
+/// The TWS scanner subscription surface shared by every scanner in a
+/// request: fixed subscription fields plus free-form filter tags.
+///
+/// Tags are the `code`s of the filters advertised in the scanner
+/// parameters XML (e.g. `changePercAbove`, `floatSharesBelow`) and are sent
+/// as TagValue filter options.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanFilters {
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    /// Minimum volume (TWS `aboveVolume`).
+    #[serde(default = "default_min_volume")]
+    pub min_volume: Option<i32>,
+    pub min_market_cap: Option<f64>,
+    pub max_market_cap: Option<f64>,
+    #[serde(default = "default_location")]
+    pub location: String,
+    #[serde(default = "default_instrument")]
+    pub instrument: String,
+    /// TWS stock type filter: CORP, ADR, ETF, REIT or CEF.
+    pub stock_type: Option<String>,
+    /// Filter tags applied to every scanner.
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+}
+
+/// A named group of TWS scanners polled together in alert mode.
+///
+/// Defined under `[scanner_sets.<name>]` in `scanner.toml`. Set-wide
+/// subscription fields sit at the top level; `filters` holds extra filter
+/// tags (e.g. `changePercAbove = "10"`) keyed by scanner code, sent only
+/// with that scanner's subscription.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScannerSet {
+    /// Set name (the table key in `scanner.toml`).
+    #[serde(skip)]
+    pub name: String,
+    /// Scanner codes, polled in order.
+    pub codes: Vec<String>,
+    #[serde(default = "default_rows")]
+    pub rows: u32,
+    #[serde(flatten)]
+    pub scan: ScanFilters,
+    /// Per-scanner filter tags, keyed by scanner code.
+    #[serde(default)]
+    pub filters: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+fn default_rows() -> u32 {
+    50
+}
+
+fn default_min_volume() -> Option<i32> {
+    Some(100_000)
+}
+
+fn default_location() -> String {
+    "STK.US.MAJOR".to_string()
+}
+
+fn default_instrument() -> String {
+    "STK".to_string()
+}
+
+impl Default for ScanFilters {
+    /// US major-exchange stocks trading at least 100K shares, no price band.
+    fn default() -> Self {
+        Self {
+            min_price: None,
+            max_price: None,
+            min_volume: default_min_volume(),
+            min_market_cap: None,
+            max_market_cap: None,
+            location: default_location(),
+            instrument: default_instrument(),
+            stock_type: None,
+            tags: BTreeMap::new(),
+        }
+    }
+}
+
+impl ScanFilters {
+    /// True when the request uses anything beyond the fixed price, volume
+    /// and market-cap fields, i.e. something TWS may not advertise.
+    pub fn needs_validation(&self) -> bool {
+        let defaults = Self::default();
+        self.location != defaults.location
+            || self.instrument != defaults.instrument
+            || self.stock_type.is_some()
+            || !self.tags.is_empty()
+    }
+
+    /// Price band summary, e.g. "$1-$20".
+    pub fn price_band(&self) -> String {
+        match (self.min_price, self.max_price) {
+            (Some(lo), Some(hi)) => format!("${lo}-${hi}"),
+            (Some(lo), None) => format!(">= ${lo}"),
+            (None, Some(hi)) => format!("<= ${hi}"),
+            (None, None) => "any price".to_string(),
+        }
+    }
+}
+
+impl Default for ScannerSet {
+    /// The eight `ALERT_SCANNERS`, 50 rows each, $1-$20, US major exchanges.
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_SCANNER_SET.to_string(),
+            codes: ALERT_SCANNERS.iter().map(|(code, _)| code.to_string()).collect(),
+            rows: default_rows(),
+            scan: ScanFilters {
+                min_price: Some(1.0),
+                max_price: Some(20.0),
+                ..ScanFilters::default()
+            },
+            filters: BTreeMap::new(),
+        }
+    }
+}
+
+impl ScannerSet {
+    /// Number of scanners in the set (the denominator of "N/M scanners").
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    /// Filter tags for one scanner code: the set-wide tags overlaid with
+    /// that scanner's own, as (tag, value) pairs.
+    pub fn filters_for(&self, code: &str) -> Vec<(String, String)> {
+        let mut tags = self.scan.tags.clone();
+        if let Some(own) = self.filters.get(code) {
+            tags.extend(own.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        tags.into_iter().collect()
+    }
+
+    /// One-line summary, e.g. "3 scanners, 25 rows, $1-$10, STK.US.MAJOR".
+    pub fn describe(&self) -> String {
+        format!(
+            "{} scanners, {} rows, {}, {}",
+            self.len(),
+            self.rows,
+            self.scan.price_band(),
+            self.scan.location
+        )
+    }
+
+    /// Reject sets that would poll nothing or filter on unknown scanners.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.codes.is_empty() {
+            anyhow::bail!("scanner set '{}' has no codes", self.name);
+        }
+        if let Some(code) = self.filters.keys().find(|c| !self.codes.contains(c)) {
+            anyhow::bail!(
+                "scanner set '{}' has filters for '{code}', which is not in its codes",
+                self.name
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_set_matches_alert_scanners() {
+        let set = ScannerSet::default();
+        assert_eq!(set.len(), ALERT_SCANNERS.len());
+        assert_eq!(set.codes[0], "HOT_BY_VOLUME");
+        assert_eq!(set.describe(), "8 scanners, 50 rows, $1-$20, STK.US.MAJOR");
+        set.validate().unwrap();
+    }
+
+    #[test]
+    fn test_needs_validation() {
+        let mut filters = ScanFilters {
+            min_price: Some(2.0),
+            max_market_cap: Some(5e8),
+            ..ScanFilters::default()
+        };
+        assert!(!filters.needs_validation());
+        filters.location = "STK.US".to_string();
+        assert!(filters.needs_validation());
+        assert!(!ScannerSet::default().scan.needs_validation());
+    }
+
+    #[test]
+    fn test_filters_for() {
+        let mut set = ScannerSet::default();
+        set.filters.insert(
+            "TOP_PERC_GAIN".to_string(),
+            BTreeMap::from([("changePercAbove".to_string(), "10".to_string())]),
+        );
+        assert_eq!(
+            set.filters_for("TOP_PERC_GAIN"),
+            vec![("changePercAbove".to_string(), "10".to_string())]
+        );
+        assert!(set.filters_for("HOT_BY_VOLUME").is_empty());
+
+        // Per-scanner tags override set-wide ones
+        set.scan.tags.insert("changePercAbove".to_string(), "5".to_string());
+        set.scan.tags.insert("floatSharesBelow".to_string(), "20000000".to_string());
+        assert_eq!(
+            set.filters_for("TOP_PERC_GAIN"),
+            vec![
+                ("changePercAbove".to_string(), "10".to_string()),
+                ("floatSharesBelow".to_string(), "20000000".to_string()),
+            ]
+        );
+        assert_eq!(set.filters_for("HOT_BY_VOLUME").len(), 2);
+
+        set.filters.insert("NOT_IN_SET".to_string(), BTreeMap::new());
+        assert!(set.validate().is_err());
+    }
+}
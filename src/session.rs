@@ -0,0 +1,64 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::America::New_York;
+use chrono_tz::Tz;
+
+/// Exchange time zone: US equity sessions follow US/Eastern.
+pub const MARKET_TZ: Tz = New_York;
+
+/// The trading date (US/Eastern calendar day) an instant falls on.
+///
+/// Late-evening scans on the US west coast or in Asia still belong to the
+/// Eastern session date, not the local one.
+pub fn trading_date(at: DateTime<Utc>) -> NaiveDate {
+    at.with_timezone(&MARKET_TZ).date_naive()
+}
+
+/// Today's trading date.
+pub fn today() -> NaiveDate {
+    trading_date(Utc::now())
+}
+
+/// `at` on the Eastern clock, "HH:MM:SS".
+pub fn clock_time(at: DateTime<Utc>) -> String {
+    at.with_timezone(&MARKET_TZ).format("%H:%M:%S").to_string()
+}
+
+/// UTC instant of midnight US/Eastern starting `date`.
+pub fn day_start_utc(date: NaiveDate) -> DateTime<Utc> {
+    eastern_time(date, 0, 0)
+}
+
+/// UTC instant of an Eastern wall-clock time on `date`, e.g. the 16:00 close.
+pub fn eastern_time(date: NaiveDate, hour: u32, minute: u32) -> DateTime<Utc> {
+    let local = date.and_hms_opt(hour, minute, 0).unwrap();
+    MARKET_TZ
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+        // Only 02:xx on spring-forward days is skipped; callers use market hours
+        .unwrap_or_else(|| Utc.from_utc_datetime(&local))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trading_date_uses_eastern_calendar() {
+        // 02:30 UTC on Jan 16 is still 21:30 on Jan 15 in New York
+        let at = Utc.with_ymd_and_hms(2024, 1, 16, 2, 30, 0).unwrap();
+        assert_eq!(trading_date(at), NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        let at = Utc.with_ymd_and_hms(2024, 1, 16, 5, 30, 0).unwrap();
+        assert_eq!(trading_date(at), NaiveDate::from_ymd_opt(2024, 1, 16).unwrap());
+    }
+
+    #[test]
+    fn test_day_start_utc_tracks_dst() {
+        let winter = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let summer = NaiveDate::from_ymd_opt(2024, 7, 15).unwrap();
+        assert_eq!(day_start_utc(winter), Utc.with_ymd_and_hms(2024, 1, 15, 5, 0, 0).unwrap());
+        assert_eq!(day_start_utc(summer), Utc.with_ymd_and_hms(2024, 7, 15, 4, 0, 0).unwrap());
+        assert_eq!(eastern_time(summer, 16, 0), Utc.with_ymd_and_hms(2024, 7, 15, 20, 0, 0).unwrap());
+        assert_eq!(clock_time(eastern_time(summer, 9, 30)), "09:30:00");
+    }
+}
@@ -0,0 +1,104 @@
+//! Short-side state of alert rows: borrow availability from the TWS
+//! shortable ticks and the short-sale restriction (SSR, SEC rule 201).
+//!
+//! Hard-to-borrow names squeeze harder, so borrow availability feeds the
+//! momentum score; both it and SSR can be filtered on (see `criteria`).
+
+use serde::{Deserialize, Serialize};
+
+use crate::units::Shares;
+
+/// SSR triggers once a stock trades this far below its prior close, in
+/// percent.
+pub const SSR_TRIGGER_PCT: f64 = -10.0;
+
+/// Borrow difficulty from the TWS `Shortable` tick (46).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Borrow {
+    /// At least 1000 shares available.
+    Easy,
+    /// Shortable only if shares can be located.
+    Hard,
+    Unavailable,
+}
+
+impl Borrow {
+    /// From the tick value: above 2.5 easy, above 1.5 hard, else not
+    /// shortable.
+    pub fn from_tick(value: f64) -> Self {
+        if value > 2.5 {
+            Self::Easy
+        } else if value > 1.5 {
+            Self::Hard
+        } else {
+            Self::Unavailable
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Easy => "easy to borrow",
+            Self::Hard => "hard to borrow",
+            Self::Unavailable => "not shortable",
+        }
+    }
+}
+
+/// True once `low` is `SSR_TRIGGER_PCT` or more below `prior_close`.
+pub fn ssr_triggered(prior_close: Option<f64>, low: Option<f64>) -> Option<bool> {
+    let (close, low) = (prior_close?, low?);
+    (close > 0.0).then(|| (low - close) / close * 100.0 <= SSR_TRIGGER_PCT)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShortInfo {
+    /// `None` until TWS sends the shortable tick.
+    pub borrow: Option<Borrow>,
+    /// Shares available to borrow (tick 89).
+    pub shortable_shares: Option<Shares>,
+    /// SSR triggered today; `None` without a prior close. A restriction
+    /// carried over from yesterday's trigger is not detected.
+    pub ssr: Option<bool>,
+}
+
+impl ShortInfo {
+    /// Fold in shortable ticks; missing ones keep their last value.
+    pub fn on_shortable(&mut self, borrow: Option<Borrow>, shares: Option<Shares>) {
+        self.borrow = borrow.or(self.borrow);
+        self.shortable_shares = shares.or(self.shortable_shares);
+    }
+
+    /// Recompute SSR from the prior close and the day's low.
+    pub fn refresh(&mut self, prior_close: Option<f64>, low_of_day: Option<f64>) {
+        self.ssr = ssr_triggered(prior_close, low_of_day);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_borrow_from_tick() {
+        assert_eq!(Borrow::from_tick(3.0), Borrow::Easy);
+        assert_eq!(Borrow::from_tick(2.0), Borrow::Hard);
+        assert_eq!(Borrow::from_tick(1.0), Borrow::Unavailable);
+    }
+
+    #[test]
+    fn test_ssr_trigger() {
+        assert_eq!(ssr_triggered(Some(5.0), Some(4.6)), Some(false));
+        assert_eq!(ssr_triggered(Some(5.0), Some(4.5)), Some(true));
+        assert_eq!(ssr_triggered(None, Some(4.0)), None);
+
+        let mut s = ShortInfo::default();
+        s.on_shortable(Some(Borrow::Hard), None);
+        s.on_shortable(None, Some(Shares(20_000)));
+        s.refresh(Some(2.0), Some(1.75));
+        assert_eq!(s.borrow, Some(Borrow::Hard));
+        assert_eq!(s.shortable_shares, Some(Shares(20_000)));
+        assert_eq!(s.ssr, Some(true));
+    }
+}
@@ -0,0 +1,294 @@
+//! Historical bars from TWS with an on-disk cache.
+//!
+//! Every bar request goes through `fetch_bars`. A request for a window that
+//! has already closed never changes, so its bars are cached in
+//! `BARS_CACHE_DIR` indefinitely; a window that ends now is reused for
+//! `LIVE_BARS_MAX_AGE`.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+pub use ibapi::market_data::historical::{BarSize, Duration as BarDuration, WhatToShow};
+
+use super::connection::{ClientRole, ConnectionManager};
+use crate::market_data::Bar;
+use crate::units::{Shares, VolumeSource};
+
+pub const BARS_CACHE_DIR: &str = "var/bars";
+
+/// How long bars for a window ending now are reused.
+pub const LIVE_BARS_MAX_AGE: Duration = Duration::from_secs(60);
+
+/// One historical bars request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BarRequest {
+    pub symbol: String,
+    pub bar_size: BarSize,
+    /// How far back from `end` the window reaches.
+    pub duration: BarDuration,
+    pub what_to_show: WhatToShow,
+    /// Regular trading hours only; otherwise pre- and post-market bars are included.
+    pub rth: bool,
+    /// End of the window; `None` means now.
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl BarRequest {
+    /// Trade bars over extended hours, ending now.
+    pub fn new(symbol: &str, bar_size: BarSize, duration: BarDuration) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            bar_size,
+            duration,
+            what_to_show: WhatToShow::Trades,
+            rth: false,
+            end: None,
+        }
+    }
+
+    /// One trading date's 5-minute bars, including extended hours. The
+    /// extended session ends at 20:00 ET; today's request ends now.
+    pub fn intraday(symbol: &str, date: NaiveDate) -> Self {
+        let end = (date < crate::session::today()).then(|| crate::session::eastern_time(date, 20, 0));
+        Self::new(symbol, BarSize::Min5, BarDuration::DAY).ending(end)
+    }
+
+    pub fn what_to_show(mut self, what_to_show: WhatToShow) -> Self {
+        self.what_to_show = what_to_show;
+        self
+    }
+
+    pub fn rth(mut self, rth: bool) -> Self {
+        self.rth = rth;
+        self
+    }
+
+    pub fn ending(mut self, end: Option<DateTime<Utc>>) -> Self {
+        self.end = end;
+        self
+    }
+
+    /// Whether the window has closed, so its bars can no longer change.
+    pub fn is_settled(&self, now: DateTime<Utc>) -> bool {
+        self.end.is_some_and(|end| end <= now)
+    }
+
+    /// Cache file name, unique per request.
+    pub fn cache_key(&self) -> String {
+        let end = match self.end {
+            Some(end) => end.format("%Y%m%dT%H%M%SZ").to_string(),
+            None => "now".to_string(),
+        };
+        let key = format!(
+            "{}_{}_{}_{}_{}_{end}",
+            self.symbol,
+            self.bar_size,
+            self.duration,
+            self.what_to_show,
+            if self.rth { "rth" } else { "ext" },
+        );
+        format!("{}.json", key.replace(' ', ""))
+    }
+
+    pub fn cache_path(&self) -> PathBuf {
+        Path::new(BARS_CACHE_DIR).join(self.cache_key())
+    }
+}
+
+/// Parse a bar size like `5m`, `1h`, `1d` or an ibapi name like `MIN5`.
+pub fn parse_bar_size(s: &str) -> Result<BarSize> {
+    let size = match s.trim().to_lowercase().as_str() {
+        "1s" => BarSize::Sec,
+        "5s" => BarSize::Sec5,
+        "10s" => BarSize::Sec10,
+        "15s" => BarSize::Sec15,
+        "30s" => BarSize::Sec30,
+        "1m" => BarSize::Min,
+        "2m" => BarSize::Min2,
+        "3m" => BarSize::Min3,
+        "5m" => BarSize::Min5,
+        "10m" => BarSize::Min10,
+        "15m" => BarSize::Min15,
+        "20m" => BarSize::Min20,
+        "30m" => BarSize::Min30,
+        "1h" => BarSize::Hour,
+        "2h" => BarSize::Hour2,
+        "3h" => BarSize::Hour3,
+        "4h" => BarSize::Hour4,
+        "8h" => BarSize::Hour8,
+        "1d" => BarSize::Day,
+        "1w" => BarSize::Week,
+        "1mo" => BarSize::Month,
+        other => other.parse()?,
+    };
+    Ok(size)
+}
+
+/// Parse a duration like `2d`, `1 W` or `3600s`. Units are TWS's: S, D, W,
+/// M (months) and Y.
+pub fn parse_duration(s: &str) -> Result<BarDuration> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let unit = match unit.trim().to_uppercase().as_str() {
+        "MO" => "M".to_string(),
+        u => u.to_string(),
+    };
+    Ok(format!("{value} {unit}").parse()?)
+}
+
+/// Parse what to show, e.g. `trades`, `midpoint` or `bid_ask`.
+pub fn parse_what_to_show(s: &str) -> Result<WhatToShow> {
+    Ok(s.trim().parse()?)
+}
+
+/// Bars for one request with the time they were fetched from TWS.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedBars {
+    pub fetched_at: DateTime<Utc>,
+    pub bars: Vec<Bar>,
+}
+
+impl CachedBars {
+    /// Whether these bars can still be used for `req`.
+    pub fn is_fresh(&self, req: &BarRequest) -> bool {
+        if req.end.is_some_and(|end| end <= self.fetched_at) {
+            return true;
+        }
+        let age = Utc::now().signed_duration_since(self.fetched_at);
+        age.to_std().is_ok_and(|a| a < LIVE_BARS_MAX_AGE)
+    }
+
+    /// Read a cache file. Missing or unreadable files yield `None`.
+    pub fn load(path: &Path) -> Option<Self> {
+        let text = std::fs::read_to_string(path).ok()?;
+        match serde_json::from_str(&text) {
+            Ok(cached) => Some(cached),
+            Err(e) => {
+                warn!("Ignoring unreadable bars cache {}: {e}", path.display());
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+/// Historical bars for `req`, from the cache when still fresh. `refresh`
+/// skips the cache read; the fetched bars are cached either way.
+pub async fn fetch_bars(conn: &ConnectionManager, req: &BarRequest, refresh: bool) -> Result<Vec<Bar>> {
+    let path = req.cache_path();
+    if !refresh
+        && let Some(cached) = CachedBars::load(&path).filter(|c| c.is_fresh(req))
+    {
+        debug!(symbol = %req.symbol, bars = cached.bars.len(), "bars from cache");
+        return Ok(cached.bars);
+    }
+
+    let cached = CachedBars {
+        fetched_at: Utc::now(),
+        bars: fetch_from_tws(conn, req).await?,
+    };
+    if let Err(e) = cached.save(&path) {
+        warn!("Failed to cache bars: {e:#}");
+    }
+    Ok(cached.bars)
+}
+
+async fn fetch_from_tws(conn: &ConnectionManager, req: &BarRequest) -> Result<Vec<Bar>> {
+    use ibapi::market_data::TradingHours;
+
+    let end = req
+        .end
+        .map(|end| time::OffsetDateTime::from_unix_timestamp(end.timestamp()))
+        .transpose()?;
+    let hours = if req.rth { TradingHours::Regular } else { TradingHours::Extended };
+
+    let (client, _port) = conn.client(ClientRole::Volume).await?;
+    let contract = ibapi::contracts::Contract::stock(&req.symbol).build();
+    let hist = client
+        .historical_data(&contract, end, req.duration, req.bar_size, Some(req.what_to_show), hours)
+        .await
+        .with_context(|| format!("historical bars for {}", req.symbol))?;
+
+    Ok(hist
+        .bars
+        .iter()
+        .filter_map(|b| {
+            Some(Bar {
+                time: DateTime::from_timestamp(b.date.unix_timestamp(), 0)?,
+                open: b.open,
+                high: b.high,
+                low: b.low,
+                close: b.close,
+                volume: Shares::from_reported(b.volume, VolumeSource::HistoricalBar).as_f64(),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request_parts() {
+        assert_eq!(parse_bar_size("5m").unwrap(), BarSize::Min5);
+        assert_eq!(parse_bar_size("1D").unwrap(), BarSize::Day);
+        assert_eq!(parse_bar_size("min15").unwrap(), BarSize::Min15);
+        assert!(parse_bar_size("7m").is_err());
+
+        assert_eq!(parse_duration("2d").unwrap(), BarDuration::days(2));
+        assert_eq!(parse_duration("1 W").unwrap(), BarDuration::weeks(1));
+        assert_eq!(parse_duration("3mo").unwrap(), BarDuration::months(3));
+        assert!(parse_duration("d").is_err());
+
+        assert_eq!(parse_what_to_show("midpoint").unwrap(), WhatToShow::MidPoint);
+        assert!(parse_what_to_show("last").is_err());
+    }
+
+    #[test]
+    fn test_cache_key_and_freshness() {
+        let date = NaiveDate::from_ymd_opt(2024, 7, 15).unwrap();
+        let req = BarRequest::intraday("abcd", date);
+        assert_eq!(req.cache_key(), "ABCD_5mins_1D_TRADES_ext_20240716T000000Z.json");
+        assert!(req.is_settled(Utc::now()));
+        let live = BarRequest::new("ABCD", BarSize::Min, BarDuration::DAY).rth(true);
+        assert_eq!(live.cache_key(), "ABCD_1min_1D_TRADES_rth_now.json");
+        assert!(!live.is_settled(Utc::now()));
+
+        let stale = CachedBars {
+            fetched_at: Utc::now() - chrono::Duration::hours(1),
+            bars: Vec::new(),
+        };
+        // A closed window fetched after it closed never goes stale
+        assert!(stale.is_fresh(&req));
+        assert!(!stale.is_fresh(&live));
+
+        let dir = std::env::temp_dir().join(format!("bars-cache-{}", std::process::id()));
+        let path = dir.join(req.cache_key());
+        let bar = Bar {
+            time: Utc::now(),
+            open: 1.0,
+            high: 2.0,
+            low: 0.5,
+            close: 1.5,
+            volume: 1000.0,
+        };
+        CachedBars { fetched_at: Utc::now(), bars: vec![bar.clone()] }.save(&path).unwrap();
+        assert_eq!(CachedBars::load(&path).unwrap().bars, vec![bar]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,346 @@
+//! Shared TWS connections.
+//!
+//! `ConnectionManager` owns the client-id allocation (see
+//! `docs/tws-market-data.md`), keeps one long-lived connection per
+//! persistent role, and reports connection state changes to a listener.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use anyhow::Result;
+use tracing::{info, warn};
+
+use crate::models::DEFAULT_PORTS;
+
+/// What a TWS connection is used for. Each role has its own client id so
+/// concurrent operations never collide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClientRole {
+    /// Port discovery.
+    Probe,
+    /// Interactive one-shot scans.
+    Scan,
+    /// Scanner parameters XML.
+    Params,
+    /// Alert-mode scanner polling.
+    Poll,
+    /// Snapshot quotes for one-shot scans.
+    ScanSnapshots,
+    /// Snapshot quotes for poll cycles.
+    PollSnapshots,
+    /// Historical bars (`tws::bars`).
+    Volume,
+    /// Fundamental ratios for enrichment (`tws::fundamentals`).
+    Fundamentals,
+    /// News providers, headlines, articles and broad-tape ticks (`tws::news`).
+    News,
+    /// Streaming market data.
+    Streaming,
+}
+
+impl ClientRole {
+    pub const fn client_id(self) -> i32 {
+        match self {
+            Self::Probe => 0,
+            Self::Scan => 1,
+            Self::Params => 3,
+            Self::Poll => 10,
+            Self::ScanSnapshots => 20,
+            Self::PollSnapshots => 21,
+            Self::Volume => 22,
+            Self::Fundamentals => 23,
+            Self::News => 24,
+            Self::Streaming => 30,
+        }
+    }
+
+    /// Whether the connection is kept open between uses.
+    pub const fn is_persistent(self) -> bool {
+        !matches!(self, Self::Probe | Self::Params | Self::Volume)
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Probe => "probe",
+            Self::Scan => "scan",
+            Self::Params => "params",
+            Self::Poll => "poll",
+            Self::ScanSnapshots => "scan snapshots",
+            Self::PollSnapshots => "poll snapshots",
+            Self::Volume => "volume",
+            Self::Fundamentals => "fundamentals",
+            Self::News => "news",
+            Self::Streaming => "streaming",
+        }
+    }
+}
+
+impl fmt::Display for ClientRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (client {})", self.label(), self.client_id())
+    }
+}
+
+/// State of one persistent connection.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionState {
+    Connected { port: u16 },
+    Disconnected { reason: String },
+    /// Waiting `delay` before reconnect attempt `attempt`.
+    Reconnecting { attempt: u32, delay: Duration },
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connected { port } => write!(f, "connected on port {port}"),
+            Self::Disconnected { reason } => write!(f, "disconnected ({reason})"),
+            Self::Reconnecting { attempt, delay } => {
+                write!(f, "reconnecting (attempt {attempt} in {}s)", delay.as_secs())
+            }
+        }
+    }
+}
+
+/// Exponential reconnect delay: 1s, 2s, 4s, ... capped at `max`.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    attempt: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(1), Duration::from_secs(60))
+    }
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self { initial, max, attempt: 0 }
+    }
+
+    /// Attempts made since the last reset.
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.initial.saturating_mul(1 << self.attempt.min(16));
+        self.attempt += 1;
+        delay.min(self.max)
+    }
+
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+/// Called on every connection state change.
+pub type StateListener = Box<dyn Fn(ClientRole, &ConnectionState) + Send + Sync>;
+
+/// Shared TWS connections, one per persistent `ClientRole`.
+pub struct ConnectionManager {
+    endpoint: Mutex<(String, Vec<u16>)>,
+    clients: Mutex<HashMap<ClientRole, (Arc<ibapi::Client>, u16)>>,
+    states: Mutex<HashMap<ClientRole, ConnectionState>>,
+    listener: Option<StateListener>,
+}
+
+impl ConnectionManager {
+    /// Empty `ports` means `DEFAULT_PORTS`.
+    pub fn new(host: &str, ports: &[u16]) -> Self {
+        Self {
+            endpoint: Mutex::new((host.to_string(), ports.to_vec())),
+            clients: Mutex::new(HashMap::new()),
+            states: Mutex::new(HashMap::new()),
+            listener: None,
+        }
+    }
+
+    pub fn with_listener(mut self, listener: StateListener) -> Self {
+        self.listener = Some(listener);
+        self
+    }
+
+    /// Point at a different host or ports. Open connections are dropped.
+    pub fn set_endpoint(&self, host: &str, ports: &[u16]) {
+        let mut endpoint = self.endpoint.lock().unwrap();
+        if endpoint.0 == host && endpoint.1 == ports {
+            return;
+        }
+        *endpoint = (host.to_string(), ports.to_vec());
+        drop(endpoint);
+        self.clients.lock().unwrap().clear();
+    }
+
+    fn endpoint(&self) -> (String, Vec<u16>) {
+        let (host, ports) = self.endpoint.lock().unwrap().clone();
+        let ports = if ports.is_empty() { DEFAULT_PORTS.to_vec() } else { ports };
+        (host, ports)
+    }
+
+    /// Last reported state of a persistent role.
+    pub fn state(&self, role: ClientRole) -> Option<ConnectionState> {
+        self.states.lock().unwrap().get(&role).cloned()
+    }
+
+    fn set_state(&self, role: ClientRole, state: ConnectionState) {
+        if !role.is_persistent() {
+            return;
+        }
+        let changed = self.states.lock().unwrap().insert(role, state.clone()) != Some(state.clone());
+        if changed {
+            info!(role = role.label(), "TWS {state}");
+            if let Some(listener) = &self.listener {
+                listener(role, &state);
+            }
+        }
+    }
+
+    /// A connected client for `role`. Persistent roles reuse their open
+    /// connection; a dropped one is reported and replaced.
+    pub async fn client(&self, role: ClientRole) -> Result<(Arc<ibapi::Client>, u16)> {
+        if role.is_persistent() {
+            let cached = self.clients.lock().unwrap().get(&role).cloned();
+            if let Some((client, port)) = cached {
+                if client.is_connected() {
+                    return Ok((client, port));
+                }
+                self.release(role);
+                self.set_state(role, ConnectionState::Disconnected {
+                    reason: "connection lost".to_string(),
+                });
+            }
+        }
+
+        let (host, ports) = self.endpoint();
+        match super::connect(&host, &ports, role.client_id()).await {
+            Ok((client, port)) => {
+                let client = Arc::new(client);
+                if role.is_persistent() {
+                    self.clients.lock().unwrap().insert(role, (Arc::clone(&client), port));
+                }
+                self.set_state(role, ConnectionState::Connected { port });
+                Ok((client, port))
+            }
+            Err(e) => {
+                self.set_state(role, ConnectionState::Disconnected { reason: e.to_string() });
+                Err(e)
+            }
+        }
+    }
+
+    /// Like `client`, but retries with `backoff` until connected.
+    pub async fn reconnect(&self, role: ClientRole, backoff: &mut Backoff) -> (Arc<ibapi::Client>, u16) {
+        loop {
+            match self.client(role).await {
+                Ok(connected) => {
+                    backoff.reset();
+                    return connected;
+                }
+                Err(e) => {
+                    let delay = backoff.next_delay();
+                    warn!(role = role.label(), "TWS connect failed: {e}");
+                    self.set_state(role, ConnectionState::Reconnecting {
+                        attempt: backoff.attempt(),
+                        delay,
+                    });
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    /// Whether `role` holds an open connection.
+    pub fn is_connected(&self, role: ClientRole) -> bool {
+        self.clients
+            .lock()
+            .unwrap()
+            .get(&role)
+            .is_some_and(|(client, _)| client.is_connected())
+    }
+
+    /// Close the connection held for `role`, if any.
+    pub fn release(&self, role: ClientRole) {
+        self.clients.lock().unwrap().remove(&role);
+    }
+}
+
+/// Runtime that owns the engine's long-lived TWS connections.
+///
+/// An ibapi client's reader task lives on the runtime that connected it,
+/// so connections reused across poll cycles must not be created on a
+/// short-lived per-call runtime.
+pub fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("tws")
+            .enable_all()
+            .build()
+            .expect("tws runtime")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_ids_are_unique() {
+        let roles = [
+            ClientRole::Probe,
+            ClientRole::Scan,
+            ClientRole::Params,
+            ClientRole::Poll,
+            ClientRole::ScanSnapshots,
+            ClientRole::PollSnapshots,
+            ClientRole::Volume,
+            ClientRole::Fundamentals,
+            ClientRole::News,
+            ClientRole::Streaming,
+        ];
+        let ids: std::collections::HashSet<i32> = roles.iter().map(|r| r.client_id()).collect();
+        assert_eq!(ids.len(), roles.len());
+        assert_eq!(ClientRole::Streaming.to_string(), "streaming (client 30)");
+        assert!(ClientRole::Poll.is_persistent());
+        assert!(!ClientRole::Probe.is_persistent());
+    }
+
+    #[test]
+    fn test_backoff() {
+        let mut b = Backoff::new(Duration::from_secs(1), Duration::from_secs(10));
+        let delays: Vec<u64> = (0..6).map(|_| b.next_delay().as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 10, 10]);
+        assert_eq!(b.attempt(), 6);
+        b.reset();
+        assert_eq!(b.next_delay(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_unreachable_reports_disconnected_once() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        // Port 1 on localhost refuses immediately
+        let conn = ConnectionManager::new("127.0.0.1", &[1]).with_listener(Box::new(move |role, state| {
+            sink.lock().unwrap().push((role, state.clone()));
+        }));
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        assert!(rt.block_on(conn.client(ClientRole::Poll)).is_err());
+        assert!(rt.block_on(conn.client(ClientRole::Poll)).is_err());
+        // Ephemeral roles do not report state
+        assert!(rt.block_on(conn.client(ClientRole::Probe)).is_err());
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].0, ClientRole::Poll);
+        assert!(matches!(seen[0].1, ConnectionState::Disconnected { .. }));
+        assert!(matches!(conn.state(ClientRole::Poll), Some(ConnectionState::Disconnected { .. })));
+        assert_eq!(conn.state(ClientRole::Probe), None);
+    }
+}
@@ -0,0 +1,94 @@
+//! Shares outstanding from IB fundamental data.
+//!
+//! ibapi 2.9.2 has no fundamental data request (`reqFundamentalData`), so
+//! the ReportSnapshot XML with its float figure is out of reach. What the
+//! market data request does offer is the fundamental ratios tick (47,
+//! generic tick 258), whose market cap and price give shares outstanding.
+//! `enrichment` reconciles that against the float from other providers.
+
+use anyhow::{Context, Result};
+use ibapi::contracts::tick_types::TickType;
+use ibapi::market_data::realtime::TickTypes;
+use serde::{Deserialize, Serialize};
+
+use super::connection::{ClientRole, ConnectionManager};
+
+/// Generic tick id for the fundamental ratios tick.
+const FUNDAMENTAL_RATIOS_GENERIC_TICK: &str = "258";
+
+/// How long to wait for the ratios tick before giving up.
+const RATIOS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Fundamentals IB reports for a stock.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Fundamentals {
+    pub shares_outstanding: Option<f64>,
+    /// Market cap in dollars.
+    pub market_cap: Option<f64>,
+}
+
+/// Parse the ratios tick, "KEY=value;KEY=value;...". `MKTCAP` is in
+/// millions of dollars and `NPRICE` is the last close, so shares
+/// outstanding is their quotient.
+pub fn parse_fundamental_ratios(value: &str) -> Fundamentals {
+    let ratio = |key: &str| {
+        value
+            .split(';')
+            .filter_map(|kv| kv.split_once('='))
+            .find(|(k, _)| k.trim() == key)
+            .and_then(|(_, v)| v.trim().parse::<f64>().ok())
+            .filter(|v| *v > 0.0)
+    };
+    let market_cap = ratio("MKTCAP").map(|m| m * 1e6);
+    let shares_outstanding = match (market_cap, ratio("NPRICE")) {
+        (Some(cap), Some(price)) => Some((cap / price).round()),
+        _ => None,
+    };
+    Fundamentals { shares_outstanding, market_cap }
+}
+
+/// Request the ratios tick for `symbol` on the fundamentals connection and
+/// cancel once it arrives.
+pub async fn fetch_fundamentals(conn: &ConnectionManager, symbol: &str) -> Result<Fundamentals> {
+    let (client, _port) = conn.client(ClientRole::Fundamentals).await?;
+    let contract = ibapi::contracts::Contract::stock(symbol).build();
+    let mut subscription = client
+        .market_data(&contract)
+        .generic_ticks(&[FUNDAMENTAL_RATIOS_GENERIC_TICK])
+        .subscribe()
+        .await?;
+
+    let deadline = tokio::time::Instant::now() + RATIOS_TIMEOUT;
+    loop {
+        let tick = tokio::time::timeout_at(deadline, subscription.next())
+            .await
+            .with_context(|| format!("no fundamental ratios for {symbol}"))?;
+        match tick {
+            Some(Ok(TickTypes::String(ts))) if ts.tick_type == TickType::FundamentalRatios => {
+                return Ok(parse_fundamental_ratios(&ts.value));
+            }
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return Err(e.into()),
+            None => anyhow::bail!("market data for {symbol} ended without fundamental ratios"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fundamental_ratios() {
+        let f = parse_fundamental_ratios(
+            "TTMNPMGN=-35.1;NLOW=1.02;MKTCAP=42.5;NPRICE=2.5;BETA=1.8;AFEEPSNTM=;",
+        );
+        assert_eq!(f.market_cap, Some(42_500_000.0));
+        assert_eq!(f.shares_outstanding, Some(17_000_000.0));
+
+        // No price, no shares outstanding
+        let f = parse_fundamental_ratios("MKTCAP=42.5;NPRICE=-99999.99");
+        assert_eq!(f.shares_outstanding, None);
+        assert_eq!(parse_fundamental_ratios(""), Fundamentals::default());
+    }
+}
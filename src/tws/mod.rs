@@ -1,9 +1,21 @@
+pub mod bars;
+pub mod connection;
+pub mod fundamentals;
+pub mod news;
+pub mod params;
+
 use std::collections::HashMap;
 
 use anyhow::Result;
 use tracing::{debug, info, warn};
 
+use crate::market_data::{Bar, MarketTick, PollScan, TickStream};
 use crate::models::{ScanResult, DEFAULT_PORTS};
+use crate::quote::HaltState;
+use connection::{ClientRole, ConnectionManager};
+use crate::scanner_set::{ScanFilters, ScannerSet};
+use crate::short::Borrow;
+use crate::units::{Shares, VolumeSource};
 use ibapi::contracts::tick_types::TickType;
 use ibapi::market_data::realtime::TickTypes;
 
@@ -11,21 +23,30 @@ use ibapi::market_data::realtime::TickTypes;
 fn build_subscription(
     scan_code: &str,
     rows: u32,
-    min_price: Option<f64>,
-    max_price: Option<f64>,
+    filters: &ScanFilters,
 ) -> ibapi::scanner::ScannerSubscription {
     ibapi::scanner::ScannerSubscription {
         number_of_rows: rows as i32,
-        instrument: Some("STK".to_string()),
-        location_code: Some("STK.US.MAJOR".to_string()),
+        instrument: Some(filters.instrument.clone()),
+        location_code: Some(filters.location.clone()),
         scan_code: Some(scan_code.to_string()),
-        above_price: min_price,
-        below_price: max_price,
-        above_volume: Some(100_000),
+        above_price: filters.min_price,
+        below_price: filters.max_price,
+        above_volume: filters.min_volume,
+        market_cap_above: filters.min_market_cap,
+        market_cap_below: filters.max_market_cap,
+        stock_type_filter: filters.stock_type.clone(),
         ..Default::default()
     }
 }
 
+/// Convert (tag, value) pairs to TWS scanner filter options.
+fn tag_values(tags: Vec<(String, String)>) -> Vec<ibapi::orders::TagValue> {
+    tags.into_iter()
+        .map(|(tag, value)| ibapi::orders::TagValue { tag, value })
+        .collect()
+}
+
 /// Convert ibapi ScannerData to our ScanResult.
 fn scanner_data_to_result(data: &ibapi::scanner::ScannerData) -> ScanResult {
     let c = &data.contract_details.contract;
@@ -51,6 +72,7 @@ fn scanner_data_to_result(data: &ibapi::scanner::ScannerData) -> ScanResult {
 
 /// Try connecting to TWS on the given ports, return the first successful client and port.
 /// Each port attempt has a 3-second timeout to avoid hanging when TWS is not running.
+/// Prefer `ConnectionManager::client`, which owns client-id allocation.
 async fn connect(
     host: &str,
     ports: &[u16],
@@ -90,17 +112,14 @@ async fn connect(
 
 /// Run a scanner subscription and return results with the connected port.
 pub async fn run_scan(
+    conn: &ConnectionManager,
     scanner_code: &str,
-    host: &str,
-    ports: &[u16],
-    client_id: i32,
     rows: u32,
-    min_price: Option<f64>,
-    max_price: Option<f64>,
+    filters: &ScanFilters,
 ) -> (Vec<ScanResult>, Option<u16>) {
     eprintln!("Scanning {scanner_code} (rows={rows})...");
 
-    let (client, port) = match connect(host, ports, client_id).await {
+    let (client, port) = match conn.client(ClientRole::Scan).await {
         Ok(c) => c,
         Err(e) => {
             eprintln!("{e}");
@@ -108,8 +127,9 @@ pub async fn run_scan(
         }
     };
 
-    let sub = build_subscription(scanner_code, rows, min_price, max_price);
-    let mut subscription = match client.scanner_subscription(&sub, &vec![]).await {
+    let sub = build_subscription(scanner_code, rows, filters);
+    let tags = tag_values(filters.tags.clone().into_iter().collect());
+    let mut subscription = match client.scanner_subscription(&sub, &tags).await {
         Ok(s) => s,
         Err(e) => {
             eprintln!("Failed to request scanner: {e}");
@@ -138,7 +158,7 @@ pub async fn run_scan(
             .into_iter()
             .map(|r| (r.symbol.clone(), r))
             .collect();
-        fetch_snapshots(&mut data_map, host, ports, 50, 20).await;
+        fetch_snapshots(&mut data_map, conn, 50, ClientRole::ScanSnapshots).await;
         results = data_map.into_values().collect();
         results.sort_by_key(|r| r.rank);
     }
@@ -146,32 +166,30 @@ pub async fn run_scan(
     (results, Some(port))
 }
 
-/// Run multiple scanner subscriptions over a single TWS connection.
-/// Returns (symbol_scanners, symbol_data, connected_port).
-pub async fn run_poll_scan(
-    scanners: &[(&str, i32)],
-    host: &str,
-    ports: &[u16],
-    _client_id: i32,
-    rows: u32,
-    min_price: Option<f64>,
-    max_price: Option<f64>,
-) -> (HashMap<String, Vec<String>>, HashMap<String, ScanResult>, Option<u16>) {
-    // Use client_id 10 for the shared connection
-    let (client, port) = match connect(host, ports, 10).await {
+/// Rank of each symbol within each scanner, keyed by (symbol, scanner code).
+pub type ScannerRanks = HashMap<(String, String), u32>;
+
+/// Run every scanner of a set over a single TWS connection.
+/// Returns (symbol_scanners, symbol_data, scanner_ranks, connected_port).
+pub async fn run_poll_scan(conn: &ConnectionManager, set: &ScannerSet) -> PollScan {
+    // The poll connection stays open between cycles
+    let (client, port) = match conn.client(ClientRole::Poll).await {
         Ok(c) => c,
         Err(e) => {
             warn!("Poll scan connect failed: {e}");
-            return (HashMap::new(), HashMap::new(), None);
+            return (HashMap::new(), HashMap::new(), HashMap::new(), None);
         }
     };
 
     let mut symbol_scanners: HashMap<String, Vec<String>> = HashMap::new();
     let mut symbol_data: HashMap<String, ScanResult> = HashMap::new();
+    let mut scanner_ranks: ScannerRanks = HashMap::new();
 
-    for (i, &(code, _cid)) in scanners.iter().enumerate() {
-        let sub = build_subscription(code, rows, min_price, max_price);
-        let mut subscription = match client.scanner_subscription(&sub, &vec![]).await {
+    for (i, code) in set.codes.iter().enumerate() {
+        let code = code.as_str();
+        let sub = build_subscription(code, set.rows, &set.scan);
+        let tags = tag_values(set.filters_for(code));
+        let mut subscription = match client.scanner_subscription(&sub, &tags).await {
             Ok(s) => s,
             Err(e) => {
                 warn!(code, "failed to subscribe scanner: {e}");
@@ -191,7 +209,7 @@ pub async fn run_poll_scan(
         subscription.cancel().await;
 
         let count = results.len();
-        info!(scanner = i + 1, total = scanners.len(), code, count, "poll scanner results");
+        info!(scanner = i + 1, total = set.len(), code, count, "poll scanner results");
 
         for r in results {
             let sym = r.symbol.clone();
@@ -199,6 +217,7 @@ pub async fn run_poll_scan(
                 .entry(sym.clone())
                 .or_default()
                 .push(code.to_string());
+            scanner_ranks.insert((sym.clone(), code.to_string()), r.rank);
             symbol_data.entry(sym).or_insert(r);
         }
     }
@@ -207,7 +226,7 @@ pub async fn run_poll_scan(
     // provides live prices for all poll-discovered symbols. Snapshots would
     // compete for TWS's 100-line market data limit.
 
-    (symbol_scanners, symbol_data, Some(port))
+    (symbol_scanners, symbol_data, scanner_ranks, Some(port))
 }
 
 /// Snapshot result for a single symbol.
@@ -217,7 +236,10 @@ struct SnapshotResult {
     bid: Option<f64>,
     ask: Option<f64>,
     close: Option<f64>,
-    volume: Option<i64>,
+    volume: Option<Shares>,
+    bid_size: Option<Shares>,
+    ask_size: Option<Shares>,
+    halted: Option<HaltState>,
 }
 
 /// Fetch a single symbol's snapshot from an existing client connection.
@@ -236,67 +258,51 @@ async fn fetch_one_snapshot(client: &ibapi::Client, symbol: &str, currency: &str
     .await
     .ok()?;
 
-    let mut last = None;
-    let mut close = None;
-    let mut volume = None;
-    let mut bid = None;
-    let mut ask = None;
-
+    let mut quote = MarketTick::default();
     let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(3);
     loop {
         match tokio::time::timeout_at(deadline, subscription.next()).await {
-            Ok(Some(Ok(tick))) => match tick {
-                TickTypes::SnapshotEnd => break,
-                TickTypes::Price(tp) => match tp.tick_type {
-                    TickType::Last => last = Some(tp.price),
-                    TickType::Close => close = Some(tp.price),
-                    TickType::Bid => bid = Some(tp.price),
-                    TickType::Ask => ask = Some(tp.price),
-                    _ => {}
-                },
-                TickTypes::PriceSize(tp) => {
-                    match tp.price_tick_type {
-                        TickType::Last => last = Some(tp.price),
-                        TickType::Close => close = Some(tp.price),
-                        TickType::Bid => bid = Some(tp.price),
-                        TickType::Ask => ask = Some(tp.price),
-                        _ => {}
-                    }
-                    if tp.size_tick_type == TickType::Volume {
-                        volume = Some(tp.size as i64);
-                    }
+            Ok(Some(Ok(TickTypes::SnapshotEnd))) => break,
+            Ok(Some(Ok(tick))) => {
+                if let Some(update) = market_tick(&tick) {
+                    quote.merge(update);
                 }
-                TickTypes::Size(ts) => {
-                    if ts.tick_type == TickType::Volume {
-                        volume = Some(ts.size as i64);
-                    }
-                }
-                _ => {}
-            },
+            }
             Ok(Some(Err(_))) | Ok(None) => break,
             Err(_) => break,
         }
     }
 
-    Some(SnapshotResult { symbol: symbol.to_string(), last, bid, ask, close, volume })
+    let MarketTick { last, close, bid, ask, volume, bid_size, ask_size, halted, .. } = quote;
+    Some(SnapshotResult {
+        symbol: symbol.to_string(),
+        last,
+        bid,
+        ask,
+        close,
+        volume,
+        bid_size,
+        ask_size,
+        halted,
+    })
 }
 
 /// Fetch market data snapshots for a batch of scan results.
 /// Populates last, bid, ask, volume, close, and computes change_pct.
 /// Limited to `max_symbols`; requests run concurrently in chunks of 10.
-/// `client_id`: use 20 for one-shot scans, 21 for poll scans (avoids TWS conflicts).
+/// `role`: `ScanSnapshots` for one-shot scans, `PollSnapshots` for poll scans
+/// (separate client ids avoid TWS conflicts).
 pub async fn fetch_snapshots(
     results: &mut HashMap<String, ScanResult>,
-    host: &str,
-    ports: &[u16],
+    conn: &ConnectionManager,
     max_symbols: usize,
-    client_id: i32,
+    role: ClientRole,
 ) {
     if results.is_empty() {
         return;
     }
 
-    let (client, _port) = match connect(host, ports, client_id).await {
+    let (client, _port) = match conn.client(role).await {
         Ok(c) => c,
         Err(e) => {
             warn!("Snapshot connect failed: {e}");
@@ -329,6 +335,9 @@ pub async fn fetch_snapshots(
                 }
                 r.bid = snap.bid;
                 r.ask = snap.ask;
+                r.bid_size = snap.bid_size;
+                r.ask_size = snap.ask_size;
+                r.halted = snap.halted;
                 r.close = snap.close;
                 if let Some(v) = snap.volume {
                     r.volume = Some(v);
@@ -348,8 +357,8 @@ pub async fn fetch_snapshots(
 }
 
 /// Fetch scanner parameters XML from TWS.
-pub async fn fetch_scanner_params(host: &str, ports: &[u16], client_id: i32) -> Option<String> {
-    let (client, _port) = connect(host, ports, client_id).await.ok()?;
+pub async fn fetch_scanner_params(conn: &ConnectionManager) -> Option<String> {
+    let (client, _port) = conn.client(ClientRole::Params).await.ok()?;
 
     match client.scanner_parameters().await {
         Ok(xml) => Some(xml),
@@ -360,51 +369,32 @@ pub async fn fetch_scanner_params(host: &str, ports: &[u16], client_id: i32) ->
     }
 }
 
-/// Fetch today's 5-min historical bars for a symbol and return (summed_volume, tick_volume, bars).
-/// Used to cross-check whether the tick Volume value matches reality.
+/// Fetch today's 5-min bars for a symbol and return (summed_volume, tick_volume, bars).
+/// Used to cross-check that the tick Volume, once normalized to shares,
+/// matches the bars.
 pub async fn fetch_volume_check(
     symbol: &str,
-    host: &str,
-    ports: &[u16],
-) -> Result<(f64, Option<i64>, Vec<(String, f64, f64)>)> {
-    use ibapi::market_data::historical::{BarSize, ToDuration, WhatToShow};
-    use ibapi::market_data::TradingHours;
-
-    let (client, _port) = connect(host, ports, 22).await?;
-
-    let contract = ibapi::contracts::Contract::stock(symbol).build();
-
-    // Fetch 1 day of 5-min bars (includes pre-market with Extended hours)
-    let hist = client
-        .historical_data(
-            &contract,
-            None, // end_date = now
-            1.days(),
-            BarSize::Min5,
-            Some(WhatToShow::Trades),
-            TradingHours::Extended,
-        )
-        .await?;
-
-    let bars: Vec<(String, f64, f64)> = hist
-        .bars
+    conn: &ConnectionManager,
+) -> Result<(Shares, Option<Shares>, Vec<Bar>)> {
+    use bars::{BarDuration, BarRequest, BarSize};
+
+    // 1 day of 5-min bars, including pre-market
+    let req = BarRequest::new(symbol, BarSize::Min5, BarDuration::DAY);
+    let bars = bars::fetch_bars(conn, &req, false).await?;
+    let bar_volume_sum: Shares = bars
         .iter()
-        .map(|b| {
-            let ts = format!("{}", b.date);
-            (ts, b.close, b.volume)
-        })
-        .collect();
-
-    let bar_volume_sum: f64 = hist.bars.iter().map(|b| b.volume).sum();
+        .map(|b| Shares::from_reported(b.volume, VolumeSource::HistoricalBar))
+        .sum();
 
     // Also fetch a snapshot to get the tick Volume for comparison
+    let (client, _port) = conn.client(ClientRole::Volume).await?;
     let snap = fetch_one_snapshot(&client, symbol, "USD").await;
     let tick_volume = snap.and_then(|s| s.volume);
 
     info!(
         symbol,
-        bar_volume_sum,
-        tick_volume = tick_volume.unwrap_or(-1),
+        bar_volume_sum = bar_volume_sum.get(),
+        tick_volume = tick_volume.map_or(-1, Shares::get),
         bar_count = bars.len(),
         "volume check"
     );
@@ -412,9 +402,99 @@ pub async fn fetch_volume_check(
     Ok((bar_volume_sum, tick_volume, bars))
 }
 
+/// Fold one streaming tick into a `MarketTick`. Ticks that carry no
+/// price, size, halt state or borrow availability yield `None`.
+pub fn market_tick(tick: &TickTypes) -> Option<MarketTick> {
+    let mut update = MarketTick::default();
+    let price = |update: &mut MarketTick, tick_type: &TickType, price: f64| match tick_type {
+        TickType::Last => update.last = Some(price),
+        TickType::Close => update.close = Some(price),
+        TickType::Bid => update.bid = Some(price),
+        TickType::Ask => update.ask = Some(price),
+        TickType::High => update.high = Some(price),
+        _ => {}
+    };
+    let size = |update: &mut MarketTick, tick_type: &TickType, size: f64| match tick_type {
+        TickType::Volume => update.volume = Some(Shares::from_reported(size, VolumeSource::VolumeTick)),
+        TickType::BidSize => update.bid_size = Some(Shares::from_reported(size, VolumeSource::QuoteSize)),
+        TickType::AskSize => update.ask_size = Some(Shares::from_reported(size, VolumeSource::QuoteSize)),
+        TickType::ShortableShares => {
+            update.shortable_shares = Some(Shares::from_reported(size, VolumeSource::ShortableShares))
+        }
+        _ => {}
+    };
+    match tick {
+        TickTypes::Price(tp) => price(&mut update, &tp.tick_type, tp.price),
+        TickTypes::PriceSize(tp) => {
+            price(&mut update, &tp.price_tick_type, tp.price);
+            size(&mut update, &tp.size_tick_type, tp.size);
+        }
+        TickTypes::Size(ts) => size(&mut update, &ts.tick_type, ts.size),
+        // RTVolume (tick 233): "price;size;time;totalVolume;vwap;single"
+        TickTypes::String(ts) if ts.tick_type == TickType::RtVolume => {
+            let parts: Vec<&str> = ts.value.split(';').collect();
+            if parts.len() >= 4 {
+                update.volume = parts[3]
+                    .parse::<f64>()
+                    .ok()
+                    .map(|tv| Shares::from_reported(tv, VolumeSource::RtVolume));
+                update.last = parts[0].parse::<f64>().ok().filter(|p| *p > 0.0);
+            }
+            if let Some(vwap) = parts.get(4) {
+                update.vwap = vwap.parse::<f64>().ok().filter(|p| *p > 0.0);
+            }
+        }
+        TickTypes::Generic(tg) if tg.tick_type == TickType::Halted => {
+            update.halted = HaltState::from_tick(tg.value);
+        }
+        TickTypes::Generic(tg) if tg.tick_type == TickType::Shortable => {
+            update.borrow = Some(Borrow::from_tick(tg.value));
+        }
+        _ => {}
+    }
+    (!update.is_empty()).then_some(update)
+}
+
+/// Generic ticks requested on streaming subscriptions: RTVolume (233) for
+/// volume and VWAP, and Shortable (236) for the shortable (46) and
+/// shortable shares (89) ticks. The halted tick (49) needs no generic tick
+/// id: TWS sends it on every subscription, and again whenever the state
+/// changes.
+const STREAMING_GENERIC_TICKS: &[&str] = &["233", "236"];
+
+/// Subscribe a symbol to streaming market data on the streaming connection.
+/// The stream ends when TWS ends the subscription (e.g. on a connection
+/// reset); dropping it cancels the subscription.
+pub async fn subscribe_ticks(
+    conn: &ConnectionManager,
+    symbol: &str,
+    currency: &str,
+) -> Result<TickStream> {
+    let (client, _port) = conn.client(ClientRole::Streaming).await?;
+    let cur = if currency.is_empty() { "USD" } else { currency };
+    let contract = ibapi::contracts::Contract {
+        currency: ibapi::contracts::Currency::from(cur),
+        ..ibapi::contracts::Contract::stock(symbol).build()
+    };
+
+    let subscription = client
+        .market_data(&contract)
+        .generic_ticks(STREAMING_GENERIC_TICKS)
+        .subscribe()
+        .await?;
+
+    let ticks = futures::stream::unfold(subscription, |mut sub| async move {
+        match sub.next().await {
+            Some(Ok(tick)) => Some((market_tick(&tick), sub)),
+            _ => None,
+        }
+    });
+    Ok(Box::pin(futures::StreamExt::filter_map(ticks, |t| async move { t })))
+}
+
 /// Probe TWS to find the first connectable port.
-pub async fn probe_port(host: &str, ports: &[u16]) -> Option<u16> {
-    let (_client, port) = connect(host, ports, 0).await.ok()?;
+pub async fn probe_port(conn: &ConnectionManager) -> Option<u16> {
+    let (_client, port) = conn.client(ClientRole::Probe).await.ok()?;
     Some(port)
 }
 
@@ -423,71 +503,7 @@ pub async fn probe_port(host: &str, ports: &[u16]) -> Option<u16> {
 pub fn group_scans(
     xml: &str,
 ) -> HashMap<String, HashMap<String, Vec<(String, String)>>> {
-    use quick_xml::events::Event;
-    use quick_xml::Reader;
-
-    let mut tree: HashMap<String, HashMap<String, Vec<(String, String)>>> = HashMap::new();
-    let mut reader = Reader::from_str(xml);
-
-    let mut in_scan_type = false;
-    let mut current_field = String::new();
-    let mut code = String::new();
-    let mut display_name = String::new();
-    let mut vendor = String::new();
-    let mut instruments = String::new();
-
-    let mut buf = Vec::new();
-    loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) => {
-                let tag = String::from_utf8_lossy(e.name().as_ref()).to_string();
-                if tag == "ScanType" {
-                    in_scan_type = true;
-                    code.clear();
-                    display_name.clear();
-                    vendor.clear();
-                    instruments.clear();
-                } else if in_scan_type {
-                    current_field = tag;
-                }
-            }
-            Ok(Event::Text(e)) => {
-                if in_scan_type {
-                    let text = e.unescape().unwrap_or_default().to_string();
-                    match current_field.as_str() {
-                        "scanCode" => code = text,
-                        "displayName" => display_name = text,
-                        "vendor" => vendor = text,
-                        "instruments" => instruments = text,
-                        _ => {}
-                    }
-                }
-            }
-            Ok(Event::End(e)) => {
-                let tag = String::from_utf8_lossy(e.name().as_ref()).to_string();
-                if tag == "ScanType" && in_scan_type {
-                    let (instrument, category) =
-                        categorize_scan(&code, &display_name, &vendor, &instruments);
-                    tree.entry(instrument)
-                        .or_default()
-                        .entry(category)
-                        .or_default()
-                        .push((code.clone(), display_name.clone()));
-                    in_scan_type = false;
-                }
-                current_field.clear();
-            }
-            Ok(Event::Eof) => break,
-            Err(e) => {
-                warn!("XML parse error: {e}");
-                break;
-            }
-            _ => {}
-        }
-        buf.clear();
-    }
-
-    tree
+    params::ScannerParams::parse(xml).grouped()
 }
 
 /// Categorize a scanner into (instrument_type, category).
@@ -594,8 +610,8 @@ fn categorize_scan(code: &str, name: &str, vendor: &str, instruments: &str) -> (
 }
 
 /// Print scanner parameters in a formatted table.
-pub fn print_scanner_params(xml: &str, scan_group: Option<&str>) {
-    let tree = group_scans(xml);
+pub fn print_scanner_params(params: &params::ScannerParams, scan_group: Option<&str>) {
+    let tree = params.grouped();
     let total: usize = tree.values().flat_map(|cats| cats.values().map(|s| s.len())).sum();
 
     if let Some(query) = scan_group {
@@ -718,4 +734,98 @@ mod tests {
         let total: usize = stocks.values().map(|v| v.len()).sum();
         assert_eq!(total, 2);
     }
+
+    /// Parse one line of `testdata/market_ticks.txt` into a tick and the
+    /// expected volume.
+    fn fixture_tick(line: &str) -> (TickTypes, Option<Shares>) {
+        use ibapi::market_data::realtime::{TickGeneric, TickPrice, TickPriceSize, TickSize, TickString};
+
+        let (tick, expected) = line.split_once("=>").unwrap();
+        let expected = match expected.trim() {
+            "-" => None,
+            v => Some(Shares(v.parse().unwrap())),
+        };
+        let mut fields = tick.split_whitespace();
+        let kind = fields.next().unwrap();
+        let mut next = || fields.next().unwrap();
+        let tick_type = |s: &str| TickType::from(s.parse::<i32>().unwrap());
+        let tick = match kind {
+            "price" => TickTypes::Price(TickPrice {
+                tick_type: tick_type(next()),
+                price: next().parse().unwrap(),
+                ..Default::default()
+            }),
+            "size" => TickTypes::Size(TickSize {
+                tick_type: tick_type(next()),
+                size: next().parse().unwrap(),
+            }),
+            "price_size" => TickTypes::PriceSize(TickPriceSize {
+                price_tick_type: tick_type(next()),
+                price: next().parse().unwrap(),
+                size_tick_type: tick_type(next()),
+                size: next().parse().unwrap(),
+                ..Default::default()
+            }),
+            "string" => TickTypes::String(TickString {
+                tick_type: tick_type(next()),
+                value: next().to_string(),
+            }),
+            "generic" => TickTypes::Generic(TickGeneric {
+                tick_type: tick_type(next()),
+                value: next().parse().unwrap(),
+            }),
+            other => panic!("unknown tick kind {other}"),
+        };
+        (tick, expected)
+    }
+
+    #[test]
+    fn test_market_tick_fixtures() {
+        let fixtures = include_str!("testdata/market_ticks.txt");
+        let mut quote = MarketTick::default();
+        let mut count = 0;
+        for line in fixtures.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (tick, expected) = fixture_tick(line);
+            let update = market_tick(&tick);
+            assert_eq!(update.as_ref().and_then(|u| u.volume), expected, "{line}");
+            if let Some(update) = update {
+                quote.merge(update);
+            }
+            count += 1;
+        }
+        assert_eq!(count, 17);
+
+        assert_eq!(quote.volume, Some(Shares(4_522_000)));
+        assert_eq!(quote.last, Some(2.46));
+        assert_eq!((quote.bid, quote.ask, quote.close), (Some(2.44), Some(2.46), Some(2.05)));
+        assert_eq!((quote.high, quote.vwap), (Some(2.61), Some(2.4313)));
+        // Quote sizes are round lots too
+        assert_eq!((quote.bid_size, quote.ask_size), (Some(Shares(1_500)), Some(Shares(3_000))));
+        assert_eq!(quote.halted, Some(HaltState::Volatility));
+        // Shortable shares are shares, not round lots
+        assert_eq!((quote.borrow, quote.shortable_shares), (Some(Borrow::Hard), Some(Shares(45_000))));
+    }
+
+    #[test]
+    fn test_volume_sources_agree() {
+        // The same cumulative volume from a Volume tick, RTVolume and 5-minute bars
+        let tick = market_tick(&TickTypes::Size(ibapi::market_data::realtime::TickSize {
+            tick_type: TickType::Volume,
+            size: 1234.0,
+        }));
+        let rt = market_tick(&TickTypes::String(ibapi::market_data::realtime::TickString {
+            tick_type: TickType::RtVolume,
+            value: "2.45;100;1721052000000;1234;2.43;false".to_string(),
+        }));
+        let bars: Shares = [60_000.0, 40_000.0, 23_400.0]
+            .into_iter()
+            .map(|v| Shares::from_reported(v, VolumeSource::HistoricalBar))
+            .sum();
+        assert_eq!(tick.unwrap().volume, Some(bars));
+        assert_eq!(rt.unwrap().volume, Some(bars));
+        assert_eq!(crate::scanner::fmt_volume(Some(bars)), "123.4K");
+    }
 }
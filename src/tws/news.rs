@@ -0,0 +1,217 @@
+//! IB news: subscribed providers, historical headlines for a contract,
+//! article bodies and broad-tape headline ticks.
+//!
+//! What comes back depends on the account's news subscriptions; the free
+//! Briefing.com feeds (BRFG, BRFUPDN) and Dow Jones (DJ-N) are the usual
+//! ones. Headlines carry their provider code and article id so the body can
+//! be fetched later with `fetch_article`.
+
+use anyhow::{bail, Context, Result};
+use futures::StreamExt;
+use ibapi::news::{ArticleType, NewsArticle};
+use tracing::{debug, warn};
+
+use super::connection::{ClientRole, ConnectionManager};
+use crate::market_data::NewsStream;
+use crate::models::NewsHeadline;
+
+/// How far back historical headlines go.
+const HISTORICAL_NEWS_DAYS: i64 = 3;
+
+/// Most headlines one historical request returns (IB allows up to 300).
+const HISTORICAL_NEWS_MAX: u8 = 20;
+
+/// How long to wait for historical headlines before giving up.
+const NEWS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Codes of the news providers the account is subscribed to.
+pub async fn fetch_news_providers(conn: &ConnectionManager) -> Result<Vec<String>> {
+    let (client, _port) = conn.client(ClientRole::News).await?;
+    let providers = client.news_providers().await?;
+    debug!(
+        "news providers: {}",
+        providers.iter().map(|p| format!("{} ({})", p.code, p.name)).collect::<Vec<_>>().join(", ")
+    );
+    Ok(providers.into_iter().map(|p| p.code).collect())
+}
+
+/// Recent headlines for `symbol` from `providers`, newest first.
+pub async fn fetch_headlines(
+    conn: &ConnectionManager,
+    symbol: &str,
+    providers: &[String],
+) -> Result<Vec<NewsHeadline>> {
+    if providers.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (client, _port) = conn.client(ClientRole::News).await?;
+    let contract = ibapi::contracts::Contract::stock(symbol).build();
+    let con_id = client
+        .contract_details(&contract)
+        .await?
+        .first()
+        .map(|d| d.contract.contract_id)
+        .with_context(|| format!("no contract details for {symbol}"))?;
+
+    let codes: Vec<&str> = providers.iter().map(String::as_str).collect();
+    let end = time::OffsetDateTime::now_utc();
+    let start = end - time::Duration::days(HISTORICAL_NEWS_DAYS);
+    let mut subscription = client
+        .historical_news(con_id, &codes, start, end, HISTORICAL_NEWS_MAX)
+        .await?;
+
+    let mut headlines = Vec::new();
+    let deadline = tokio::time::Instant::now() + NEWS_TIMEOUT;
+    loop {
+        match tokio::time::timeout_at(deadline, subscription.next()).await {
+            Ok(Some(Ok(article))) => headlines.push(headline_from_article(&article)),
+            Ok(Some(Err(e))) => return Err(e.into()),
+            Ok(None) => break,
+            Err(_) => {
+                warn!("historical news for {symbol} timed out after {} headlines", headlines.len());
+                break;
+            }
+        }
+    }
+    headlines.sort_by_key(|h| std::cmp::Reverse(h.published));
+    Ok(headlines)
+}
+
+/// The body of one article. TWS sends HTML or plain text; PDFs are refused.
+pub async fn fetch_article(conn: &ConnectionManager, provider: &str, article_id: &str) -> Result<String> {
+    let (client, _port) = conn.client(ClientRole::News).await?;
+    let body = client.news_article(provider, article_id).await?;
+    if body.article_type == ArticleType::Binary {
+        bail!("article {article_id} is a binary document");
+    }
+    Ok(body.article_text)
+}
+
+/// Broad-tape headline ticks from every provider in `providers`, merged
+/// into one stream. Providers without a broad-tape feed are skipped.
+pub async fn subscribe_broad_tape(conn: &ConnectionManager, providers: &[String]) -> Result<NewsStream> {
+    let (client, _port) = conn.client(ClientRole::News).await?;
+    let mut streams = Vec::new();
+    for code in providers {
+        match client.broad_tape_news(code).await {
+            Ok(subscription) => {
+                let ticks = futures::stream::unfold(subscription, |mut sub| async move {
+                    match sub.next().await {
+                        Some(Ok(article)) => Some((headline_from_article(&article), sub)),
+                        _ => None,
+                    }
+                });
+                streams.push(ticks.boxed());
+            }
+            Err(e) => debug!("no broad-tape news from {code}: {e}"),
+        }
+    }
+    if streams.is_empty() {
+        bail!("no broad-tape news feeds available");
+    }
+    Ok(Box::pin(futures::stream::select_all(streams)))
+}
+
+fn headline_from_article(article: &NewsArticle) -> NewsHeadline {
+    NewsHeadline {
+        title: clean_headline(&article.headline).to_string(),
+        published: Some(article.time.unix_timestamp()),
+        source: Some(article.provider_code.clone()),
+        article_id: Some(article.article_id.clone()),
+    }
+}
+
+/// Strip the `{A:...:L:en:K:...:C:...}` metadata TWS prefixes headlines
+/// with.
+pub fn clean_headline(headline: &str) -> &str {
+    match headline.strip_prefix('{').and_then(|rest| rest.split_once('}')) {
+        Some((_, title)) => title.trim(),
+        None => headline.trim(),
+    }
+}
+
+/// Article text for display: HTML tags dropped (block tags become line
+/// breaks), common entities decoded and blank runs collapsed.
+pub fn plain_text(body: &str) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim_start_matches('/').to_ascii_lowercase();
+        let name = tag.split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or("");
+        if matches!(name, "br" | "p" | "div" | "li" | "tr" | "h1" | "h2" | "h3") {
+            out.push('\n');
+        }
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    let out = out
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    let lines: Vec<&str> = out.lines().map(str::trim).collect();
+    let mut text = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        if line.is_empty() && (i == 0 || lines[i - 1].is_empty()) {
+            continue;
+        }
+        text.push_str(line);
+        text.push('\n');
+    }
+    text.trim().to_string()
+}
+
+/// Which of `symbols` a broad-tape headline names, as a whole word.
+/// Single-letter tickers are skipped; they match too much prose.
+pub fn mentioned_symbols<'a>(headline: &str, symbols: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let words: Vec<&str> = headline
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+        .map(|w| w.trim_end_matches('.'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    symbols
+        .into_iter()
+        .filter(|s| s.len() >= 2 && words.contains(s))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_headline() {
+        assert_eq!(
+            clean_headline("{A:800015:L:en:K:n/a:C:0.98}ACME Therapeutics (ACME) receives FDA approval"),
+            "ACME Therapeutics (ACME) receives FDA approval"
+        );
+        assert_eq!(clean_headline(" Plain headline "), "Plain headline");
+        assert_eq!(clean_headline("{unterminated"), "{unterminated");
+    }
+
+    #[test]
+    fn test_plain_text() {
+        let html = "<html><body><p>Acme &amp; Bolt <b>merge</b></p>\n\n\n<p>Terms:<br/>$2.50 &lt;cash&gt;</p></body></html>";
+        assert_eq!(plain_text(html), "Acme & Bolt merge\n\nTerms:\n$2.50 <cash>");
+        assert_eq!(plain_text("plain text"), "plain text");
+    }
+
+    #[test]
+    fn test_mentioned_symbols() {
+        let tracked = ["ACME", "BOLT", "A", "BRK.B"];
+        assert_eq!(
+            mentioned_symbols("ACME Therapeutics (ACME) upgraded; BOLT. falls", tracked),
+            vec!["ACME", "BOLT"]
+        );
+        assert_eq!(mentioned_symbols("A stock to watch: BRK.B", tracked), vec!["BRK.B"]);
+        // Whole words only
+        assert!(mentioned_symbols("ACMECORP and bolt", tracked).is_empty());
+    }
+}
@@ -0,0 +1,691 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::scanner_set::{ScanFilters, ScannerSet};
+
+/// On-disk cache of the parsed scanner parameters.
+pub const PARAMS_CACHE: &str = "var/scanner_params.json";
+
+/// How long a cached copy is used before TWS is asked again.
+pub const PARAMS_MAX_AGE: Duration = Duration::from_secs(24 * 3600);
+
+/// Typed model of the TWS `scanner_parameters` XML document.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScannerParams {
+    pub instruments: Vec<Instrument>,
+    /// Top-level locations; each may nest narrower ones.
+    pub locations: Vec<Location>,
+    pub scan_types: Vec<ScanType>,
+    pub filters: Vec<FilterDef>,
+}
+
+/// A scannable instrument type, e.g. US Stocks (`STK`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Instrument {
+    pub name: String,
+    /// Instrument code used in subscriptions and `ScanType.instruments`.
+    pub code: String,
+    /// Ids of the filters (`FilterDef.id`) usable with this instrument.
+    pub filters: Vec<String>,
+}
+
+/// A node of the location tree, e.g. `STK.US` > `STK.US.MAJOR`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub code: String,
+    pub display_name: String,
+    pub instruments: Vec<String>,
+    pub children: Vec<Location>,
+}
+
+/// A scanner, e.g. `TOP_PERC_GAIN`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScanType {
+    pub code: String,
+    pub display_name: String,
+    pub vendor: String,
+    pub instruments: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterKind {
+    /// A pair of above/below fields bounding one value.
+    Range,
+    /// A single field (checkbox, combo or value).
+    Simple,
+}
+
+/// A filter definition. Its fields' `code`s are the TagValue filter tags.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterDef {
+    pub id: String,
+    pub category: String,
+    pub kind: FilterKind,
+    pub fields: Vec<FilterField>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FilterField {
+    pub code: String,
+    pub display_name: String,
+    /// Field type without the Java package, e.g. `DoubleField`, `ComboField`.
+    pub field_type: String,
+    /// False when TWS rejects negative values for this field.
+    pub accept_negative: bool,
+    /// Allowed values for combo fields, as (code, display name).
+    pub values: Vec<(String, String)>,
+}
+
+impl FilterDef {
+    /// For range filters, the (lower bound, upper bound) tag codes.
+    pub fn range_codes(&self) -> Option<(&str, &str)> {
+        if self.kind != FilterKind::Range {
+            return None;
+        }
+        let lower = self.fields.iter().find(|f| f.code.ends_with("Above"))?;
+        let upper = self.fields.iter().find(|f| f.code.ends_with("Below"))?;
+        Some((&lower.code, &upper.code))
+    }
+}
+
+/// Minimal element tree used to map the XML onto the typed model.
+#[derive(Debug, Default)]
+struct Node {
+    name: String,
+    type_attr: String,
+    text: String,
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn from_start(e: &BytesStart) -> Self {
+        let type_attr = e
+            .try_get_attribute("type")
+            .ok()
+            .flatten()
+            .and_then(|a| a.unescape_value().ok())
+            .map(|v| v.to_string())
+            .unwrap_or_default();
+        Self {
+            name: String::from_utf8_lossy(e.name().as_ref()).to_string(),
+            type_attr,
+            ..Default::default()
+        }
+    }
+
+    fn child(&self, name: &str) -> Option<&Node> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Node> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    fn text_of(&self, name: &str) -> String {
+        self.child(name).map(|c| c.text.clone()).unwrap_or_default()
+    }
+
+    fn list_of(&self, name: &str) -> Vec<String> {
+        split_list(&self.text_of(name))
+    }
+
+    /// Depth-first search for every descendant with this name.
+    fn descendants<'a>(&'a self, name: &str, out: &mut Vec<&'a Node>) {
+        for c in &self.children {
+            if c.name == name {
+                out.push(c);
+            } else {
+                c.descendants(name, out);
+            }
+        }
+    }
+}
+
+fn split_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+fn parse_tree(xml: &str) -> Node {
+    let mut reader = Reader::from_str(xml);
+    let mut stack = vec![Node::default()];
+
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => stack.push(Node::from_start(&e)),
+            Ok(Event::Empty(e)) => {
+                let node = Node::from_start(&e);
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(node);
+                }
+            }
+            Ok(Event::End(_)) if stack.len() > 1 => {
+                let node = stack.pop().unwrap_or_default();
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(node);
+                }
+            }
+            Ok(Event::Text(e)) => {
+                if let Some(node) = stack.last_mut() {
+                    node.text.push_str(e.unescape().unwrap_or_default().trim());
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                warn!("XML parse error: {e}");
+                break;
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    // Close anything left open by a truncated document
+    while stack.len() > 1 {
+        let node = stack.pop().unwrap_or_default();
+        if let Some(parent) = stack.last_mut() {
+            parent.children.push(node);
+        }
+    }
+    stack.pop().unwrap_or_default()
+}
+
+fn parse_location(node: &Node) -> Location {
+    Location {
+        code: node.text_of("locationCode"),
+        display_name: node.text_of("displayName"),
+        instruments: node.list_of("instruments"),
+        children: node
+            .child("LocationTree")
+            .map(|t| t.children_named("Location").map(parse_location).collect())
+            .unwrap_or_default(),
+    }
+}
+
+fn parse_filter(node: &Node) -> Option<FilterDef> {
+    let kind = match node.name.as_str() {
+        "RangeFilter" => FilterKind::Range,
+        "SimpleFilter" => FilterKind::Simple,
+        _ => return None,
+    };
+    let fields = node
+        .children_named("AbstractField")
+        .map(|f| FilterField {
+            code: f.text_of("code"),
+            display_name: f.text_of("displayName"),
+            field_type: f.type_attr.rsplit('.').next().unwrap_or_default().to_string(),
+            accept_negative: f.text_of("acceptNegative") != "false",
+            values: f
+                .child("ComboValues")
+                .map(|cv| {
+                    cv.children_named("ComboValue")
+                        .map(|v| (v.text_of("code"), v.text_of("displayName")))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+        .filter(|f| !f.code.is_empty())
+        .collect();
+    Some(FilterDef {
+        id: node.text_of("id"),
+        category: node.text_of("category"),
+        kind,
+        fields,
+    })
+}
+
+impl ScannerParams {
+    pub fn parse(xml: &str) -> Self {
+        let root = parse_tree(xml);
+
+        let mut nodes = Vec::new();
+        root.descendants("Instrument", &mut nodes);
+        let instruments = nodes
+            .iter()
+            .map(|n| Instrument {
+                name: n.text_of("name"),
+                code: n.text_of("type"),
+                filters: n.list_of("filters"),
+            })
+            .filter(|i| !i.code.is_empty())
+            .collect();
+
+        // The outermost LocationTree holds the top-level locations
+        let mut trees = Vec::new();
+        root.descendants("LocationTree", &mut trees);
+        let locations = trees
+            .first()
+            .map(|t| t.children_named("Location").map(parse_location).collect())
+            .unwrap_or_default();
+
+        let mut nodes = Vec::new();
+        root.descendants("ScanType", &mut nodes);
+        let scan_types = nodes
+            .iter()
+            .map(|n| ScanType {
+                code: n.text_of("scanCode"),
+                display_name: n.text_of("displayName"),
+                vendor: n.text_of("vendor"),
+                instruments: n.list_of("instruments"),
+            })
+            .filter(|s| !s.code.is_empty())
+            .collect();
+
+        let mut lists = Vec::new();
+        root.descendants("FilterList", &mut lists);
+        let filters = lists
+            .iter()
+            .flat_map(|l| l.children.iter().filter_map(parse_filter))
+            .collect();
+
+        Self {
+            instruments,
+            locations,
+            scan_types,
+            filters,
+        }
+    }
+
+    pub fn scan_type(&self, code: &str) -> Option<&ScanType> {
+        self.scan_types.iter().find(|s| s.code.eq_ignore_ascii_case(code))
+    }
+
+    /// Scan types whose code or display name contains `query` (case-insensitive).
+    pub fn search(&self, query: &str) -> Vec<&ScanType> {
+        let q = query.to_lowercase();
+        self.scan_types
+            .iter()
+            .filter(|s| s.code.to_lowercase().contains(&q) || s.display_name.to_lowercase().contains(&q))
+            .collect()
+    }
+
+    /// Filters usable with a scan type: those enabled for any of its instruments.
+    pub fn filters_for_scan(&self, code: &str) -> Vec<&FilterDef> {
+        let Some(scan) = self.scan_type(code) else {
+            return Vec::new();
+        };
+        let ids: BTreeSet<&str> = self
+            .instruments
+            .iter()
+            .filter(|i| scan.instruments.contains(&i.code))
+            .flat_map(|i| i.filters.iter().map(|f| f.as_str()))
+            .collect();
+        self.filters.iter().filter(|f| ids.contains(f.id.as_str())).collect()
+    }
+
+    /// Every location code in the tree.
+    pub fn location_codes(&self) -> BTreeSet<&str> {
+        fn walk<'a>(locs: &'a [Location], out: &mut BTreeSet<&'a str>) {
+            for l in locs {
+                out.insert(&l.code);
+                walk(&l.children, out);
+            }
+        }
+        let mut out = BTreeSet::new();
+        walk(&self.locations, &mut out);
+        out
+    }
+
+    /// Every filter tag code.
+    pub fn filter_codes(&self) -> BTreeSet<&str> {
+        self.filters
+            .iter()
+            .flat_map(|f| f.fields.iter().map(|x| x.code.as_str()))
+            .collect()
+    }
+
+    /// Scan types grouped as {instrument: {category: [(code, display_name)]}}.
+    pub fn grouped(&self) -> HashMap<String, HashMap<String, Vec<(String, String)>>> {
+        let mut tree: HashMap<String, HashMap<String, Vec<(String, String)>>> = HashMap::new();
+        for s in &self.scan_types {
+            let (instrument, category) =
+                super::categorize_scan(&s.code, &s.display_name, &s.vendor, &s.instruments.join(","));
+            tree.entry(instrument)
+                .or_default()
+                .entry(category)
+                .or_default()
+                .push((s.code.clone(), s.display_name.clone()));
+        }
+        tree
+    }
+
+    /// Check a scan code and its filters. Every problem is reported at once.
+    pub fn validate(&self, code: Option<&str>, filters: &ScanFilters) -> Result<()> {
+        let mut problems = Vec::new();
+        if let Some(code) = code {
+            self.check_scan_code(code, &mut problems);
+        }
+        self.check_filters(filters, filters.tags.keys(), &mut problems);
+        finish(problems)
+    }
+
+    /// Check every code, set-wide field and per-scanner tag of a scanner set.
+    pub fn validate_set(&self, set: &ScannerSet) -> Result<()> {
+        let mut problems = Vec::new();
+        for code in &set.codes {
+            self.check_scan_code(code, &mut problems);
+        }
+        let tags = set.scan.tags.keys().chain(set.filters.values().flat_map(|t| t.keys()));
+        self.check_filters(&set.scan, tags, &mut problems);
+        finish(problems)
+    }
+
+    fn check_scan_code(&self, code: &str, problems: &mut Vec<String>) {
+        if !self.scan_types.is_empty() && self.scan_type(code).is_none() {
+            problems.push(format!("unknown scan code '{code}'"));
+        }
+    }
+
+    fn check_filters<'a>(
+        &self,
+        filters: &ScanFilters,
+        tags: impl Iterator<Item = &'a String>,
+        problems: &mut Vec<String>,
+    ) {
+        // Empty lists mean the parameters didn't parse; nothing to check against
+        if !self.instruments.is_empty() && !self.instruments.iter().any(|i| i.code == filters.instrument) {
+            problems.push(format!("unknown instrument '{}'", filters.instrument));
+        }
+        let locations = self.location_codes();
+        if !locations.is_empty() && !locations.contains(filters.location.as_str()) {
+            problems.push(format!("unknown location '{}'", filters.location));
+        }
+        let known = self.filter_codes();
+        if known.is_empty() {
+            return;
+        }
+        let unknown: BTreeSet<&str> = tags
+            .map(|t| t.as_str())
+            .filter(|t| !known.contains(t))
+            .collect();
+        for tag in unknown {
+            problems.push(format!("unknown filter tag '{tag}'"));
+        }
+    }
+}
+
+fn finish(problems: Vec<String>) -> Result<()> {
+    if problems.is_empty() {
+        return Ok(());
+    }
+    anyhow::bail!(
+        "scanner request not supported by TWS scanner parameters: {}",
+        problems.join("; ")
+    )
+}
+
+/// Scanner parameters with the time they were fetched from TWS.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedParams {
+    pub fetched_at: DateTime<Utc>,
+    pub params: ScannerParams,
+}
+
+impl CachedParams {
+    pub fn is_fresh(&self, max_age: Duration) -> bool {
+        let age = Utc::now().signed_duration_since(self.fetched_at);
+        age.to_std().is_ok_and(|a| a < max_age)
+    }
+
+    /// Read a cache file. Missing or unreadable files yield `None`.
+    pub fn load(path: &Path) -> Option<Self> {
+        let text = std::fs::read_to_string(path).ok()?;
+        match serde_json::from_str(&text) {
+            Ok(cached) => Some(cached),
+            Err(e) => {
+                warn!("Ignoring unreadable scanner params cache {}: {e}", path.display());
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+/// Scanner parameters from the cache at `PARAMS_CACHE`, refetched from TWS
+/// when older than `PARAMS_MAX_AGE` or when `refresh` is set. A stale cache
+/// is still used if TWS cannot be reached.
+pub async fn load_or_fetch(conn: &super::ConnectionManager, refresh: bool) -> Result<CachedParams> {
+    let path = Path::new(PARAMS_CACHE);
+    let cached = CachedParams::load(path);
+    if !refresh
+        && let Some(cached) = cached.as_ref().filter(|c| c.is_fresh(PARAMS_MAX_AGE))
+    {
+        return Ok(cached.clone());
+    }
+
+    match super::fetch_scanner_params(conn).await {
+        Some(xml) => {
+            let fresh = CachedParams {
+                fetched_at: Utc::now(),
+                params: ScannerParams::parse(&xml),
+            };
+            info!(
+                scan_types = fresh.params.scan_types.len(),
+                filters = fresh.params.filters.len(),
+                "scanner parameters fetched"
+            );
+            if let Err(e) = fresh.save(path) {
+                warn!("Failed to cache scanner parameters: {e}");
+            }
+            Ok(fresh)
+        }
+        None => match cached {
+            Some(stale) => {
+                warn!(fetched_at = %stale.fetched_at, "TWS unavailable, using stale scanner parameters");
+                Ok(stale)
+            }
+            None => anyhow::bail!("could not fetch scanner parameters from TWS and no cache exists"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = r#"<?xml version="1.0"?>
+        <ScanParameterResponse>
+            <InstrumentList varName="fullInstrumentList">
+                <Instrument>
+                    <name>US Stocks</name>
+                    <type>STK</type>
+                    <filters>CHANGEPERC,FLOATSHARES,STKTYPE</filters>
+                </Instrument>
+                <Instrument>
+                    <name>US Futures</name>
+                    <type>FUT.US</type>
+                    <filters>CHANGEPERC</filters>
+                </Instrument>
+            </InstrumentList>
+            <LocationTree varName="locationTree">
+                <Location>
+                    <displayName>US Stocks</displayName>
+                    <locationCode>STK.US</locationCode>
+                    <instruments>STK</instruments>
+                    <LocationTree>
+                        <Location>
+                            <displayName>Listed/NASDAQ</displayName>
+                            <locationCode>STK.US.MAJOR</locationCode>
+                            <instruments>STK</instruments>
+                        </Location>
+                    </LocationTree>
+                </Location>
+            </LocationTree>
+            <ScanTypeList varName="scanTypeList">
+                <ScanType>
+                    <displayName>Top % Gainers</displayName>
+                    <scanCode>TOP_PERC_GAIN</scanCode>
+                    <instruments>STK,FUT.US</instruments>
+                    <vendor></vendor>
+                </ScanType>
+                <ScanType>
+                    <displayName>Hot Contracts by Volume</displayName>
+                    <scanCode>HOT_BY_VOLUME</scanCode>
+                    <instruments>FUT.US</instruments>
+                </ScanType>
+            </ScanTypeList>
+            <FilterList varName="filterList">
+                <RangeFilter>
+                    <id>CHANGEPERC</id>
+                    <category>Price</category>
+                    <AbstractField type="scanner.filter.DoubleField">
+                        <code>changePercAbove</code>
+                        <displayName>Change Above (%)</displayName>
+                        <acceptNegative>true</acceptNegative>
+                    </AbstractField>
+                    <AbstractField type="scanner.filter.DoubleField">
+                        <code>changePercBelow</code>
+                        <displayName>Change Below (%)</displayName>
+                        <acceptNegative>true</acceptNegative>
+                    </AbstractField>
+                </RangeFilter>
+                <RangeFilter>
+                    <id>FLOATSHARES</id>
+                    <category>Fundamentals</category>
+                    <AbstractField type="scanner.filter.IntField">
+                        <code>floatSharesAbove</code>
+                        <acceptNegative>false</acceptNegative>
+                    </AbstractField>
+                    <AbstractField type="scanner.filter.IntField">
+                        <code>floatSharesBelow</code>
+                        <acceptNegative>false</acceptNegative>
+                    </AbstractField>
+                </RangeFilter>
+                <SimpleFilter>
+                    <id>STKTYPE</id>
+                    <category>Other</category>
+                    <AbstractField type="scanner.filter.ComboField">
+                        <code>stkTypes</code>
+                        <ComboValues>
+                            <ComboValue><code>CORP</code><displayName>Corporation</displayName></ComboValue>
+                            <ComboValue><code>ETF</code><displayName>ETF</displayName></ComboValue>
+                        </ComboValues>
+                    </AbstractField>
+                </SimpleFilter>
+            </FilterList>
+        </ScanParameterResponse>"#;
+
+    #[test]
+    fn test_parse_model() {
+        let p = ScannerParams::parse(XML);
+        assert_eq!(p.instruments.len(), 2);
+        assert_eq!(p.instruments[0].code, "STK");
+        assert_eq!(p.instruments[0].filters, vec!["CHANGEPERC", "FLOATSHARES", "STKTYPE"]);
+
+        assert_eq!(p.locations.len(), 1);
+        assert_eq!(p.locations[0].children[0].code, "STK.US.MAJOR");
+        assert_eq!(p.location_codes().into_iter().collect::<Vec<_>>(), vec!["STK.US", "STK.US.MAJOR"]);
+
+        assert_eq!(p.scan_types.len(), 2);
+        assert_eq!(p.scan_types[0].instruments, vec!["STK", "FUT.US"]);
+
+        assert_eq!(p.filters.len(), 3);
+        let change = &p.filters[0];
+        assert_eq!(change.kind, FilterKind::Range);
+        assert_eq!(change.fields[0].field_type, "DoubleField");
+        assert_eq!(change.range_codes(), Some(("changePercAbove", "changePercBelow")));
+        assert!(!p.filters[1].fields[0].accept_negative);
+        let combo = &p.filters[2];
+        assert_eq!(combo.kind, FilterKind::Simple);
+        assert_eq!(combo.range_codes(), None);
+        assert_eq!(combo.fields[0].values[0], ("CORP".to_string(), "Corporation".to_string()));
+    }
+
+    #[test]
+    fn test_search_and_filters_for_scan() {
+        let p = ScannerParams::parse(XML);
+        assert_eq!(p.search("gain").len(), 1);
+        assert_eq!(p.search("HOT_").len(), 1);
+        assert_eq!(p.search("contracts")[0].code, "HOT_BY_VOLUME");
+
+        let ids: Vec<&str> = p.filters_for_scan("top_perc_gain").iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["CHANGEPERC", "FLOATSHARES", "STKTYPE"]);
+        let ids: Vec<&str> = p.filters_for_scan("HOT_BY_VOLUME").iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["CHANGEPERC"]);
+        assert!(p.filters_for_scan("NOPE").is_empty());
+    }
+
+    #[test]
+    fn test_validate_scan() {
+        let params = ScannerParams::parse(XML);
+        let mut filters = ScanFilters::default();
+        filters.tags.insert("changePercAbove".to_string(), "10".to_string());
+        params.validate(Some("TOP_PERC_GAIN"), &filters).unwrap();
+
+        filters.tags.insert("floatSharesBelowX".to_string(), "1".to_string());
+        filters.location = "STK.EU".to_string();
+        let err = params.validate(Some("NOPE"), &filters).unwrap_err().to_string();
+        assert!(err.contains("unknown scan code 'NOPE'"));
+        assert!(err.contains("unknown location 'STK.EU'"));
+        assert!(err.contains("unknown filter tag 'floatSharesBelowX'"));
+    }
+
+    #[test]
+    fn test_validate_set() {
+        let params = ScannerParams::parse(XML);
+        let mut set = ScannerSet {
+            codes: vec!["TOP_PERC_GAIN".to_string(), "HOT_BY_VOLUME".to_string()],
+            ..ScannerSet::default()
+        };
+        params.validate_set(&set).unwrap();
+
+        set.filters.insert(
+            "HOT_BY_VOLUME".to_string(),
+            [("priceAboveX".to_string(), "1".to_string())].into(),
+        );
+        let err = params.validate_set(&set).unwrap_err().to_string();
+        assert!(err.contains("priceAboveX"));
+        // The default set's codes are not all advertised in this fixture
+        assert!(params.validate_set(&ScannerSet::default()).is_err());
+    }
+
+    #[test]
+    fn test_validate_unparsed_params() {
+        // Parameters that failed to parse leave nothing to validate against
+        let params = ScannerParams::parse("<not-scanner-parameters/>");
+        assert!(params.instruments.is_empty());
+        params.validate_set(&ScannerSet::default()).unwrap();
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let dir = std::env::temp_dir().join(format!("scanner-params-{}", std::process::id()));
+        let path = dir.join("params.json");
+        let cached = CachedParams {
+            fetched_at: Utc::now(),
+            params: ScannerParams::parse(XML),
+        };
+        cached.save(&path).unwrap();
+        let loaded = CachedParams::load(&path).unwrap();
+        assert_eq!(loaded.params, cached.params);
+        assert!(loaded.is_fresh(PARAMS_MAX_AGE));
+
+        let old = CachedParams {
+            fetched_at: Utc::now() - chrono::Duration::days(2),
+            ..loaded
+        };
+        assert!(!old.is_fresh(PARAMS_MAX_AGE));
+        assert!(CachedParams::load(&dir.join("missing.json")).is_none());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,119 @@
+//! Volume units.
+//!
+//! TWS reports US stock volume and quote sizes in round lots on its
+//! streaming and snapshot ticks but in shares on historical bars, and Yahoo
+//! averages are shares.
+//! Every volume is converted to `Shares` where it enters the program, so
+//! display, RVOL and history writes never need to know where it came from.
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
+
+use serde::{Deserialize, Serialize};
+
+/// Shares in one round lot.
+pub const ROUND_LOT: i64 = 100;
+
+/// Where a volume figure was reported, which fixes its unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeSource {
+    /// Volume size tick (tick type 8), streaming or snapshot. Scanner rows
+    /// take their volume from a snapshot of this tick.
+    VolumeTick,
+    /// Total volume field of an RTVolume string (tick type 48).
+    RtVolume,
+    /// Bid and ask size ticks (0 and 3).
+    QuoteSize,
+    /// Shortable shares tick (89).
+    ShortableShares,
+    /// Historical bar volume.
+    HistoricalBar,
+    /// Yahoo quote summary averages.
+    Yahoo,
+}
+
+impl VolumeSource {
+    /// Shares per reported unit.
+    pub const fn multiplier(self) -> i64 {
+        match self {
+            Self::VolumeTick | Self::RtVolume | Self::QuoteSize => ROUND_LOT,
+            Self::ShortableShares | Self::HistoricalBar | Self::Yahoo => 1,
+        }
+    }
+}
+
+/// A volume in shares.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Shares(pub i64);
+
+impl Shares {
+    /// Convert a volume as reported by `source`.
+    pub fn from_reported(value: f64, source: VolumeSource) -> Self {
+        Self((value * source.multiplier() as f64).round() as i64)
+    }
+
+    pub const fn get(self) -> i64 {
+        self.0
+    }
+
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64
+    }
+}
+
+/// Abbreviated, e.g. `1.2M`, `450.0K` or `800`.
+impl fmt::Display for Shares {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let v = self.as_f64();
+        if v >= 1_000_000.0 {
+            write!(f, "{:.1}M", v / 1_000_000.0)
+        } else if v >= 1_000.0 {
+            write!(f, "{:.1}K", v / 1_000.0)
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+impl Add for Shares {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Shares {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sum for Shares {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|s| s.0).sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_reported() {
+        assert_eq!(Shares::from_reported(1234.0, VolumeSource::VolumeTick), Shares(123_400));
+        assert_eq!(Shares::from_reported(1234.0, VolumeSource::RtVolume), Shares(123_400));
+        assert_eq!(Shares::from_reported(1234.0, VolumeSource::HistoricalBar), Shares(1234));
+        assert_eq!(Shares::from_reported(2_500_000.0, VolumeSource::Yahoo), Shares(2_500_000));
+        assert_eq!([Shares(1), Shares(2)].into_iter().sum::<Shares>(), Shares(3));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Shares(123_456_700).to_string(), "123.5M");
+        assert_eq!(Shares(10_000).to_string(), "10.0K");
+        assert_eq!(Shares(800).to_string(), "800");
+    }
+}